@@ -70,6 +70,7 @@ use {
         rent_collector::RentCollector,
         sorted_storages::SortedStorages,
         storable_accounts::StorableAccounts,
+        tiered_storage::TieredStorage,
         u64_align, utils,
         verify_accounts_hash_in_background::VerifyAccountsHashInBackground,
     },
@@ -499,6 +500,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_cold_storage_path: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::CompareResults,
     test_skip_rewrites_but_include_in_bank_hash: false,
 };
@@ -511,6 +513,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    ancient_cold_storage_path: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::None,
     test_skip_rewrites_but_include_in_bank_hash: false,
 };
@@ -556,6 +559,9 @@ pub struct AccountsDbConfig {
     pub exhaustively_verify_refcounts: bool,
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
+    /// if Some, storages created by packing ancient slots are also migrated into the cold tiered
+    /// storage format, in this directory
+    pub ancient_cold_storage_path: Option<PathBuf>,
     pub test_partitioned_epoch_rewards: TestPartitionedEpochRewards,
 }
 
@@ -1365,6 +1371,13 @@ pub struct AccountsDb {
     /// from AccountsDbConfig
     create_ancient_storage: CreateAncientStorage,
 
+    /// from AccountsDbConfig
+    pub(crate) ancient_cold_storage_path: Option<PathBuf>,
+
+    /// the cold storage each packed ancient slot was migrated to, the backing file is removed
+    /// when it's dropped
+    pub(crate) ancient_cold_storages: Mutex<HashMap<Slot, TieredStorage>>,
+
     /// true if this client should skip rewrites but still include those rewrites in the bank hash as if rewrites had occurred.
     pub test_skip_rewrites_but_include_in_bank_hash: bool,
 
@@ -1928,6 +1941,8 @@ pub(crate) struct ShrinkAncientStats {
     pub(crate) slots_considered: AtomicU64,
     pub(crate) ancient_scanned: AtomicU64,
     pub(crate) bytes_ancient_created: AtomicU64,
+    pub(crate) cold_storages_created: AtomicU64,
+    pub(crate) cold_storages_failed: AtomicU64,
 }
 
 #[derive(Debug, Default)]
@@ -2236,6 +2251,16 @@ impl ShrinkAncientStats {
                 self.bytes_ancient_created.swap(0, Ordering::Relaxed) as i64,
                 i64
             ),
+            (
+                "cold_storages_created",
+                self.cold_storages_created.swap(0, Ordering::Relaxed) as i64,
+                i64
+            ),
+            (
+                "cold_storages_failed",
+                self.cold_storages_failed.swap(0, Ordering::Relaxed) as i64,
+                i64
+            ),
         );
     }
 }
@@ -2411,6 +2436,8 @@ impl AccountsDb {
 
         AccountsDb {
             create_ancient_storage: CreateAncientStorage::Pack,
+            ancient_cold_storage_path: None,
+            ancient_cold_storages: Mutex::default(),
             verify_accounts_hash_in_bg: VerifyAccountsHashInBackground::default(),
             active_stats: ActiveStats::default(),
             skip_initial_hash_calc: false,
@@ -2527,6 +2554,13 @@ impl AccountsDb {
             .map(|config| config.create_ancient_storage)
             .unwrap_or(CreateAncientStorage::Append);
 
+        let ancient_cold_storage_path = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.ancient_cold_storage_path.clone());
+        if let Some(path) = &ancient_cold_storage_path {
+            fs::create_dir_all(path).expect("create ancient cold storage dir");
+        }
+
         let test_partitioned_epoch_rewards = accounts_db_config
             .as_ref()
             .map(|config| config.test_partitioned_epoch_rewards)
@@ -2550,6 +2584,7 @@ impl AccountsDb {
             shrink_ratio,
             accounts_update_notifier,
             create_ancient_storage,
+            ancient_cold_storage_path,
             write_cache_limit_bytes: accounts_db_config
                 .as_ref()
                 .and_then(|x| x.write_cache_limit_bytes),
@@ -4622,6 +4657,7 @@ impl AccountsDb {
             self.accounts_index.clean_dead_slot(slot);
             accounts_delta_hashes.remove(&slot);
             bank_hash_stats.remove(&slot);
+            self.ancient_cold_storages.lock().unwrap().remove(&slot);
             // the storage has been removed from this slot and recycled or dropped
             assert!(self.storage.remove(&slot, false).is_none());
             debug_assert!(
@@ -5,7 +5,10 @@
 //! Otherwise, an ancient append vec is the same as any other append vec
 use {
     crate::{
-        account_storage::{meta::StoredAccountMeta, ShrinkInProgress},
+        account_storage::{
+            meta::{StorableAccountsWithHashesAndWriteVersions, StoredAccountMeta},
+            ShrinkInProgress,
+        },
         accounts_db::{
             AccountStorageEntry, AccountsDb, AliveAccounts, GetUniqueAccountsResult, ShrinkCollect,
             ShrinkCollectAliveSeparatedByRefs, ShrinkStatsSub, StoreReclaims,
@@ -16,6 +19,7 @@ use {
         active_stats::ActiveStatItem,
         append_vec::aligned_stored_size,
         storable_accounts::{StorableAccounts, StorableAccountsBySlot},
+        tiered_storage::{cold::COLD_FORMAT, TieredStorage, TieredStorageResult},
     },
     rand::{thread_rng, Rng},
     rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
@@ -24,6 +28,7 @@ use {
     std::{
         collections::HashMap,
         num::NonZeroU64,
+        path::PathBuf,
         sync::{atomic::Ordering, Arc, Mutex},
     },
 };
@@ -379,12 +384,19 @@ impl AccountsDb {
         }
 
         let write_ancient_accounts = self.write_packed_storages(&accounts_to_combine, pack);
+        let packed_slots = write_ancient_accounts
+            .shrinks_in_progress
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
 
         self.finish_combine_ancient_slots_packed_internal(
             accounts_to_combine,
             write_ancient_accounts,
             metrics,
         );
+
+        self.write_packed_storages_to_cold_storage(packed_slots);
     }
 
     /// for each account in `unrefed_pubkeys`, in each `accounts_to_combine`, addref
@@ -724,6 +736,72 @@ impl AccountsDb {
             self.write_one_packed_storage(&packed, alive_accounts.slot, write_ancient_accounts);
         }
     }
+
+    /// Migrate the contents of the ancient `storage` into a new cold tiered
+    /// storage at `path`.
+    ///
+    /// Accounts in ancient storages are the ones that haven't been modified
+    /// for a long time, so they are stored in the cold format whose account
+    /// blocks are compressed.  Only the newest version of each account in
+    /// `storage` is written.
+    pub fn write_ancient_storage_to_cold_storage(
+        &self,
+        storage: &Arc<AccountStorageEntry>,
+        path: impl Into<PathBuf>,
+    ) -> TieredStorageResult<TieredStorage> {
+        let unique_accounts = self.get_unique_accounts_from_storage(storage);
+        let accounts = unique_accounts.stored_accounts.iter().collect::<Vec<_>>();
+        let accounts = (storage.slot(), &accounts[..]);
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::<'_, '_, _, _, &AccountHash>::new(
+                &accounts,
+            );
+
+        let cold_storage = TieredStorage::new_writable(path);
+        cold_storage.write_accounts(&storable_accounts, 0, &COLD_FORMAT)?;
+        Ok(cold_storage)
+    }
+
+    /// If `ancient_cold_storage_path` is configured, migrate the ancient storages just packed
+    /// into `slots` into the cold format. The cold storage previously migrated from a slot is
+    /// replaced, removing its file.
+    fn write_packed_storages_to_cold_storage(&self, slots: Vec<Slot>) {
+        let Some(cold_storage_path) = &self.ancient_cold_storage_path else {
+            return;
+        };
+        for slot in slots {
+            let Some(storage) = self
+                .storage
+                .get_slot_storage_entry_shrinking_in_progress_ok(slot)
+            else {
+                continue;
+            };
+            let path = cold_storage_path.join(format!("{slot}.{}.cold", storage.append_vec_id()));
+            // cold storages are only removed when dropped, a validator that didn't shut down
+            // cleanly may have left this one behind
+            let _ = std::fs::remove_file(&path);
+            match self.write_ancient_storage_to_cold_storage(&storage, &path) {
+                Ok(cold_storage) => {
+                    self.ancient_cold_storages
+                        .lock()
+                        .unwrap()
+                        .insert(slot, cold_storage);
+                    self.shrink_ancient_stats
+                        .cold_storages_created
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+                Err(err) => {
+                    log::error!(
+                        "failed to write ancient storage of slot {slot} to cold storage {}: {err}",
+                        path.display()
+                    );
+                    self.shrink_ancient_stats
+                        .cold_storages_failed
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
 }
 
 /// hold all alive accounts to be shrunk and/or combined
@@ -990,6 +1068,7 @@ pub mod tests {
             accounts_index::UpsertReclaim,
             append_vec::{aligned_stored_size, AppendVec, AppendVecStoredAccountMeta},
             storable_accounts::StorableAccountsBySlot,
+            tiered_storage::{index::IndexOffset, readable::TieredStorageReader},
         },
         solana_sdk::{
             account::{AccountSharedData, ReadableAccount, WritableAccount},
@@ -999,6 +1078,7 @@ pub mod tests {
         std::ops::Range,
         strum::IntoEnumIterator,
         strum_macros::EnumIter,
+        tempfile::TempDir,
     };
 
     fn get_sample_storages(
@@ -3328,4 +3408,82 @@ pub mod tests {
             assert!(expected_ref_counts.is_empty());
         }
     }
+
+    /// assert that every account in `storage` reads back from `cold_storage`
+    fn assert_cold_storage_matches(
+        db: &AccountsDb,
+        storage: &AccountStorageEntry,
+        cold_storage: &TieredStorage,
+    ) {
+        let Some(TieredStorageReader::Cold(reader)) = cold_storage.reader() else {
+            panic!("expect a cold storage reader");
+        };
+        // both the original accounts and the cold accounts are sorted by pubkey
+        let original = db.get_unique_accounts_from_storage(storage);
+        assert_eq!(reader.num_accounts(), original.stored_accounts.len());
+        for (index, account) in original.stored_accounts.iter().enumerate() {
+            let (cold_account, _) = reader
+                .get_account(IndexOffset(index as u32))
+                .unwrap()
+                .unwrap();
+            assert_eq!(cold_account.pubkey(), account.pubkey());
+            assert_eq!(cold_account.hash(), account.hash());
+            assert_eq!(
+                cold_account.to_account_shared_data(),
+                account.to_account_shared_data()
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_ancient_storage_to_cold_storage() {
+        for account_data_size in [None, Some(1), Some(1000)] {
+            let (db, storages, _slots, _infos) = get_sample_storages(1, account_data_size);
+            let storage = &storages[0];
+            let temp_dir = TempDir::new().unwrap();
+            let cold_storage = db
+                .write_ancient_storage_to_cold_storage(storage, temp_dir.path().join("cold"))
+                .unwrap();
+            assert_cold_storage_matches(&db, storage, &cold_storage);
+        }
+    }
+
+    #[test]
+    fn test_combine_ancient_slots_packed_to_cold_storage() {
+        for account_data_size in [None, Some(1), Some(1000)] {
+            let (mut db, storages, slots, _infos) = get_sample_storages(3, account_data_size);
+            let temp_dir = TempDir::new().unwrap();
+            db.ancient_cold_storage_path = Some(temp_dir.path().to_path_buf());
+            let original = storages
+                .iter()
+                .map(|storage| db.get_unique_accounts_from_storage(storage))
+                .collect::<Vec<_>>();
+            let mut original_accounts = unique_to_accounts(original.iter());
+            drop(original);
+            drop(storages);
+
+            combine_ancient_slots_packed_for_tests(&db, slots.collect());
+
+            // every packed storage was migrated, and together they hold every account
+            let cold_storages = db.ancient_cold_storages.lock().unwrap();
+            assert!(!cold_storages.is_empty());
+            let mut packed_accounts = vec![];
+            for (slot, cold_storage) in cold_storages.iter() {
+                assert!(cold_storage.path().starts_with(temp_dir.path()));
+                let storage = db.storage.get_slot_storage_entry(*slot).unwrap();
+                assert_cold_storage_matches(&db, &storage, cold_storage);
+                packed_accounts.extend(unique_to_accounts(std::iter::once(
+                    &db.get_unique_accounts_from_storage(&storage),
+                )));
+            }
+            original_accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+            packed_accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+            assert_eq!(packed_accounts, original_accounts);
+            drop(cold_storages);
+
+            // cold storage files are removed with the db
+            drop(db);
+            assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+        }
+    }
 }
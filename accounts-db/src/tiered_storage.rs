@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -18,6 +19,7 @@ use {
         accounts_hash::AccountHash,
         storable_accounts::StorableAccounts,
     },
+    cold::ColdStorageWriter,
     error::TieredStorageError,
     footer::{AccountBlockFormat, AccountMetaFormat},
     index::IndexBlockFormat,
@@ -115,9 +117,15 @@ impl TieredStorage {
             ));
         }
 
-        let result = {
-            let writer = TieredStorageWriter::new(&self.path, format)?;
-            writer.write_accounts(accounts, skip)
+        let result = match format.account_meta_format {
+            AccountMetaFormat::Hot => {
+                let writer = TieredStorageWriter::new(&self.path, format)?;
+                writer.write_accounts(accounts, skip)
+            }
+            AccountMetaFormat::Cold => {
+                let writer = ColdStorageWriter::new(&self.path, format.account_block_format)?;
+                writer.write_accounts(accounts, skip)
+            }
         };
 
         // panic here if self.reader.get() is not None as self.reader can only be
@@ -157,6 +165,7 @@ mod tests {
     use {
         super::*,
         crate::account_storage::meta::{StoredMeta, StoredMetaWriteVersion},
+        cold::COLD_FORMAT,
         footer::{TieredStorageFooter, TieredStorageMagicNumber},
         hot::HOT_FORMAT,
         solana_accounts_db::rent_collector::RENT_EXEMPT_RENT_EPOCH,
@@ -426,4 +435,67 @@ mod tests {
             HOT_FORMAT.clone(),
         );
     }
+
+    #[test]
+    fn test_write_cold_accounts() {
+        let accounts: Vec<_> = [1, 2, 3, 4, 5, 1000, 2000, 3000, 4000, 5, 4, 3, 2, 1]
+            .iter()
+            .map(|size| create_account(*size))
+            .collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+
+        // Slot information is not used here
+        let account_data = (Slot::MAX, &account_refs[..]);
+        let hashes: Vec<_> = std::iter::repeat_with(|| AccountHash(Hash::new_unique()))
+            .take(accounts.len())
+            .collect();
+        let write_versions: Vec<_> = accounts
+            .iter()
+            .map(|account| account.0.write_version_obsolete)
+            .collect();
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                &account_data,
+                hashes,
+                write_versions,
+            );
+
+        let temp_dir = tempdir().unwrap();
+        let tiered_storage_path = temp_dir.path().join("test_write_cold_accounts");
+        let tiered_storage = TieredStorage::new_writable(tiered_storage_path);
+        let stored_infos = tiered_storage
+            .write_accounts(&storable_accounts, 0, &COLD_FORMAT)
+            .unwrap();
+        assert_eq!(stored_infos.len(), accounts.len());
+
+        let Some(TieredStorageReader::Cold(reader)) = tiered_storage.reader() else {
+            panic!("expect a cold storage reader");
+        };
+        assert_eq!(reader.num_accounts(), accounts.len());
+
+        let footer = reader.footer();
+        assert_eq!(footer.account_meta_format, COLD_FORMAT.account_meta_format);
+        assert_eq!(footer.owners_block_format, COLD_FORMAT.owners_block_format);
+        assert_eq!(footer.index_block_format, COLD_FORMAT.index_block_format);
+        assert_eq!(
+            footer.account_block_format,
+            COLD_FORMAT.account_block_format
+        );
+
+        for ((stored_meta, account), stored_info) in accounts.iter().zip(stored_infos) {
+            let (stored_account, _) = reader
+                .get_account(index::IndexOffset(stored_info.offset as u32))
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored_account.pubkey(), &stored_meta.pubkey);
+            assert_eq!(stored_account.lamports(), account.lamports());
+            assert_eq!(stored_account.data(), account.data());
+            assert_eq!(stored_account.owner(), account.owner());
+            assert_eq!(stored_account.executable(), account.executable());
+            assert_eq!(stored_account.rent_epoch(), account.rent_epoch());
+        }
+    }
 }
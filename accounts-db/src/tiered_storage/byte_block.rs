@@ -14,6 +14,8 @@ use {
 pub enum ByteBlockEncoder {
     Raw(Cursor<Vec<u8>>),
     Lz4(lz4::Encoder<Vec<u8>>),
+    /// The raw bytes are buffered and compressed as a whole by `finish`.
+    Zstd(Cursor<Vec<u8>>),
 }
 
 /// The byte block writer.
@@ -43,6 +45,7 @@ impl ByteBlockWriter {
                         .build(Vec::new())
                         .unwrap(),
                 ),
+                AccountBlockFormat::Zstd => ByteBlockEncoder::Zstd(Cursor::new(Vec::new())),
             },
             len: 0,
         }
@@ -110,6 +113,7 @@ impl ByteBlockWriter {
         match &mut self.encoder {
             ByteBlockEncoder::Raw(cursor) => cursor.write_all(buf)?,
             ByteBlockEncoder::Lz4(lz4_encoder) => lz4_encoder.write_all(buf)?,
+            ByteBlockEncoder::Zstd(cursor) => cursor.write_all(buf)?,
         };
         self.len += buf.len();
         Ok(())
@@ -125,6 +129,9 @@ impl ByteBlockWriter {
                 result?;
                 Ok(compressed_block)
             }
+            ByteBlockEncoder::Zstd(cursor) => {
+                zstd::stream::encode_all(cursor.get_ref().as_slice(), 0)
+            }
         }
     }
 }
@@ -184,6 +191,7 @@ impl ByteBlockReader {
                 decoder.read_to_end(&mut output)?;
                 Ok(output)
             }
+            AccountBlockFormat::Zstd => zstd::stream::decode_all(input),
             AccountBlockFormat::AlignedRaw => panic!("the input buffer is already decoded"),
         }
     }
@@ -242,6 +250,11 @@ mod tests {
         write_single(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_single_zstd_format() {
+        write_single(AccountBlockFormat::Zstd);
+    }
+
     #[derive(Debug, PartialEq)]
     struct TestMetaStruct {
         lamports: u64,
@@ -346,6 +359,11 @@ mod tests {
         write_multiple(AccountBlockFormat::Lz4);
     }
 
+    #[test]
+    fn test_write_multiple_zstd_format() {
+        write_multiple(AccountBlockFormat::Zstd);
+    }
+
     fn write_optional_fields(format: AccountBlockFormat) {
         let mut test_epoch = 5432312;
 
@@ -418,4 +436,9 @@ mod tests {
     fn test_write_optional_fields_lz4_format() {
         write_optional_fields(AccountBlockFormat::Lz4);
     }
+
+    #[test]
+    fn test_write_optional_fields_zstd_format() {
+        write_optional_fields(AccountBlockFormat::Zstd);
+    }
 }
@@ -0,0 +1,1135 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Cold accounts files are meant for accounts that are rarely modified and
+//! rarely read.  Unlike hot accounts, which are stored one by one at aligned
+//! offsets so that they can be accessed directly from the mmap, cold accounts
+//! are packed into account blocks that are encoded with one of the byte block
+//! encodings (i.e. lz4 or zstd).  A cold accounts file consists of:
+//!
+//! * account blocks: each account block is an encoded byte block holding one
+//!   or more account entries (ColdAccountMeta, account data, padding and
+//!   optional fields).
+//! * block table: the offset and the encoded size of each account block,
+//!   followed by the number of account blocks.
+//! * index block: the addresses of all accounts sorted in ascending order,
+//!   followed by the ColdAccountOffset of each account.
+//! * owners block
+//! * footer
+
+use {
+    crate::{
+        account_storage::meta::{StoredAccountInfo, StoredAccountMeta},
+        accounts_file::MatchAccountOwnerError,
+        accounts_hash::AccountHash,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
+        tiered_storage::{
+            byte_block::{self, ByteBlockReader, ByteBlockWriter},
+            file::TieredStorageFile,
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
+            meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
+            mmap_utils::{get_pod, get_slice},
+            owners::{OwnerOffset, OwnersBlockFormat, OwnersTable, OWNER_NO_OWNER},
+            readable::TieredReadableAccount,
+            StorableAccounts, StorableAccountsWithHashesAndWriteVersions, TieredStorageError,
+            TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    bytemuck::{Pod, Zeroable},
+    memmap2::{Mmap, MmapOptions},
+    solana_sdk::{account::ReadableAccount, pubkey::Pubkey, stake_history::Epoch},
+    std::{borrow::Borrow, fs::OpenOptions, path::Path, sync::OnceLock},
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::AddressesOnly,
+    index_block_format: IndexBlockFormat::AddressesThenOffsets,
+    account_block_format: AccountBlockFormat::Zstd,
+};
+
+/// The size of a cold account block before encoding.  An account whose entry
+/// does not fit into this size is stored in an account block of its own.
+pub const COLD_ACCOUNT_BLOCK_SIZE: usize = 64 * 1024;
+
+/// An helper function that creates a new default footer for cold
+/// accounts storage with the specified account block format.
+fn new_cold_footer(account_block_format: AccountBlockFormat) -> TieredStorageFooter {
+    TieredStorageFooter {
+        account_meta_format: COLD_FORMAT.account_meta_format,
+        account_meta_entry_size: COLD_FORMAT.meta_entry_size as u32,
+        account_block_format,
+        account_block_size: COLD_ACCOUNT_BLOCK_SIZE as u64,
+        index_block_format: COLD_FORMAT.index_block_format,
+        owners_block_format: COLD_FORMAT.owners_block_format,
+        ..TieredStorageFooter::default()
+    }
+}
+
+/// The byte alignment for the account entries inside a decoded cold account
+/// block.  This allows the account meta and the optional fields of a cold
+/// account to be directly accessed from its decoded account block.
+pub(crate) const COLD_ACCOUNT_ALIGNMENT: usize = 8;
+
+/// The alignment for the blocks inside a cold accounts file.
+pub(crate) const COLD_BLOCK_ALIGNMENT: usize = 8;
+
+/// The buffer that is used for padding.
+const PADDING_BUFFER: [u8; 8] = [0u8; COLD_ACCOUNT_ALIGNMENT];
+
+// returns the required number of padding
+fn padding_bytes(len: usize, alignment: usize) -> usize {
+    (alignment - (len % alignment)) % alignment
+}
+
+/// The offset to access a cold account.  It consists of the index of the
+/// account block that contains the account and the offset of the account
+/// entry inside the decoded account block.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+pub struct ColdAccountOffset {
+    block_index: u32,
+    intra_block_offset: u32,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountOffset>() == 4 + 4);
+
+impl AccountOffset for ColdAccountOffset {}
+
+impl ColdAccountOffset {
+    /// Creates a new AccountOffset instance
+    pub fn new(block_index: usize, intra_block_offset: usize) -> TieredStorageResult<Self> {
+        const MAX_OFFSET: usize = u32::MAX as usize;
+        if block_index > MAX_OFFSET {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                block_index,
+                MAX_OFFSET,
+            ));
+        }
+        if intra_block_offset > MAX_OFFSET {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                intra_block_offset,
+                MAX_OFFSET,
+            ));
+        }
+
+        // Cold account entries are aligned based on COLD_ACCOUNT_ALIGNMENT
+        // inside their account block.
+        if intra_block_offset % COLD_ACCOUNT_ALIGNMENT != 0 {
+            return Err(TieredStorageError::OffsetAlignmentError(
+                intra_block_offset,
+                COLD_ACCOUNT_ALIGNMENT,
+            ));
+        }
+
+        Ok(Self {
+            block_index: block_index as u32,
+            intra_block_offset: intra_block_offset as u32,
+        })
+    }
+
+    /// Returns the index of the account block that contains the account.
+    fn block_index(&self) -> usize {
+        self.block_index as usize
+    }
+
+    /// Returns the offset to the account inside its decoded account block.
+    fn intra_block_offset(&self) -> usize {
+        self.intra_block_offset as usize
+    }
+}
+
+/// The entry of the block table that describes one account block.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+struct ColdBlockEntry {
+    /// The offset pointing to the first byte of the encoded account block.
+    offset: u64,
+    /// The size of the encoded account block in bytes.
+    encoded_size: u64,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdBlockEntry>() == 8 + 8);
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// The size of the account data in bytes.
+    account_data_size: u64,
+    /// The index to the owner of this account inside its AccountsFile.
+    owner_offset: u32,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountMeta>() == 8 + 8 + 4 + 4);
+
+impl ColdAccountMeta {
+    /// Returns the size of the account entry that follows this meta inside
+    /// its account block, which includes the account data, its padding, and
+    /// the optional fields.
+    fn account_entry_size(&self) -> usize {
+        (self.account_data_size as usize)
+            .saturating_add(self.account_data_padding() as usize)
+            .saturating_add(AccountMetaOptionalFields::size_from_flags(&self.flags))
+    }
+}
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            account_data_size: 0,
+            owner_offset: 0,
+            flags: AccountMetaFlags::new(),
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// A builder function that initializes the number of padding bytes
+    /// for the account data associated with the current meta.
+    fn with_account_data_padding(self, _padding: u8) -> Self {
+        // Cold meta does not store its padding as it derives the padding
+        // from its account data size.
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_offset(mut self, owner_offset: OwnerOffset) -> Self {
+        self.owner_offset = owner_offset.0;
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size;
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Returns the number of padding bytes for the associated account data
+    fn account_data_padding(&self) -> u8 {
+        padding_bytes(self.account_data_size as usize, COLD_ACCOUNT_ALIGNMENT) as u8
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_offset(&self) -> OwnerOffset {
+        OwnerOffset(self.owner_offset)
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns true as multiple ColdAccountMeta entries share the
+    /// same account block.
+    fn supports_shared_account_block() -> bool {
+        true
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                byte_block::read_pod::<Epoch>(account_block, offset).copied()
+            })
+            .flatten()
+    }
+
+    /// Returns the account hash by parsing the specified account block.  None
+    /// will be returned if this account does not persist this optional field.
+    fn account_hash<'a>(&self, account_block: &'a [u8]) -> Option<&'a AccountHash> {
+        self.flags()
+            .has_account_hash()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::account_hash_offset(self.flags());
+                byte_block::read_pod::<AccountHash>(account_block, offset)
+            })
+            .flatten()
+    }
+
+    /// Returns the offset of the optional fields based on the specified account
+    /// block.
+    fn optional_fields_offset(&self, _account_block: &[u8]) -> usize {
+        (self.account_data_size as usize).saturating_add(self.account_data_padding() as usize)
+    }
+
+    /// Returns the length of the data associated to this account based on the
+    /// specified account block.
+    fn account_data_size(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        &account_block[..self.account_data_size(account_block)]
+    }
+}
+
+/// A decoded cold account block.
+///
+/// The decoded bytes are kept in a u64 buffer so that the account entries,
+/// which are aligned to COLD_ACCOUNT_ALIGNMENT inside the account block,
+/// are also properly aligned in memory.
+#[derive(Debug)]
+struct DecodedAccountBlock {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl DecodedAccountBlock {
+    fn new(bytes: &[u8]) -> Self {
+        let mut words = vec![0u64; bytes.len().div_ceil(std::mem::size_of::<u64>())];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..bytes.len()].copy_from_slice(bytes);
+        Self {
+            words,
+            len: bytes.len(),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &bytemuck::cast_slice::<u64, u8>(&self.words)[..self.len]
+    }
+}
+
+/// The reader to a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+    /// The block table of the underlying cold accounts file.
+    block_entries: Vec<ColdBlockEntry>,
+    /// The decoded account blocks.  An account block is decoded the first
+    /// time any of its accounts is accessed, and it is kept until the
+    /// reader is dropped.
+    decoded_blocks: Vec<OnceLock<DecodedAccountBlock>>,
+}
+
+impl ColdStorageReader {
+    /// Constructs a ColdStorageReader from the specified path.
+    pub fn new_from_path(path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        let footer = *TieredStorageFooter::new_from_mmap(&mmap)?;
+        let block_entries = Self::read_block_table(&mmap, &footer)?;
+        let decoded_blocks = std::iter::repeat_with(OnceLock::new)
+            .take(block_entries.len())
+            .collect();
+
+        Ok(Self {
+            mmap,
+            footer,
+            block_entries,
+            decoded_blocks,
+        })
+    }
+
+    /// Reads the block table, which ends right before the index block.
+    fn read_block_table(
+        mmap: &Mmap,
+        footer: &TieredStorageFooter,
+    ) -> TieredStorageResult<Vec<ColdBlockEntry>> {
+        let block_count_offset =
+            (footer.index_block_offset as usize).saturating_sub(std::mem::size_of::<u64>());
+        let (&block_count, _) = get_pod::<u64>(mmap, block_count_offset)?;
+
+        let block_table_size =
+            (block_count as usize).saturating_mul(std::mem::size_of::<ColdBlockEntry>());
+        let block_table_offset = block_count_offset.checked_sub(block_table_size).ok_or(
+            TieredStorageError::OffsetOutOfBounds(block_table_size, block_count_offset),
+        )?;
+
+        (0..block_count as usize)
+            .map(|block_index| -> TieredStorageResult<ColdBlockEntry> {
+                let offset =
+                    block_table_offset + block_index * std::mem::size_of::<ColdBlockEntry>();
+                let (block_entry, _) = get_pod::<ColdBlockEntry>(mmap, offset)?;
+                Ok(*block_entry)
+            })
+            .collect()
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of files inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the decoded account block associated with the specified
+    /// block index.
+    fn get_account_block(&self, block_index: usize) -> TieredStorageResult<&[u8]> {
+        let Some(decoded_block) = self.decoded_blocks.get(block_index) else {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                block_index,
+                self.decoded_blocks.len(),
+            ));
+        };
+        if let Some(decoded_block) = decoded_block.get() {
+            return Ok(decoded_block.as_bytes());
+        }
+
+        let block_entry = &self.block_entries[block_index];
+        let (encoded_block, _) = get_slice(
+            &self.mmap,
+            block_entry.offset as usize,
+            block_entry.encoded_size as usize,
+        )?;
+        let decoded = match self.footer.account_block_format {
+            AccountBlockFormat::AlignedRaw => DecodedAccountBlock::new(encoded_block),
+            encoding => {
+                DecodedAccountBlock::new(&ByteBlockReader::decode(encoding, encoded_block)?)
+            }
+        };
+
+        // In case another thread has decoded the same account block in the
+        // meantime, its result is used and ours is dropped.
+        Ok(decoded_block.get_or_init(|| decoded).as_bytes())
+    }
+
+    /// Returns the account meta located at the specified offset.
+    fn get_account_meta_from_offset(
+        &self,
+        account_offset: ColdAccountOffset,
+    ) -> TieredStorageResult<&ColdAccountMeta> {
+        let account_block = self.get_account_block(account_offset.block_index())?;
+        let offset = account_offset.intra_block_offset();
+
+        byte_block::read_pod::<ColdAccountMeta>(account_block, offset).ok_or(
+            TieredStorageError::AccountBlockOutOfBounds(
+                offset.saturating_add(std::mem::size_of::<ColdAccountMeta>()),
+                account_block.len(),
+            ),
+        )
+    }
+
+    /// Returns the account entry (i.e. account data, padding, and optional
+    /// fields) that follows the specified account meta.
+    fn get_account_entry(
+        &self,
+        account_offset: ColdAccountOffset,
+        meta: &ColdAccountMeta,
+    ) -> TieredStorageResult<&[u8]> {
+        let account_block = self.get_account_block(account_offset.block_index())?;
+        let start = account_offset
+            .intra_block_offset()
+            .saturating_add(std::mem::size_of::<ColdAccountMeta>());
+        let end = start.saturating_add(meta.account_entry_size());
+
+        account_block
+            .get(start..end)
+            .ok_or(TieredStorageError::AccountBlockOutOfBounds(
+                end,
+                account_block.len(),
+            ))
+    }
+
+    /// Returns the offset to the account given the specified index.
+    fn get_account_offset(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<ColdAccountOffset> {
+        self.footer
+            .index_block_format
+            .get_account_offset::<ColdAccountOffset>(&self.mmap, &self.footer, index_offset)
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: IndexOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .index_block_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the account owner given the specified
+    /// owner_offset.
+    fn get_owner_address(&self, owner_offset: OwnerOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_offset)
+    }
+
+    /// Returns the index of the account with the specified address, or None
+    /// if the underlying cold accounts file does not contain such account.
+    ///
+    /// As the index block of a cold accounts file is sorted by address, this
+    /// is a binary search that only reads the index block.  If the same
+    /// address is stored more than once, the index of its last entry is
+    /// returned.
+    pub fn get_index_offset(&self, address: &Pubkey) -> TieredStorageResult<Option<IndexOffset>> {
+        if self.footer.account_entry_count == 0
+            || address < &self.footer.min_account_address
+            || address > &self.footer.max_account_address
+        {
+            return Ok(None);
+        }
+
+        // find the first entry whose address is larger than the specified one.
+        let (mut low, mut high) = (0u32, self.footer.account_entry_count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get_account_address(IndexOffset(mid))? <= address {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        let Some(last) = low.checked_sub(1) else {
+            return Ok(None);
+        };
+        let index_offset = IndexOffset(last);
+        Ok((self.get_account_address(index_offset)? == address).then_some(index_offset))
+    }
+
+    /// Returns Ok(index_of_matching_owner) if the account owner at
+    /// `account_offset` is one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::NoMatch) if the account has 0
+    /// lamports or the owner is not one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::UnableToLoad) if there is any internal
+    /// error that causes the data unable to load, including `account_offset`
+    /// causes a data overrun.
+    pub fn account_matches_owners(
+        &self,
+        account_offset: ColdAccountOffset,
+        owners: &[Pubkey],
+    ) -> Result<usize, MatchAccountOwnerError> {
+        let account_meta = self
+            .get_account_meta_from_offset(account_offset)
+            .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+        if account_meta.lamports() == 0 {
+            Err(MatchAccountOwnerError::NoMatch)
+        } else {
+            let account_owner = self
+                .get_owner_address(account_meta.owner_offset())
+                .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+            owners
+                .iter()
+                .position(|candidate| account_owner == candidate)
+                .ok_or(MatchAccountOwnerError::NoMatch)
+        }
+    }
+
+    /// Returns the account located at the specified index offset.
+    pub fn get_account(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<(StoredAccountMeta<'_>, usize)>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+
+        let meta = self.get_account_meta_from_offset(account_offset)?;
+        let address = self.get_account_address(index_offset)?;
+        let owner = self.get_owner_address(meta.owner_offset())?;
+        let account_block = self.get_account_entry(account_offset, meta)?;
+
+        Ok(Some((
+            StoredAccountMeta::Cold(TieredReadableAccount {
+                meta,
+                address,
+                owner,
+                index: index_offset.0 as usize,
+                account_block,
+            }),
+            index_offset.0.saturating_add(1) as usize,
+        )))
+    }
+}
+
+/// The writer that creates a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageWriter {
+    storage: TieredStorageFile,
+    account_block_format: AccountBlockFormat,
+}
+
+impl ColdStorageWriter {
+    /// Create a new ColdStorageWriter with the specified path and the
+    /// encoding of its account blocks.
+    pub fn new(
+        file_path: impl AsRef<Path>,
+        account_block_format: AccountBlockFormat,
+    ) -> TieredStorageResult<Self> {
+        Ok(Self {
+            storage: TieredStorageFile::new_writable(file_path)?,
+            account_block_format,
+        })
+    }
+
+    /// Appends an account with the specified information to the specified
+    /// account block and returns the stored size of the account before
+    /// encoding.
+    fn write_account(
+        block_writer: &mut ByteBlockWriter,
+        lamports: u64,
+        owner_offset: OwnerOffset,
+        account_data: &[u8],
+        executable: bool,
+        optional_fields: &AccountMetaOptionalFields,
+    ) -> TieredStorageResult<usize> {
+        let mut flags = AccountMetaFlags::new_from(optional_fields);
+        flags.set_executable(executable);
+
+        let meta = ColdAccountMeta::new()
+            .with_lamports(lamports)
+            .with_owner_offset(owner_offset)
+            .with_account_data_size(account_data.len() as u64)
+            .with_flags(&flags);
+        let padding_len = meta.account_data_padding() as usize;
+
+        let mut stored_size = 0;
+
+        stored_size += block_writer.write_pod(&meta)?;
+        block_writer.write(account_data)?;
+        block_writer.write(&PADDING_BUFFER[0..padding_len])?;
+        stored_size += account_data.len() + padding_len;
+        stored_size += block_writer.write_optional_fields(optional_fields)?;
+
+        Ok(stored_size)
+    }
+
+    /// Encodes and persists the specified account block, and appends its
+    /// block table entry to `block_entries`.  Returns the number of bytes
+    /// written, including the padding that aligns the next block.
+    fn write_account_block(
+        &self,
+        block_writer: ByteBlockWriter,
+        offset: usize,
+        block_entries: &mut Vec<ColdBlockEntry>,
+    ) -> TieredStorageResult<usize> {
+        let encoded_block = block_writer.finish()?;
+        block_entries.push(ColdBlockEntry {
+            offset: offset as u64,
+            encoded_size: encoded_block.len() as u64,
+        });
+
+        let padding_len = padding_bytes(encoded_block.len(), COLD_BLOCK_ALIGNMENT);
+        let mut bytes_written = self.storage.write_bytes(&encoded_block)?;
+        bytes_written += self.storage.write_bytes(&PADDING_BUFFER[0..padding_len])?;
+
+        Ok(bytes_written)
+    }
+
+    /// Persists `accounts` into the underlying cold accounts file associated
+    /// with this ColdStorageWriter.  The first `skip` number of accounts are
+    /// *not* persisted.
+    ///
+    /// The accounts are stored in the order of their addresses.  The
+    /// returned StoredAccountInfo of each account, which follows the order of
+    /// `accounts`, has the index of the account in the file as its offset
+    /// and the size of its entry before encoding as its size.
+    pub fn write_accounts<
+        'a,
+        'b,
+        T: ReadableAccount + Sync,
+        U: StorableAccounts<'a, T>,
+        V: Borrow<AccountHash>,
+    >(
+        &self,
+        accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
+        skip: usize,
+    ) -> TieredStorageResult<Vec<StoredAccountInfo>> {
+        let mut footer = new_cold_footer(self.account_block_format);
+        let len = accounts.accounts.len();
+
+        // Sort the accounts by their addresses so that the index block can be
+        // searched by address.  The sort is stable, which keeps multiple
+        // entries of the same address in their original order.
+        let mut sorted_indexes: Vec<_> = (skip..len).collect();
+        sorted_indexes.sort_by(|a, b| accounts.get(*a).1.cmp(accounts.get(*b).1));
+
+        let mut index = Vec::with_capacity(sorted_indexes.len());
+        let mut stored_infos = vec![StoredAccountInfo { offset: 0, size: 0 }; sorted_indexes.len()];
+        let mut owners_table = OwnersTable::default();
+        let mut block_entries = vec![];
+        let mut block_writer = ByteBlockWriter::new(self.account_block_format);
+        let mut cursor = 0;
+
+        // writing accounts blocks
+        for (position, i) in sorted_indexes.into_iter().enumerate() {
+            let (account, address, account_hash, _write_version) = accounts.get(i);
+
+            // Obtain necessary fields from the account, or default fields
+            // for a zero-lamport account in the None case.
+            let (lamports, owner, data, executable, rent_epoch, account_hash) = account
+                .map(|acc| {
+                    (
+                        acc.lamports(),
+                        acc.owner(),
+                        acc.data(),
+                        acc.executable(),
+                        // only persist rent_epoch for those rent-paying accounts
+                        (acc.rent_epoch() != RENT_EXEMPT_RENT_EPOCH).then_some(acc.rent_epoch()),
+                        Some(account_hash),
+                    )
+                })
+                .unwrap_or((0, &OWNER_NO_OWNER, &[], false, None, None));
+            let optional_fields = AccountMetaOptionalFields {
+                rent_epoch,
+                account_hash,
+            };
+
+            // Start a new account block when the current one cannot hold this
+            // account.  An account larger than the account block size ends up
+            // in an account block of its own.
+            let entry_size = std::mem::size_of::<ColdAccountMeta>()
+                + data.len()
+                + padding_bytes(data.len(), COLD_ACCOUNT_ALIGNMENT)
+                + optional_fields.size();
+            if block_writer.raw_len() > 0
+                && block_writer.raw_len() + entry_size > footer.account_block_size as usize
+            {
+                let full_block_writer = std::mem::replace(
+                    &mut block_writer,
+                    ByteBlockWriter::new(self.account_block_format),
+                );
+                cursor +=
+                    self.write_account_block(full_block_writer, cursor, &mut block_entries)?;
+            }
+
+            let index_entry = AccountIndexWriterEntry {
+                address,
+                offset: ColdAccountOffset::new(block_entries.len(), block_writer.raw_len())?,
+            };
+            let owner_offset = owners_table.insert(owner);
+            let stored_size = Self::write_account(
+                &mut block_writer,
+                lamports,
+                owner_offset,
+                data,
+                executable,
+                &optional_fields,
+            )?;
+            debug_assert_eq!(stored_size, entry_size);
+            index.push(index_entry);
+            stored_infos[i - skip] = StoredAccountInfo {
+                offset: position,
+                size: stored_size,
+            };
+        }
+        if block_writer.raw_len() > 0 {
+            cursor += self.write_account_block(block_writer, cursor, &mut block_entries)?;
+        }
+        footer.account_entry_count = index.len() as u32;
+        if let (Some(first), Some(last)) = (index.first(), index.last()) {
+            footer.min_account_address = *first.address;
+            footer.max_account_address = *last.address;
+        }
+
+        // writing block table
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        for block_entry in &block_entries {
+            cursor += self.storage.write_pod(block_entry)?;
+        }
+        cursor += self.storage.write_pod(&(block_entries.len() as u64))?;
+
+        // writing index block
+        footer.index_block_offset = cursor as u64;
+        cursor += footer
+            .index_block_format
+            .write_index_block(&self.storage, &index)?;
+
+        // writing owners block
+        // each index entry is an address followed by an 8-byte offset, so
+        // the index block never breaks the alignment.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.owners_block_offset = cursor as u64;
+        footer.owner_count = owners_table.len() as u32;
+        footer
+            .owners_block_format
+            .write_owners_block(&self.storage, &owners_table)?;
+
+        footer.write_footer_block(&self.storage)?;
+
+        Ok(stored_infos)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {
+        super::*,
+        crate::account_storage::meta::StoredMeta,
+        assert_matches::assert_matches,
+        memoffset::offset_of,
+        solana_sdk::{
+            account::{Account, AccountSharedData},
+            hash::Hash,
+            slot_history::Slot,
+        },
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, owner_offset), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x14);
+        assert_eq!(std::mem::size_of::<ColdAccountMeta>(), 24);
+    }
+
+    #[test]
+    fn test_cold_account_meta() {
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_DATA_SIZE: u64 = 1021;
+        const TEST_OWNER_OFFSET: OwnerOffset = OwnerOffset(u32::MAX);
+
+        let mut flags = AccountMetaFlags::new();
+        flags.set_has_rent_epoch(true);
+        flags.set_executable(true);
+
+        let meta = ColdAccountMeta::new()
+            .with_lamports(TEST_LAMPORTS)
+            .with_account_data_size(TEST_DATA_SIZE)
+            .with_owner_offset(TEST_OWNER_OFFSET)
+            .with_flags(&flags);
+
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.account_data_size(&[]), TEST_DATA_SIZE as usize);
+        assert_eq!(meta.account_data_padding(), 3);
+        assert_eq!(meta.owner_offset(), TEST_OWNER_OFFSET);
+        assert_eq!(*meta.flags(), flags);
+        assert_eq!(
+            meta.account_entry_size(),
+            TEST_DATA_SIZE as usize + 3 + std::mem::size_of::<Epoch>()
+        );
+    }
+
+    #[test]
+    fn test_cold_account_offset() {
+        assert_matches!(ColdAccountOffset::new(0, 0), Ok(_));
+        assert_matches!(
+            ColdAccountOffset::new(u32::MAX as usize, COLD_ACCOUNT_ALIGNMENT),
+            Ok(_)
+        );
+        assert_matches!(
+            ColdAccountOffset::new(u32::MAX as usize + 1, 0),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(0, COLD_ACCOUNT_ALIGNMENT - 1),
+            Err(TieredStorageError::OffsetAlignmentError(_, _))
+        );
+    }
+
+    #[test]
+    fn test_decoded_account_block() {
+        let bytes: Vec<u8> = (0..13).collect();
+        let decoded_block = DecodedAccountBlock::new(&bytes);
+        assert_eq!(decoded_block.as_bytes(), &bytes[..]);
+        assert_eq!(
+            decoded_block.as_bytes().as_ptr() as usize % COLD_ACCOUNT_ALIGNMENT,
+            0
+        );
+    }
+
+    /// Create a test account based on the specified seed.
+    /// The created test account might have default rent_epoch
+    /// and write_version.
+    ///
+    /// When the seed is zero, then a zero-lamport test account will be
+    /// created.
+    fn create_test_account(seed: u64) -> (StoredMeta, AccountSharedData) {
+        let data_byte = seed as u8;
+        let owner_byte = u8::MAX - data_byte;
+        let account = Account {
+            lamports: seed,
+            data: std::iter::repeat(data_byte).take(seed as usize).collect(),
+            // this will allow some test account sharing the same owner.
+            owner: [owner_byte; 32].into(),
+            executable: seed % 2 > 0,
+            rent_epoch: if seed % 3 > 0 {
+                seed
+            } else {
+                RENT_EXEMPT_RENT_EPOCH
+            },
+        };
+
+        let stored_meta = StoredMeta {
+            write_version_obsolete: u64::MAX,
+            pubkey: Pubkey::new_unique(),
+            data_len: seed,
+        };
+        (stored_meta, AccountSharedData::from(account))
+    }
+
+    /// Writes test accounts with the specified data sizes into a cold
+    /// accounts file with the specified account block format, then verifies
+    /// them by reading the file back.
+    fn do_test_write_and_read_accounts(
+        path_suffix: &str,
+        account_data_sizes: &[u64],
+        account_block_format: AccountBlockFormat,
+    ) -> ColdStorageReader {
+        let accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+
+        // Slot information is not used here
+        let account_data = (Slot::MAX, &account_refs[..]);
+        let hashes: Vec<_> = std::iter::repeat_with(|| AccountHash(Hash::new_unique()))
+            .take(account_data_sizes.len())
+            .collect();
+
+        let write_versions: Vec<_> = accounts
+            .iter()
+            .map(|account| account.0.write_version_obsolete)
+            .collect();
+
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                &account_data,
+                hashes,
+                write_versions,
+            );
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(path_suffix);
+
+        let stored_infos = {
+            let writer = ColdStorageWriter::new(&path, account_block_format).unwrap();
+            writer.write_accounts(&storable_accounts, 0).unwrap()
+        };
+        assert_eq!(stored_infos.len(), account_data_sizes.len());
+
+        let cold_storage = ColdStorageReader::new_from_path(&path).unwrap();
+        let num_accounts = account_data_sizes.len();
+        assert_eq!(cold_storage.num_accounts(), num_accounts);
+        assert_eq!(
+            cold_storage.footer().account_block_format,
+            account_block_format
+        );
+
+        for (i, stored_info) in stored_infos.iter().enumerate() {
+            let (account, address, account_hash, _write_version) = storable_accounts.get(i);
+            // zero-lamport accounts are stored with default fields
+            let (lamports, owner, data, executable, rent_epoch, account_hash) = account
+                .map(|acc| {
+                    (
+                        acc.lamports(),
+                        acc.owner(),
+                        acc.data(),
+                        acc.executable(),
+                        acc.rent_epoch(),
+                        *account_hash,
+                    )
+                })
+                .unwrap_or((
+                    0,
+                    &OWNER_NO_OWNER,
+                    &[],
+                    false,
+                    RENT_EXEMPT_RENT_EPOCH,
+                    AccountHash(Hash::default()),
+                ));
+
+            let index_offset = cold_storage.get_index_offset(address).unwrap().unwrap();
+            assert_eq!(index_offset.0 as usize, stored_info.offset);
+
+            let (stored_meta, next) = cold_storage.get_account(index_offset).unwrap().unwrap();
+            assert_eq!(stored_meta.lamports(), lamports);
+            assert_eq!(stored_meta.data().len(), data.len());
+            assert_eq!(stored_meta.data(), data);
+            assert_eq!(stored_meta.executable(), executable);
+            assert_eq!(stored_meta.rent_epoch(), rent_epoch);
+            assert_eq!(stored_meta.owner(), owner);
+            assert_eq!(stored_meta.pubkey(), address);
+            assert_eq!(*stored_meta.hash(), account_hash);
+
+            assert_eq!(index_offset.0 as usize + 1, next);
+        }
+        // Make sure it returns None on NUM_ACCOUNTS to allow termination on
+        // while loop in actual accounts-db read case.
+        assert_matches!(
+            cold_storage.get_account(IndexOffset(num_accounts as u32)),
+            Ok(None)
+        );
+
+        cold_storage
+    }
+
+    #[test]
+    fn test_write_and_read_accounts_zstd() {
+        do_test_write_and_read_accounts(
+            "test_write_and_read_accounts_zstd",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+            ],
+            AccountBlockFormat::Zstd,
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_accounts_lz4() {
+        do_test_write_and_read_accounts(
+            "test_write_and_read_accounts_lz4",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 0],
+            AccountBlockFormat::Lz4,
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_accounts_raw() {
+        do_test_write_and_read_accounts(
+            "test_write_and_read_accounts_raw",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 0],
+            AccountBlockFormat::AlignedRaw,
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_accounts_multiple_blocks() {
+        let account_data_sizes = &[
+            10_000,
+            (COLD_ACCOUNT_BLOCK_SIZE * 2) as u64,
+            20_000,
+            30_000,
+            1,
+            40_000,
+            COLD_ACCOUNT_BLOCK_SIZE as u64,
+            50_000,
+        ];
+        let cold_storage = do_test_write_and_read_accounts(
+            "test_write_and_read_accounts_multiple_blocks",
+            account_data_sizes,
+            AccountBlockFormat::Zstd,
+        );
+
+        // No account block holds more than COLD_ACCOUNT_BLOCK_SIZE bytes,
+        // unless it holds a single account.
+        assert!(cold_storage.block_entries.len() > 3);
+        for block_index in 0..cold_storage.block_entries.len() {
+            let account_block = cold_storage.get_account_block(block_index).unwrap();
+            let offset = ColdAccountOffset::new(block_index, 0).unwrap();
+            let meta = cold_storage.get_account_meta_from_offset(offset).unwrap();
+            if account_block.len() > COLD_ACCOUNT_BLOCK_SIZE {
+                assert_eq!(
+                    account_block.len(),
+                    std::mem::size_of::<ColdAccountMeta>() + meta.account_entry_size()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_index_offset_not_found() {
+        let cold_storage = do_test_write_and_read_accounts(
+            "test_get_index_offset_not_found",
+            &[1, 2, 3, 4, 5],
+            AccountBlockFormat::Zstd,
+        );
+
+        assert_matches!(cold_storage.get_index_offset(&Pubkey::default()), Ok(None));
+        assert_matches!(
+            cold_storage.get_index_offset(&Pubkey::new_unique()),
+            Ok(None)
+        );
+        assert_matches!(
+            cold_storage.get_index_offset(&Pubkey::from([u8::MAX; 32])),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_zero_accounts() {
+        let cold_storage = do_test_write_and_read_accounts(
+            "test_write_and_read_zero_accounts",
+            &[],
+            AccountBlockFormat::Zstd,
+        );
+
+        assert!(cold_storage.block_entries.is_empty());
+        assert_matches!(cold_storage.get_index_offset(&Pubkey::default()), Ok(None));
+    }
+
+    #[test]
+    fn test_account_matches_owners() {
+        let account_data_sizes = &[0, 1, 2, 3, 4, 5];
+        let cold_storage = do_test_write_and_read_accounts(
+            "test_account_matches_owners",
+            account_data_sizes,
+            AccountBlockFormat::Zstd,
+        );
+        let owners: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| Pubkey::from([u8::MAX - *size as u8; 32]))
+            .collect();
+
+        for i in 0..account_data_sizes.len() {
+            let index_offset = IndexOffset(i as u32);
+            let account_offset = cold_storage.get_account_offset(index_offset).unwrap();
+            let (stored_meta, _) = cold_storage.get_account(index_offset).unwrap().unwrap();
+
+            if stored_meta.lamports() == 0 {
+                assert_eq!(
+                    cold_storage.account_matches_owners(account_offset, &owners),
+                    Err(MatchAccountOwnerError::NoMatch)
+                );
+            } else {
+                let expected_position = owners
+                    .iter()
+                    .position(|owner| owner == stored_meta.owner())
+                    .unwrap();
+                assert_eq!(
+                    cold_storage.account_matches_owners(account_offset, &owners),
+                    Ok(expected_position)
+                );
+                assert_eq!(
+                    cold_storage.account_matches_owners(account_offset, &owners[..0]),
+                    Err(MatchAccountOwnerError::NoMatch)
+                );
+            }
+        }
+    }
+}
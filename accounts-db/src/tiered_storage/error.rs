@@ -31,4 +31,9 @@ pub enum TieredStorageError {
 
     #[error("OffsetAlignmentError: offset {0} must be multiple of {1}")]
     OffsetAlignmentError(usize, usize),
+
+    #[error(
+        "AccountBlockOutOfBounds: account entry ending at {0} exceeds the account block size {1}"
+    )]
+    AccountBlockOutOfBounds(usize, usize),
 }
@@ -57,8 +57,7 @@ impl Default for TieredStorageMagicNumber {
 pub enum AccountMetaFormat {
     #[default]
     Hot = 0,
-    // Temporarily comment out to avoid unimplemented!() block
-    // Cold = 1,
+    Cold = 1,
 }
 
 #[repr(u16)]
@@ -77,6 +76,7 @@ pub enum AccountBlockFormat {
     #[default]
     AlignedRaw = 0,
     Lz4 = 1,
+    Zstd = 2,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -2,6 +2,7 @@ use {
     crate::{
         accounts_hash::AccountHash,
         tiered_storage::{
+            cold::ColdStorageReader,
             footer::{AccountMetaFormat, TieredStorageFooter},
             hot::HotStorageReader,
             meta::TieredAccountMeta,
@@ -87,6 +88,7 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount
 #[derive(Debug)]
 pub enum TieredStorageReader {
     Hot(HotStorageReader),
+    Cold(ColdStorageReader),
 }
 
 impl TieredStorageReader {
@@ -95,13 +97,15 @@ impl TieredStorageReader {
         let footer = TieredStorageFooter::new_from_path(&path)?;
         match footer.account_meta_format {
             AccountMetaFormat::Hot => Ok(Self::Hot(HotStorageReader::new_from_path(path)?)),
+            AccountMetaFormat::Cold => Ok(Self::Cold(ColdStorageReader::new_from_path(path)?)),
         }
     }
 
-    /// Returns the footer of the associated HotAccountsFile.
+    /// Returns the footer of the associated accounts file.
     pub fn footer(&self) -> &TieredStorageFooter {
         match self {
             Self::Hot(hot) => hot.footer(),
+            Self::Cold(cold) => cold.footer(),
         }
     }
 
@@ -109,6 +113,7 @@ impl TieredStorageReader {
     pub fn num_accounts(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.num_accounts(),
+            Self::Cold(cold) => cold.num_accounts(),
         }
     }
 }
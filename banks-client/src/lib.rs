@@ -13,8 +13,8 @@ use {
     borsh::BorshDeserialize,
     futures::{future::join_all, Future, FutureExt, TryFutureExt},
     solana_banks_interface::{
-        BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation,
+        BanksBundleResultWithMetadata, BanksRequest, BanksResponse,
+        BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation,
     },
     solana_program::{
         clock::Slot, fee_calculator::FeeCalculator, hash::Hash, program_pack::Pack, pubkey::Pubkey,
@@ -152,6 +152,16 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub fn process_bundle_with_metadata_and_context(
+        &mut self,
+        ctx: Context,
+        transactions: Vec<VersionedTransaction>,
+    ) -> impl Future<Output = Result<BanksBundleResultWithMetadata, BanksClientError>> + '_ {
+        self.inner
+            .process_bundle_with_metadata_and_context(ctx, transactions)
+            .map_err(Into::into)
+    }
+
     pub fn simulate_transaction_with_commitment_and_context(
         &mut self,
         ctx: Context,
@@ -254,6 +264,27 @@ impl BanksClient {
         self.process_transaction_with_metadata_and_context(ctx, transaction.into())
     }
 
+    /// Process a bundle atomically against the working bank: either every transaction is
+    /// committed or none are. Return the bundle result with per-transaction metadata.
+    pub fn process_bundle_with_metadata(
+        &mut self,
+        transactions: Vec<impl Into<VersionedTransaction>>,
+    ) -> impl Future<Output = Result<BanksBundleResultWithMetadata, BanksClientError>> + '_ {
+        let ctx = context::current();
+        let transactions = transactions.into_iter().map(Into::into).collect();
+        self.process_bundle_with_metadata_and_context(ctx, transactions)
+    }
+
+    /// Process a bundle atomically against the working bank and return the error of the
+    /// transaction that caused the bundle to be rejected, if any.
+    pub fn process_bundle(
+        &mut self,
+        transactions: Vec<impl Into<VersionedTransaction>>,
+    ) -> impl Future<Output = Result<(), BanksClientError>> + '_ {
+        self.process_bundle_with_metadata(transactions)
+            .map(|result| Ok(result?.result?))
+    }
+
     /// Send a transaction and return any preflight (sanitization or simulation) errors, or return
     /// after the transaction has been rejected or reached the given level of commitment.
     pub fn process_transaction_with_preflight_and_commitment(
@@ -613,6 +644,53 @@ mod tests {
         })
     }
 
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_banks_server_process_bundle() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = BankForks::new_rw_arc(bank);
+
+        let mint_pubkey = genesis.mint_keypair.pubkey();
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+
+        Runtime::new()?.block_on(async {
+            let client_transport =
+                start_local_server(bank_forks, block_commitment_cache, Duration::from_millis(1))
+                    .await;
+            let mut banks_client = start_client(client_transport).await?;
+
+            let recent_blockhash = banks_client.get_latest_blockhash().await?;
+            let transfer = |lamports| {
+                let instruction = system_instruction::transfer(&mint_pubkey, &bob_pubkey, lamports);
+                let message = Message::new(&[instruction], Some(&mint_pubkey));
+                Transaction::new(&[&genesis.mint_keypair], message, recent_blockhash)
+            };
+
+            // the second transfer can't be paid for, so neither transfer lands
+            let failing_transaction = transfer(100);
+            let result = banks_client
+                .process_bundle_with_metadata(vec![transfer(1), failing_transaction.clone()])
+                .await?;
+            assert!(result.result.is_err());
+            assert_eq!(
+                result.failed_transaction,
+                Some(failing_transaction.signatures[0])
+            );
+            assert_eq!(banks_client.get_balance(bob_pubkey).await?, 0);
+
+            banks_client
+                .process_bundle(vec![transfer(1), transfer(2)])
+                .await?;
+            assert_eq!(banks_client.get_balance(bob_pubkey).await?, 3);
+            Ok(())
+        })
+    }
+
     #[test]
     #[allow(clippy::result_large_err)]
     fn test_banks_server_transfer_via_client() -> Result<(), BanksClientError> {
@@ -61,6 +61,17 @@ pub struct BanksTransactionResultWithMetadata {
     pub metadata: Option<TransactionMetadata>,
 }
 
+/// Result of executing a bundle. Bundles are all-or-nothing: when `result` is an error, none of
+/// the transactions were committed and `failed_transaction` names the one that caused the failure
+/// (if the failure can be attributed to a single transaction).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanksBundleResultWithMetadata {
+    pub result: transaction::Result<()>,
+    pub failed_transaction: Option<Signature>,
+    /// Metadata for each executed transaction, in bundle order
+    pub metadata: Vec<TransactionMetadata>,
+}
+
 #[tarpc::service]
 pub trait Banks {
     async fn send_transaction_with_context(transaction: VersionedTransaction);
@@ -86,6 +97,9 @@ pub trait Banks {
     async fn process_transaction_with_metadata_and_context(
         transaction: VersionedTransaction,
     ) -> BanksTransactionResultWithMetadata;
+    async fn process_bundle_with_metadata_and_context(
+        transactions: Vec<VersionedTransaction>,
+    ) -> BanksBundleResultWithMetadata;
     async fn simulate_transaction_with_commitment_and_context(
         transaction: VersionedTransaction,
         commitment: CommitmentLevel,
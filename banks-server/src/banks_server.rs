@@ -4,20 +4,24 @@ use {
     futures::{future, prelude::stream::StreamExt},
     solana_accounts_db::transaction_results::TransactionExecutionResult,
     solana_banks_interface::{
-        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation, TransactionConfirmationStatus, TransactionMetadata,
-        TransactionSimulationDetails, TransactionStatus,
+        Banks, BanksBundleResultWithMetadata, BanksRequest, BanksResponse,
+        BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation,
+        TransactionConfirmationStatus, TransactionMetadata, TransactionSimulationDetails,
+        TransactionStatus,
     },
+    solana_bundle::bundle_execution::{load_and_execute_bundle, LoadAndExecuteBundleError},
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
+    solana_program_runtime::timings::ExecuteTimings,
     solana_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, CommitTransactionCounts, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
     },
     solana_sdk::{
         account::Account,
-        clock::Slot,
+        bundle::{derive_bundle_id, SanitizedBundle},
+        clock::{Slot, MAX_PROCESSING_AGE},
         commitment_config::CommitmentLevel,
         feature_set::FeatureSet,
         fee_calculator::FeeCalculator,
@@ -25,13 +29,16 @@ use {
         message::{Message, SanitizedMessage},
         pubkey::Pubkey,
         signature::Signature,
-        transaction::{self, MessageHash, SanitizedTransaction, VersionedTransaction},
+        transaction::{
+            self, MessageHash, SanitizedTransaction, TransactionError, VersionedTransaction,
+        },
     },
     solana_send_transaction_service::{
         send_transaction_service::{SendTransactionService, TransactionInfo},
         tpu_info::NullTpuInfo,
     },
     std::{
+        collections::HashSet,
         convert::TryFrom,
         io,
         net::{Ipv4Addr, SocketAddr},
@@ -210,6 +217,141 @@ fn simulate_transaction(
     }
 }
 
+fn bundle_failure(
+    err: TransactionError,
+    failed_transaction: Option<Signature>,
+    metadata: Vec<TransactionMetadata>,
+) -> BanksBundleResultWithMetadata {
+    BanksBundleResultWithMetadata {
+        result: Err(err),
+        failed_transaction,
+        metadata,
+    }
+}
+
+/// Execute the bundle against `bank` and commit it only if every transaction succeeded.
+/// The caller must hold the bank's freeze lock.
+fn process_bundle(
+    bank: &Bank,
+    transactions: Vec<VersionedTransaction>,
+) -> BanksBundleResultWithMetadata {
+    if transactions.is_empty() {
+        return bundle_failure(TransactionError::SanitizeFailure, None, vec![]);
+    }
+
+    let mut signatures = HashSet::with_capacity(transactions.len());
+    let mut sanitized_transactions = Vec::with_capacity(transactions.len());
+    let bundle_id = derive_bundle_id(&transactions);
+    for transaction in transactions {
+        let signature = transaction.signatures.first().cloned();
+        let sanitized_transaction = match SanitizedTransaction::try_create(
+            transaction,
+            MessageHash::Compute,
+            Some(false), // is_simple_vote_tx
+            bank,
+        ) {
+            Ok(tx) => tx,
+            Err(err) => return bundle_failure(err, signature, vec![]),
+        };
+        if let Err(err) = verify_transaction(&sanitized_transaction, &bank.feature_set) {
+            return bundle_failure(err, signature, vec![]);
+        }
+        // load_and_execute_bundle expects the bundle to be deduplicated
+        if !signatures.insert(*sanitized_transaction.signature()) {
+            return bundle_failure(TransactionError::AlreadyProcessed, signature, vec![]);
+        }
+        sanitized_transactions.push(sanitized_transaction);
+    }
+
+    let sanitized_bundle = SanitizedBundle {
+        transactions: sanitized_transactions,
+        bundle_id,
+    };
+    let default_accounts = vec![None; sanitized_bundle.transactions.len()];
+    let mut bundle_execution_output = load_and_execute_bundle(
+        bank,
+        &sanitized_bundle,
+        MAX_PROCESSING_AGE,
+        &Duration::MAX,
+        false,
+        true,
+        true,
+        false,
+        &None,
+        false,
+        None,
+        &default_accounts,
+        &default_accounts,
+    );
+    let result = bundle_execution_output.result().clone();
+    let bundle_transaction_results = bundle_execution_output.bundle_transaction_results_mut();
+
+    let metadata = bundle_transaction_results
+        .iter()
+        .flat_map(|output| output.execution_results())
+        .filter_map(|execution_result| execution_result.details())
+        .map(|details| TransactionMetadata {
+            log_messages: details.log_messages.clone().unwrap_or_default(),
+            compute_units_consumed: details.executed_units,
+            return_data: details.return_data.clone(),
+        })
+        .collect();
+
+    match result {
+        Ok(()) => {}
+        Err(LoadAndExecuteBundleError::LockError {
+            signature,
+            transaction_error,
+        }) => return bundle_failure(transaction_error, Some(signature), metadata),
+        Err(LoadAndExecuteBundleError::TransactionError {
+            signature,
+            execution_result,
+        }) => {
+            let err = execution_result
+                .flattened_result()
+                .expect_err("failed bundle transaction must have an error");
+            return bundle_failure(err, Some(signature), metadata);
+        }
+        Err(
+            LoadAndExecuteBundleError::ProcessingTimeExceeded(_)
+            | LoadAndExecuteBundleError::InvalidPreOrPostAccounts,
+        ) => unreachable!("bundle has no time limit and no pre or post accounts"),
+    }
+
+    let (last_blockhash, lamports_per_signature) = bank.last_blockhash_and_lamports_per_signature();
+    let mut timings = ExecuteTimings::default();
+    for bundle_output in bundle_transaction_results.iter_mut() {
+        let output = bundle_output.load_and_execute_transactions_output();
+        let counts = CommitTransactionCounts {
+            committed_transactions_count: output.executed_transactions_count as u64,
+            committed_non_vote_transactions_count: output.executed_non_vote_transactions_count
+                as u64,
+            committed_with_failure_result_count: output
+                .executed_transactions_count
+                .saturating_sub(output.executed_with_successful_result_count)
+                as u64,
+            signature_count: output.signature_count,
+        };
+        let transactions = bundle_output.transactions().to_vec();
+        let execution_results = bundle_output.execution_results().to_vec();
+        bank.commit_transactions(
+            &transactions,
+            bundle_output.loaded_transactions_mut(),
+            execution_results,
+            last_blockhash,
+            lamports_per_signature,
+            counts,
+            &mut timings,
+        );
+    }
+
+    BanksBundleResultWithMetadata {
+        result: Ok(()),
+        failed_transaction: None,
+        metadata,
+    }
+}
+
 #[tarpc::server]
 impl Banks for BanksServer {
     async fn send_transaction_with_context(self, _: Context, transaction: VersionedTransaction) {
@@ -386,6 +528,21 @@ impl Banks for BanksServer {
         }
     }
 
+    async fn process_bundle_with_metadata_and_context(
+        self,
+        _: Context,
+        transactions: Vec<VersionedTransaction>,
+    ) -> BanksBundleResultWithMetadata {
+        loop {
+            let bank = self.bank_forks.read().unwrap().working_bank();
+            // hold the freeze lock so the bank can't be frozen while the bundle is committed
+            let lock = bank.freeze_lock();
+            if *lock == Hash::default() {
+                return process_bundle(&bank, transactions);
+            }
+        }
+    }
+
     async fn get_account_with_commitment_and_context(
         self,
         _: Context,
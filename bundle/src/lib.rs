@@ -57,4 +57,7 @@ pub enum BundleExecutionError {
 
     #[error("Tip payment error {0}")]
     TipError(#[from] TipError),
+
+    #[error("Bundle denied by policy rule {0}")]
+    DeniedByPolicy(String),
 }
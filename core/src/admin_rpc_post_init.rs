@@ -1,10 +1,17 @@
 use {
     crate::{
+        bundle_stage::bundle_denylist::BundleDenylist,
         cluster_slots_service::cluster_slots::ClusterSlots,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_stage::{BlockEngineConfig, BlockEngineStatus},
+            fetch_stage_manager::FetchStageModeOverride,
+            relayer_stage::RelayerConfig,
+            ProxyConnectionStatus,
+        },
         repair::{outstanding_requests::OutstandingRequests, serve_repair::ShredRepairType},
     },
     solana_gossip::cluster_info::ClusterInfo,
+    solana_ledger::blockstore::Blockstore,
     solana_runtime::bank_forks::BankForks,
     solana_sdk::{pubkey::Pubkey, quic::NotifyKeyUpdate},
     std::{
@@ -25,6 +32,11 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub outstanding_repair_requests: Arc<RwLock<OutstandingRequests<ShredRepairType>>>,
     pub cluster_slots: Arc<ClusterSlots>,
     pub block_engine_config: Arc<Mutex<BlockEngineConfig>>,
+    pub block_engine_status: Arc<RwLock<BlockEngineStatus>>,
     pub relayer_config: Arc<Mutex<RelayerConfig>>,
+    pub relayer_status: Arc<RwLock<ProxyConnectionStatus>>,
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
+    pub fetch_stage_mode_override: Arc<RwLock<FetchStageModeOverride>>,
+    pub bundle_denylist: Arc<RwLock<BundleDenylist>>,
+    pub blockstore: Arc<Blockstore>,
 }
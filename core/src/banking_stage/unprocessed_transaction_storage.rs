@@ -1292,6 +1292,13 @@ impl BundleStorage {
                         // lock errors are irrecoverable due to malformed transactions
                         debug!("bundle={} lock error", sanitized_bundle.bundle_id);
                     }
+                    Err(BundleExecutionError::DeniedByPolicy(rule)) => {
+                        // the operator doesn't want this bundle landing, drop it
+                        debug!(
+                            "bundle={} denied by policy rule {rule}",
+                            sanitized_bundle.bundle_id
+                        );
+                    }
                 },
             );
 
@@ -9,6 +9,7 @@ use {
         },
         bundle_stage::{
             bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
+            bundle_denylist::BundleDenylist, bundle_ordering_policy::BundleOrderingMethod,
             bundle_packet_receiver::BundleReceiver,
             bundle_reserved_space_manager::BundleReservedSpaceManager,
             bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
@@ -19,8 +20,9 @@ use {
     },
     crossbeam_channel::{Receiver, RecvTimeoutError},
     solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
+    solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifierArc,
     solana_gossip::cluster_info::ClusterInfo,
-    solana_ledger::blockstore_processor::TransactionStatusSender,
+    solana_ledger::{blockstore::Blockstore, blockstore_processor::TransactionStatusSender},
     solana_measure::measure,
     solana_poh::poh_recorder::PohRecorder,
     solana_runtime::{bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache},
@@ -39,12 +41,15 @@ use {
 
 pub mod bundle_account_locker;
 mod bundle_consumer;
+pub mod bundle_denylist;
+pub mod bundle_ordering_policy;
 mod bundle_packet_deserializer;
 mod bundle_packet_receiver;
 mod bundle_reserved_space_manager;
 pub(crate) mod bundle_stage_leader_metrics;
 mod committer;
 
+pub const DEFAULT_BUNDLE_EXECUTION_THREADS: usize = 1;
 const MAX_BUNDLE_RETRY_DURATION: Duration = Duration::from_millis(10);
 const SLOT_BOUNDARY_CHECK_PERIOD: Duration = Duration::from_millis(10);
 
@@ -211,6 +216,11 @@ impl BundleStage {
         preallocated_bundle_cost: u64,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        blockstore: Arc<Blockstore>,
+        bundle_ordering_method: BundleOrderingMethod,
+        num_bundle_execution_threads: usize,
+        bundle_denylist: Arc<RwLock<BundleDenylist>>,
+        bundle_notifier: Option<BundleNotifierArc>,
     ) -> Self {
         Self::start_bundle_thread(
             cluster_info,
@@ -227,6 +237,11 @@ impl BundleStage {
             preallocated_bundle_cost,
             bank_forks,
             prioritization_fee_cache,
+            blockstore,
+            bundle_ordering_method,
+            num_bundle_execution_threads,
+            bundle_denylist,
+            bundle_notifier,
         )
     }
 
@@ -250,6 +265,11 @@ impl BundleStage {
         preallocated_bundle_cost: u64,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        blockstore: Arc<Blockstore>,
+        bundle_ordering_method: BundleOrderingMethod,
+        num_bundle_execution_threads: usize,
+        bundle_denylist: Arc<RwLock<BundleDenylist>>,
+        bundle_notifier: Option<BundleNotifierArc>,
     ) -> Self {
         const BUNDLE_STAGE_ID: u32 = 10_000;
         let poh_recorder = poh_recorder.clone();
@@ -258,10 +278,15 @@ impl BundleStage {
         let mut bundle_receiver =
             BundleReceiver::new(BUNDLE_STAGE_ID, bundle_receiver, bank_forks, Some(5));
 
+        let bundle_ordering_policy =
+            bundle_ordering_method.new_policy(tip_manager.get_tip_accounts());
         let committer = Committer::new(
             transaction_status_sender,
             replay_vote_sender,
             prioritization_fee_cache.clone(),
+            Some(blockstore),
+            tip_manager.get_tip_accounts(),
+            bundle_notifier,
         );
         let decision_maker = DecisionMaker::new(cluster_info.id(), poh_recorder.clone());
 
@@ -296,6 +321,9 @@ impl BundleStage {
             max_bundle_retry_duration,
             cluster_info,
             reserved_space,
+            bundle_ordering_policy,
+            num_bundle_execution_threads,
+            bundle_denylist,
         );
 
         let bundle_thread = Builder::new()
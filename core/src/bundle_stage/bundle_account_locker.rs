@@ -14,6 +14,7 @@ use {
     solana_sdk::{bundle::SanitizedBundle, pubkey::Pubkey, transaction::TransactionAccountLocks},
     std::{
         collections::{hash_map::Entry, HashMap, HashSet},
+        ops::Range,
         sync::{Arc, Mutex, MutexGuard},
     },
     thiserror::Error,
@@ -173,6 +174,57 @@ impl BundleAccountLocker {
         Ok(())
     }
 
+    /// Splits `bundles` into consecutive batches of at most `max_batch_size` bundles where no
+    /// bundle write-locks an account that another bundle in the same batch locks. Bundles within
+    /// a batch can be executed in parallel, but batches must be processed in the order returned
+    /// to preserve the order the bundles were given in.
+    pub fn schedule_non_conflicting_batches(
+        bundles: &[&SanitizedBundle],
+        bank: &Bank,
+        max_batch_size: usize,
+    ) -> Vec<Range<usize>> {
+        let max_batch_size = max_batch_size.max(1);
+        let mut batches = Vec::new();
+        let mut batch_start = 0;
+        let mut batch_read_locks = HashSet::new();
+        let mut batch_write_locks = HashSet::new();
+        // bundles whose locks can't be determined are put in a batch by themselves
+        let mut batch_is_exclusive = false;
+
+        for (index, bundle) in bundles.iter().enumerate() {
+            let locks = Self::get_read_write_locks(bundle, bank).ok();
+            let conflicts = batch_is_exclusive
+                || match &locks {
+                    Some((read_locks, write_locks)) => {
+                        write_locks.keys().any(|account| {
+                            batch_write_locks.contains(account)
+                                || batch_read_locks.contains(account)
+                        }) || read_locks
+                            .keys()
+                            .any(|account| batch_write_locks.contains(account))
+                    }
+                    None => true,
+                };
+            if index > batch_start && (conflicts || index - batch_start >= max_batch_size) {
+                batches.push(batch_start..index);
+                batch_start = index;
+                batch_read_locks.clear();
+                batch_write_locks.clear();
+            }
+
+            batch_is_exclusive = locks.is_none();
+            if let Some((read_locks, write_locks)) = locks {
+                batch_read_locks.extend(read_locks.into_keys());
+                batch_write_locks.extend(write_locks.into_keys());
+            }
+        }
+        if batch_start < bundles.len() {
+            batches.push(batch_start..bundles.len());
+        }
+
+        batches
+    }
+
     /// Returns the read and write locks for this bundle
     /// Each lock type contains a HashMap which maps Pubkey to number of locks held
     fn get_read_write_locks(
@@ -323,4 +375,51 @@ mod tests {
         assert!(bundle_account_locker.write_locks().is_empty());
         assert!(bundle_account_locker.read_locks().is_empty());
     }
+
+    #[test]
+    fn test_schedule_non_conflicting_batches() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(2);
+        let (bank, _) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+
+        let payers: Vec<_> = (0..4).map(|_| Keypair::new()).collect();
+        let recipient = Keypair::new();
+        let mut transaction_errors = TransactionErrorMetrics::default();
+        let mut sanitized_bundle = |from: &Keypair, to: &Keypair| {
+            let tx =
+                VersionedTransaction::from(transfer(from, &to.pubkey(), 1, genesis_config.hash()));
+            let mut packet_bundle = PacketBundle {
+                batch: PacketBatch::new(vec![Packet::from_data(None, &tx).unwrap()]),
+                bundle_id: tx.signatures[0].to_string(),
+            };
+            ImmutableDeserializedBundle::new(&mut packet_bundle, None)
+                .unwrap()
+                .build_sanitized_bundle(&bank, &HashSet::default(), &mut transaction_errors)
+                .unwrap()
+        };
+
+        // bundles 0 and 1 are disjoint, bundle 2 writes to the account bundle 1 writes to,
+        // bundle 3 is disjoint from bundle 2 and bundle 4 spends from the account bundle 3 funds
+        let bundles = vec![
+            sanitized_bundle(&payers[0], &payers[1]),
+            sanitized_bundle(&payers[2], &recipient),
+            sanitized_bundle(&payers[3], &recipient),
+            sanitized_bundle(&mint_keypair, &payers[0]),
+            sanitized_bundle(&payers[0], &payers[2]),
+        ];
+        let bundles: Vec<_> = bundles.iter().collect();
+
+        assert_eq!(
+            BundleAccountLocker::schedule_non_conflicting_batches(&bundles, &bank, 4),
+            vec![0..2, 2..4, 4..5]
+        );
+        assert_eq!(
+            BundleAccountLocker::schedule_non_conflicting_batches(&bundles, &bank, 1),
+            vec![0..1, 1..2, 2..3, 3..4, 4..5]
+        );
+        assert!(BundleAccountLocker::schedule_non_conflicting_batches(&[], &bank, 4).is_empty());
+    }
 }
@@ -7,6 +7,8 @@ use {
         },
         bundle_stage::{
             bundle_account_locker::{BundleAccountLocker, LockedBundle},
+            bundle_denylist::BundleDenylist,
+            bundle_ordering_policy::BundleOrderingPolicy,
             bundle_reserved_space_manager::BundleReservedSpaceManager,
             bundle_stage_leader_metrics::BundleStageLeaderMetrics,
             committer::Committer,
@@ -16,9 +18,12 @@ use {
         proxy::block_engine_stage::BlockBuilderFeeInfo,
         tip_manager::TipManager,
     },
+    rayon::{prelude::*, ThreadPool, ThreadPoolBuilder},
     solana_accounts_db::transaction_error_metrics::TransactionErrorMetrics,
     solana_bundle::{
-        bundle_execution::{load_and_execute_bundle, BundleExecutionMetrics},
+        bundle_execution::{
+            load_and_execute_bundle, BundleExecutionMetrics, LoadAndExecuteBundleOutput,
+        },
         BundleExecutionError, BundleExecutionResult, TipError,
     },
     solana_cost_model::transaction_cost::TransactionCost,
@@ -35,7 +40,7 @@ use {
     },
     std::{
         collections::HashSet,
-        sync::{Arc, Mutex},
+        sync::{Arc, Mutex, RwLock},
         time::{Duration, Instant},
     },
 };
@@ -48,6 +53,14 @@ pub struct ExecuteRecordCommitResult {
     transaction_error_counter: TransactionErrorMetrics,
 }
 
+/// A bundle that has been executed but not yet recorded or committed
+struct ExecutedBundle<'a> {
+    bundle_execution_results: LoadAndExecuteBundleOutput<'a>,
+    execution_metrics: BundleExecutionMetrics,
+    execute_and_commit_timings: LeaderExecuteAndCommitTimings,
+    transaction_error_counter: TransactionErrorMetrics,
+}
+
 pub struct BundleConsumer {
     committer: Committer,
     transaction_recorder: TransactionRecorder,
@@ -61,6 +74,10 @@ pub struct BundleConsumer {
 
     blacklisted_accounts: HashSet<Pubkey>,
 
+    // Operator supplied programs and accounts that bundles aren't allowed to reference, reloadable
+    // through the admin rpc
+    bundle_denylist: Arc<RwLock<BundleDenylist>>,
+
     // Manages account locks across multiple transactions within a bundle to prevent race conditions
     // with BankingStage
     bundle_account_locker: BundleAccountLocker,
@@ -72,6 +89,11 @@ pub struct BundleConsumer {
     cluster_info: Arc<ClusterInfo>,
 
     reserved_space: BundleReservedSpaceManager,
+
+    bundle_ordering_policy: Box<dyn BundleOrderingPolicy>,
+
+    // Executes bundles that don't lock any of the same accounts in parallel
+    thread_pool: ThreadPool,
 }
 
 impl BundleConsumer {
@@ -87,6 +109,9 @@ impl BundleConsumer {
         max_bundle_retry_duration: Duration,
         cluster_info: Arc<ClusterInfo>,
         reserved_space: BundleReservedSpaceManager,
+        bundle_ordering_policy: Box<dyn BundleOrderingPolicy>,
+        num_execution_threads: usize,
+        bundle_denylist: Arc<RwLock<BundleDenylist>>,
     ) -> Self {
         Self {
             committer,
@@ -98,11 +123,18 @@ impl BundleConsumer {
             // MAX because sending tips during slot 0 in tests doesn't work
             last_tip_update_slot: u64::MAX,
             blacklisted_accounts: HashSet::default(),
+            bundle_denylist,
             bundle_account_locker,
             block_builder_fee_info,
             max_bundle_retry_duration,
             cluster_info,
             reserved_space,
+            bundle_ordering_policy,
+            thread_pool: ThreadPoolBuilder::new()
+                .num_threads(num_execution_threads.max(1))
+                .thread_name(|i| format!("solBundleExec{i:02}"))
+                .build()
+                .unwrap(),
         }
     }
 
@@ -123,6 +155,7 @@ impl BundleConsumer {
     // payment program and set the tip receiver to themself.
     // A bundle is not allowed to touch consensus-related accounts
     //  - This is to avoid stalling the voting BankingStage threads.
+    // A bundle is not allowed to touch programs or accounts on the operator's bundle denylist.
     pub fn consume_buffered_bundles(
         &mut self,
         bank_start: &BankStart,
@@ -131,13 +164,64 @@ impl BundleConsumer {
     ) {
         self.maybe_update_blacklist(bank_start);
         self.reserved_space.tick(&bank_start.working_bank);
+        // snapshot the denylist so a reload doesn't block on bundle execution
+        let bundle_denylist = self.bundle_denylist.read().unwrap().clone();
 
         let reached_end_of_slot = unprocessed_transaction_storage.process_bundles(
             bank_start.working_bank.clone(),
             bundle_stage_leader_metrics,
             &self.blacklisted_accounts,
             |bundles, bundle_stage_leader_metrics| {
-                Self::do_process_bundles(
+                // results are returned in the order the bundles were received in
+                let mut results: Vec<_> = bundles
+                    .iter()
+                    .map(|(_, sanitized_bundle)| {
+                        bundle_denylist
+                            .denied_by(sanitized_bundle)
+                            .map(|rule| Err(BundleExecutionError::DeniedByPolicy(rule.to_string())))
+                    })
+                    .collect();
+                results.iter().flatten().for_each(|result| {
+                    bundle_stage_leader_metrics
+                        .bundle_stage_metrics_tracker()
+                        .increment_bundle_execution_result(result);
+                });
+                let allowed_indexes: Vec<_> = results
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, result)| result.is_none())
+                    .map(|(index, _)| index)
+                    .collect();
+
+                let (execution_order, ordering_us) =
+                    measure_us!(self.bundle_ordering_policy.execution_order(
+                        &bank_start.working_bank,
+                        &allowed_indexes
+                            .iter()
+                            .map(|index| &bundles[*index].1)
+                            .collect::<Vec<_>>(),
+                    ));
+                let num_bundles_reordered = execution_order
+                    .iter()
+                    .enumerate()
+                    .filter(|(position, index)| position != *index)
+                    .count();
+                let execution_order: Vec<_> = execution_order
+                    .into_iter()
+                    .map(|index| allowed_indexes[index])
+                    .collect();
+                bundle_stage_leader_metrics
+                    .bundle_stage_metrics_tracker()
+                    .increment_ordering_elapsed_us(ordering_us);
+                bundle_stage_leader_metrics
+                    .bundle_stage_metrics_tracker()
+                    .increment_num_bundles_reordered(num_bundles_reordered as u64);
+
+                let ordered_bundles: Vec<_> = execution_order
+                    .iter()
+                    .map(|index| &bundles[*index])
+                    .collect();
+                let ordered_results = Self::do_process_bundles(
                     &self.bundle_account_locker,
                     &self.tip_manager,
                     &mut self.last_tip_update_slot,
@@ -149,10 +233,16 @@ impl BundleConsumer {
                     &self.log_messages_bytes_limit,
                     self.max_bundle_retry_duration,
                     &self.reserved_space,
-                    bundles,
+                    &self.thread_pool,
+                    &ordered_bundles,
                     bank_start,
                     bundle_stage_leader_metrics,
-                )
+                );
+
+                for (index, result) in execution_order.into_iter().zip(ordered_results) {
+                    results[index] = Some(result);
+                }
+                results.into_iter().map(Option::unwrap).collect()
             },
         );
 
@@ -200,7 +290,8 @@ impl BundleConsumer {
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
         reserved_space: &BundleReservedSpaceManager,
-        bundles: &[(ImmutableDeserializedBundle, SanitizedBundle)],
+        thread_pool: &ThreadPool,
+        bundles: &[&(ImmutableDeserializedBundle, SanitizedBundle)],
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
     ) -> Vec<Result<(), BundleExecutionError>> {
@@ -223,36 +314,93 @@ impl BundleConsumer {
             .bundle_stage_metrics_tracker()
             .increment_locked_bundle_elapsed_us(locked_bundles_elapsed.as_us());
 
-        let (execution_results, execute_locked_bundles_elapsed) = measure!(locked_bundle_results
-            .into_iter()
-            .map(|r| match r {
-                Ok(locked_bundle) => {
-                    let (r, measure) = measure_us!(Self::process_bundle(
-                        bundle_account_locker,
-                        tip_manager,
-                        last_tip_updated_slot,
-                        cluster_info,
-                        block_builder_fee_info,
+        let (execution_results, execute_locked_bundles_elapsed) = measure!({
+            let mut locked_bundles = Vec::with_capacity(locked_bundle_results.len());
+            let mut is_locked = Vec::with_capacity(locked_bundle_results.len());
+            for locked_bundle_result in locked_bundle_results {
+                is_locked.push(locked_bundle_result.is_ok());
+                locked_bundles.extend(locked_bundle_result.ok());
+            }
+
+            let batches = BundleAccountLocker::schedule_non_conflicting_batches(
+                &locked_bundles
+                    .iter()
+                    .map(|locked_bundle| locked_bundle.sanitized_bundle())
+                    .collect::<Vec<_>>(),
+                &bank_start.working_bank,
+                thread_pool.current_num_threads(),
+            );
+
+            let mut locked_bundle_execution_results = Vec::with_capacity(locked_bundles.len());
+            let mut locked_bundles = locked_bundles.into_iter();
+            for batch in batches {
+                // locks are released as soon as the batch is done
+                let batch: Vec<_> = locked_bundles.by_ref().take(batch.len()).collect();
+                let tip_accounts = tip_manager.get_tip_accounts();
+                let needs_tip_programs_update = bank_start.working_bank.slot()
+                    != *last_tip_updated_slot
+                    && batch.iter().any(|locked_bundle| {
+                        Self::bundle_touches_tip_pdas(
+                            locked_bundle.sanitized_bundle(),
+                            &tip_accounts,
+                        )
+                    });
+
+                if batch.len() == 1 || needs_tip_programs_update {
+                    for locked_bundle in &batch {
+                        let (r, measure) = measure_us!(Self::process_bundle(
+                            bundle_account_locker,
+                            tip_manager,
+                            last_tip_updated_slot,
+                            cluster_info,
+                            block_builder_fee_info,
+                            committer,
+                            recorder,
+                            qos_service,
+                            log_messages_bytes_limit,
+                            max_bundle_retry_duration,
+                            reserved_space,
+                            locked_bundle,
+                            bank_start,
+                            bundle_stage_leader_metrics,
+                        ));
+                        bundle_stage_leader_metrics
+                            .leader_slot_metrics_tracker()
+                            .increment_process_packets_transactions_us(measure);
+                        locked_bundle_execution_results.push(r);
+                    }
+                } else {
+                    let (r, measure) = measure_us!(Self::process_bundle_batch(
+                        thread_pool,
                         committer,
                         recorder,
                         qos_service,
                         log_messages_bytes_limit,
                         max_bundle_retry_duration,
                         reserved_space,
-                        &locked_bundle,
+                        &batch,
                         bank_start,
                         bundle_stage_leader_metrics,
                     ));
                     bundle_stage_leader_metrics
                         .leader_slot_metrics_tracker()
                         .increment_process_packets_transactions_us(measure);
-                    r
+                    locked_bundle_execution_results.extend(r);
                 }
-                Err(_) => {
-                    Err(BundleExecutionError::LockError)
-                }
-            })
-            .collect::<Vec<_>>());
+            }
+
+            let mut locked_bundle_execution_results = locked_bundle_execution_results.into_iter();
+            is_locked
+                .into_iter()
+                .map(|is_locked| {
+                    if is_locked {
+                        locked_bundle_execution_results.next().unwrap()
+                    } else {
+                        Err(BundleExecutionError::LockError)
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
 
         bundle_stage_leader_metrics
             .bundle_stage_metrics_tracker()
@@ -533,6 +681,119 @@ impl BundleConsumer {
             bank_start,
         ));
 
+        Self::update_qos_and_metrics(
+            qos_service,
+            transaction_qos_cost_results,
+            cost_model_elapsed_us,
+            result,
+            process_transactions_us,
+            sanitized_bundle,
+            bank_start,
+            bundle_stage_leader_metrics,
+        )
+    }
+
+    /// Executes a batch of bundles that don't lock any of the same accounts in parallel, then
+    /// records and commits them one at a time in the order they were scheduled in.
+    #[allow(clippy::too_many_arguments)]
+    fn process_bundle_batch(
+        thread_pool: &ThreadPool,
+        committer: &Committer,
+        recorder: &TransactionRecorder,
+        qos_service: &QosService,
+        log_messages_bytes_limit: &Option<usize>,
+        max_bundle_retry_duration: Duration,
+        reserved_space: &BundleReservedSpaceManager,
+        locked_bundles: &[LockedBundle],
+        bank_start: &BankStart,
+        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
+    ) -> Vec<Result<(), BundleExecutionError>> {
+        // blockspace is reserved in order so the bundles that fit don't depend on how execution
+        // is scheduled across threads
+        let reservations: Vec<_> = locked_bundles
+            .iter()
+            .map(|locked_bundle| {
+                if !Bank::should_bank_still_be_processing_txs(
+                    &bank_start.bank_creation_time,
+                    bank_start.working_bank.ns_per_slot,
+                ) {
+                    return Err(BundleExecutionError::BankProcessingTimeLimitReached);
+                }
+                let ((transaction_qos_cost_results, _), cost_model_elapsed_us) =
+                    measure_us!(Self::reserve_bundle_blockspace(
+                        qos_service,
+                        reserved_space,
+                        locked_bundle.sanitized_bundle(),
+                        &bank_start.working_bank
+                    )?);
+                Ok((transaction_qos_cost_results, cost_model_elapsed_us))
+            })
+            .collect();
+
+        let transaction_status_sender_enabled = committer.transaction_status_sender_enabled();
+        let executed_bundles: Vec<_> = thread_pool.install(|| {
+            locked_bundles
+                .par_iter()
+                .zip(reservations.into_par_iter())
+                .map(|(locked_bundle, reservation)| {
+                    let reservation = reservation?;
+                    let (executed_bundle, execute_us) = measure_us!(Self::execute_bundle(
+                        transaction_status_sender_enabled,
+                        log_messages_bytes_limit,
+                        max_bundle_retry_duration,
+                        locked_bundle.sanitized_bundle(),
+                        bank_start,
+                    ));
+                    Ok::<_, BundleExecutionError>((reservation, executed_bundle, execute_us))
+                })
+                .collect()
+        });
+
+        locked_bundles
+            .iter()
+            .zip(executed_bundles)
+            .map(|(locked_bundle, executed_bundle)| {
+                let (
+                    (transaction_qos_cost_results, cost_model_elapsed_us),
+                    executed_bundle,
+                    execute_us,
+                ) = executed_bundle?;
+                let sanitized_bundle = locked_bundle.sanitized_bundle();
+                let (result, record_commit_us) = measure_us!(Self::record_commit_bundle(
+                    committer,
+                    recorder,
+                    executed_bundle,
+                    sanitized_bundle,
+                    bank_start,
+                ));
+
+                Self::update_qos_and_metrics(
+                    qos_service,
+                    transaction_qos_cost_results,
+                    cost_model_elapsed_us,
+                    result,
+                    execute_us.saturating_add(record_commit_us),
+                    sanitized_bundle,
+                    bank_start,
+                    bundle_stage_leader_metrics,
+                )
+            })
+            .collect()
+    }
+
+    /// Accumulates the metrics for an executed bundle. Updates the reserved blockspace to the
+    /// actual cost if the bundle was committed, otherwise removes the reservation.
+    #[allow(clippy::too_many_arguments)]
+    fn update_qos_and_metrics(
+        qos_service: &QosService,
+        transaction_qos_cost_results: Vec<transaction::Result<TransactionCost>>,
+        cost_model_elapsed_us: u64,
+        result: ExecuteRecordCommitResult,
+        process_transactions_us: u64,
+        sanitized_bundle: &SanitizedBundle,
+        bank_start: &BankStart,
+        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
+    ) -> BundleExecutionResult<()> {
         bundle_stage_leader_metrics
             .bundle_stage_metrics_tracker()
             .increment_num_execution_retries(result.execution_metrics.num_retries);
@@ -616,13 +877,34 @@ impl BundleConsumer {
         sanitized_bundle: &SanitizedBundle,
         bank_start: &BankStart,
     ) -> ExecuteRecordCommitResult {
-        let transaction_status_sender_enabled = committer.transaction_status_sender_enabled();
+        let executed_bundle = Self::execute_bundle(
+            committer.transaction_status_sender_enabled(),
+            log_messages_bytes_limit,
+            max_bundle_retry_duration,
+            sanitized_bundle,
+            bank_start,
+        );
+        Self::record_commit_bundle(
+            committer,
+            recorder,
+            executed_bundle,
+            sanitized_bundle,
+            bank_start,
+        )
+    }
 
+    fn execute_bundle<'a>(
+        transaction_status_sender_enabled: bool,
+        log_messages_bytes_limit: &Option<usize>,
+        max_bundle_retry_duration: Duration,
+        sanitized_bundle: &'a SanitizedBundle,
+        bank_start: &BankStart,
+    ) -> ExecutedBundle<'a> {
         let mut execute_and_commit_timings = LeaderExecuteAndCommitTimings::default();
 
         debug!("bundle: {} executing", sanitized_bundle.bundle_id);
         let default_accounts = vec![None; sanitized_bundle.transactions.len()];
-        let mut bundle_execution_results = load_and_execute_bundle(
+        let bundle_execution_results = load_and_execute_bundle(
             &bank_start.working_bank,
             sanitized_bundle,
             MAX_PROCESSING_AGE,
@@ -661,6 +943,28 @@ impl BundleConsumer {
             bundle_execution_results.result().is_ok()
         );
 
+        ExecutedBundle {
+            bundle_execution_results,
+            execution_metrics,
+            execute_and_commit_timings,
+            transaction_error_counter,
+        }
+    }
+
+    fn record_commit_bundle(
+        committer: &Committer,
+        recorder: &TransactionRecorder,
+        executed_bundle: ExecutedBundle,
+        sanitized_bundle: &SanitizedBundle,
+        bank_start: &BankStart,
+    ) -> ExecuteRecordCommitResult {
+        let ExecutedBundle {
+            mut bundle_execution_results,
+            execution_metrics,
+            mut execute_and_commit_timings,
+            transaction_error_counter,
+        } = executed_bundle;
+
         // don't commit bundle if failure executing any part of the bundle
         if let Err(e) = bundle_execution_results.result() {
             return ExecuteRecordCommitResult {
@@ -728,6 +1032,7 @@ impl BundleConsumer {
 
         // note: execute_and_commit_timings.commit_us handled inside this function
         let (commit_us, commit_bundle_details) = committer.commit_bundle(
+            &sanitized_bundle.bundle_id,
             &mut bundle_execution_results,
             last_blockhash,
             lamports_per_signature,
@@ -783,6 +1088,7 @@ mod tests {
         crate::{
             bundle_stage::{
                 bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
+                bundle_denylist::BundleDenylist, bundle_ordering_policy::FifoOrderingPolicy,
                 bundle_packet_deserializer::BundlePacketDeserializer,
                 bundle_reserved_space_manager::BundleReservedSpaceManager,
                 bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
@@ -800,10 +1106,12 @@ mod tests {
             transaction_results::TransactionCheckResult,
         },
         solana_cost_model::{block_cost_limits::MAX_BLOCK_UNITS, cost_model::CostModel},
+        solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifier,
         solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
         solana_ledger::{
-            blockstore::Blockstore, genesis_utils::create_genesis_config,
-            get_tmp_ledger_path_auto_delete, leader_schedule_cache::LeaderScheduleCache,
+            blockstore::Blockstore, blockstore_meta::BundleStatusMeta,
+            genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete,
+            leader_schedule_cache::LeaderScheduleCache,
         },
         solana_perf::packet::PacketBatch,
         solana_poh::{
@@ -819,7 +1127,7 @@ mod tests {
         },
         solana_sdk::{
             bundle::{derive_bundle_id, SanitizedBundle},
-            clock::MAX_PROCESSING_AGE,
+            clock::{Slot, MAX_PROCESSING_AGE},
             fee_calculator::{FeeRateGovernor, DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE},
             genesis_config::ClusterType,
             hash::Hash,
@@ -828,9 +1136,9 @@ mod tests {
             poh_config::PohConfig,
             pubkey::Pubkey,
             rent::Rent,
-            signature::{Keypair, Signer},
+            signature::{Keypair, Signature, Signer},
             system_transaction::transfer,
-            transaction::{SanitizedTransaction, TransactionError, VersionedTransaction},
+            transaction::{self, SanitizedTransaction, TransactionError, VersionedTransaction},
             vote::state::VoteState,
         },
         solana_streamer::socket::SocketAddrSpace,
@@ -846,6 +1154,30 @@ mod tests {
         },
     };
 
+    #[derive(Default)]
+    struct TestBundleNotifier {
+        notifications: Mutex<Vec<(String, Slot, Vec<Signature>, Vec<transaction::Result<()>>)>>,
+    }
+
+    impl BundleNotifier for TestBundleNotifier {
+        fn notify_bundle(
+            &self,
+            bundle_id: &str,
+            slot: Slot,
+            signatures: &[Signature],
+            transaction_results: &[transaction::Result<()>],
+            tips: &[(Pubkey, u64)],
+        ) {
+            assert!(tips.is_empty());
+            self.notifications.lock().unwrap().push((
+                bundle_id.to_string(),
+                slot,
+                signatures.to_vec(),
+                transaction_results.to_vec(),
+            ));
+        }
+    }
+
     struct TestFixture {
         genesis_config_info: GenesisConfigInfo,
         leader_keypair: Keypair,
@@ -1045,15 +1377,22 @@ mod tests {
         let status = poh_recorder.read().unwrap().reached_leader_slot();
         info!("status: {:?}", status);
 
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+
+        let block_builder_pubkey = Pubkey::new_unique();
+        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
+
         let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let bundle_notifier = Arc::new(TestBundleNotifier::default());
         let committer = Committer::new(
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            Some(blockstore.clone()),
+            tip_manager.get_tip_accounts(),
+            Some(bundle_notifier.clone()),
         );
-
-        let block_builder_pubkey = Pubkey::new_unique();
-        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
         let block_builder_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
             block_builder: block_builder_pubkey,
             block_builder_commission: 10,
@@ -1085,6 +1424,9 @@ mod tests {
                     .saturating_mul(8)
                     .saturating_div(10),
             ),
+            Box::new(FifoOrderingPolicy),
+            1,
+            Arc::new(RwLock::new(BundleDenylist::default())),
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
@@ -1172,6 +1514,36 @@ mod tests {
 
         assert_eq!(check_results, expected_result);
 
+        assert_eq!(
+            blockstore
+                .read_bundle_statuses(&sanitized_bundle.bundle_id)
+                .unwrap(),
+            vec![(
+                bank.slot(),
+                BundleStatusMeta {
+                    signatures: sanitized_bundle
+                        .transactions
+                        .iter()
+                        .map(|tx| *tx.signature())
+                        .collect(),
+                    tip_lamports: 0,
+                }
+            )]
+        );
+        assert_eq!(
+            *bundle_notifier.notifications.lock().unwrap(),
+            vec![(
+                sanitized_bundle.bundle_id.clone(),
+                bank.slot(),
+                sanitized_bundle
+                    .transactions
+                    .iter()
+                    .map(|tx| *tx.signature())
+                    .collect(),
+                vec![Ok(()); sanitized_bundle.transactions.len()],
+            )]
+        );
+
         poh_recorder
             .write()
             .unwrap()
@@ -1182,6 +1554,181 @@ mod tests {
         // TODO (LB): cleanup blockstore
     }
 
+    /// Bundles that don't conflict are executed in parallel, while bundles that conflict with an
+    /// earlier one in the same batch wait for the next batch. Either way they're committed in the
+    /// order they were received in.
+    #[test]
+    fn test_bundle_parallel_execution_commit_order() {
+        solana_logger::setup();
+        let TestFixture {
+            genesis_config_info,
+            leader_keypair,
+            bank,
+            exit,
+            poh_recorder,
+            poh_simulator,
+            entry_receiver,
+        } = create_test_fixture(1_000_000);
+        let recorder = poh_recorder.read().unwrap().new_recorder();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+
+        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
+
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let committer = Committer::new(
+            None,
+            replay_vote_sender,
+            Arc::new(PrioritizationFeeCache::new(0u64)),
+            Some(blockstore),
+            tip_manager.get_tip_accounts(),
+            None,
+        );
+        let block_builder_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 10,
+        }));
+
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new(leader_keypair.pubkey(), 0, 0),
+            Arc::new(leader_keypair),
+            SocketAddrSpace::new(true),
+        ));
+
+        let mut consumer = BundleConsumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            tip_manager,
+            BundleAccountLocker::default(),
+            block_builder_info,
+            Duration::from_secs(10),
+            cluster_info,
+            BundleReservedSpaceManager::new(
+                MAX_BLOCK_UNITS,
+                3_000_000,
+                poh_recorder
+                    .read()
+                    .unwrap()
+                    .ticks_per_slot()
+                    .saturating_mul(8)
+                    .saturating_div(10),
+            ),
+            Box::new(FifoOrderingPolicy),
+            4,
+            Arc::new(RwLock::new(BundleDenylist::default())),
+        );
+
+        let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
+
+        let payer_balance = sol_to_lamports(10.0);
+        let payers: Vec<_> = (0..3).map(|_| Keypair::new()).collect();
+        for payer in &payers {
+            bank.transfer(
+                payer_balance,
+                &genesis_config_info.mint_keypair,
+                &payer.pubkey(),
+            )
+            .unwrap();
+        }
+
+        // the first two bundles run in parallel, the third conflicts with the first one so it
+        // starts the next batch, where it runs in parallel with the fourth
+        let recipients: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let transfers: Vec<_> = [(0, 1_000), (1, 2_000), (0, 3_000), (2, 4_000)]
+            .into_iter()
+            .zip(&recipients)
+            .map(|((payer_index, lamports), recipient)| {
+                (
+                    payer_index,
+                    lamports,
+                    VersionedTransaction::from(transfer(
+                        &payers[payer_index],
+                        recipient,
+                        lamports,
+                        bank.last_blockhash(),
+                    )),
+                )
+            })
+            .collect();
+
+        let mut bundle_storage = UnprocessedTransactionStorage::new_bundle_storage(
+            VecDeque::with_capacity(10),
+            VecDeque::with_capacity(10),
+        );
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
+
+        let deserialized_bundles: Vec<_> = transfers
+            .iter()
+            .map(|(_, _, tx)| {
+                let mut packet_bundle = PacketBundle {
+                    batch: PacketBatch::new(vec![Packet::from_data(None, tx).unwrap()]),
+                    bundle_id: derive_bundle_id(&[tx.clone()]),
+                };
+                BundlePacketDeserializer::deserialize_bundle(&mut packet_bundle, false, None)
+                    .unwrap()
+            })
+            .collect();
+        let summary = bundle_storage.insert_bundles(deserialized_bundles);
+        assert_eq!(summary.num_bundles_dropped, 0);
+        assert_eq!(summary.num_bundles_inserted, transfers.len());
+
+        consumer.consume_buffered_bundles(
+            &bank_start,
+            &mut bundle_storage,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        let mut transactions = Vec::new();
+        while let Ok(WorkingBankEntry {
+            bank: wbe_bank,
+            entries_ticks,
+        }) = entry_receiver.recv()
+        {
+            assert_eq!(bank.slot(), wbe_bank.slot());
+            for (entry, _) in entries_ticks {
+                transactions.extend(entry.transactions);
+            }
+            if transactions.len() == transfers.len() {
+                break;
+            }
+        }
+        assert_eq!(
+            transactions,
+            transfers
+                .iter()
+                .map(|(_, _, tx)| tx.clone())
+                .collect::<Vec<_>>()
+        );
+
+        for (recipient, (_, lamports, _)) in recipients.iter().zip(&transfers) {
+            assert_eq!(bank.get_balance(recipient), *lamports);
+        }
+        for (payer_index, payer) in payers.iter().enumerate() {
+            let spent: u64 = transfers
+                .iter()
+                .filter(|(index, _, _)| *index == payer_index)
+                .map(|(_, lamports, tx)| {
+                    let tx = SanitizedTransaction::from_transaction_for_tests(
+                        tx.clone().into_legacy_transaction().unwrap(),
+                    );
+                    lamports + bank.get_fee_for_message(tx.message()).unwrap()
+                })
+                .sum();
+            assert_eq!(bank.get_balance(&payer.pubkey()), payer_balance - spent);
+        }
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .is_exited
+            .store(true, Ordering::Relaxed);
+        exit.store(true, Ordering::Relaxed);
+        poh_simulator.join().unwrap();
+    }
+
     /// Happy-path bundle execution to ensure tip management works.
     /// Tip management involves cranking setup bundles before executing the test bundle
     #[test]
@@ -1203,6 +1750,9 @@ mod tests {
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
+            HashSet::default(),
+            None,
         );
 
         let block_builder_pubkey = Pubkey::new_unique();
@@ -1238,6 +1788,9 @@ mod tests {
                     .saturating_mul(8)
                     .saturating_div(10),
             ),
+            Box::new(FifoOrderingPolicy),
+            1,
+            Arc::new(RwLock::new(BundleDenylist::default())),
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
@@ -1387,6 +1940,9 @@ mod tests {
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
+            HashSet::default(),
+            None,
         );
 
         let block_builder_pubkey = Pubkey::new_unique();
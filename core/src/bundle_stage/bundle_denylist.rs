@@ -0,0 +1,283 @@
+//! Operator supplied denylist of programs and accounts that bundles aren't allowed to reference.
+//!
+//! The denylist is loaded from a JSON policy file and can be reloaded at runtime through the admin
+//! RPC. Each rule is named so rejections can be attributed to the rule that caused them:
+//! ```json
+//! {
+//!   "rules": [
+//!     {
+//!       "name": "sandwich-prone-pools",
+//!       "programs": [],
+//!       "accounts": ["58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"]
+//!     }
+//!   ]
+//! }
+//! ```
+//! Bundles are checked in addition to the consensus accounts and tip payment program that
+//! BundleStage always blacklists.
+use {
+    solana_sdk::{bundle::SanitizedBundle, pubkey::Pubkey},
+    std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::Arc,
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum BundleDenylistError {
+    #[error("failed to read bundle denylist policy file {0:?}: {1}")]
+    ReadPolicyFile(PathBuf, std::io::Error),
+
+    #[error("failed to parse bundle denylist policy file: {0}")]
+    ParsePolicyFile(#[from] serde_json::Error),
+
+    #[error("bundle denylist rule {rule} contains invalid pubkey {pubkey}")]
+    InvalidPubkey { rule: String, pubkey: String },
+
+    #[error("bundle denylist rule name {0} is used more than once")]
+    DuplicateRuleName(String),
+
+    #[error("no bundle denylist policy file is configured")]
+    NoPolicyFile,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default)]
+    rules: Vec<PolicyFileRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFileRule {
+    name: String,
+    #[serde(default)]
+    programs: Vec<String>,
+    #[serde(default)]
+    accounts: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BundleDenylistRule {
+    pub name: String,
+    /// Bundles containing a top-level instruction that invokes one of these programs are denied
+    pub programs: HashSet<Pubkey>,
+    /// Bundles containing a transaction that references one of these accounts are denied
+    pub accounts: HashSet<Pubkey>,
+}
+
+impl BundleDenylistRule {
+    fn from_policy_file_rule(rule: PolicyFileRule) -> Result<Self, BundleDenylistError> {
+        let parse_pubkeys = |pubkeys: Vec<String>| {
+            pubkeys
+                .into_iter()
+                .map(|pubkey| {
+                    Pubkey::from_str(&pubkey).map_err(|_| BundleDenylistError::InvalidPubkey {
+                        rule: rule.name.clone(),
+                        pubkey,
+                    })
+                })
+                .collect::<Result<HashSet<_>, _>>()
+        };
+        let programs = parse_pubkeys(rule.programs)?;
+        let accounts = parse_pubkeys(rule.accounts)?;
+        Ok(Self {
+            name: rule.name,
+            programs,
+            accounts,
+        })
+    }
+
+    fn denies(&self, bundle: &SanitizedBundle) -> bool {
+        bundle.transactions.iter().any(|tx| {
+            let message = tx.message();
+            message
+                .program_instructions_iter()
+                .any(|(program_id, _)| self.programs.contains(program_id))
+                || message
+                    .account_keys()
+                    .iter()
+                    .any(|account| self.accounts.contains(account))
+        })
+    }
+}
+
+/// Cheap to clone so BundleStage can take a snapshot of the rules without holding a lock while
+/// bundles execute.
+#[derive(Clone, Debug, Default)]
+pub struct BundleDenylist {
+    policy_file: Option<PathBuf>,
+    rules: Arc<Vec<BundleDenylistRule>>,
+}
+
+impl BundleDenylist {
+    pub fn load(policy_file: impl Into<PathBuf>) -> Result<Self, BundleDenylistError> {
+        let policy_file = policy_file.into();
+        let rules = Self::read_rules(&policy_file)?;
+        Ok(Self {
+            policy_file: Some(policy_file),
+            rules: Arc::new(rules),
+        })
+    }
+
+    /// Re-reads the policy file, switching to `policy_file` if one is provided, and returns the
+    /// new denylist. The caller swaps it in, so the current rules stay in use while the file is
+    /// read and are left in place if it can't be loaded.
+    pub fn reload(&self, policy_file: Option<PathBuf>) -> Result<Self, BundleDenylistError> {
+        let policy_file = policy_file
+            .or_else(|| self.policy_file.clone())
+            .ok_or(BundleDenylistError::NoPolicyFile)?;
+        Self::load(policy_file)
+    }
+
+    pub fn policy_file(&self) -> Option<&Path> {
+        self.policy_file.as_deref()
+    }
+
+    pub fn rules(&self) -> &[BundleDenylistRule] {
+        &self.rules
+    }
+
+    /// Returns the name of the first rule, in policy file order, that denies the bundle
+    pub fn denied_by(&self, bundle: &SanitizedBundle) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.denies(bundle))
+            .map(|rule| rule.name.as_str())
+    }
+
+    fn read_rules(policy_file: &Path) -> Result<Vec<BundleDenylistRule>, BundleDenylistError> {
+        let contents = fs::read_to_string(policy_file)
+            .map_err(|e| BundleDenylistError::ReadPolicyFile(policy_file.to_path_buf(), e))?;
+        let policy: PolicyFile = serde_json::from_str(&contents)?;
+
+        let mut rule_names = HashSet::new();
+        policy
+            .rules
+            .into_iter()
+            .map(|rule| {
+                if !rule_names.insert(rule.name.clone()) {
+                    return Err(BundleDenylistError::DuplicateRuleName(rule.name));
+                }
+                BundleDenylistRule::from_policy_file_rule(rule)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, system_program, system_transaction,
+            transaction::SanitizedTransaction,
+        },
+        std::io::Write,
+        tempfile::NamedTempFile,
+    };
+
+    fn write_policy_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn transfer_bundle(to: &Pubkey) -> SanitizedBundle {
+        let payer = Keypair::new();
+        SanitizedBundle {
+            transactions: vec![SanitizedTransaction::from_transaction_for_tests(
+                system_transaction::transfer(&payer, to, 1, Hash::default()),
+            )],
+            bundle_id: String::default(),
+        }
+    }
+
+    #[test]
+    fn test_denied_by() {
+        let pool = Pubkey::new_unique();
+        let policy_file = write_policy_file(&format!(
+            r#"{{"rules": [
+                {{"name": "pools", "accounts": ["{pool}"]}},
+                {{"name": "system", "programs": ["{}"]}}
+            ]}}"#,
+            system_program::id()
+        ));
+        let denylist = BundleDenylist::load(policy_file.path()).unwrap();
+        assert_eq!(denylist.rules().len(), 2);
+        assert_eq!(denylist.policy_file(), Some(policy_file.path()));
+
+        // rules are checked in policy file order
+        assert_eq!(denylist.denied_by(&transfer_bundle(&pool)), Some("pools"));
+        assert_eq!(
+            denylist.denied_by(&transfer_bundle(&Pubkey::new_unique())),
+            Some("system")
+        );
+
+        assert_eq!(
+            BundleDenylist::default().denied_by(&transfer_bundle(&pool)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let policy_file = write_policy_file(r#"{"rules": [{"name": "bad", "accounts": ["foo"]}]}"#);
+        assert!(matches!(
+            BundleDenylist::load(policy_file.path()),
+            Err(BundleDenylistError::InvalidPubkey { rule, pubkey }) if rule == "bad" && pubkey == "foo"
+        ));
+
+        let policy_file = write_policy_file(r#"{"rules": [{"name": "a"}, {"name": "a"}]}"#);
+        assert!(matches!(
+            BundleDenylist::load(policy_file.path()),
+            Err(BundleDenylistError::DuplicateRuleName(name)) if name == "a"
+        ));
+
+        let policy_file = write_policy_file(r#"{"rules": [{"name": "a", "pools": []}]}"#);
+        assert!(matches!(
+            BundleDenylist::load(policy_file.path()),
+            Err(BundleDenylistError::ParsePolicyFile(_))
+        ));
+
+        assert!(matches!(
+            BundleDenylist::load("/does/not/exist.json"),
+            Err(BundleDenylistError::ReadPolicyFile(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_reload() {
+        assert!(matches!(
+            BundleDenylist::default().reload(None),
+            Err(BundleDenylistError::NoPolicyFile)
+        ));
+
+        let account = Pubkey::new_unique();
+        let mut policy_file = write_policy_file(&format!(
+            r#"{{"rules": [{{"name": "a", "accounts": ["{account}"]}}]}}"#
+        ));
+        let denylist = BundleDenylist::load(policy_file.path()).unwrap();
+
+        // a bad policy file is rejected without touching the existing rules
+        policy_file.as_file_mut().set_len(0).unwrap();
+        assert!(denylist.reload(None).is_err());
+        assert_eq!(denylist.denied_by(&transfer_bundle(&account)), Some("a"));
+
+        let other_policy_file = write_policy_file(r#"{"rules": [{"name": "b"}, {"name": "c"}]}"#);
+        let reloaded = denylist
+            .reload(Some(other_policy_file.path().to_path_buf()))
+            .unwrap();
+        assert_eq!(reloaded.rules().len(), 2);
+        assert_eq!(reloaded.policy_file(), Some(other_policy_file.path()));
+        assert_eq!(reloaded.denied_by(&transfer_bundle(&account)), None);
+
+        // the denylist it was reloaded from is unaffected
+        assert_eq!(denylist.denied_by(&transfer_bundle(&account)), Some("a"));
+    }
+}
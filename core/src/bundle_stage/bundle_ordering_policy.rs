@@ -0,0 +1,260 @@
+//! Decides the order BundleStage executes buffered bundles in during a leader slot.
+use {
+    lazy_static::lazy_static,
+    solana_cost_model::cost_model::CostModel,
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        bundle::SanitizedBundle, program_utils::limited_deserialize, pubkey::Pubkey,
+        system_instruction::SystemInstruction, system_program,
+    },
+    std::{cmp::Ordering, collections::HashSet},
+    strum::VariantNames,
+    strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr},
+};
+
+#[derive(Clone, Copy, Debug, EnumString, EnumVariantNames, Default, IntoStaticStr, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BundleOrderingMethod {
+    /// Bundles are executed in the order they were received.
+    #[default]
+    Fifo,
+    /// Bundles paying the most in tips per compute unit are executed first.
+    TipPerComputeUnit,
+    /// Bundles paying the most in tips per write-locked account are executed first.
+    TipPerWriteLock,
+}
+
+impl BundleOrderingMethod {
+    pub const fn cli_names() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+
+    pub fn cli_message() -> &'static str {
+        lazy_static! {
+            static ref MESSAGE: String = format!(
+                "Switch the order bundles are executed in during a leader slot [default: {}]",
+                BundleOrderingMethod::default()
+            );
+        };
+
+        &MESSAGE
+    }
+
+    pub fn new_policy(&self, tip_accounts: HashSet<Pubkey>) -> Box<dyn BundleOrderingPolicy> {
+        match self {
+            BundleOrderingMethod::Fifo => Box::new(FifoOrderingPolicy),
+            BundleOrderingMethod::TipPerComputeUnit => {
+                Box::new(TipPerComputeUnitOrderingPolicy { tip_accounts })
+            }
+            BundleOrderingMethod::TipPerWriteLock => {
+                Box::new(TipPerWriteLockOrderingPolicy { tip_accounts })
+            }
+        }
+    }
+}
+
+pub trait BundleOrderingPolicy: Send {
+    /// Returns the indexes of `bundles` in the order they should be executed. `bundles` is in the
+    /// order the bundles were received.
+    fn execution_order(&self, bank: &Bank, bundles: &[&SanitizedBundle]) -> Vec<usize>;
+}
+
+pub struct FifoOrderingPolicy;
+
+impl BundleOrderingPolicy for FifoOrderingPolicy {
+    fn execution_order(&self, _bank: &Bank, bundles: &[&SanitizedBundle]) -> Vec<usize> {
+        (0..bundles.len()).collect()
+    }
+}
+
+pub struct TipPerComputeUnitOrderingPolicy {
+    tip_accounts: HashSet<Pubkey>,
+}
+
+impl BundleOrderingPolicy for TipPerComputeUnitOrderingPolicy {
+    fn execution_order(&self, bank: &Bank, bundles: &[&SanitizedBundle]) -> Vec<usize> {
+        order_by_tip_ratio(bundles, |bundle| {
+            let compute_units = bundle
+                .transactions
+                .iter()
+                .map(|tx| CostModel::calculate_cost(tx, &bank.feature_set).sum())
+                .sum();
+            (bundle_tip(bundle, &self.tip_accounts), compute_units)
+        })
+    }
+}
+
+pub struct TipPerWriteLockOrderingPolicy {
+    tip_accounts: HashSet<Pubkey>,
+}
+
+impl BundleOrderingPolicy for TipPerWriteLockOrderingPolicy {
+    fn execution_order(&self, _bank: &Bank, bundles: &[&SanitizedBundle]) -> Vec<usize> {
+        order_by_tip_ratio(bundles, |bundle| {
+            let write_locks: HashSet<&Pubkey> = bundle
+                .transactions
+                .iter()
+                .flat_map(|tx| {
+                    let message = tx.message();
+                    message
+                        .account_keys()
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| message.is_writable(*index))
+                        .map(|(_, pubkey)| pubkey)
+                })
+                .collect();
+            (
+                bundle_tip(bundle, &self.tip_accounts),
+                write_locks.len() as u64,
+            )
+        })
+    }
+}
+
+/// Orders bundles by `tip / denominator` descending, where `ratio` returns the pair for a bundle.
+/// Bundles with equal ratios keep the order they were received in.
+fn order_by_tip_ratio(
+    bundles: &[&SanitizedBundle],
+    ratio: impl Fn(&SanitizedBundle) -> (u64, u64),
+) -> Vec<usize> {
+    let ratios: Vec<(u128, u128)> = bundles
+        .iter()
+        .map(|bundle| {
+            let (tip, denominator) = ratio(bundle);
+            (u128::from(tip), u128::from(denominator.max(1)))
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..bundles.len()).collect();
+    order.sort_by(|a, b| {
+        let (tip_a, denominator_a) = ratios[*a];
+        let (tip_b, denominator_b) = ratios[*b];
+        match (tip_b * denominator_a).cmp(&(tip_a * denominator_b)) {
+            Ordering::Equal => a.cmp(b),
+            ordering => ordering,
+        }
+    });
+    order
+}
+
+/// Sums the lamports transferred to tip accounts by top-level system transfers in the bundle.
+fn bundle_tip(bundle: &SanitizedBundle, tip_accounts: &HashSet<Pubkey>) -> u64 {
+    bundle
+        .transactions
+        .iter()
+        .flat_map(|tx| {
+            let account_keys = tx.message().account_keys();
+            tx.message()
+                .program_instructions_iter()
+                .filter(|(program_id, _)| **program_id == system_program::id())
+                .filter_map(move |(_, instruction)| {
+                    let destination =
+                        account_keys.get(usize::from(*instruction.accounts.get(1)?))?;
+                    if !tip_accounts.contains(destination) {
+                        return None;
+                    }
+                    match limited_deserialize::<SystemInstruction>(&instruction.data) {
+                        Ok(SystemInstruction::Transfer { lamports }) => Some(lamports),
+                        _ => None,
+                    }
+                })
+        })
+        .fold(0, u64::saturating_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_sdk::{
+            hash::Hash,
+            instruction::AccountMeta,
+            signature::{Keypair, Signer},
+            system_instruction, system_transaction,
+            transaction::{SanitizedTransaction, Transaction},
+        },
+    };
+
+    fn tip_bundle(
+        payer: &Keypair,
+        tip_account: &Pubkey,
+        tip: u64,
+        write_locked_accounts: &[Pubkey],
+    ) -> SanitizedBundle {
+        let mut transfer = system_instruction::transfer(&payer.pubkey(), tip_account, tip);
+        transfer.accounts.extend(
+            write_locked_accounts
+                .iter()
+                .map(|pubkey| AccountMeta::new(*pubkey, false)),
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[transfer],
+            Some(&payer.pubkey()),
+            &[payer],
+            Hash::default(),
+        );
+        SanitizedBundle {
+            transactions: vec![SanitizedTransaction::from_transaction_for_tests(tx)],
+            bundle_id: format!("{tip}"),
+        }
+    }
+
+    #[test]
+    fn test_fifo_ordering() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let tip_account = Pubkey::new_unique();
+        let bundles: Vec<_> = [1, 3, 2]
+            .into_iter()
+            .map(|tip| tip_bundle(&Keypair::new(), &tip_account, tip, &[]))
+            .collect();
+        let bundles: Vec<_> = bundles.iter().collect();
+
+        let policy = BundleOrderingMethod::Fifo.new_policy(HashSet::from([tip_account]));
+        assert_eq!(policy.execution_order(&bank, &bundles), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_tip_per_compute_unit_ordering() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let tip_account = Pubkey::new_unique();
+        let not_tip_account = Pubkey::new_unique();
+        let payer = Keypair::new();
+        let untipped = SanitizedBundle {
+            transactions: vec![SanitizedTransaction::from_transaction_for_tests(
+                system_transaction::transfer(&payer, &not_tip_account, 1_000, Hash::default()),
+            )],
+            bundle_id: "untipped".to_string(),
+        };
+        let bundles = [
+            untipped,
+            tip_bundle(&payer, &tip_account, 1_000, &[]),
+            tip_bundle(&payer, &tip_account, 5_000, &[]),
+            tip_bundle(&payer, &tip_account, 1_000, &[]),
+        ];
+        let bundles: Vec<_> = bundles.iter().collect();
+
+        let policy =
+            BundleOrderingMethod::TipPerComputeUnit.new_policy(HashSet::from([tip_account]));
+        assert_eq!(policy.execution_order(&bank, &bundles), vec![2, 1, 3, 0]);
+    }
+
+    #[test]
+    fn test_tip_per_write_lock_ordering() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let tip_account = Pubkey::new_unique();
+        let payer = Keypair::new();
+        // 3 write locks: payer, tip account and one extra account
+        let contended = tip_bundle(&payer, &tip_account, 3_000, &[Pubkey::new_unique()]);
+        // 2 write locks: payer and tip account
+        let narrow = tip_bundle(&payer, &tip_account, 2_500, &[]);
+        let bundles = [contended, narrow];
+        let bundles: Vec<_> = bundles.iter().collect();
+
+        let policy = BundleOrderingMethod::TipPerWriteLock.new_policy(HashSet::from([tip_account]));
+        assert_eq!(policy.execution_order(&bank, &bundles), vec![1, 0]);
+    }
+}
@@ -6,6 +6,7 @@ use {
     solana_bundle::{bundle_execution::LoadAndExecuteBundleError, BundleExecutionError},
     solana_poh::poh_recorder::BankStart,
     solana_sdk::{bundle::SanitizedBundle, clock::Slot, saturating_add_assign},
+    std::collections::HashMap,
 };
 
 pub struct BundleStageLeaderMetrics {
@@ -253,6 +254,17 @@ impl BundleStageStatsMetricsTracker {
                 )) => {
                     saturating_add_assign!(bundle_stage_metrics.bad_argument, 1);
                 }
+                Err(BundleExecutionError::DeniedByPolicy(rule)) => {
+                    saturating_add_assign!(
+                        bundle_stage_metrics.execution_results_denied_by_policy,
+                        1
+                    );
+                    let count = bundle_stage_metrics
+                        .denied_by_policy_rule
+                        .entry(rule.clone())
+                        .or_default();
+                    saturating_add_assign!(*count, 1);
+                }
             }
         }
     }
@@ -305,6 +317,18 @@ impl BundleStageStatsMetricsTracker {
         }
     }
 
+    pub(crate) fn increment_ordering_elapsed_us(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.ordering_elapsed_us, count);
+        }
+    }
+
+    pub(crate) fn increment_num_bundles_reordered(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.num_bundles_reordered, count);
+        }
+    }
+
     pub(crate) fn increment_execute_locked_bundles_elapsed_us(&mut self, count: u64) {
         if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
             saturating_add_assign!(
@@ -335,6 +359,10 @@ pub struct BundleStageStats {
 
     locked_bundle_elapsed_us: u64,
 
+    // time spent ordering bundles and the number executed out of the order they were received in
+    ordering_elapsed_us: u64,
+    num_bundles_reordered: u64,
+
     num_lock_errors: u64,
 
     num_init_tip_account_errors: u64,
@@ -355,6 +383,10 @@ pub struct BundleStageStats {
     execution_results_exceeds_cost_model: u64,
     execution_results_tip_errors: u64,
     execution_results_max_retries: u64,
+    execution_results_denied_by_policy: u64,
+
+    // number of bundles rejected by each bundle denylist rule
+    denied_by_policy_rule: HashMap<String, u64>,
 
     bad_argument: u64,
 }
@@ -441,6 +473,8 @@ impl BundleStageStats {
                 self.locked_bundle_elapsed_us,
                 i64
             ),
+            ("ordering_elapsed_us", self.ordering_elapsed_us, i64),
+            ("num_bundles_reordered", self.num_bundles_reordered, i64),
             ("num_lock_errors", self.num_lock_errors, i64),
             (
                 "num_init_tip_account_errors",
@@ -496,7 +530,22 @@ impl BundleStageStats {
                 self.execution_results_max_retries,
                 i64
             ),
+            (
+                "execution_results_denied_by_policy",
+                self.execution_results_denied_by_policy,
+                i64
+            ),
             ("bad_argument", self.bad_argument, i64)
         );
+
+        for (rule, count) in &self.denied_by_policy_rule {
+            datapoint_info!(
+                "bundle_stage-denylist",
+                ("id", self.id, i64),
+                ("slot", self.slot, i64),
+                ("rule", rule.clone(), String),
+                ("count", *count, i64)
+            );
+        }
     }
 }
@@ -5,20 +5,26 @@ use {
     },
     solana_accounts_db::transaction_results::TransactionResults,
     solana_bundle::bundle_execution::LoadAndExecuteBundleOutput,
-    solana_ledger::blockstore_processor::TransactionStatusSender,
+    solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifierArc,
+    solana_ledger::{
+        blockstore::Blockstore, blockstore_meta::BundleStatusMeta,
+        blockstore_processor::TransactionStatusSender,
+    },
     solana_measure::measure_us,
     solana_runtime::{
         bank::{Bank, CommitTransactionCounts, TransactionBalances, TransactionBalancesSet},
         bank_utils,
         prioritization_fee_cache::PrioritizationFeeCache,
     },
-    solana_sdk::{hash::Hash, saturating_add_assign, transaction::SanitizedTransaction},
+    solana_sdk::{
+        hash::Hash, pubkey::Pubkey, saturating_add_assign, transaction::SanitizedTransaction,
+    },
     solana_transaction_status::{
         token_balances::{TransactionTokenBalances, TransactionTokenBalancesSet},
         PreBalanceInfo,
     },
     solana_vote::vote_sender_types::ReplayVoteSender,
-    std::sync::Arc,
+    std::{collections::HashSet, sync::Arc},
 };
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -30,6 +36,11 @@ pub struct Committer {
     transaction_status_sender: Option<TransactionStatusSender>,
     replay_vote_sender: ReplayVoteSender,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    /// Committed bundles are recorded here, if set
+    blockstore: Option<Arc<Blockstore>>,
+    tip_accounts: HashSet<Pubkey>,
+    /// Committed bundles are streamed to geyser plugins through here, if set
+    bundle_notifier: Option<BundleNotifierArc>,
 }
 
 impl Committer {
@@ -37,11 +48,17 @@ impl Committer {
         transaction_status_sender: Option<TransactionStatusSender>,
         replay_vote_sender: ReplayVoteSender,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        blockstore: Option<Arc<Blockstore>>,
+        tip_accounts: HashSet<Pubkey>,
+        bundle_notifier: Option<BundleNotifierArc>,
     ) -> Self {
         Self {
             transaction_status_sender,
             replay_vote_sender,
             prioritization_fee_cache,
+            blockstore,
+            tip_accounts,
+            bundle_notifier,
         }
     }
 
@@ -52,9 +69,12 @@ impl Committer {
     /// Very similar to Committer::commit_transactions, but works with bundles.
     /// The main difference is there's multiple non-parallelizable transaction vectors to commit
     /// and post-balances are collected after execution instead of from the bank in Self::collect_balances_and_send_status_batch.
+    /// The committed bundle is recorded in the blockstore along with the tip it paid, and
+    /// notified to geyser plugins.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn commit_bundle<'a>(
         &self,
+        bundle_id: &str,
         bundle_execution_output: &'a mut LoadAndExecuteBundleOutput<'a>,
         last_blockhash: Hash,
         lamports_per_signature: u64,
@@ -63,6 +83,12 @@ impl Committer {
         execute_and_commit_timings: &mut LeaderExecuteAndCommitTimings,
     ) -> (u64, CommitBundleDetails) {
         let transaction_output = bundle_execution_output.bundle_transaction_results_mut();
+        // BankingStage drops transactions that touch the tip accounts, so any change to their
+        // balances across the commit below was paid by this bundle
+        let pre_tip_balances = (self.blockstore.is_some() || self.bundle_notifier.is_some())
+            .then(|| self.tip_account_balances(bank));
+        let mut committed_signatures = vec![];
+        let mut committed_results = vec![];
 
         let (commit_transaction_details, commit_times): (Vec<_>, Vec<_>) = transaction_output
             .iter_mut()
@@ -111,6 +137,20 @@ impl Committer {
                     &mut execute_and_commit_timings.execute_timings,
                 ));
 
+                let (signatures, results): (Vec<_>, Vec<_>) = sanitized_transactions
+                    .iter()
+                    .zip(tx_results.execution_results.iter())
+                    .filter(|(_, execution_result)| execution_result.was_executed())
+                    .map(|(transaction, execution_result)| {
+                        (
+                            *transaction.signature(),
+                            execution_result.flattened_result(),
+                        )
+                    })
+                    .unzip();
+                committed_signatures.extend(signatures);
+                committed_results.extend(results);
+
                 let commit_transaction_statuses: Vec<_> = tx_results
                     .execution_results
                     .iter()
@@ -166,6 +206,31 @@ impl Committer {
             })
             .unzip();
 
+        if let Some(pre_tip_balances) = pre_tip_balances {
+            if !committed_signatures.is_empty() {
+                let tips = self.credited_tips(bank, &pre_tip_balances);
+                if let Some(bundle_notifier) = &self.bundle_notifier {
+                    bundle_notifier.notify_bundle(
+                        bundle_id,
+                        bank.slot(),
+                        &committed_signatures,
+                        &committed_results,
+                        &tips,
+                    );
+                }
+                if let Some(blockstore) = &self.blockstore {
+                    let status = BundleStatusMeta {
+                        signatures: committed_signatures,
+                        tip_lamports: tips.iter().map(|(_, lamports)| lamports).sum(),
+                    };
+                    if let Err(e) = blockstore.write_bundle_status(bundle_id, bank.slot(), &status)
+                    {
+                        warn!("failed to record status of bundle {bundle_id}: {e:?}");
+                    }
+                }
+            }
+        }
+
         (
             commit_times.iter().sum(),
             CommitBundleDetails {
@@ -174,6 +239,26 @@ impl Committer {
         )
     }
 
+    fn tip_account_balances(&self, bank: &Bank) -> Vec<u64> {
+        self.tip_accounts
+            .iter()
+            .map(|tip_account| bank.get_balance(tip_account))
+            .collect()
+    }
+
+    /// Returns the tip accounts whose balance grew since `pre_tip_balances` was taken, along
+    /// with how much they were credited.
+    fn credited_tips(&self, bank: &Bank, pre_tip_balances: &[u64]) -> Vec<(Pubkey, u64)> {
+        self.tip_accounts
+            .iter()
+            .zip(pre_tip_balances)
+            .filter_map(|(tip_account, pre_balance)| {
+                let credited = bank.get_balance(tip_account).saturating_sub(*pre_balance);
+                (credited > 0).then_some((*tip_account, credited))
+            })
+            .collect()
+    }
+
     fn collect_balances_and_send_status_batch(
         &self,
         tx_results: TransactionResults,
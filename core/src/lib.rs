@@ -28,6 +28,7 @@ pub mod immutable_deserialized_bundle;
 pub mod next_leader;
 pub mod optimistic_confirmation_verifier;
 pub mod packet_bundle;
+mod plugin_submission_forwarder;
 pub mod poh_timing_report_service;
 pub mod poh_timing_reporter;
 pub mod proxy;
@@ -35,6 +36,7 @@ pub mod repair;
 pub mod replay_stage;
 mod result;
 pub mod rewards_recorder_service;
+mod rpc_bundle_forwarder;
 pub mod sample_performance_service;
 mod shred_fetch_stage;
 pub mod sigverify;
@@ -44,6 +46,7 @@ pub mod staked_nodes_updater_service;
 pub mod stats_reporter_service;
 pub mod system_monitor_service;
 pub mod tip_manager;
+pub mod tip_revenue_service;
 pub mod tpu;
 mod tpu_entry_notifier;
 pub mod tracer_packet_stats;
@@ -0,0 +1,215 @@
+//! Forwards transactions and bundles submitted by runtime plugins into the TPU. Transactions are
+//! sent to sigverify like transactions received over the network, and bundles to the BundleStage
+//! like bundles received from a block engine.
+
+use {
+    crate::{packet_bundle::PacketBundle, rpc_bundle_forwarder::RpcBundleForwarder},
+    crossbeam_channel::{select, Sender},
+    log::*,
+    solana_perf::packet::{Packet, PacketBatch},
+    solana_runtime_plugin::plugin_submitter::PluginSubmissionReceivers,
+    solana_sdk::transaction::VersionedTransaction,
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+pub(crate) struct PluginSubmissionForwarder {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl PluginSubmissionForwarder {
+    pub(crate) fn new(
+        receivers: PluginSubmissionReceivers,
+        packet_sender: Sender<PacketBatch>,
+        bundle_sender: Sender<Vec<PacketBundle>>,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let PluginSubmissionReceivers {
+            transaction_receiver,
+            bundle_receiver,
+        } = receivers;
+        let thread_hdl = Builder::new()
+            .name("solPluginSubFwd".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    select! {
+                        recv(transaction_receiver) -> transaction => {
+                            let Ok(transaction) = transaction else {
+                                break;
+                            };
+                            let transactions = std::iter::once(transaction)
+                                .chain(transaction_receiver.try_iter());
+                            if let Some(batch) = Self::packet_batch(transactions) {
+                                if packet_sender.send(batch).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        recv(bundle_receiver) -> bundle => {
+                            let Ok(bundle) = bundle else {
+                                break;
+                            };
+                            if let Some(packet_bundle) = RpcBundleForwarder::packet_bundle(bundle) {
+                                if bundle_sender.send(vec![packet_bundle]).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        default(Duration::from_secs(1)) => {}
+                    }
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn packet_batch(
+        transactions: impl Iterator<Item = VersionedTransaction>,
+    ) -> Option<PacketBatch> {
+        let packets: Vec<_> = transactions
+            .filter_map(|transaction| {
+                Packet::from_data(None, &transaction)
+                    .map_err(|err| match transaction.signatures.first() {
+                        Some(signature) => {
+                            warn!("dropping plugin transaction {signature}: {err}")
+                        }
+                        None => warn!("dropping unsigned plugin transaction: {err}"),
+                    })
+                    .ok()
+            })
+            .collect();
+        (!packets.is_empty()).then(|| PacketBatch::new(packets))
+    }
+
+    pub(crate) fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        solana_sdk::{
+            bundle::{derive_bundle_id, VersionedBundle},
+            hash::Hash,
+            instruction::Instruction,
+            message::{Message, VersionedMessage},
+            packet::PACKET_DATA_SIZE,
+            pubkey::Pubkey,
+            signature::Keypair,
+            signer::Signer,
+            system_transaction,
+        },
+    };
+
+    fn test_transactions(count: u64) -> Vec<VersionedTransaction> {
+        let keypair = Keypair::new();
+        (1..=count)
+            .map(|lamports| {
+                VersionedTransaction::from(system_transaction::transfer(
+                    &keypair,
+                    &keypair.pubkey(),
+                    lamports,
+                    Hash::default(),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_forward_plugin_submissions() {
+        let (transaction_sender, transaction_receiver) = unbounded();
+        let (plugin_bundle_sender, plugin_bundle_receiver) = unbounded();
+        let (packet_sender, packet_receiver) = unbounded();
+        let (bundle_sender, bundle_receiver) = unbounded();
+        let exit = Arc::new(AtomicBool::new(false));
+        let forwarder = PluginSubmissionForwarder::new(
+            PluginSubmissionReceivers {
+                transaction_receiver,
+                bundle_receiver: plugin_bundle_receiver,
+            },
+            packet_sender,
+            bundle_sender,
+            exit.clone(),
+        );
+
+        let transactions = test_transactions(2);
+        for transaction in &transactions {
+            transaction_sender.send(transaction.clone()).unwrap();
+        }
+        let mut forwarded: Vec<VersionedTransaction> = vec![];
+        while forwarded.len() < transactions.len() {
+            let batch = packet_receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap();
+            forwarded.extend(
+                batch
+                    .iter()
+                    .map(|packet| packet.deserialize_slice(..).unwrap()),
+            );
+        }
+        assert_eq!(forwarded, transactions);
+
+        let bundle_transactions = test_transactions(3);
+        plugin_bundle_sender
+            .send(VersionedBundle {
+                transactions: bundle_transactions.clone(),
+            })
+            .unwrap();
+        let packet_bundles = bundle_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(packet_bundles.len(), 1);
+        assert_eq!(
+            packet_bundles[0].bundle_id,
+            derive_bundle_id(&bundle_transactions)
+        );
+
+        exit.store(true, Ordering::Relaxed);
+        forwarder.join().unwrap();
+    }
+
+    #[test]
+    fn test_packet_batch_drops_oversized() {
+        let mut transactions = test_transactions(2);
+        // too large for a packet, and without any signature to log
+        let mut oversized = VersionedTransaction::from(system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            Hash::default(),
+        ));
+        oversized.signatures.clear();
+        oversized.message = VersionedMessage::Legacy(Message::new(
+            &[Instruction::new_with_bytes(
+                Pubkey::new_unique(),
+                &[0; PACKET_DATA_SIZE],
+                vec![],
+            )],
+            None,
+        ));
+        transactions.insert(1, oversized);
+
+        let batch =
+            PluginSubmissionForwarder::packet_batch(transactions.clone().into_iter()).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(
+            batch
+                .iter()
+                .map(|packet| packet.deserialize_slice(..).unwrap())
+                .collect::<Vec<VersionedTransaction>>(),
+            vec![transactions[0].clone(), transactions[2].clone()]
+        );
+
+        let oversized = transactions.remove(1);
+        assert!(PluginSubmissionForwarder::packet_batch(std::iter::once(oversized)).is_none());
+    }
+}
@@ -169,6 +169,14 @@ pub async fn refresh_access_token(
     get_validated_token(response.into_inner().access_token)
 }
 
+/// Returns when `token` expires as a unix timestamp in milliseconds.
+pub(crate) fn token_expiry_ms(token: &Token) -> Option<u64> {
+    token
+        .expires_at_utc
+        .as_ref()
+        .map(|ts| (ts.seconds as u64).saturating_mul(1000))
+}
+
 /// An invalid token is one where any of its fields are None or the token itself is None.
 /// Performs the necessary validations on the auth tokens before returning,
 /// i.e. it is safe to call .unwrap() on the token fields from the call-site.
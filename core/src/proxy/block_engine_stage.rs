@@ -3,17 +3,24 @@
 //! The Block Engine is responsible for the following:
 //! - Acts as a system that sends high profit bundles and transactions to a validator.
 //! - Sends transactions and bundles to the validator.
+//!
+//! Multiple Block Engine URLs can be configured. Before connecting, the stage measures how long it
+//! takes to connect to each of them and tries them from lowest to highest latency, failing over to
+//! the next one whenever the connection to the current one is lost.
 use {
     crate::{
         banking_trace::BankingPacketSender,
         packet_bundle::PacketBundle,
         proto_packet_to_packet,
         proxy::{
-            auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
-            ProxyError,
+            auth::{
+                generate_auth_tokens, maybe_refresh_auth_tokens, token_expiry_ms, AuthInterceptor,
+            },
+            update_status, ProxyConnectionStatus, ProxyError,
         },
     },
     crossbeam_channel::Sender,
+    futures::future::join_all,
     jito_protos::proto::{
         auth::{auth_service_client::AuthServiceClient, Token},
         block_engine::{
@@ -27,13 +34,14 @@ use {
         pubkey::Pubkey, saturating_add_assign, signature::Signer, signer::keypair::Keypair,
     },
     std::{
+        fmt::{self, Display},
         str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc, Mutex,
+            Arc, Mutex, RwLock,
         },
         thread::{self, Builder, JoinHandle},
-        time::Duration,
+        time::{Duration, Instant},
     },
     tokio::{
         task,
@@ -76,20 +84,53 @@ pub struct BlockBuilderFeeInfo {
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct BlockEngineConfig {
-    /// Block Engine URL
-    pub block_engine_url: String,
+    /// Block Engine URLs. The lowest latency reachable one is connected to; ties are broken by
+    /// the order they're listed in.
+    pub block_engine_urls: Vec<String>,
 
     /// If set then it will be assumed the backend verified packets so signature verification will be bypassed in the validator.
     pub trust_packets: bool,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEngineEndpointProbe {
+    pub url: String,
+    /// Time taken to connect to the endpoint, None if it couldn't be reached.
+    pub latency_us: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEngineStatus {
+    #[serde(flatten)]
+    pub connection: ProxyConnectionStatus,
+    /// Results of the most recent latency probe, in the order the endpoints will be tried.
+    pub probes: Vec<BlockEngineEndpointProbe>,
+}
+
+impl Display for BlockEngineStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.connection)?;
+        writeln!(f, "Latency Probes:")?;
+        for probe in &self.probes {
+            match probe.latency_us {
+                Some(latency_us) => writeln!(f, "  {}: {}us", probe.url, latency_us)?,
+                None => writeln!(f, "  {}: unreachable", probe.url)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct BlockEngineStage {
     t_hdls: Vec<JoinHandle<()>>,
 }
 
 impl BlockEngineStage {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         block_engine_config: Arc<Mutex<BlockEngineConfig>>,
+        // Updated with the state of the connection and the latest probe results.
+        block_engine_status: Arc<RwLock<BlockEngineStatus>>,
         // Channel that bundles get piped through.
         bundle_tx: Sender<Vec<PacketBundle>>,
         // The keypair stored here is used to sign auth challenges.
@@ -112,6 +153,7 @@ impl BlockEngineStage {
                     .unwrap();
                 rt.block_on(Self::start(
                     block_engine_config,
+                    block_engine_status,
                     cluster_info,
                     bundle_tx,
                     packet_tx,
@@ -137,6 +179,7 @@ impl BlockEngineStage {
     #[allow(clippy::too_many_arguments)]
     async fn start(
         block_engine_config: Arc<Mutex<BlockEngineConfig>>,
+        block_engine_status: Arc<RwLock<BlockEngineStatus>>,
         cluster_info: Arc<ClusterInfo>,
         bundle_tx: Sender<Vec<PacketBundle>>,
         packet_tx: Sender<PacketBatch>,
@@ -150,7 +193,6 @@ impl BlockEngineStage {
 
         while !exit.load(Ordering::Relaxed) {
             // Wait until a valid config is supplied (either initially or by admin rpc)
-            // Use if!/else here to avoid extra CONNECTION_BACKOFF wait on successful termination
             let local_block_engine_config = {
                 let block_engine_config = block_engine_config.clone();
                 task::spawn_blocking(move || block_engine_config.lock().unwrap().clone())
@@ -159,42 +201,160 @@ impl BlockEngineStage {
             };
             if !Self::is_valid_block_engine_config(&local_block_engine_config) {
                 sleep(CONNECTION_BACKOFF).await;
-            } else if let Err(e) = Self::connect_auth_and_stream(
-                &local_block_engine_config,
-                &block_engine_config,
-                &cluster_info,
-                &bundle_tx,
-                &packet_tx,
-                &banking_packet_sender,
-                &exit,
-                &block_builder_fee_info,
+                continue;
+            }
+
+            let probes = Self::probe_block_engines(
+                &local_block_engine_config.block_engine_urls,
                 &CONNECTION_TIMEOUT,
             )
-            .await
+            .await;
             {
-                match e {
+                let probes = probes.clone();
+                update_status(&block_engine_status, move |status| status.probes = probes).await;
+            }
+
+            for (attempt, probe) in probes.iter().enumerate() {
+                if attempt > 0 {
+                    datapoint_warn!(
+                        "block_engine_stage-failover",
+                        ("from", probes[attempt - 1].url, String),
+                        ("to", probe.url, String),
+                    );
+                }
+
+                let result = Self::connect_auth_and_stream(
+                    &probe.url,
+                    &local_block_engine_config,
+                    &block_engine_config,
+                    &block_engine_status,
+                    &cluster_info,
+                    &bundle_tx,
+                    &packet_tx,
+                    &banking_packet_sender,
+                    &exit,
+                    &block_builder_fee_info,
+                    &CONNECTION_TIMEOUT,
+                )
+                .await;
+                let error = result.as_ref().err().map(|e| e.to_string());
+                update_status(&block_engine_status, move |status| {
+                    status.connection.on_disconnected(error)
+                })
+                .await;
+
+                match result {
+                    Ok(()) => break,
                     // This error is frequent on hot spares, and the parsed string does not work
-                    // with datapoints (incorrect escaping).
-                    ProxyError::AuthenticationPermissionDenied => {
-                        warn!("block engine permission denied. not on leader schedule. ignore if hot-spare.")
+                    // with datapoints (incorrect escaping). A block engine may also deny a
+                    // validator the others accept, so still fail over.
+                    Err(ProxyError::AuthenticationPermissionDenied) => {
+                        warn!(
+                            "block engine {} permission denied. not on leader schedule. ignore if hot-spare.",
+                            probe.url
+                        );
                     }
-                    e => {
+                    Err(e) => {
                         error_count += 1;
                         datapoint_warn!(
                             "block_engine_stage-proxy_error",
                             ("count", error_count, i64),
+                            ("url", probe.url, String),
                             ("error", e.to_string(), String),
                         );
                     }
                 }
+
+                // Start over with the new endpoints instead of failing over within stale ones
+                let global_block_engine_config = block_engine_config.clone();
+                if exit.load(Ordering::Relaxed)
+                    || local_block_engine_config
+                        != task::spawn_blocking(move || {
+                            global_block_engine_config.lock().unwrap().clone()
+                        })
+                        .await
+                        .unwrap()
+                {
+                    break;
+                }
+            }
+
+            // Avoid an extra CONNECTION_BACKOFF wait on successful termination
+            if !exit.load(Ordering::Relaxed) {
                 sleep(CONNECTION_BACKOFF).await;
             }
         }
     }
 
+    /// Measures how long it takes to connect to each block engine and returns them ordered from
+    /// lowest to highest latency. Unreachable block engines are tried last, in configured order.
+    async fn probe_block_engines(
+        block_engine_urls: &[String],
+        connection_timeout: &Duration,
+    ) -> Vec<BlockEngineEndpointProbe> {
+        let mut probes = join_all(block_engine_urls.iter().map(|block_engine_url| async move {
+            BlockEngineEndpointProbe {
+                url: block_engine_url.clone(),
+                latency_us: Self::probe_latency(block_engine_url, connection_timeout).await,
+            }
+        }))
+        .await;
+        // sort is stable, so equal latencies keep the configured order
+        probes.sort_by_key(|probe| probe.latency_us.unwrap_or(u64::MAX));
+
+        for probe in &probes {
+            datapoint_info!(
+                "block_engine_stage-latency_probe",
+                ("url", probe.url, String),
+                ("reachable", probe.latency_us.is_some(), bool),
+                ("latency_us", probe.latency_us.unwrap_or_default(), i64),
+            );
+        }
+        probes
+    }
+
+    async fn probe_latency(block_engine_url: &str, connection_timeout: &Duration) -> Option<u64> {
+        let endpoint = Self::block_engine_endpoint(block_engine_url).ok()?;
+        let start = Instant::now();
+        match timeout(*connection_timeout, endpoint.connect()).await {
+            Ok(Ok(_)) => Some(start.elapsed().as_micros() as u64),
+            Ok(Err(e)) => {
+                warn!("failed to connect to block engine {block_engine_url}: {e}");
+                None
+            }
+            Err(_) => {
+                warn!("timed out connecting to block engine {block_engine_url}");
+                None
+            }
+        }
+    }
+
+    fn block_engine_endpoint(block_engine_url: &str) -> crate::proxy::Result<Endpoint> {
+        let mut backend_endpoint = Endpoint::from_shared(block_engine_url.to_string())
+            .map_err(|_| {
+                ProxyError::BlockEngineConnectionError(format!(
+                    "invalid block engine url value: {block_engine_url}"
+                ))
+            })?
+            .tcp_keepalive(Some(Duration::from_secs(60)));
+        if block_engine_url.starts_with("https") {
+            backend_endpoint = backend_endpoint
+                .tls_config(tonic::transport::ClientTlsConfig::new())
+                .map_err(|_| {
+                    ProxyError::BlockEngineConnectionError(
+                        "failed to set tls_config for block engine service".to_string(),
+                    )
+                })?;
+        }
+        Ok(backend_endpoint)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
+        block_engine_url: &str,
         local_block_engine_config: &BlockEngineConfig,
         global_block_engine_config: &Arc<Mutex<BlockEngineConfig>>,
+        block_engine_status: &Arc<RwLock<BlockEngineStatus>>,
         cluster_info: &Arc<ClusterInfo>,
         bundle_tx: &Sender<Vec<PacketBundle>>,
         packet_tx: &Sender<PacketBatch>,
@@ -206,32 +366,9 @@ impl BlockEngineStage {
         // Get a copy of configs here in case they have changed at runtime
         let keypair = cluster_info.keypair().clone();
 
-        let mut backend_endpoint =
-            Endpoint::from_shared(local_block_engine_config.block_engine_url.clone())
-                .map_err(|_| {
-                    ProxyError::BlockEngineConnectionError(format!(
-                        "invalid block engine url value: {}",
-                        local_block_engine_config.block_engine_url
-                    ))
-                })?
-                .tcp_keepalive(Some(Duration::from_secs(60)));
-        if local_block_engine_config
-            .block_engine_url
-            .starts_with("https")
-        {
-            backend_endpoint = backend_endpoint
-                .tls_config(tonic::transport::ClientTlsConfig::new())
-                .map_err(|_| {
-                    ProxyError::BlockEngineConnectionError(
-                        "failed to set tls_config for block engine service".to_string(),
-                    )
-                })?;
-        }
+        let backend_endpoint = Self::block_engine_endpoint(block_engine_url)?;
 
-        debug!(
-            "connecting to auth: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to auth: {}", block_engine_url);
         let auth_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::AuthenticationConnectionTimeout)?
@@ -249,26 +386,36 @@ impl BlockEngineStage {
 
         datapoint_info!(
             "block_engine_stage-tokens_generated",
-            ("url", local_block_engine_config.block_engine_url, String),
+            ("url", block_engine_url, String),
             ("count", 1, i64),
         );
 
-        debug!(
-            "connecting to block engine: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to block engine: {}", block_engine_url);
         let block_engine_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::BlockEngineConnectionTimeout)?
             .map_err(|e| ProxyError::BlockEngineConnectionError(e.to_string()))?;
 
+        let access_token_expiry_ms = token_expiry_ms(&access_token);
         let access_token = Arc::new(Mutex::new(access_token));
         let block_engine_client = BlockEngineValidatorClient::with_interceptor(
             block_engine_channel,
             AuthInterceptor::new(access_token.clone()),
         );
+        {
+            let connected_endpoint = Some(block_engine_url.to_string());
+            let refresh_token_expiry_ms = token_expiry_ms(&refresh_token);
+            update_status(block_engine_status, move |status| {
+                status.connection.connected_endpoint = connected_endpoint;
+                status.connection.access_token_expiry_ms = access_token_expiry_ms;
+                status.connection.refresh_token_expiry_ms = refresh_token_expiry_ms;
+            })
+            .await;
+        }
 
         Self::start_consuming_block_engine_bundles_and_packets(
+            block_engine_url,
+            block_engine_status,
             bundle_tx,
             block_engine_client,
             packet_tx,
@@ -289,6 +436,8 @@ impl BlockEngineStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn start_consuming_block_engine_bundles_and_packets(
+        block_engine_url: &str,
+        block_engine_status: &Arc<RwLock<BlockEngineStatus>>,
         bundle_tx: &Sender<Vec<PacketBundle>>,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
         packet_tx: &Sender<PacketBatch>,
@@ -345,6 +494,8 @@ impl BlockEngineStage {
         }
 
         Self::consume_bundle_and_packet_stream(
+            block_engine_url,
+            block_engine_status,
             client,
             (subscribe_bundles_stream, subscribe_packets_stream),
             bundle_tx,
@@ -366,6 +517,8 @@ impl BlockEngineStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn consume_bundle_and_packet_stream(
+        block_engine_url: &str,
+        block_engine_status: &Arc<RwLock<BlockEngineStatus>>,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
         (mut bundle_stream, mut packet_stream): (
             Streaming<block_engine::SubscribeBundlesResponse>,
@@ -408,6 +561,11 @@ impl BlockEngineStage {
                 }
                 _ = metrics_and_auth_tick.tick() => {
                     block_engine_stats.report();
+                    let (num_bundles, num_packets) = (block_engine_stats.num_bundles, block_engine_stats.num_packets);
+                    update_status(block_engine_status, move |status| {
+                        saturating_add_assign!(status.connection.num_bundles_received, num_bundles);
+                        saturating_add_assign!(status.connection.num_packets_received, num_packets);
+                    }).await;
                     block_engine_stats = BlockEngineStageStats::default();
 
                     if cluster_info.id() != keypair.pubkey() {
@@ -433,10 +591,12 @@ impl BlockEngineStage {
                         num_refresh_access_token += 1;
                         datapoint_info!(
                             "block_engine_stage-refresh_access_token",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_refresh_access_token, i64),
                         );
 
+                        let access_token_expiry_ms = token_expiry_ms(&new_token);
+                        update_status(block_engine_status, move |status| status.connection.access_token_expiry_ms = access_token_expiry_ms).await;
                         let access_token = access_token.clone();
                         task::spawn_blocking(move || *access_token.lock().unwrap() = new_token)
                             .await
@@ -446,9 +606,11 @@ impl BlockEngineStage {
                         num_full_refreshes += 1;
                         datapoint_info!(
                             "block_engine_stage-tokens_generated",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_full_refreshes, i64),
                         );
+                        let refresh_token_expiry_ms = token_expiry_ms(&new_token);
+                        update_status(block_engine_status, move |status| status.connection.refresh_token_expiry_ms = refresh_token_expiry_ms).await;
                         refresh_token = new_token;
                     }
                 }
@@ -555,16 +717,19 @@ impl BlockEngineStage {
     }
 
     pub fn is_valid_block_engine_config(config: &BlockEngineConfig) -> bool {
-        if config.block_engine_url.is_empty() {
+        if config.block_engine_urls.is_empty() {
             warn!("can't connect to block_engine. missing block_engine_url.");
             return false;
         }
-        if let Err(e) = Endpoint::from_str(&config.block_engine_url) {
-            error!(
-                "can't connect to block engine. error creating block engine endpoint - {}",
-                e.to_string()
-            );
-            return false;
+        for block_engine_url in &config.block_engine_urls {
+            if let Err(e) = Endpoint::from_str(block_engine_url) {
+                error!(
+                    "can't connect to block engine. error creating block engine endpoint for {} - {}",
+                    block_engine_url,
+                    e.to_string()
+                );
+                return false;
+            }
         }
         true
     }
@@ -5,10 +5,12 @@ use {
     solana_gossip::{cluster_info::ClusterInfo, contact_info},
     solana_perf::packet::PacketBatch,
     std::{
+        fmt::{self, Display},
         net::SocketAddr,
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
         time::{Duration, Instant},
@@ -16,11 +18,182 @@ use {
 };
 
 const HEARTBEAT_TIMEOUT: Duration = Duration::from_millis(1500); // Empirically determined from load testing
-const DISCONNECT_DELAY: Duration = Duration::from_secs(60);
 const METRICS_CADENCE: Duration = Duration::from_secs(1);
 
+/// How long the relayer has to send heartbeats without interruption before the validator starts
+/// advertising the relayer's TPU ports instead of its own.
+pub const DEFAULT_RELAYER_GRACE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Which TPU ports the validator advertises and whether packets from its own fetch stage are
+/// forwarded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FetchStageMode {
+    /// The validator's own TPU ports are advertised and fetch stage packets are forwarded.
+    Direct,
+    /// The relayer's TPU ports are advertised and fetch stage packets are dropped.
+    Relayer,
+}
+
+impl FetchStageMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Relayer => "relayer",
+        }
+    }
+}
+
+/// Overrides the heartbeat driven choice of [FetchStageMode], set over the admin RPC.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchStageModeOverride {
+    /// Switch modes based on the relayer's health.
+    #[default]
+    Auto,
+    /// Always use [FetchStageMode::Direct].
+    Direct,
+    /// Use [FetchStageMode::Relayer] whenever the relayer's TPU ports are known, even if its
+    /// heartbeats are late.
+    Relayer,
+}
+
+impl FromStr for FetchStageModeOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "direct" => Ok(Self::Direct),
+            "relayer" => Ok(Self::Relayer),
+            _ => Err(format!(
+                "invalid fetch stage mode {s}, expected one of: auto, direct, relayer"
+            )),
+        }
+    }
+}
+
+impl Display for FetchStageModeOverride {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Direct => write!(f, "direct"),
+            Self::Relayer => write!(f, "relayer"),
+        }
+    }
+}
+
+/// Health of the relayer, as seen through its heartbeats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RelayerHealth {
+    /// A heartbeat was missed, or none were ever received.
+    Unhealthy,
+    /// Heartbeats have been arriving on time since `since`, but for less than the grace window.
+    Recovering { since: Instant },
+    /// Heartbeats have been arriving on time for at least the grace window.
+    Healthy,
+}
+
+/// Why the [FetchStageManager] switched modes; reported in logs and metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SwitchReason {
+    /// The relayer stayed healthy for the whole grace window.
+    RelayerHealthy,
+    /// The relayer missed a heartbeat.
+    HeartbeatTimeout,
+    /// An operator forced direct mode.
+    ForcedDirect,
+    /// An operator forced relayer mode.
+    ForcedRelayer,
+}
+
+impl SwitchReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::RelayerHealthy => "relayer_healthy",
+            Self::HeartbeatTimeout => "heartbeat_timeout",
+            Self::ForcedDirect => "forced_direct",
+            Self::ForcedRelayer => "forced_relayer",
+        }
+    }
+}
+
+/// The state machine behind the [FetchStageManager], kept separate from the thread so the
+/// switching rules can be exercised without sockets or gossip.
+struct FetchStageState {
+    mode: FetchStageMode,
+    health: RelayerHealth,
+    heartbeat_received: bool,
+    relayer_tpu_addresses: Option<HeartbeatEvent>,
+    grace_window: Duration,
+}
+
+impl FetchStageState {
+    fn new(grace_window: Duration) -> Self {
+        Self {
+            mode: FetchStageMode::Direct,
+            health: RelayerHealth::Unhealthy,
+            heartbeat_received: false,
+            relayer_tpu_addresses: None,
+            grace_window,
+        }
+    }
+
+    fn on_heartbeat(
+        &mut self,
+        tpu_addresses: HeartbeatEvent,
+        now: Instant,
+        mode_override: FetchStageModeOverride,
+    ) -> Option<SwitchReason> {
+        self.heartbeat_received = true;
+        self.relayer_tpu_addresses = Some(tpu_addresses);
+        self.health = match self.health {
+            RelayerHealth::Unhealthy => RelayerHealth::Recovering { since: now },
+            RelayerHealth::Recovering { since }
+                if now.saturating_duration_since(since) >= self.grace_window =>
+            {
+                RelayerHealth::Healthy
+            }
+            health => health,
+        };
+        self.update_mode(mode_override)
+    }
+
+    fn on_heartbeat_tick(&mut self, mode_override: FetchStageModeOverride) -> Option<SwitchReason> {
+        if !self.heartbeat_received {
+            self.health = RelayerHealth::Unhealthy;
+        }
+        self.heartbeat_received = false;
+        self.update_mode(mode_override)
+    }
+
+    /// Moves to the mode the relayer's health and `mode_override` call for, returning why if
+    /// the mode changed.
+    fn update_mode(&mut self, mode_override: FetchStageModeOverride) -> Option<SwitchReason> {
+        let (mode, reason) = match mode_override {
+            FetchStageModeOverride::Direct => (FetchStageMode::Direct, SwitchReason::ForcedDirect),
+            FetchStageModeOverride::Relayer if self.relayer_tpu_addresses.is_some() => {
+                (FetchStageMode::Relayer, SwitchReason::ForcedRelayer)
+            }
+            FetchStageModeOverride::Relayer => return None,
+            FetchStageModeOverride::Auto => match self.health {
+                RelayerHealth::Healthy => (FetchStageMode::Relayer, SwitchReason::RelayerHealthy),
+                RelayerHealth::Unhealthy => {
+                    (FetchStageMode::Direct, SwitchReason::HeartbeatTimeout)
+                }
+                // Hold whatever mode we're in until the grace window has passed.
+                RelayerHealth::Recovering { .. } => return None,
+            },
+        };
+        if mode == self.mode {
+            return None;
+        }
+        self.mode = mode;
+        Some(reason)
+    }
+}
+
 /// Manages switching between the validator's tpu ports and that of the proxy's.
-/// Switch-overs are triggered by late and missed heartbeats.    
+/// Switch-overs are triggered by late and missed heartbeats, or forced over the admin RPC.
 pub struct FetchStageManager {
     t_hdl: JoinHandle<()>,
 }
@@ -29,12 +202,15 @@ impl FetchStageManager {
     pub fn new(
         // ClusterInfo is used to switch between advertising the proxy's TPU ports and that of this validator's.
         cluster_info: Arc<ClusterInfo>,
-        // Channel that heartbeats are received from. Entirely responsible for triggering switch-overs.
+        // Channel that heartbeats are received from. Responsible for triggering switch-overs unless overridden.
         heartbeat_rx: Receiver<HeartbeatEvent>,
         // Channel that packets from FetchStage are intercepted from.
         packet_intercept_rx: Receiver<PacketBatch>,
         // Intercepted packets get piped through here.
         packet_tx: Sender<PacketBatch>,
+        // How long the relayer has to be healthy before its TPU ports are advertised.
+        grace_window: Duration,
+        mode_override: Arc<RwLock<FetchStageModeOverride>>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let t_hdl = Self::start(
@@ -42,50 +218,51 @@ impl FetchStageManager {
             heartbeat_rx,
             packet_intercept_rx,
             packet_tx,
+            grace_window,
+            mode_override,
             exit,
         );
 
         Self { t_hdl }
     }
 
-    /// Disconnect fetch behaviour
-    /// Starts connected
-    /// When connected and a packet is received, forward it
-    /// When disconnected, packet is dropped
-    /// When receiving heartbeat while connected and not pending disconnect
-    ///      Sets pending_disconnect to true and records time
-    /// When receiving heartbeat while connected, and pending for > DISCONNECT_DELAY_SEC
-    ///      Sets fetch_connected to false, pending_disconnect to false
-    ///      Advertises TPU ports sent in heartbeat
-    /// When tick is received without heartbeat_received
-    ///      Sets fetch_connected to true, pending_disconnect to false
-    ///      Advertises saved contact info
+    /// Fetch behaviour
+    /// Starts in direct mode: the validator's own TPU ports are advertised and packets are forwarded
+    /// The relayer becomes healthy once it has sent heartbeats without missing one for the grace window
+    ///      Switches to relayer mode: the TPU ports sent in the heartbeat are advertised and packets are dropped
+    /// When a heartbeat tick passes without a heartbeat, the relayer becomes unhealthy
+    ///      Switches to direct mode: the saved contact info is advertised
+    /// A mode override set over the admin RPC takes precedence over the relayer's health
     fn start(
         cluster_info: Arc<ClusterInfo>,
         heartbeat_rx: Receiver<HeartbeatEvent>,
         packet_intercept_rx: Receiver<PacketBatch>,
         packet_tx: Sender<PacketBatch>,
+        grace_window: Duration,
+        mode_override: Arc<RwLock<FetchStageModeOverride>>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
         Builder::new().name("fetch-stage-manager".into()).spawn(move || {
             let my_fallback_contact_info = cluster_info.my_contact_info();
+            // unwrap safe here bc contact_info.tpu(Protocol::QUIC) and contact_info.tpu_forwards(Protocol::QUIC)
+            // are checked on startup
+            let my_tpu_addresses = (
+                my_fallback_contact_info.tpu(Protocol::QUIC).unwrap(),
+                my_fallback_contact_info.tpu_forwards(Protocol::QUIC).unwrap(),
+            );
 
-            let mut fetch_connected = true;
-            let mut heartbeat_received = false;
-            let mut pending_disconnect = false;
-
-            let mut pending_disconnect_ts = Instant::now();
+            let mut state = FetchStageState::new(grace_window);
 
             let heartbeat_tick = tick(HEARTBEAT_TIMEOUT);
             let metrics_tick = tick(METRICS_CADENCE);
             let mut packets_forwarded = 0;
             let mut heartbeats_received = 0;
             loop {
-                select! {
+                let switch_reason = select! {
                     recv(packet_intercept_rx) -> pkt => {
                         match pkt {
                             Ok(pkt) => {
-                                if fetch_connected {
+                                if state.mode == FetchStageMode::Direct {
                                     if packet_tx.send(pkt).is_err() {
                                         error!("{:?}", ProxyError::PacketForwardError);
                                         return;
@@ -98,62 +275,76 @@ impl FetchStageManager {
                                 return;
                             }
                         }
+                        None
                     }
                     recv(heartbeat_tick) -> _ => {
                         if exit.load(Ordering::Relaxed) {
                             break;
                         }
-                        if !heartbeat_received && (!fetch_connected || pending_disconnect) {
-                            warn!("heartbeat late, reconnecting fetch stage");
-                            fetch_connected = true;
-                            pending_disconnect = false;
-
-                            // unwrap safe here bc contact_info.tpu(Protocol::QUIC) and contact_info.tpu_forwards(Protocol::QUIC)
-                            // are checked on startup
-                            if let Err(e) = Self::set_tpu_addresses(&cluster_info, my_fallback_contact_info.tpu(Protocol::QUIC).unwrap(), my_fallback_contact_info.tpu_forwards(Protocol::QUIC).unwrap()) {
-                                error!("error setting tpu or tpu_fwd to ({:?}, {:?}), error: {:?}", my_fallback_contact_info.tpu(Protocol::QUIC).unwrap(), my_fallback_contact_info.tpu_forwards(Protocol::QUIC).unwrap(), e);
-                            }
-                            heartbeats_received = 0;
-                        }
-                        heartbeat_received = false;
+                        state.on_heartbeat_tick(*mode_override.read().unwrap())
                     }
                     recv(heartbeat_rx) -> tpu_info => {
-                        if let Ok((tpu_addr, tpu_forward_addr)) = tpu_info {
-                            heartbeats_received += 1;
-                            heartbeat_received = true;
-                            if fetch_connected && !pending_disconnect {
-                                info!("received heartbeat while fetch stage connected, pending disconnect after delay");
-                                pending_disconnect_ts = Instant::now();
-                                pending_disconnect = true;
-                            }
-                            if fetch_connected && pending_disconnect && pending_disconnect_ts.elapsed() > DISCONNECT_DELAY {
-                                info!("disconnecting fetch stage");
-                                fetch_connected = false;
-                                pending_disconnect = false;
-                                if let Err(e) = Self::set_tpu_addresses(&cluster_info, tpu_addr, tpu_forward_addr) {
-                                    error!("error setting tpu or tpu_fwd to ({:?}, {:?}), error: {:?}", tpu_addr, tpu_forward_addr, e);
-                                }
-                            }
-                        } else {
-                            {
-                                warn!("relayer heartbeat receiver disconnected, shutting down");
-                                return;
-                            }
-                        }
+                        let Ok(tpu_info) = tpu_info else {
+                            warn!("relayer heartbeat receiver disconnected, shutting down");
+                            return;
+                        };
+                        heartbeats_received += 1;
+                        state.on_heartbeat(tpu_info, Instant::now(), *mode_override.read().unwrap())
                     }
                     recv(metrics_tick) -> _ => {
                         datapoint_info!(
                             "relayer-heartbeat",
                             ("fetch_stage_packets_forwarded", packets_forwarded, i64),
                             ("heartbeats_received", heartbeats_received, i64),
+                            ("fetch_stage_mode", state.mode.as_str(), String),
                         );
+                        None
+                    }
+                };
 
+                if let Some(reason) = switch_reason {
+                    let (tpu_address, tpu_forward_address) = match state.mode {
+                        FetchStageMode::Direct => my_tpu_addresses,
+                        // unwrap safe here bc relayer mode is only entered once a heartbeat was received
+                        FetchStageMode::Relayer => state.relayer_tpu_addresses.unwrap(),
+                    };
+                    Self::report_switch(state.mode, reason, tpu_address, tpu_forward_address);
+                    if state.mode == FetchStageMode::Direct {
+                        heartbeats_received = 0;
+                    }
+                    if let Err(e) = Self::set_tpu_addresses(&cluster_info, tpu_address, tpu_forward_address) {
+                        error!("error setting tpu or tpu_fwd to ({:?}, {:?}), error: {:?}", tpu_address, tpu_forward_address, e);
                     }
                 }
             }
         }).unwrap()
     }
 
+    fn report_switch(
+        mode: FetchStageMode,
+        reason: SwitchReason,
+        tpu_address: SocketAddr,
+        tpu_forward_address: SocketAddr,
+    ) {
+        match reason {
+            SwitchReason::HeartbeatTimeout => warn!(
+                "switching fetch stage to {} mode ({}), advertising tpu {tpu_address} and tpu_fwd {tpu_forward_address}",
+                mode.as_str(),
+                reason.as_str(),
+            ),
+            _ => info!(
+                "switching fetch stage to {} mode ({}), advertising tpu {tpu_address} and tpu_fwd {tpu_forward_address}",
+                mode.as_str(),
+                reason.as_str(),
+            ),
+        }
+        datapoint_info!(
+            "fetch_stage_manager-switch",
+            ("mode", mode.as_str(), String),
+            ("reason", reason.as_str(), String),
+        );
+    }
+
     fn set_tpu_addresses(
         cluster_info: &Arc<ClusterInfo>,
         tpu_address: SocketAddr,
@@ -168,3 +359,130 @@ impl FetchStageManager {
         self.t_hdl.join()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::net::Ipv4Addr};
+
+    const GRACE_WINDOW: Duration = Duration::from_secs(60);
+
+    fn relayer_tpu_addresses() -> HeartbeatEvent {
+        (
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 11_222)),
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 11_223)),
+        )
+    }
+
+    #[test]
+    fn test_switch_to_relayer_after_grace_window() {
+        let mut state = FetchStageState::new(GRACE_WINDOW);
+        let start = Instant::now();
+        let auto = FetchStageModeOverride::Auto;
+
+        assert_eq!(
+            state.on_heartbeat(relayer_tpu_addresses(), start, auto),
+            None
+        );
+        assert_eq!(state.on_heartbeat_tick(auto), None);
+        assert_eq!(
+            state.on_heartbeat(relayer_tpu_addresses(), start + GRACE_WINDOW / 2, auto),
+            None
+        );
+        assert_eq!(state.mode, FetchStageMode::Direct);
+
+        assert_eq!(
+            state.on_heartbeat(relayer_tpu_addresses(), start + GRACE_WINDOW, auto),
+            Some(SwitchReason::RelayerHealthy)
+        );
+        assert_eq!(state.mode, FetchStageMode::Relayer);
+        assert_eq!(state.on_heartbeat_tick(auto), None);
+    }
+
+    #[test]
+    fn test_missed_heartbeat_restarts_grace_window() {
+        let mut state = FetchStageState::new(GRACE_WINDOW);
+        let start = Instant::now();
+        let auto = FetchStageModeOverride::Auto;
+
+        state.on_heartbeat(relayer_tpu_addresses(), start, auto);
+        state.on_heartbeat_tick(auto);
+        // A tick without a heartbeat marks the relayer unhealthy while still in direct mode.
+        assert_eq!(state.on_heartbeat_tick(auto), None);
+        assert_eq!(state.health, RelayerHealth::Unhealthy);
+
+        let restart = start + GRACE_WINDOW;
+        assert_eq!(
+            state.on_heartbeat(relayer_tpu_addresses(), restart, auto),
+            None
+        );
+        assert_eq!(state.health, RelayerHealth::Recovering { since: restart });
+        assert_eq!(state.mode, FetchStageMode::Direct);
+    }
+
+    #[test]
+    fn test_switch_to_direct_on_heartbeat_timeout() {
+        let mut state = FetchStageState::new(Duration::ZERO);
+        let now = Instant::now();
+        let auto = FetchStageModeOverride::Auto;
+
+        state.on_heartbeat(relayer_tpu_addresses(), now, auto);
+        assert_eq!(
+            state.on_heartbeat(relayer_tpu_addresses(), now, auto),
+            Some(SwitchReason::RelayerHealthy)
+        );
+        assert_eq!(state.on_heartbeat_tick(auto), None);
+        assert_eq!(
+            state.on_heartbeat_tick(auto),
+            Some(SwitchReason::HeartbeatTimeout)
+        );
+        assert_eq!(state.mode, FetchStageMode::Direct);
+    }
+
+    #[test]
+    fn test_mode_override() {
+        let mut state = FetchStageState::new(GRACE_WINDOW);
+        let now = Instant::now();
+
+        // Relayer mode can't be forced before the relayer's TPU ports are known.
+        assert_eq!(
+            state.on_heartbeat_tick(FetchStageModeOverride::Relayer),
+            None
+        );
+        assert_eq!(
+            state.on_heartbeat(
+                relayer_tpu_addresses(),
+                now,
+                FetchStageModeOverride::Relayer
+            ),
+            Some(SwitchReason::ForcedRelayer)
+        );
+        // Missed heartbeats don't switch back while relayer mode is forced.
+        state.on_heartbeat_tick(FetchStageModeOverride::Relayer);
+        assert_eq!(
+            state.on_heartbeat_tick(FetchStageModeOverride::Relayer),
+            None
+        );
+        assert_eq!(state.mode, FetchStageMode::Relayer);
+
+        assert_eq!(
+            state.on_heartbeat_tick(FetchStageModeOverride::Direct),
+            Some(SwitchReason::ForcedDirect)
+        );
+        assert_eq!(state.mode, FetchStageMode::Direct);
+    }
+
+    #[test]
+    fn test_parse_mode_override() {
+        for mode_override in [
+            FetchStageModeOverride::Auto,
+            FetchStageModeOverride::Direct,
+            FetchStageModeOverride::Relayer,
+        ] {
+            assert_eq!(
+                mode_override.to_string().parse::<FetchStageModeOverride>(),
+                Ok(mode_override)
+            );
+        }
+        assert!("tpu".parse::<FetchStageModeOverride>().is_err());
+    }
+}
@@ -0,0 +1,877 @@
+#![cfg(feature = "dev-context-only-utils")]
+//! An in-process stand-in for the Block Engine and Relayer backends.
+//!
+//! [`MockProxyServer`] serves the auth, block engine and relayer gRPC services on a single local
+//! port so [`BlockEngineStage`] and [`RelayerStage`] can be pointed at it from tests. It runs the
+//! same challenge/response flow the validator's auth client expects, rejects requests without a
+//! valid access token, and lets the test script exactly which bundles and packets are streamed
+//! to the validator. Messages published before the validator subscribes are buffered and flushed
+//! on subscription, so tests don't have to race the connection.
+//!
+//! [`BlockEngineStage`]: crate::proxy::block_engine_stage::BlockEngineStage
+//! [`RelayerStage`]: crate::proxy::relayer_stage::RelayerStage
+
+use {
+    crate::packet_bundle::PacketBundle,
+    chrono::Utc,
+    jito_protos::proto::{
+        auth::{
+            auth_service_server::{AuthService, AuthServiceServer},
+            GenerateAuthChallengeRequest, GenerateAuthChallengeResponse, GenerateAuthTokensRequest,
+            GenerateAuthTokensResponse, RefreshAccessTokenRequest, RefreshAccessTokenResponse,
+            Role, Token,
+        },
+        block_engine::{
+            self,
+            block_engine_validator_server::{BlockEngineValidator, BlockEngineValidatorServer},
+            BlockBuilderFeeInfoRequest, BlockBuilderFeeInfoResponse,
+        },
+        bundle::{Bundle, BundleUuid},
+        packet::{
+            Meta as ProtoMeta, Packet as ProtoPacket, PacketBatch as ProtoPacketBatch,
+            PacketFlags as ProtoPacketFlags,
+        },
+        relayer::{
+            self,
+            relayer_server::{Relayer, RelayerServer},
+            GetTpuConfigsRequest, GetTpuConfigsResponse,
+        },
+        shared::{Header, Heartbeat, Socket},
+    },
+    solana_perf::packet::PacketBatch,
+    solana_sdk::{
+        packet::{Packet, PacketFlags},
+        pubkey::Pubkey,
+        signature::Signature,
+    },
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        net::{SocketAddr, TcpListener},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+    tokio::{
+        sync::{mpsc, oneshot},
+        time::interval,
+    },
+    tokio_stream::wrappers::{TcpListenerStream, UnboundedReceiverStream},
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+#[derive(Clone, Debug)]
+pub struct MockProxyServerConfig {
+    /// If set, only these validator identities are allowed to authenticate. Everyone else gets
+    /// `PermissionDenied`, the same response a real backend gives a node that isn't scheduled.
+    pub allowed_validators: Option<HashSet<Pubkey>>,
+
+    /// Lifetime of issued access tokens.
+    pub access_token_ttl: Duration,
+
+    /// Lifetime of issued refresh tokens.
+    pub refresh_token_ttl: Duration,
+
+    /// Interval at which the relayer service sends heartbeats to subscribers.
+    pub heartbeat_interval: Duration,
+
+    /// TPU address handed out by the relayer service.
+    pub tpu: SocketAddr,
+
+    /// TPU forward address handed out by the relayer service.
+    pub tpu_forward: SocketAddr,
+
+    /// Block builder returned by `get_block_builder_fee_info`.
+    pub block_builder: Pubkey,
+
+    /// Block builder commission returned by `get_block_builder_fee_info`.
+    pub block_builder_commission: u64,
+}
+
+impl Default for MockProxyServerConfig {
+    fn default() -> Self {
+        Self {
+            allowed_validators: None,
+            access_token_ttl: Duration::from_secs(30 * 60),
+            refresh_token_ttl: Duration::from_secs(24 * 60 * 60),
+            heartbeat_interval: Duration::from_millis(100),
+            tpu: SocketAddr::from(([127, 0, 0, 1], 0)),
+            tpu_forward: SocketAddr::from(([127, 0, 0, 1], 0)),
+            block_builder: Pubkey::default(),
+            block_builder_commission: 0,
+        }
+    }
+}
+
+/// Snapshot of what the mock backend has seen and sent so far.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockProxyServerStats {
+    pub num_auth_challenges: u64,
+    pub num_tokens_generated: u64,
+    pub num_access_tokens_refreshed: u64,
+    pub num_auth_failures: u64,
+    pub num_block_engine_packet_subscriptions: u64,
+    pub num_bundle_subscriptions: u64,
+    pub num_relayer_packet_subscriptions: u64,
+    pub num_bundles_sent: u64,
+    pub num_packets_sent: u64,
+    pub num_heartbeats_sent: u64,
+}
+
+#[derive(Default)]
+struct MockProxyServerCounters {
+    num_auth_challenges: AtomicU64,
+    num_tokens_generated: AtomicU64,
+    num_access_tokens_refreshed: AtomicU64,
+    num_auth_failures: AtomicU64,
+    num_block_engine_packet_subscriptions: AtomicU64,
+    num_bundle_subscriptions: AtomicU64,
+    num_relayer_packet_subscriptions: AtomicU64,
+    num_bundles_sent: AtomicU64,
+    num_packets_sent: AtomicU64,
+    num_heartbeats_sent: AtomicU64,
+}
+
+impl MockProxyServerCounters {
+    fn snapshot(&self) -> MockProxyServerStats {
+        MockProxyServerStats {
+            num_auth_challenges: self.num_auth_challenges.load(Ordering::Relaxed),
+            num_tokens_generated: self.num_tokens_generated.load(Ordering::Relaxed),
+            num_access_tokens_refreshed: self.num_access_tokens_refreshed.load(Ordering::Relaxed),
+            num_auth_failures: self.num_auth_failures.load(Ordering::Relaxed),
+            num_block_engine_packet_subscriptions: self
+                .num_block_engine_packet_subscriptions
+                .load(Ordering::Relaxed),
+            num_bundle_subscriptions: self.num_bundle_subscriptions.load(Ordering::Relaxed),
+            num_relayer_packet_subscriptions: self
+                .num_relayer_packet_subscriptions
+                .load(Ordering::Relaxed),
+            num_bundles_sent: self.num_bundles_sent.load(Ordering::Relaxed),
+            num_packets_sent: self.num_packets_sent.load(Ordering::Relaxed),
+            num_heartbeats_sent: self.num_heartbeats_sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Fan-out of a scripted message stream to every live subscriber. Messages published while
+/// nobody is subscribed are held until the next subscription.
+struct Subscribers<T> {
+    senders: Vec<mpsc::UnboundedSender<Result<T, Status>>>,
+    pending: VecDeque<T>,
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            senders: Vec::default(),
+            pending: VecDeque::default(),
+        }
+    }
+}
+
+impl<T: Clone> Subscribers<T> {
+    fn subscribe(&mut self) -> UnboundedReceiverStream<Result<T, Status>> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        for msg in self.pending.drain(..) {
+            // receiver is alive, can't fail
+            let _ = sender.send(Ok(msg));
+        }
+        self.senders.push(sender);
+        UnboundedReceiverStream::new(receiver)
+    }
+
+    fn publish(&mut self, msg: T) {
+        self.senders
+            .retain(|sender| sender.send(Ok(msg.clone())).is_ok());
+        if self.senders.is_empty() {
+            self.pending.push_back(msg);
+        }
+    }
+
+    /// Closes every open stream, which the validator observes as a disconnect.
+    fn disconnect_all(&mut self) {
+        self.senders.clear();
+    }
+}
+
+#[derive(Default)]
+struct MockProxyServerState {
+    config: MockProxyServerConfig,
+    counters: MockProxyServerCounters,
+    /// Outstanding auth challenges keyed by the validator that requested them.
+    challenges: Mutex<HashMap<Pubkey, String>>,
+    /// Issued access tokens and their expiry (unix seconds).
+    access_tokens: Mutex<HashMap<String, i64>>,
+    /// Issued refresh tokens, the validator they belong to and their expiry (unix seconds).
+    refresh_tokens: Mutex<HashMap<String, (Pubkey, i64)>>,
+    bundles: Mutex<Subscribers<block_engine::SubscribeBundlesResponse>>,
+    block_engine_packets: Mutex<Subscribers<block_engine::SubscribePacketsResponse>>,
+    relayer_packets: Mutex<Subscribers<relayer::SubscribePacketsResponse>>,
+}
+
+impl MockProxyServerState {
+    fn new(config: MockProxyServerConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    fn issue_token(&self, ttl: Duration) -> Token {
+        Token {
+            value: format!("{:032x}", rand::random::<u128>()),
+            expires_at_utc: Some(prost_types::Timestamp {
+                seconds: Utc::now().timestamp() + ttl.as_secs() as i64,
+                nanos: 0,
+            }),
+        }
+    }
+
+    fn issue_access_token(&self) -> Token {
+        let token = self.issue_token(self.config.access_token_ttl);
+        self.access_tokens.lock().unwrap().insert(
+            token.value.clone(),
+            token.expires_at_utc.as_ref().unwrap().seconds,
+        );
+        token
+    }
+
+    fn issue_refresh_token(&self, pubkey: Pubkey) -> Token {
+        let token = self.issue_token(self.config.refresh_token_ttl);
+        self.refresh_tokens.lock().unwrap().insert(
+            token.value.clone(),
+            (pubkey, token.expires_at_utc.as_ref().unwrap().seconds),
+        );
+        token
+    }
+
+    /// Rejects any request that doesn't carry a live access token issued by this server.
+    fn check_access_token<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string);
+
+        let is_valid = token
+            .and_then(|token| self.access_tokens.lock().unwrap().get(&token).copied())
+            .map(|expires_at| expires_at > Utc::now().timestamp())
+            .unwrap_or_default();
+        if is_valid {
+            Ok(request)
+        } else {
+            self.counters
+                .num_auth_failures
+                .fetch_add(1, Ordering::Relaxed);
+            Err(Status::unauthenticated("missing or expired access token"))
+        }
+    }
+
+    fn auth_failure(&self, status: Status) -> Status {
+        self.counters
+            .num_auth_failures
+            .fetch_add(1, Ordering::Relaxed);
+        status
+    }
+}
+
+struct MockAuthService {
+    state: Arc<MockProxyServerState>,
+}
+
+#[tonic::async_trait]
+impl AuthService for MockAuthService {
+    async fn generate_auth_challenge(
+        &self,
+        request: Request<GenerateAuthChallengeRequest>,
+    ) -> Result<Response<GenerateAuthChallengeResponse>, Status> {
+        let request = request.into_inner();
+        self.state
+            .counters
+            .num_auth_challenges
+            .fetch_add(1, Ordering::Relaxed);
+
+        if request.role != Role::Validator as i32 {
+            return Err(self
+                .state
+                .auth_failure(Status::invalid_argument("only validators are supported")));
+        }
+        let pubkey = Pubkey::try_from(request.pubkey.as_slice()).map_err(|_| {
+            self.state
+                .auth_failure(Status::invalid_argument("bad pubkey"))
+        })?;
+        if let Some(allowed_validators) = &self.state.config.allowed_validators {
+            if !allowed_validators.contains(&pubkey) {
+                return Err(self
+                    .state
+                    .auth_failure(Status::permission_denied("validator not allowed")));
+            }
+        }
+
+        let challenge = format!("{:016x}", rand::random::<u64>());
+        self.state
+            .challenges
+            .lock()
+            .unwrap()
+            .insert(pubkey, challenge.clone());
+
+        Ok(Response::new(GenerateAuthChallengeResponse { challenge }))
+    }
+
+    async fn generate_auth_tokens(
+        &self,
+        request: Request<GenerateAuthTokensRequest>,
+    ) -> Result<Response<GenerateAuthTokensResponse>, Status> {
+        let request = request.into_inner();
+        let pubkey = Pubkey::try_from(request.client_pubkey.as_slice()).map_err(|_| {
+            self.state
+                .auth_failure(Status::invalid_argument("bad pubkey"))
+        })?;
+
+        let challenge = self
+            .state
+            .challenges
+            .lock()
+            .unwrap()
+            .remove(&pubkey)
+            .ok_or_else(|| {
+                self.state
+                    .auth_failure(Status::failed_precondition("no outstanding challenge"))
+            })?;
+        if request.challenge != format!("{pubkey}-{challenge}") {
+            return Err(self
+                .state
+                .auth_failure(Status::invalid_argument("challenge mismatch")));
+        }
+        let signature = Signature::try_from(request.signed_challenge.as_slice()).map_err(|_| {
+            self.state
+                .auth_failure(Status::invalid_argument("bad signature"))
+        })?;
+        if !signature.verify(pubkey.as_ref(), request.challenge.as_bytes()) {
+            return Err(self
+                .state
+                .auth_failure(Status::unauthenticated("signature verification failed")));
+        }
+
+        self.state
+            .counters
+            .num_tokens_generated
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(GenerateAuthTokensResponse {
+            access_token: Some(self.state.issue_access_token()),
+            refresh_token: Some(self.state.issue_refresh_token(pubkey)),
+        }))
+    }
+
+    async fn refresh_access_token(
+        &self,
+        request: Request<RefreshAccessTokenRequest>,
+    ) -> Result<Response<RefreshAccessTokenResponse>, Status> {
+        let request = request.into_inner();
+        let is_valid = self
+            .state
+            .refresh_tokens
+            .lock()
+            .unwrap()
+            .get(&request.refresh_token)
+            .map(|(_, expires_at)| *expires_at > Utc::now().timestamp())
+            .unwrap_or_default();
+        if !is_valid {
+            return Err(self
+                .state
+                .auth_failure(Status::unauthenticated("invalid refresh token")));
+        }
+
+        self.state
+            .counters
+            .num_access_tokens_refreshed
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(RefreshAccessTokenResponse {
+            access_token: Some(self.state.issue_access_token()),
+        }))
+    }
+}
+
+struct MockBlockEngineService {
+    state: Arc<MockProxyServerState>,
+}
+
+#[tonic::async_trait]
+impl BlockEngineValidator for MockBlockEngineService {
+    type SubscribePacketsStream =
+        UnboundedReceiverStream<Result<block_engine::SubscribePacketsResponse, Status>>;
+    type SubscribeBundlesStream =
+        UnboundedReceiverStream<Result<block_engine::SubscribeBundlesResponse, Status>>;
+
+    async fn subscribe_packets(
+        &self,
+        _request: Request<block_engine::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.state
+            .counters
+            .num_block_engine_packet_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(
+            self.state.block_engine_packets.lock().unwrap().subscribe(),
+        ))
+    }
+
+    async fn subscribe_bundles(
+        &self,
+        _request: Request<block_engine::SubscribeBundlesRequest>,
+    ) -> Result<Response<Self::SubscribeBundlesStream>, Status> {
+        self.state
+            .counters
+            .num_bundle_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(
+            self.state.bundles.lock().unwrap().subscribe(),
+        ))
+    }
+
+    async fn get_block_builder_fee_info(
+        &self,
+        _request: Request<BlockBuilderFeeInfoRequest>,
+    ) -> Result<Response<BlockBuilderFeeInfoResponse>, Status> {
+        Ok(Response::new(BlockBuilderFeeInfoResponse {
+            pubkey: self.state.config.block_builder.to_string(),
+            commission: self.state.config.block_builder_commission,
+        }))
+    }
+}
+
+struct MockRelayerService {
+    state: Arc<MockProxyServerState>,
+}
+
+#[tonic::async_trait]
+impl Relayer for MockRelayerService {
+    type SubscribePacketsStream =
+        UnboundedReceiverStream<Result<relayer::SubscribePacketsResponse, Status>>;
+
+    async fn get_tpu_configs(
+        &self,
+        _request: Request<GetTpuConfigsRequest>,
+    ) -> Result<Response<GetTpuConfigsResponse>, Status> {
+        let to_socket = |addr: &SocketAddr| Socket {
+            ip: addr.ip().to_string(),
+            port: addr.port() as i64,
+        };
+        Ok(Response::new(GetTpuConfigsResponse {
+            tpu: Some(to_socket(&self.state.config.tpu)),
+            tpu_forward: Some(to_socket(&self.state.config.tpu_forward)),
+        }))
+    }
+
+    async fn subscribe_packets(
+        &self,
+        _request: Request<relayer::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.state
+            .counters
+            .num_relayer_packet_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(
+            self.state.relayer_packets.lock().unwrap().subscribe(),
+        ))
+    }
+}
+
+/// Sends a heartbeat on the relayer packet stream every `heartbeat_interval` while any
+/// subscriber is connected.
+async fn run_heartbeats(state: Arc<MockProxyServerState>) {
+    let mut heartbeat_tick = interval(state.config.heartbeat_interval);
+    let mut count: u64 = 0;
+    loop {
+        heartbeat_tick.tick().await;
+        let mut relayer_packets = state.relayer_packets.lock().unwrap();
+        if relayer_packets.senders.is_empty() {
+            continue;
+        }
+        count += 1;
+        relayer_packets.senders.retain(|sender| {
+            sender
+                .send(Ok(relayer::SubscribePacketsResponse {
+                    header: Some(new_header()),
+                    msg: Some(relayer::subscribe_packets_response::Msg::Heartbeat(
+                        Heartbeat { count },
+                    )),
+                }))
+                .is_ok()
+        });
+        state
+            .counters
+            .num_heartbeats_sent
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn new_header() -> Header {
+    Header {
+        ts: Some(prost_types::Timestamp {
+            seconds: Utc::now().timestamp(),
+            nanos: 0,
+        }),
+    }
+}
+
+/// Inverse of [`crate::proto_packet_to_packet`].
+pub fn packet_to_proto_packet(packet: &Packet) -> ProtoPacket {
+    let meta = packet.meta();
+    ProtoPacket {
+        data: packet.data(..).unwrap_or_default().to_vec(),
+        meta: Some(ProtoMeta {
+            size: meta.size as u64,
+            addr: meta.addr.to_string(),
+            port: meta.port as u32,
+            flags: Some(ProtoPacketFlags {
+                discard: meta.discard(),
+                forwarded: meta.forwarded(),
+                repair: meta.repair(),
+                simple_vote_tx: meta.flags.contains(PacketFlags::SIMPLE_VOTE_TX),
+                tracer_packet: meta.is_tracer_packet(),
+            }),
+            sender_stake: 0,
+        }),
+    }
+}
+
+fn packet_batch_to_proto_packet_batch(batch: &PacketBatch) -> ProtoPacketBatch {
+    ProtoPacketBatch {
+        packets: batch.iter().map(packet_to_proto_packet).collect(),
+    }
+}
+
+/// Serves the auth, block engine and relayer services on a local port until dropped.
+pub struct MockProxyServer {
+    addr: SocketAddr,
+    state: Arc<MockProxyServerState>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    t_hdl: Option<JoinHandle<()>>,
+}
+
+impl MockProxyServer {
+    pub fn new(config: MockProxyServerConfig) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let state = Arc::new(MockProxyServerState::new(config));
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let t_hdl = {
+            let state = state.clone();
+            Builder::new()
+                .name("mock-proxy-server".to_string())
+                .spawn(move || {
+                    let rt = tokio::runtime::Builder::new_multi_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap();
+                    rt.block_on(Self::serve(listener, state, shutdown_rx));
+                })
+                .unwrap()
+        };
+
+        Self {
+            addr,
+            state,
+            shutdown_tx: Some(shutdown_tx),
+            t_hdl: Some(t_hdl),
+        }
+    }
+
+    async fn serve(
+        listener: TcpListener,
+        state: Arc<MockProxyServerState>,
+        shutdown_rx: oneshot::Receiver<()>,
+    ) {
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        let heartbeats = tokio::spawn(run_heartbeats(state.clone()));
+
+        let block_engine_state = state.clone();
+        let relayer_state = state.clone();
+        let result = Server::builder()
+            .add_service(AuthServiceServer::new(MockAuthService {
+                state: state.clone(),
+            }))
+            .add_service(BlockEngineValidatorServer::with_interceptor(
+                MockBlockEngineService {
+                    state: state.clone(),
+                },
+                move |request| block_engine_state.check_access_token(request),
+            ))
+            .add_service(RelayerServer::with_interceptor(
+                MockRelayerService {
+                    state: state.clone(),
+                },
+                move |request| relayer_state.check_access_token(request),
+            ))
+            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        heartbeats.abort();
+
+        if let Err(e) = result {
+            error!("mock proxy server error: {e:?}");
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// URL to use for both `BlockEngineConfig::block_engine_urls` and `RelayerConfig::relayer_url`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn stats(&self) -> MockProxyServerStats {
+        self.state.counters.snapshot()
+    }
+
+    /// Streams `bundles` to the validator in a single `SubscribeBundlesResponse`.
+    pub fn send_bundles(&self, bundles: &[PacketBundle]) {
+        let bundles = bundles
+            .iter()
+            .map(|bundle| BundleUuid {
+                bundle: Some(Bundle {
+                    header: Some(new_header()),
+                    packets: bundle.batch.iter().map(packet_to_proto_packet).collect(),
+                }),
+                uuid: bundle.bundle_id.clone(),
+            })
+            .collect::<Vec<_>>();
+        self.state
+            .counters
+            .num_bundles_sent
+            .fetch_add(bundles.len() as u64, Ordering::Relaxed);
+        self.state
+            .bundles
+            .lock()
+            .unwrap()
+            .publish(block_engine::SubscribeBundlesResponse { bundles });
+    }
+
+    /// Streams `batch` to the validator over the block engine packet stream.
+    pub fn send_block_engine_packets(&self, batch: &PacketBatch) {
+        self.state
+            .counters
+            .num_packets_sent
+            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        self.state.block_engine_packets.lock().unwrap().publish(
+            block_engine::SubscribePacketsResponse {
+                header: Some(new_header()),
+                batch: Some(packet_batch_to_proto_packet_batch(batch)),
+            },
+        );
+    }
+
+    /// Streams `batch` to the validator over the relayer packet stream.
+    pub fn send_relayer_packets(&self, batch: &PacketBatch) {
+        self.state
+            .counters
+            .num_packets_sent
+            .fetch_add(batch.len() as u64, Ordering::Relaxed);
+        self.state
+            .relayer_packets
+            .lock()
+            .unwrap()
+            .publish(relayer::SubscribePacketsResponse {
+                header: Some(new_header()),
+                msg: Some(relayer::subscribe_packets_response::Msg::Batch(
+                    packet_batch_to_proto_packet_batch(batch),
+                )),
+            });
+    }
+
+    /// Drops every open stream. The validator sees `GrpcStreamDisconnected` and reconnects.
+    pub fn disconnect_all(&self) {
+        self.state.bundles.lock().unwrap().disconnect_all();
+        self.state
+            .block_engine_packets
+            .lock()
+            .unwrap()
+            .disconnect_all();
+        self.state.relayer_packets.lock().unwrap().disconnect_all();
+    }
+
+    pub fn join(mut self) -> thread::Result<()> {
+        self.shutdown();
+        self.t_hdl.take().unwrap().join()
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+    }
+}
+
+impl Drop for MockProxyServer {
+    fn drop(&mut self) {
+        self.shutdown();
+        if let Some(t_hdl) = self.t_hdl.take() {
+            let _ = t_hdl.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::proxy::{
+            auth::{generate_auth_tokens, refresh_access_token, AuthInterceptor},
+            ProxyError,
+        },
+        jito_protos::proto::{
+            auth::auth_service_client::AuthServiceClient,
+            block_engine::block_engine_validator_client::BlockEngineValidatorClient,
+            relayer::relayer_client::RelayerClient,
+        },
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+        tonic::transport::Endpoint,
+    };
+
+    fn new_runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    async fn connect(server: &MockProxyServer) -> tonic::transport::Channel {
+        Endpoint::from_shared(server.url())
+            .unwrap()
+            .connect()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_auth_challenge_flow() {
+        let server = MockProxyServer::new(MockProxyServerConfig::default());
+        let keypair = Keypair::new();
+
+        new_runtime().block_on(async {
+            let mut auth_client = AuthServiceClient::new(connect(&server).await);
+            let (access_token, refresh_token) = generate_auth_tokens(&mut auth_client, &keypair)
+                .await
+                .unwrap();
+            assert!(access_token.expires_at_utc.is_some());
+
+            let new_access_token = refresh_access_token(&mut auth_client, &refresh_token)
+                .await
+                .unwrap();
+            assert_ne!(new_access_token.value, access_token.value);
+        });
+
+        assert_eq!(
+            server.stats(),
+            MockProxyServerStats {
+                num_auth_challenges: 1,
+                num_tokens_generated: 1,
+                num_access_tokens_refreshed: 1,
+                ..MockProxyServerStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_auth_permission_denied() {
+        let server = MockProxyServer::new(MockProxyServerConfig {
+            allowed_validators: Some(HashSet::from([Pubkey::new_unique()])),
+            ..MockProxyServerConfig::default()
+        });
+
+        new_runtime().block_on(async {
+            let mut auth_client = AuthServiceClient::new(connect(&server).await);
+            assert_matches!(
+                generate_auth_tokens(&mut auth_client, &Keypair::new()).await,
+                Err(ProxyError::AuthenticationPermissionDenied)
+            );
+        });
+        assert_eq!(server.stats().num_auth_failures, 1);
+    }
+
+    #[test]
+    fn test_unauthenticated_stream_rejected() {
+        let server = MockProxyServer::new(MockProxyServerConfig::default());
+
+        new_runtime().block_on(async {
+            let mut client = BlockEngineValidatorClient::new(connect(&server).await);
+            let status = client
+                .subscribe_bundles(block_engine::SubscribeBundlesRequest {})
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::Unauthenticated);
+        });
+        assert_eq!(server.stats().num_bundle_subscriptions, 0);
+    }
+
+    #[test]
+    fn test_scripted_bundles_and_heartbeats() {
+        let server = MockProxyServer::new(MockProxyServerConfig::default());
+        let keypair = Keypair::new();
+
+        // published before anyone subscribes, must be buffered
+        let tx = system_transaction::transfer(&keypair, &Pubkey::new_unique(), 1, Hash::default());
+        let bundle = PacketBundle {
+            batch: PacketBatch::new(vec![Packet::from_data(None, &tx).unwrap()]),
+            bundle_id: "bundle-0".to_string(),
+        };
+        server.send_bundles(&[bundle.clone()]);
+
+        new_runtime().block_on(async {
+            let channel = connect(&server).await;
+            let mut auth_client = AuthServiceClient::new(channel.clone());
+            let (access_token, _) = generate_auth_tokens(&mut auth_client, &keypair)
+                .await
+                .unwrap();
+            let access_token = Arc::new(Mutex::new(access_token));
+
+            let mut block_engine_client = BlockEngineValidatorClient::with_interceptor(
+                channel.clone(),
+                AuthInterceptor::new(access_token.clone()),
+            );
+            let mut bundle_stream = block_engine_client
+                .subscribe_bundles(block_engine::SubscribeBundlesRequest {})
+                .await
+                .unwrap()
+                .into_inner();
+            let received = bundle_stream.message().await.unwrap().unwrap();
+            assert_eq!(received.bundles.len(), 1);
+            assert_eq!(received.bundles[0].uuid, bundle.bundle_id);
+            let packets = received.bundles[0].bundle.clone().unwrap().packets;
+            assert_eq!(
+                crate::proto_packet_to_packet(packets[0].clone()).data(..),
+                bundle.batch[0].data(..)
+            );
+
+            let mut relayer_client =
+                RelayerClient::with_interceptor(channel, AuthInterceptor::new(access_token));
+            let mut packet_stream = relayer_client
+                .subscribe_packets(relayer::SubscribePacketsRequest {})
+                .await
+                .unwrap()
+                .into_inner();
+            let msg = packet_stream.message().await.unwrap().unwrap();
+            assert_matches!(
+                msg.msg,
+                Some(relayer::subscribe_packets_response::Msg::Heartbeat(_))
+            );
+        });
+
+        let stats = server.stats();
+        assert_eq!(stats.num_bundles_sent, 1);
+        assert_eq!(stats.num_bundle_subscriptions, 1);
+        assert_eq!(stats.num_relayer_packet_subscriptions, 1);
+        assert!(stats.num_heartbeats_sent >= 1);
+        server.join().unwrap();
+    }
+}
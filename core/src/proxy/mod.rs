@@ -13,20 +13,106 @@
 mod auth;
 pub mod block_engine_stage;
 pub mod fetch_stage_manager;
+pub mod mock_server;
 pub mod relayer_stage;
 
 use {
+    chrono::{TimeZone, Utc},
+    solana_sdk::timing::timestamp,
     std::{
+        fmt::{self, Display},
         net::{AddrParseError, SocketAddr},
         result,
+        sync::{Arc, RwLock},
     },
     thiserror::Error,
+    tokio::task,
     tonic::Status,
 };
 
 type Result<T> = result::Result<T, ProxyError>;
 type HeartbeatEvent = (SocketAddr, SocketAddr);
 
+/// Connection state of the [block_engine_stage::BlockEngineStage] or [relayer_stage::RelayerStage],
+/// exposed over the admin RPC. Timestamps are unix timestamps in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyConnectionStatus {
+    /// The endpoint the stage is currently authenticated with and streaming from.
+    pub connected_endpoint: Option<String>,
+    pub access_token_expiry_ms: Option<u64>,
+    pub refresh_token_expiry_ms: Option<u64>,
+    /// Only relayers send heartbeats.
+    pub last_heartbeat_ms: Option<u64>,
+    /// Only block engines send bundles.
+    pub num_bundles_received: u64,
+    pub num_packets_received: u64,
+    pub last_error: Option<String>,
+    pub last_error_ms: Option<u64>,
+}
+
+impl ProxyConnectionStatus {
+    fn on_disconnected(&mut self, error: Option<String>) {
+        self.connected_endpoint = None;
+        self.access_token_expiry_ms = None;
+        self.refresh_token_expiry_ms = None;
+        if error.is_some() {
+            self.last_error = error;
+            self.last_error_ms = Some(timestamp());
+        }
+    }
+}
+
+impl Display for ProxyConnectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn format_timestamp(timestamp_ms: Option<u64>) -> String {
+            timestamp_ms
+                .and_then(|ms| Utc.timestamp_millis_opt(ms as i64).single())
+                .map(|datetime| datetime.to_rfc3339())
+                .unwrap_or_else(|| "-".to_string())
+        }
+
+        writeln!(
+            f,
+            "Connected Endpoint: {}",
+            self.connected_endpoint.as_deref().unwrap_or("-")
+        )?;
+        writeln!(
+            f,
+            "Access Token Expiry: {}",
+            format_timestamp(self.access_token_expiry_ms)
+        )?;
+        writeln!(
+            f,
+            "Refresh Token Expiry: {}",
+            format_timestamp(self.refresh_token_expiry_ms)
+        )?;
+        writeln!(
+            f,
+            "Last Heartbeat: {}",
+            format_timestamp(self.last_heartbeat_ms)
+        )?;
+        writeln!(f, "Bundles Received: {}", self.num_bundles_received)?;
+        writeln!(f, "Packets Received: {}", self.num_packets_received)?;
+        writeln!(
+            f,
+            "Last Error: {} ({})",
+            self.last_error.as_deref().unwrap_or("-"),
+            format_timestamp(self.last_error_ms)
+        )
+    }
+}
+
+/// Applies `update` to a status shared with the admin RPC without blocking the runtime.
+async fn update_status<T: Send + Sync + 'static>(
+    status: &Arc<RwLock<T>>,
+    update: impl FnOnce(&mut T) + Send + 'static,
+) {
+    let status = status.clone();
+    task::spawn_blocking(move || update(&mut status.write().unwrap()))
+        .await
+        .unwrap();
+}
+
 #[derive(Error, Debug)]
 pub enum ProxyError {
     #[error("grpc error: {0}")]
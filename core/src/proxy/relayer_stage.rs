@@ -13,8 +13,10 @@ use {
         banking_trace::BankingPacketSender,
         proto_packet_to_packet,
         proxy::{
-            auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
-            HeartbeatEvent, ProxyError,
+            auth::{
+                generate_auth_tokens, maybe_refresh_auth_tokens, token_expiry_ms, AuthInterceptor,
+            },
+            update_status, HeartbeatEvent, ProxyConnectionStatus, ProxyError,
         },
     },
     crossbeam_channel::Sender,
@@ -27,13 +29,14 @@ use {
     solana_sdk::{
         saturating_add_assign,
         signature::{Keypair, Signer},
+        timing::timestamp,
     },
     std::{
         net::{IpAddr, Ipv4Addr, SocketAddr},
         str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc, Mutex,
+            Arc, Mutex, RwLock,
         },
         thread::{self, Builder, JoinHandle},
         time::{Duration, Instant},
@@ -90,8 +93,11 @@ pub struct RelayerStage {
 }
 
 impl RelayerStage {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         relayer_config: Arc<Mutex<RelayerConfig>>,
+        // Updated with the state of the connection.
+        relayer_status: Arc<RwLock<ProxyConnectionStatus>>,
         // The keypair stored here is used to sign auth challenges.
         cluster_info: Arc<ClusterInfo>,
         // Channel that server-sent heartbeats are piped through.
@@ -112,6 +118,7 @@ impl RelayerStage {
 
                 rt.block_on(Self::start(
                     relayer_config,
+                    relayer_status,
                     cluster_info,
                     heartbeat_tx,
                     packet_tx,
@@ -136,6 +143,7 @@ impl RelayerStage {
     #[allow(clippy::too_many_arguments)]
     async fn start(
         relayer_config: Arc<Mutex<RelayerConfig>>,
+        relayer_status: Arc<RwLock<ProxyConnectionStatus>>,
         cluster_info: Arc<ClusterInfo>,
         heartbeat_tx: Sender<HeartbeatEvent>,
         packet_tx: Sender<PacketBatch>,
@@ -149,7 +157,6 @@ impl RelayerStage {
 
         while !exit.load(Ordering::Relaxed) {
             // Wait until a valid config is supplied (either initially or by admin rpc)
-            // Use if!/else here to avoid extra CONNECTION_BACKOFF wait on successful termination
             let local_relayer_config = {
                 let relayer_config = relayer_config.clone();
                 task::spawn_blocking(move || relayer_config.lock().unwrap().clone())
@@ -158,9 +165,13 @@ impl RelayerStage {
             };
             if !Self::is_valid_relayer_config(&local_relayer_config) {
                 sleep(CONNECTION_BACKOFF).await;
-            } else if let Err(e) = Self::connect_auth_and_stream(
+                continue;
+            }
+
+            let result = Self::connect_auth_and_stream(
                 &local_relayer_config,
                 &relayer_config,
+                &relayer_status,
                 &cluster_info,
                 &heartbeat_tx,
                 &packet_tx,
@@ -168,8 +179,11 @@ impl RelayerStage {
                 &exit,
                 &CONNECTION_TIMEOUT,
             )
-            .await
-            {
+            .await;
+            let error = result.as_ref().err().map(|e| e.to_string());
+            update_status(&relayer_status, move |status| status.on_disconnected(error)).await;
+
+            if let Err(e) = result {
                 match e {
                     // This error is frequent on hot spares, and the parsed string does not work
                     // with datapoints (incorrect escaping).
@@ -190,9 +204,11 @@ impl RelayerStage {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
         local_relayer_config: &RelayerConfig,
         global_relayer_config: &Arc<Mutex<RelayerConfig>>,
+        relayer_status: &Arc<RwLock<ProxyConnectionStatus>>,
         cluster_info: &Arc<ClusterInfo>,
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
@@ -252,14 +268,26 @@ impl RelayerStage {
             .map_err(|_| ProxyError::RelayerConnectionTimeout)?
             .map_err(|e| ProxyError::RelayerConnectionError(e.to_string()))?;
 
+        let access_token_expiry_ms = token_expiry_ms(&access_token);
         let access_token = Arc::new(Mutex::new(access_token));
         let relayer_client = RelayerClient::with_interceptor(
             relayer_channel,
             AuthInterceptor::new(access_token.clone()),
         );
+        {
+            let connected_endpoint = Some(local_relayer_config.relayer_url.clone());
+            let refresh_token_expiry_ms = token_expiry_ms(&refresh_token);
+            update_status(relayer_status, move |status| {
+                status.connected_endpoint = connected_endpoint;
+                status.access_token_expiry_ms = access_token_expiry_ms;
+                status.refresh_token_expiry_ms = refresh_token_expiry_ms;
+            })
+            .await;
+        }
 
         Self::start_consuming_relayer_packets(
             relayer_client,
+            relayer_status,
             heartbeat_tx,
             packet_tx,
             banking_packet_sender,
@@ -279,6 +307,7 @@ impl RelayerStage {
     #[allow(clippy::too_many_arguments)]
     async fn start_consuming_relayer_packets(
         mut client: RelayerClient<InterceptedService<Channel, AuthInterceptor>>,
+        relayer_status: &Arc<RwLock<ProxyConnectionStatus>>,
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
@@ -327,6 +356,7 @@ impl RelayerStage {
         .into_inner();
 
         Self::consume_packet_stream(
+            relayer_status,
             heartbeat_event,
             heartbeat_tx,
             packet_stream,
@@ -347,6 +377,7 @@ impl RelayerStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn consume_packet_stream(
+        relayer_status: &Arc<RwLock<ProxyConnectionStatus>>,
         heartbeat_event: HeartbeatEvent,
         heartbeat_tx: &Sender<HeartbeatEvent>,
         mut packet_stream: Streaming<relayer::SubscribePacketsResponse>,
@@ -389,6 +420,15 @@ impl RelayerStage {
                 }
                 _ = metrics_and_auth_tick.tick() => {
                     relayer_stats.report();
+                    let num_packets = relayer_stats.num_packets;
+                    let last_heartbeat_ms = (relayer_stats.num_heartbeats > 0)
+                        .then(|| timestamp().saturating_sub(last_heartbeat_ts.elapsed().as_millis() as u64));
+                    update_status(relayer_status, move |status| {
+                        saturating_add_assign!(status.num_packets_received, num_packets);
+                        if last_heartbeat_ms.is_some() {
+                            status.last_heartbeat_ms = last_heartbeat_ms;
+                        }
+                    }).await;
                     relayer_stats = RelayerStageStats::default();
 
                     if cluster_info.id() != keypair.pubkey() {
@@ -418,6 +458,8 @@ impl RelayerStage {
                             ("count", num_refresh_access_token, i64),
                         );
 
+                        let access_token_expiry_ms = token_expiry_ms(&new_token);
+                        update_status(relayer_status, move |status| status.access_token_expiry_ms = access_token_expiry_ms).await;
                         let access_token = access_token.clone();
                         task::spawn_blocking(move || *access_token.lock().unwrap() = new_token)
                             .await
@@ -430,6 +472,8 @@ impl RelayerStage {
                             ("url", &local_config.relayer_url, String),
                             ("count", num_full_refreshes, i64),
                         );
+                        let refresh_token_expiry_ms = token_expiry_ms(&new_token);
+                        update_status(relayer_status, move |status| status.refresh_token_expiry_ms = refresh_token_expiry_ms).await;
                         refresh_token = new_token;
                     }
                 }
@@ -224,6 +224,8 @@ pub struct RepairInfo {
     pub repair_validators: Option<HashSet<Pubkey>>,
     // Validators which should be given priority when serving
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
+    // Slots wen_restart needs locally, replaces the weighting heuristic when set
+    pub wen_restart_repair_slots: Option<Arc<RwLock<Vec<Slot>>>>,
 }
 
 pub struct RepairSlotRange {
@@ -397,17 +399,24 @@ impl RepairService {
                 );
                 add_votes_elapsed.stop();
 
-                let repairs = repair_weight.get_best_weighted_repairs(
-                    blockstore,
-                    root_bank.epoch_stakes_map(),
-                    root_bank.epoch_schedule(),
-                    MAX_ORPHANS,
-                    MAX_REPAIR_LENGTH,
-                    MAX_UNKNOWN_LAST_INDEX_REPAIRS,
-                    MAX_CLOSEST_COMPLETION_REPAIRS,
-                    &mut repair_timing,
-                    &mut best_repairs_stats,
-                );
+                let repairs = match &repair_info.wen_restart_repair_slots {
+                    Some(wen_restart_repair_slots) => Self::generate_repairs_for_wen_restart(
+                        blockstore,
+                        MAX_REPAIR_LENGTH,
+                        &wen_restart_repair_slots.read().unwrap(),
+                    ),
+                    None => repair_weight.get_best_weighted_repairs(
+                        blockstore,
+                        root_bank.epoch_stakes_map(),
+                        root_bank.epoch_schedule(),
+                        MAX_ORPHANS,
+                        MAX_REPAIR_LENGTH,
+                        MAX_UNKNOWN_LAST_INDEX_REPAIRS,
+                        MAX_CLOSEST_COMPLETION_REPAIRS,
+                        &mut repair_timing,
+                        &mut best_repairs_stats,
+                    ),
+                };
 
                 let mut popular_pruned_forks = repair_weight.get_popular_pruned_forks(
                     root_bank.epoch_stakes_map(),
@@ -663,6 +672,30 @@ impl RepairService {
         }
     }
 
+    /// Repairs the slots wen_restart has decided we must have, in the given order
+    pub(crate) fn generate_repairs_for_wen_restart(
+        blockstore: &Blockstore,
+        max_repairs: usize,
+        slots: &[Slot],
+    ) -> Vec<ShredRepairType> {
+        let mut repairs = Vec::new();
+        for slot in slots {
+            if repairs.len() >= max_repairs {
+                break;
+            }
+            match blockstore.meta(*slot).unwrap() {
+                Some(slot_meta) => repairs.extend(Self::generate_repairs_for_slot(
+                    blockstore,
+                    *slot,
+                    &slot_meta,
+                    max_repairs - repairs.len(),
+                )),
+                None => repairs.push(ShredRepairType::HighestShred(*slot, 0)),
+            }
+        }
+        repairs
+    }
+
     /// Repairs any fork starting at the input slot (uses blockstore for fork info)
     pub fn generate_repairs_for_fork(
         blockstore: &Blockstore,
@@ -1256,6 +1289,43 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_generate_repairs_for_wen_restart() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        // Slot 1 is complete, slot 2 is missing its last shred, slot 3 is unknown
+        let (shreds, _) = make_slot_entries(1, 0, 1, /*merkle_variant:*/ true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        let (mut shreds, _) = make_slot_entries(2, 1, 100, /*merkle_variant:*/ true);
+        let num_shreds_per_slot = shreds.len() as u64;
+        shreds.pop();
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        sleep_shred_deferment_period();
+        assert_eq!(
+            RepairService::generate_repairs_for_wen_restart(
+                &blockstore,
+                MAX_REPAIR_LENGTH,
+                &[1, 2, 3]
+            ),
+            vec![
+                ShredRepairType::HighestShred(2, num_shreds_per_slot - 1),
+                ShredRepairType::HighestShred(3, 0),
+            ]
+        );
+        assert_eq!(
+            RepairService::generate_repairs_for_wen_restart(&blockstore, 1, &[1, 2, 3]),
+            vec![ShredRepairType::HighestShred(2, num_shreds_per_slot - 1)]
+        );
+        assert!(RepairService::generate_repairs_for_wen_restart(
+            &blockstore,
+            MAX_REPAIR_LENGTH,
+            &[]
+        )
+        .is_empty());
+    }
+
     #[test]
     pub fn test_repair_range() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
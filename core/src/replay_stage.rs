@@ -65,6 +65,7 @@ use {
         installed_scheduler_pool::BankWithScheduler,
         prioritization_fee_cache::PrioritizationFeeCache,
     },
+    solana_runtime_plugin::runtime_plugin_service::{BankLifecycleEvent, BankLifecycleEventSender},
     solana_sdk::{
         clock::{BankId, Slot, MAX_PROCESSING_AGE, NUM_CONSECUTIVE_LEADER_SLOTS},
         feature_set,
@@ -275,6 +276,7 @@ pub struct ReplayStageConfig {
     pub cache_block_meta_sender: Option<CacheBlockMetaSender>,
     pub entry_notification_sender: Option<EntryNotifierSender>,
     pub bank_notification_sender: Option<BankNotificationSenderConfig>,
+    pub bank_lifecycle_event_sender: Option<BankLifecycleEventSender>,
     pub wait_for_vote_to_start_leader: bool,
     pub ancestor_hashes_replay_update_sender: AncestorHashesReplayUpdateSender,
     pub tower_storage: Arc<dyn TowerStorage>,
@@ -545,6 +547,7 @@ impl ReplayStage {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            bank_lifecycle_event_sender,
             wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage,
@@ -645,6 +648,7 @@ impl ReplayStage {
                     &mut heaviest_subtree_fork_choice,
                     &replay_vote_sender,
                     &bank_notification_sender,
+                    &bank_lifecycle_event_sender,
                     &rewards_recorder_sender,
                     &rpc_subscriptions,
                     &mut duplicate_slots_tracker,
@@ -925,6 +929,7 @@ impl ReplayStage {
                         &block_commitment_cache,
                         &mut heaviest_subtree_fork_choice,
                         &bank_notification_sender,
+                        &bank_lifecycle_event_sender,
                         &mut duplicate_slots_tracker,
                         &mut duplicate_confirmed_slots,
                         &mut unfrozen_gossip_verified_vote_hashes,
@@ -1077,6 +1082,7 @@ impl ReplayStage {
                         &banking_tracer,
                         has_new_vote_been_rooted,
                         transaction_status_sender.is_some(),
+                        &bank_lifecycle_event_sender,
                     );
 
                     let poh_bank = poh_recorder.read().unwrap().bank();
@@ -1904,6 +1910,7 @@ impl ReplayStage {
         banking_tracer: &Arc<BankingTracer>,
         has_new_vote_been_rooted: bool,
         track_transaction_indexes: bool,
+        bank_lifecycle_event_sender: &Option<BankLifecycleEventSender>,
     ) {
         // all the individual calls to poh_recorder.read() are designed to
         // increase granularity, decrease contention
@@ -2027,6 +2034,13 @@ impl ReplayStage {
             banking_tracer.hash_event(parent.slot(), &parent.last_blockhash(), &parent.hash());
 
             let tpu_bank = bank_forks.write().unwrap().insert(tpu_bank);
+            if let Some(sender) = bank_lifecycle_event_sender {
+                sender
+                    .send(BankLifecycleEvent::NewLeaderSlot(
+                        tpu_bank.clone_without_scheduler(),
+                    ))
+                    .unwrap_or_else(|err| warn!("bank_lifecycle_event_sender failed: {:?}", err));
+            }
             poh_recorder
                 .write()
                 .unwrap()
@@ -2194,6 +2208,7 @@ impl ReplayStage {
         block_commitment_cache: &Arc<RwLock<BlockCommitmentCache>>,
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        bank_lifecycle_event_sender: &Option<BankLifecycleEventSender>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &mut DuplicateConfirmedSlots,
         unfrozen_gossip_verified_vote_hashes: &mut UnfrozenGossipVerifiedVoteHashes,
@@ -2212,6 +2227,7 @@ impl ReplayStage {
         let new_root = tower.record_bank_vote(bank);
 
         if let Some(new_root) = new_root {
+            let old_root = bank_forks.read().unwrap().root();
             // get the root bank before squash
             let root_bank = bank_forks
                 .read()
@@ -2265,6 +2281,21 @@ impl ReplayStage {
             blockstore.slots_stats.mark_rooted(new_root);
 
             rpc_subscriptions.notify_roots(rooted_slots);
+            if let Some(sender) = bank_lifecycle_event_sender {
+                // `rooted_banks` also contains the previous root, which was already notified
+                let mut newly_rooted_banks: Vec<_> = rooted_banks
+                    .iter()
+                    .filter(|rooted_bank| rooted_bank.slot() > old_root)
+                    .collect();
+                newly_rooted_banks.sort_by_key(|rooted_bank| rooted_bank.slot());
+                for rooted_bank in newly_rooted_banks {
+                    sender
+                        .send(BankLifecycleEvent::Rooted(rooted_bank.clone()))
+                        .unwrap_or_else(|err| {
+                            warn!("bank_lifecycle_event_sender failed: {:?}", err)
+                        });
+                }
+            }
             if let Some(sender) = bank_notification_sender {
                 sender
                     .sender
@@ -2805,6 +2836,7 @@ impl ReplayStage {
         cache_block_meta_sender: Option<&CacheBlockMetaSender>,
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        bank_lifecycle_event_sender: &Option<BankLifecycleEventSender>,
         rewards_recorder_sender: &Option<RewardsRecorderSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
@@ -2993,6 +3025,13 @@ impl ReplayStage {
                         .send(BankNotification::Frozen(bank.clone_without_scheduler()))
                         .unwrap_or_else(|err| warn!("bank_notification_sender failed: {:?}", err));
                 }
+                if let Some(sender) = bank_lifecycle_event_sender {
+                    sender
+                        .send(BankLifecycleEvent::Frozen(bank.clone_without_scheduler()))
+                        .unwrap_or_else(|err| {
+                            warn!("bank_lifecycle_event_sender failed: {:?}", err)
+                        });
+                }
                 blockstore_processor::cache_block_meta(bank, cache_block_meta_sender);
 
                 let bank_hash = bank.hash();
@@ -3063,6 +3102,7 @@ impl ReplayStage {
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         replay_vote_sender: &ReplayVoteSender,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        bank_lifecycle_event_sender: &Option<BankLifecycleEventSender>,
         rewards_recorder_sender: &Option<RewardsRecorderSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
@@ -3136,6 +3176,7 @@ impl ReplayStage {
                 cache_block_meta_sender,
                 heaviest_subtree_fork_choice,
                 bank_notification_sender,
+                bank_lifecycle_event_sender,
                 rewards_recorder_sender,
                 rpc_subscriptions,
                 duplicate_slots_tracker,
@@ -0,0 +1,120 @@
+//! Forwards bundles submitted over the `sendBundle` RPC method to the BundleStage, as if they
+//! had been received from a block engine.
+
+use {
+    crate::packet_bundle::PacketBundle,
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
+    log::*,
+    solana_perf::packet::{Packet, PacketBatch},
+    solana_sdk::bundle::{derive_bundle_id, VersionedBundle},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+pub(crate) struct RpcBundleForwarder {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl RpcBundleForwarder {
+    pub(crate) fn new(
+        rpc_bundle_receiver: Receiver<VersionedBundle>,
+        bundle_sender: Sender<Vec<PacketBundle>>,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let thread_hdl = Builder::new()
+            .name("solRpcBundleFwd".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    let bundle = match rpc_bundle_receiver.recv_timeout(Duration::from_secs(1)) {
+                        Ok(bundle) => bundle,
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+                    if let Some(packet_bundle) = Self::packet_bundle(bundle) {
+                        if bundle_sender.send(vec![packet_bundle]).is_err() {
+                            break;
+                        }
+                    }
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    pub(crate) fn packet_bundle(bundle: VersionedBundle) -> Option<PacketBundle> {
+        let bundle_id = derive_bundle_id(&bundle.transactions);
+        let packets = bundle
+            .transactions
+            .iter()
+            .map(|transaction| Packet::from_data(None, transaction))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| warn!("dropping bundle {bundle_id}: {err}"))
+            .ok()?;
+        Some(PacketBundle {
+            batch: PacketBatch::new(packets),
+            bundle_id,
+        })
+    }
+
+    pub(crate) fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_transaction,
+            transaction::VersionedTransaction,
+        },
+    };
+
+    #[test]
+    fn test_forward_rpc_bundle() {
+        let (rpc_bundle_sender, rpc_bundle_receiver) = unbounded();
+        let (bundle_sender, bundle_receiver) = unbounded();
+        let exit = Arc::new(AtomicBool::new(false));
+        let forwarder = RpcBundleForwarder::new(rpc_bundle_receiver, bundle_sender, exit.clone());
+
+        let keypair = Keypair::new();
+        let transactions: Vec<_> = (1..=3)
+            .map(|lamports| {
+                VersionedTransaction::from(system_transaction::transfer(
+                    &keypair,
+                    &keypair.pubkey(),
+                    lamports,
+                    Hash::default(),
+                ))
+            })
+            .collect();
+        rpc_bundle_sender
+            .send(VersionedBundle {
+                transactions: transactions.clone(),
+            })
+            .unwrap();
+
+        let packet_bundles = bundle_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(packet_bundles.len(), 1);
+        assert_eq!(packet_bundles[0].bundle_id, derive_bundle_id(&transactions));
+        let forwarded: Vec<VersionedTransaction> = packet_bundles[0]
+            .batch
+            .iter()
+            .map(|packet| packet.deserialize_slice(..).unwrap())
+            .collect();
+        assert_eq!(forwarded, transactions);
+
+        exit.store(true, Ordering::Relaxed);
+        forwarder.join().unwrap();
+    }
+}
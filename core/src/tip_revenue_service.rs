@@ -0,0 +1,231 @@
+//! Records the MEV tips collected in this validator's leader slots so tip revenue can be accounted
+//! for without indexing the chain.
+//!
+//! Leader banks are picked up from [BankForks] once frozen and their tip revenue is written to the
+//! blockstore after the slot is rooted. Leader slots that end up on a dead fork are dropped.
+//!
+//! The block builder and its commission are read from the tip payment program's config in each
+//! leader bank, so they're the ones in effect during that slot even if the block engine has since
+//! sent a different fee.
+use {
+    crate::tip_manager::{self, TipManager},
+    solana_gossip::cluster_info::ClusterInfo,
+    solana_ledger::{blockstore::Blockstore, blockstore_meta::TipRevenueMeta},
+    solana_runtime::{bank::Bank, bank_forks::BankForks},
+    solana_sdk::clock::Slot,
+    std::{
+        collections::{BTreeMap, HashMap},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+const LOOP_INTERVAL: Duration = Duration::from_millis(200);
+
+pub struct TipRevenueService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl TipRevenueService {
+    pub fn new(
+        cluster_info: Arc<ClusterInfo>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        tip_manager: TipManager,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let thread_hdl = Builder::new()
+            .name("solTipRevenue".to_string())
+            .spawn(move || Self::run(cluster_info, bank_forks, blockstore, tip_manager, exit))
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn run(
+        cluster_info: Arc<ClusterInfo>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        tip_manager: TipManager,
+        exit: Arc<AtomicBool>,
+    ) {
+        // tip revenue of frozen leader slots waiting to be rooted
+        let mut pending = BTreeMap::new();
+        let mut highest_leader_slot = bank_forks.read().unwrap().root();
+
+        while !exit.load(Ordering::Relaxed) {
+            let identity = cluster_info.id();
+            let (root, mut leader_banks) = {
+                let bank_forks = bank_forks.read().unwrap();
+                let leader_banks: Vec<_> = bank_forks
+                    .frozen_banks()
+                    .into_values()
+                    .filter(|bank| {
+                        bank.slot() > highest_leader_slot && bank.collector_id() == &identity
+                    })
+                    .collect();
+                (bank_forks.root(), leader_banks)
+            };
+
+            leader_banks.sort_by_key(|bank| bank.slot());
+            for bank in leader_banks {
+                highest_leader_slot = bank.slot();
+                match get_tip_revenue(&tip_manager, &bank) {
+                    Ok(tip_revenue) => {
+                        pending.insert(bank.slot(), tip_revenue);
+                    }
+                    Err(e) => {
+                        warn!("unable to get tip revenue for slot {}: {e:?}", bank.slot());
+                    }
+                }
+            }
+
+            record_rooted_tip_revenue(&blockstore, &mut pending, root);
+            sleep(LOOP_INTERVAL);
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+/// Returns the tips collected in a frozen leader bank, along with the share paid to the block
+/// builder and the tip receiver when the tip accounts are next drained.
+fn get_tip_revenue(
+    tip_manager: &TipManager,
+    bank: &Arc<Bank>,
+) -> tip_manager::Result<TipRevenueMeta> {
+    let config = tip_manager.get_tip_payment_config_account(bank)?;
+    let tip_receiver = config.tip_receiver;
+
+    // Changing the tip receiver drains the tips left over from earlier slots to the previous tip
+    // receiver, otherwise they're still sitting in the tip accounts.
+    let parent_balances: HashMap<_, _> = match bank.parent() {
+        Some(parent)
+            if tip_manager.get_configured_tip_receiver(&parent).ok() == Some(tip_receiver) =>
+        {
+            tip_manager
+                .get_tip_account_balances_above_rent_exempt(&parent)
+                .into_iter()
+                .collect()
+        }
+        _ => HashMap::default(),
+    };
+
+    let mut tip_accounts: Vec<_> = tip_manager
+        .get_tip_account_balances_above_rent_exempt(bank)
+        .into_iter()
+        .map(|(tip_account, balance)| {
+            let parent_balance = parent_balances
+                .get(&tip_account)
+                .copied()
+                .unwrap_or_default();
+            (tip_account, balance.saturating_sub(parent_balance))
+        })
+        .collect();
+    tip_accounts.sort_unstable();
+
+    let total_tips = tip_accounts.iter().map(|(_, tips)| tips).sum();
+    let (block_builder_commission, net_tip_distribution) =
+        split_tips(total_tips, config.block_builder_commission_pct);
+
+    Ok(TipRevenueMeta {
+        epoch: bank.epoch(),
+        tip_accounts,
+        total_tips,
+        block_builder: config.block_builder,
+        block_builder_commission_pct: config.block_builder_commission_pct,
+        block_builder_commission,
+        tip_distribution_account: tip_receiver,
+        net_tip_distribution,
+    })
+}
+
+/// Splits tips into the block builder's commission and the remainder paid to the tip receiver,
+/// rounding the commission down like the tip payment program does.
+fn split_tips(total_tips: u64, block_builder_commission_pct: u64) -> (u64, u64) {
+    let block_builder_commission =
+        (u128::from(total_tips) * u128::from(block_builder_commission_pct.min(100)) / 100) as u64;
+    (
+        block_builder_commission,
+        total_tips.saturating_sub(block_builder_commission),
+    )
+}
+
+/// Writes the tip revenue of leader slots that are now rooted and drops those that were skipped.
+fn record_rooted_tip_revenue(
+    blockstore: &Blockstore,
+    pending: &mut BTreeMap<Slot, TipRevenueMeta>,
+    root: Slot,
+) {
+    let unrooted = pending.split_off(&root.saturating_add(1));
+    let rooted = std::mem::replace(pending, unrooted);
+    for (slot, tip_revenue) in rooted {
+        if !blockstore.is_root(slot) {
+            debug!("dropping tip revenue for slot {slot}, it was not rooted");
+            continue;
+        }
+        if let Err(e) = blockstore.write_tip_revenue(slot, &tip_revenue) {
+            error!("write_tip_revenue failed: slot {slot} {e:?}");
+            continue;
+        }
+        datapoint_info!(
+            "tip_revenue",
+            ("slot", slot, i64),
+            ("total_tips", tip_revenue.total_tips, i64),
+            (
+                "block_builder_commission",
+                tip_revenue.block_builder_commission,
+                i64
+            ),
+            (
+                "net_tip_distribution",
+                tip_revenue.net_tip_distribution,
+                i64
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_ledger::get_tmp_ledger_path_auto_delete, solana_sdk::pubkey::Pubkey};
+
+    #[test]
+    fn test_split_tips() {
+        assert_eq!(split_tips(1_000, 0), (0, 1_000));
+        assert_eq!(split_tips(1_000, 5), (50, 950));
+        assert_eq!(split_tips(999, 5), (49, 950));
+        assert_eq!(split_tips(1_000, 100), (1_000, 0));
+        assert_eq!(split_tips(1_000, 150), (1_000, 0));
+        assert_eq!(split_tips(u64::MAX, 50), (u64::MAX / 2, u64::MAX / 2 + 1));
+    }
+
+    #[test]
+    fn test_record_rooted_tip_revenue() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        blockstore.set_roots([1, 3].iter()).unwrap();
+
+        let tip_revenue = |total_tips| TipRevenueMeta {
+            total_tips,
+            tip_distribution_account: Pubkey::new_unique(),
+            ..TipRevenueMeta::default()
+        };
+        let mut pending: BTreeMap<_, _> = [1, 2, 3, 5]
+            .into_iter()
+            .map(|slot| (slot, tip_revenue(slot * 1_000)))
+            .collect();
+        let expected_rooted = vec![(1, pending[&1].clone()), (3, pending[&3].clone())];
+
+        record_rooted_tip_revenue(&blockstore, &mut pending, 3);
+
+        // slot 2 was skipped, slot 5 isn't rooted yet
+        assert_eq!(pending.keys().copied().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(blockstore.read_tip_revenue(0, 10).unwrap(), expected_rooted);
+    }
+}
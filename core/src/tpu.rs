@@ -6,27 +6,37 @@ use {
     crate::{
         banking_stage::BankingStage,
         banking_trace::{BankingTracer, TracerThread},
-        bundle_stage::{bundle_account_locker::BundleAccountLocker, BundleStage},
+        bundle_stage::{
+            bundle_account_locker::BundleAccountLocker, bundle_denylist::BundleDenylist,
+            bundle_ordering_policy::BundleOrderingMethod, BundleStage,
+        },
         cluster_info_vote_listener::{
             ClusterInfoVoteListener, DuplicateConfirmedSlotsSender, GossipVerifiedVoteHashSender,
             VerifiedVoteSender, VoteTracker,
         },
         fetch_stage::FetchStage,
+        plugin_submission_forwarder::PluginSubmissionForwarder,
         proxy::{
-            block_engine_stage::{BlockBuilderFeeInfo, BlockEngineConfig, BlockEngineStage},
-            fetch_stage_manager::FetchStageManager,
+            block_engine_stage::{
+                BlockBuilderFeeInfo, BlockEngineConfig, BlockEngineStage, BlockEngineStatus,
+            },
+            fetch_stage_manager::{FetchStageManager, FetchStageModeOverride},
             relayer_stage::{RelayerConfig, RelayerStage},
+            ProxyConnectionStatus,
         },
+        rpc_bundle_forwarder::RpcBundleForwarder,
         sigverify::TransactionSigVerifier,
         sigverify_stage::SigVerifyStage,
         staked_nodes_updater_service::StakedNodesUpdaterService,
         tip_manager::{TipManager, TipManagerConfig},
+        tip_revenue_service::TipRevenueService,
         tpu_entry_notifier::TpuEntryNotifier,
         validator::{BlockProductionMethod, GeneratorConfig},
     },
     bytes::Bytes,
     crossbeam_channel::{unbounded, Receiver},
     solana_client::connection_cache::ConnectionCache,
+    solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifierArc,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         blockstore::Blockstore, blockstore_processor::TransactionStatusSender,
@@ -38,8 +48,10 @@ use {
         rpc_subscriptions::RpcSubscriptions,
     },
     solana_runtime::{bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache},
+    solana_runtime_plugin::plugin_submitter::PluginSubmissionReceivers,
     solana_sdk::{
-        clock::Slot, pubkey::Pubkey, quic::NotifyKeyUpdate, signature::Keypair, signer::Signer,
+        bundle::VersionedBundle, clock::Slot, pubkey::Pubkey, quic::NotifyKeyUpdate,
+        signature::Keypair, signer::Signer,
     },
     solana_streamer::{
         nonblocking::quic::DEFAULT_WAIT_FOR_CHUNK_TIMEOUT,
@@ -86,6 +98,9 @@ pub struct Tpu {
     block_engine_stage: BlockEngineStage,
     fetch_stage_manager: FetchStageManager,
     bundle_stage: BundleStage,
+    rpc_bundle_forwarder: Option<RpcBundleForwarder>,
+    plugin_submission_forwarder: Option<PluginSubmissionForwarder>,
+    tip_revenue_service: TipRevenueService,
 }
 
 impl Tpu {
@@ -125,10 +140,20 @@ impl Tpu {
         block_production_method: BlockProductionMethod,
         _generator_config: Option<GeneratorConfig>, /* vestigial code for replay invalidator */
         block_engine_config: Arc<Mutex<BlockEngineConfig>>,
+        block_engine_status: Arc<RwLock<BlockEngineStatus>>,
         relayer_config: Arc<Mutex<RelayerConfig>>,
+        relayer_status: Arc<RwLock<ProxyConnectionStatus>>,
+        relayer_grace_window: Duration,
+        fetch_stage_mode_override: Arc<RwLock<FetchStageModeOverride>>,
         tip_manager_config: TipManagerConfig,
         shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
         preallocated_bundle_cost: u64,
+        bundle_ordering_method: BundleOrderingMethod,
+        num_bundle_execution_threads: usize,
+        bundle_denylist: Arc<RwLock<BundleDenylist>>,
+        bundle_notifier: Option<BundleNotifierArc>,
+        rpc_bundle_receiver: Option<Receiver<VersionedBundle>>,
+        plugin_submission_receivers: Option<PluginSubmissionReceivers>,
     ) -> (Self, Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>>) {
         let TpuSockets {
             transactions: transactions_sockets,
@@ -230,8 +255,20 @@ impl Tpu {
         }));
 
         let (bundle_sender, bundle_receiver) = unbounded();
+        let rpc_bundle_forwarder = rpc_bundle_receiver.map(|rpc_bundle_receiver| {
+            RpcBundleForwarder::new(rpc_bundle_receiver, bundle_sender.clone(), exit.clone())
+        });
+        let plugin_submission_forwarder = plugin_submission_receivers.map(|receivers| {
+            PluginSubmissionForwarder::new(
+                receivers,
+                packet_sender.clone(),
+                bundle_sender.clone(),
+                exit.clone(),
+            )
+        });
         let block_engine_stage = BlockEngineStage::new(
             block_engine_config,
+            block_engine_status,
             bundle_sender,
             cluster_info.clone(),
             packet_sender.clone(),
@@ -246,11 +283,14 @@ impl Tpu {
             heartbeat_rx,
             packet_intercept_receiver,
             packet_sender.clone(),
+            relayer_grace_window,
+            fetch_stage_mode_override,
             exit.clone(),
         );
 
         let relayer_stage = RelayerStage::new(
             relayer_config,
+            relayer_status,
             cluster_info.clone(),
             heartbeat_tx,
             packet_sender,
@@ -276,6 +316,14 @@ impl Tpu {
 
         let tip_manager = TipManager::new(tip_manager_config);
 
+        let tip_revenue_service = TipRevenueService::new(
+            cluster_info.clone(),
+            bank_forks.clone(),
+            blockstore.clone(),
+            tip_manager.clone(),
+            exit.clone(),
+        );
+
         let bundle_account_locker = BundleAccountLocker::default();
 
         // tip accounts can't be used in BankingStage to avoid someone from stealing tips mid-slot.
@@ -314,6 +362,11 @@ impl Tpu {
             preallocated_bundle_cost,
             bank_forks.clone(),
             prioritization_fee_cache,
+            blockstore.clone(),
+            bundle_ordering_method,
+            num_bundle_execution_threads,
+            bundle_denylist,
+            bundle_notifier,
         );
 
         let (entry_receiver, tpu_entry_notifier) =
@@ -360,6 +413,9 @@ impl Tpu {
                 relayer_stage,
                 fetch_stage_manager,
                 bundle_stage,
+                rpc_bundle_forwarder,
+                plugin_submission_forwarder,
+                tip_revenue_service,
             },
             vec![key_updater, forwards_key_updater],
         )
@@ -379,6 +435,7 @@ impl Tpu {
             self.relayer_stage.join(),
             self.block_engine_stage.join(),
             self.fetch_stage_manager.join(),
+            self.tip_revenue_service.join(),
         ];
         let broadcast_result = self.broadcast_stage.join();
         for result in results {
@@ -387,6 +444,12 @@ impl Tpu {
         if let Some(tpu_entry_notifier) = self.tpu_entry_notifier {
             tpu_entry_notifier.join()?;
         }
+        if let Some(rpc_bundle_forwarder) = self.rpc_bundle_forwarder {
+            rpc_bundle_forwarder.join()?;
+        }
+        if let Some(plugin_submission_forwarder) = self.plugin_submission_forwarder {
+            plugin_submission_forwarder.join()?;
+        }
         let _ = broadcast_result?;
         if let Some(tracer_thread_hdl) = self.tracer_thread_hdl {
             if let Err(tracer_result) = tracer_thread_hdl.join()? {
@@ -47,6 +47,7 @@ use {
         accounts_background_service::AbsRequestSender, bank_forks::BankForks,
         commitment::BlockCommitmentCache, prioritization_fee_cache::PrioritizationFeeCache,
     },
+    solana_runtime_plugin::runtime_plugin_service::BankLifecycleEventSender,
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Keypair},
     solana_turbine::retransmit_stage::RetransmitStage,
     solana_vote::vote_sender_types::ReplayVoteSender,
@@ -65,7 +66,7 @@ pub struct Tvu {
     retransmit_stage: RetransmitStage,
     window_service: WindowService,
     cluster_slots_service: ClusterSlotsService,
-    replay_stage: ReplayStage,
+    replay_stage: Option<ReplayStage>,
     blockstore_cleanup_service: Option<BlockstoreCleanupService>,
     cost_update_service: CostUpdateService,
     voting_service: VotingService,
@@ -128,6 +129,7 @@ impl Tvu {
         replay_vote_sender: ReplayVoteSender,
         completed_data_sets_sender: CompletedDataSetsSender,
         bank_notification_sender: Option<BankNotificationSenderConfig>,
+        bank_lifecycle_event_sender: Option<BankLifecycleEventSender>,
         duplicate_confirmed_slots_receiver: DuplicateConfirmedSlotsReceiver,
         tvu_config: TvuConfig,
         max_slots: &Arc<MaxSlots>,
@@ -144,6 +146,7 @@ impl Tvu {
         outstanding_repair_requests: Arc<RwLock<OutstandingShredRepairs>>,
         cluster_slots: Arc<ClusterSlots>,
         shred_receiver_addr: Arc<RwLock<Option<SocketAddr>>>,
+        wen_restart_repair_slots: Option<Arc<RwLock<Vec<Slot>>>>,
     ) -> Result<Self, String> {
         let TvuSockets {
             repair: repair_socket,
@@ -195,6 +198,8 @@ impl Tvu {
             shred_receiver_addr,
         );
 
+        // During wen_restart the repaired slots are replayed by the protocol itself.
+        let in_wen_restart = wen_restart_repair_slots.is_some();
         let (ancestor_duplicate_slots_sender, ancestor_duplicate_slots_receiver) = unbounded();
         let (duplicate_slots_sender, duplicate_slots_receiver) = unbounded();
         let (ancestor_hashes_replay_update_sender, ancestor_hashes_replay_update_receiver) =
@@ -216,6 +221,7 @@ impl Tvu {
                 repair_whitelist: tvu_config.repair_whitelist,
                 cluster_info: cluster_info.clone(),
                 cluster_slots: cluster_slots.clone(),
+                wen_restart_repair_slots,
             };
             WindowService::new(
                 blockstore.clone(),
@@ -263,6 +269,7 @@ impl Tvu {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            bank_lifecycle_event_sender,
             wait_for_vote_to_start_leader: tvu_config.wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage: tower_storage.clone(),
@@ -295,33 +302,37 @@ impl Tvu {
 
         let drop_bank_service = DropBankService::new(drop_bank_receiver);
 
-        let replay_stage = ReplayStage::new(
-            replay_stage_config,
-            blockstore.clone(),
-            bank_forks.clone(),
-            cluster_info.clone(),
-            ledger_signal_receiver,
-            duplicate_slots_receiver,
-            poh_recorder.clone(),
-            tower,
-            vote_tracker,
-            cluster_slots,
-            retransmit_slots_sender,
-            ancestor_duplicate_slots_receiver,
-            replay_vote_sender,
-            duplicate_confirmed_slots_receiver,
-            gossip_verified_vote_hash_receiver,
-            cluster_slots_update_sender,
-            cost_update_sender,
-            voting_sender,
-            drop_bank_sender,
-            block_metadata_notifier,
-            log_messages_bytes_limit,
-            prioritization_fee_cache.clone(),
-            dumped_slots_sender,
-            banking_tracer,
-            popular_pruned_forks_receiver,
-        )?;
+        let replay_stage = if in_wen_restart {
+            None
+        } else {
+            Some(ReplayStage::new(
+                replay_stage_config,
+                blockstore.clone(),
+                bank_forks.clone(),
+                cluster_info.clone(),
+                ledger_signal_receiver,
+                duplicate_slots_receiver,
+                poh_recorder.clone(),
+                tower,
+                vote_tracker,
+                cluster_slots,
+                retransmit_slots_sender,
+                ancestor_duplicate_slots_receiver,
+                replay_vote_sender,
+                duplicate_confirmed_slots_receiver,
+                gossip_verified_vote_hash_receiver,
+                cluster_slots_update_sender,
+                cost_update_sender,
+                voting_sender,
+                drop_bank_sender,
+                block_metadata_notifier,
+                log_messages_bytes_limit,
+                prioritization_fee_cache.clone(),
+                dumped_slots_sender,
+                banking_tracer,
+                popular_pruned_forks_receiver,
+            )?)
+        };
 
         let blockstore_cleanup_service = tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
             BlockstoreCleanupService::new(
@@ -368,7 +379,9 @@ impl Tvu {
         if self.blockstore_cleanup_service.is_some() {
             self.blockstore_cleanup_service.unwrap().join()?;
         }
-        self.replay_stage.join()?;
+        if let Some(replay_stage) = self.replay_stage {
+            replay_stage.join()?;
+        }
         self.cost_update_service.join()?;
         self.voting_service.join()?;
         if let Some(warmup_service) = self.warm_quic_cache_service {
@@ -493,6 +506,7 @@ pub mod tests {
             replay_vote_sender,
             completed_data_sets_sender,
             None,
+            None,
             gossip_confirmed_slots_receiver,
             TvuConfig::default(),
             &Arc::new(MaxSlots::default()),
@@ -509,6 +523,7 @@ pub mod tests {
             outstanding_repair_requests,
             cluster_slots,
             Arc::new(RwLock::new(None)),
+            None,
         )
         .expect("assume success");
         exit.store(true, Ordering::Relaxed);
@@ -6,6 +6,10 @@ use {
         accounts_hash_verifier::AccountsHashVerifier,
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         banking_trace::{self, BankingTracer},
+        bundle_stage::{
+            bundle_denylist::BundleDenylist, bundle_ordering_policy::BundleOrderingMethod,
+            DEFAULT_BUNDLE_EXECUTION_THREADS,
+        },
         cache_block_meta_service::{CacheBlockMetaSender, CacheBlockMetaService},
         cluster_info_vote_listener::VoteTracker,
         completed_data_sets_service::CompletedDataSetsService,
@@ -15,7 +19,12 @@ use {
             ExternalRootSource, Tower,
         },
         poh_timing_report_service::PohTimingReportService,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_stage::{BlockEngineConfig, BlockEngineStatus},
+            fetch_stage_manager::{FetchStageModeOverride, DEFAULT_RELAYER_GRACE_WINDOW},
+            relayer_stage::RelayerConfig,
+            ProxyConnectionStatus,
+        },
         repair::{self, serve_repair::ServeRepair, serve_repair_service::ServeRepairService},
         rewards_recorder_service::{RewardsRecorderSender, RewardsRecorderService},
         sample_performance_service::SamplePerformanceService,
@@ -69,6 +78,7 @@ use {
     solana_measure::measure::Measure,
     solana_metrics::{
         datapoint_info, metrics::metrics_config_sanity_check, poh_timing_point::PohTimingSender,
+        prometheus::PrometheusService,
     },
     solana_poh::{
         poh_recorder::PohRecorder,
@@ -107,6 +117,7 @@ use {
         },
     },
     solana_runtime_plugin::{
+        plugin_submitter::{PluginSubmissionConfig, PluginSubmissionContext},
         runtime_plugin_admin_rpc_service::RuntimePluginManagerRpcRequest,
         runtime_plugin_service::RuntimePluginService,
     },
@@ -126,7 +137,7 @@ use {
     solana_turbine::{self, broadcast_stage::BroadcastStageType},
     solana_unified_scheduler_pool::DefaultSchedulerPool,
     solana_vote_program::vote_state,
-    solana_wen_restart::wen_restart::wait_for_wen_restart,
+    solana_wen_restart::wen_restart::{wait_for_wen_restart, WenRestartConfig},
     std::{
         collections::{HashMap, HashSet},
         net::SocketAddr,
@@ -246,6 +257,8 @@ pub struct ValidatorConfig {
     pub no_os_network_stats_reporting: bool,
     pub no_os_cpu_stats_reporting: bool,
     pub no_os_disk_stats_reporting: bool,
+    /// Serve metrics in the Prometheus text format at `http://<address>/metrics`
+    pub prometheus_bind_address: Option<SocketAddr>,
     pub poh_pinned_cpu_core: usize,
     pub poh_hashes_per_batch: u64,
     pub process_ledger_before_services: bool,
@@ -267,6 +280,7 @@ pub struct ValidatorConfig {
     pub banking_trace_dir_byte_limit: banking_trace::DirByteLimit,
     pub block_verification_method: BlockVerificationMethod,
     pub block_production_method: BlockProductionMethod,
+    pub unified_scheduler_handler_threads: Option<usize>,
     pub generator_config: Option<GeneratorConfig>,
     pub use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup,
     pub wen_restart_proto_path: Option<PathBuf>,
@@ -274,8 +288,15 @@ pub struct ValidatorConfig {
     pub block_engine_config: Arc<Mutex<BlockEngineConfig>>,
     // Using Option inside RwLock is ugly, but only convenient way to allow toggle on/off
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
+    pub relayer_grace_window: Duration,
+    pub fetch_stage_mode_override: Arc<RwLock<FetchStageModeOverride>>,
     pub tip_manager_config: TipManagerConfig,
     pub preallocated_bundle_cost: u64,
+    pub bundle_ordering_method: BundleOrderingMethod,
+    pub bundle_execution_threads: usize,
+    pub bundle_denylist: Arc<RwLock<BundleDenylist>>,
+    /// Lets runtime plugins submit transactions and bundles while leader when set.
+    pub runtime_plugin_submission_config: Option<PluginSubmissionConfig>,
 }
 
 impl Default for ValidatorConfig {
@@ -320,6 +341,7 @@ impl Default for ValidatorConfig {
             no_os_network_stats_reporting: true,
             no_os_cpu_stats_reporting: true,
             no_os_disk_stats_reporting: true,
+            prometheus_bind_address: None,
             poh_pinned_cpu_core: poh_service::DEFAULT_PINNED_CPU_CORE,
             poh_hashes_per_batch: poh_service::DEFAULT_HASHES_PER_BATCH,
             process_ledger_before_services: false,
@@ -341,14 +363,21 @@ impl Default for ValidatorConfig {
             banking_trace_dir_byte_limit: 0,
             block_verification_method: BlockVerificationMethod::default(),
             block_production_method: BlockProductionMethod::default(),
+            unified_scheduler_handler_threads: None,
             generator_config: None,
             use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup::default(),
             wen_restart_proto_path: None,
             relayer_config: Arc::new(Mutex::new(RelayerConfig::default())),
             block_engine_config: Arc::new(Mutex::new(BlockEngineConfig::default())),
             shred_receiver_address: Arc::new(RwLock::new(None)),
+            relayer_grace_window: DEFAULT_RELAYER_GRACE_WINDOW,
+            fetch_stage_mode_override: Arc::new(RwLock::new(FetchStageModeOverride::default())),
             tip_manager_config: TipManagerConfig::default(),
             preallocated_bundle_cost: u64::default(),
+            bundle_ordering_method: BundleOrderingMethod::default(),
+            bundle_execution_threads: DEFAULT_BUNDLE_EXECUTION_THREADS,
+            bundle_denylist: Arc::new(RwLock::new(BundleDenylist::default())),
+            runtime_plugin_submission_config: None,
         }
     }
 }
@@ -468,6 +497,7 @@ pub struct Validator {
     cache_block_meta_service: Option<CacheBlockMetaService>,
     entry_notifier_service: Option<EntryNotifierService>,
     system_monitor_service: Option<SystemMonitorService>,
+    prometheus_service: Option<PrometheusService>,
     sample_performance_service: Option<SamplePerformanceService>,
     poh_timing_report_service: PohTimingReportService,
     stats_reporter_service: StatsReporterService,
@@ -675,6 +705,10 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let bundle_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_bundle_notifier());
+
         info!(
             "Geyser plugin: accounts_update_notifier: {}, \
             transaction_notifier: {}, \
@@ -694,6 +728,15 @@ impl Validator {
             },
         ));
 
+        let prometheus_service = config
+            .prometheus_bind_address
+            .map(|bind_address| {
+                PrometheusService::new(bind_address, exit.clone()).map_err(|err| {
+                    format!("Failed to start the Prometheus endpoint at {bind_address}: {err}")
+                })
+            })
+            .transpose()?;
+
         let (poh_timing_point_sender, poh_timing_point_receiver) = unbounded();
         let poh_timing_report_service =
             PohTimingReportService::new(poh_timing_point_receiver, exit.clone());
@@ -840,6 +883,7 @@ impl Validator {
             }
             BlockVerificationMethod::UnifiedScheduler => {
                 let scheduler_pool = DefaultSchedulerPool::new_dyn(
+                    config.unified_scheduler_handler_threads,
                     config.runtime_config.log_messages_bytes_limit,
                     transaction_status_sender.clone(),
                     Some(replay_vote_sender.clone()),
@@ -922,16 +966,35 @@ impl Validator {
             None,
         ));
 
-        if let Some((runtime_plugin_configs, request_rx)) = runtime_plugin_configs_and_request_rx {
+        let mut plugin_submission_receivers = None;
+        let bank_lifecycle_event_sender = if let Some((runtime_plugin_configs, request_rx)) =
+            runtime_plugin_configs_and_request_rx
+        {
+            let (bank_lifecycle_event_sender, bank_lifecycle_event_receiver) = unbounded();
+            let submission_context =
+                config
+                    .runtime_plugin_submission_config
+                    .clone()
+                    .map(|submission_config| {
+                        let (submission_context, receivers) =
+                            PluginSubmissionContext::new(submission_config, cluster_info.clone());
+                        plugin_submission_receivers = Some(receivers);
+                        submission_context
+                    });
             RuntimePluginService::start(
                 &runtime_plugin_configs,
                 request_rx,
+                bank_lifecycle_event_receiver,
                 bank_forks.clone(),
                 block_commitment_cache.clone(),
                 exit.clone(),
+                submission_context,
             )
             .map_err(|e| format!("Failed to start runtime plugin service: {e:?}"))?;
-        }
+            Some(bank_lifecycle_event_sender)
+        } else {
+            None
+        };
 
         let max_slots = Arc::new(MaxSlots::default());
         let (completed_data_sets_sender, completed_data_sets_receiver) =
@@ -992,6 +1055,12 @@ impl Validator {
 
         let rpc_override_health_check =
             Arc::new(AtomicBool::new(config.rpc_config.disable_health_check));
+        let (rpc_bundle_sender, rpc_bundle_receiver) = if config.rpc_config.enable_send_bundle {
+            let (rpc_bundle_sender, rpc_bundle_receiver) = unbounded();
+            (Some(rpc_bundle_sender), Some(rpc_bundle_receiver))
+        } else {
+            (None, None)
+        };
         let (
             json_rpc_service,
             pubsub_service,
@@ -1038,6 +1107,7 @@ impl Validator {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
+                rpc_bundle_sender,
             )?;
 
             (
@@ -1262,6 +1332,11 @@ impl Validator {
             };
 
         let in_wen_restart = config.wen_restart_proto_path.is_some() && !waited_for_supermajority;
+        let wen_restart_repair_slots = if in_wen_restart {
+            Some(Arc::new(RwLock::new(Vec::new())))
+        } else {
+            None
+        };
         let tower = match process_blockstore.process_to_create_tower() {
             Ok(tower) => {
                 info!("Tower state: {:?}", tower);
@@ -1314,6 +1389,7 @@ impl Validator {
             replay_vote_sender.clone(),
             completed_data_sets_sender,
             bank_notification_sender.clone(),
+            bank_lifecycle_event_sender,
             duplicate_confirmed_slots_receiver,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
@@ -1326,7 +1402,7 @@ impl Validator {
             &max_slots,
             block_metadata_notifier,
             config.wait_to_vote_slot,
-            accounts_background_request_sender,
+            accounts_background_request_sender.clone(),
             config.runtime_config.log_messages_bytes_limit,
             &connection_cache,
             &prioritization_fee_cache,
@@ -1337,23 +1413,36 @@ impl Validator {
             outstanding_repair_requests.clone(),
             cluster_slots.clone(),
             config.shred_receiver_address.clone(),
+            wen_restart_repair_slots.clone(),
         )?;
 
         if in_wen_restart {
-            info!("Waiting for wen_restart phase one to finish");
-            match wait_for_wen_restart(
-                &config.wen_restart_proto_path.clone().unwrap(),
+            info!("Waiting for wen_restart to finish");
+            match wait_for_wen_restart(WenRestartConfig {
+                wen_restart_path: config.wen_restart_proto_path.clone().unwrap(),
                 last_vote,
-                blockstore.clone(),
-                cluster_info.clone(),
-            ) {
+                blockstore: blockstore.clone(),
+                cluster_info: cluster_info.clone(),
+                bank_forks: bank_forks.clone(),
+                wen_restart_repair_slots,
+                wait_for_supermajority_threshold_percent: WAIT_FOR_SUPERMAJORITY_THRESHOLD_PERCENT,
+                snapshot_config: config.snapshot_config.clone(),
+                accounts_background_request_sender: accounts_background_request_sender.clone(),
+                genesis_config_hash: genesis_config.hash(),
+                exit: exit.clone(),
+            }) {
                 Ok(()) => {
-                    return Err("wen_restart phase one completedy".to_string());
+                    return Err(
+                        "wen_restart completed, restart the validator with the logged arguments"
+                            .to_string(),
+                    );
                 }
                 Err(e) => return Err(format!("wait_for_wen_restart failed: {e:?}")),
             };
         }
 
+        let block_engine_status = Arc::new(RwLock::new(BlockEngineStatus::default()));
+        let relayer_status = Arc::new(RwLock::new(ProxyConnectionStatus::default()));
         let (tpu, mut key_notifies) = Tpu::new(
             &cluster_info,
             &poh_recorder,
@@ -1396,10 +1485,20 @@ impl Validator {
             config.block_production_method.clone(),
             config.generator_config.clone(),
             config.block_engine_config.clone(),
+            block_engine_status.clone(),
             config.relayer_config.clone(),
+            relayer_status.clone(),
+            config.relayer_grace_window,
+            config.fetch_stage_mode_override.clone(),
             config.tip_manager_config.clone(),
             config.shred_receiver_address.clone(),
             config.preallocated_bundle_cost,
+            config.bundle_ordering_method,
+            config.bundle_execution_threads,
+            config.bundle_denylist.clone(),
+            bundle_notifier,
+            rpc_bundle_receiver,
+            plugin_submission_receivers,
         );
 
         datapoint_info!(
@@ -1422,8 +1521,13 @@ impl Validator {
             outstanding_repair_requests,
             cluster_slots,
             block_engine_config: config.block_engine_config.clone(),
+            block_engine_status,
             relayer_config: config.relayer_config.clone(),
+            relayer_status,
             shred_receiver_address: config.shred_receiver_address.clone(),
+            fetch_stage_mode_override: config.fetch_stage_mode_override.clone(),
+            bundle_denylist: config.bundle_denylist.clone(),
+            blockstore: blockstore.clone(),
         });
 
         Ok(Self {
@@ -1439,6 +1543,7 @@ impl Validator {
             cache_block_meta_service,
             entry_notifier_service,
             system_monitor_service,
+            prometheus_service,
             sample_performance_service,
             poh_timing_report_service,
             snapshot_packager_service,
@@ -1554,6 +1659,10 @@ impl Validator {
                 .expect("system_monitor_service");
         }
 
+        if let Some(prometheus_service) = self.prometheus_service {
+            prometheus_service.join().expect("prometheus_service");
+        }
+
         if let Some(sample_performance_service) = self.sample_performance_service {
             sample_performance_service
                 .join()
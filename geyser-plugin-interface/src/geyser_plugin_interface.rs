@@ -5,8 +5,9 @@
 use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
+        pubkey::Pubkey,
         signature::Signature,
-        transaction::SanitizedTransaction,
+        transaction::{Result as TransactionResult, SanitizedTransaction},
     },
     solana_transaction_status::{Reward, TransactionStatusMeta},
     std::{any::Any, error, io},
@@ -258,6 +259,35 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_3(&'a ReplicaBlockInfoV3<'a>),
 }
 
+/// Information about a bundle committed in a slot
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaBundleInfo<'a> {
+    /// The bundle's id, derived from the signatures of its transactions.
+    pub bundle_id: &'a str,
+
+    /// The slot the bundle was committed in.
+    pub slot: Slot,
+
+    /// The first signature of each of the bundle's transactions, in execution order.
+    pub signatures: &'a [Signature],
+
+    /// The execution result of each of the bundle's transactions, in the same order as
+    /// `signatures`.
+    pub transaction_results: &'a [TransactionResult<()>],
+
+    /// The tip accounts credited by the bundle, along with the lamports each received.
+    pub tips: &'a [(Pubkey, u64)],
+}
+
+/// A wrapper to future-proof ReplicaBundleInfo handling. To make a change to the structure of
+/// ReplicaBundleInfo, add an new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaBundleInfoVersions<'a> {
+    V0_0_1(&'a ReplicaBundleInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -418,6 +448,12 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when a bundle is committed while this validator is leader.
+    #[allow(unused_variables)]
+    fn notify_bundle(&self, bundle: ReplicaBundleInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -438,4 +474,11 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in bundle data
+    /// Default is false -- if the plugin is interested in
+    /// bundle data, return true.
+    fn bundle_notifications_enabled(&self) -> bool {
+        false
+    }
 }
@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_delivery::GeyserNotification, geyser_plugin_manager::GeyserPluginManager,
+    },
     log::*,
     solana_accounts_db::{
         account_storage::meta::StoredAccountMeta,
@@ -74,6 +76,10 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::EndOfStartup);
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-end-of-restore-from-snapshot");
             match plugin.notify_end_of_startup() {
                 Err(err) => {
@@ -152,6 +158,18 @@ impl AccountsUpdateNotifierImpl {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
+            if !plugin
+                .filter()
+                .account_matches(account.pubkey, account.owner, account.data.len())
+            {
+                continue;
+            }
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::account_update(
+                    &account, slot, is_startup,
+                ));
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
             match plugin.update_account(
                 ReplicaAccountInfoVersions::V0_0_3(&account),
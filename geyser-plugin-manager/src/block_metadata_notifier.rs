@@ -1,7 +1,7 @@
 use {
     crate::{
         block_metadata_notifier_interface::BlockMetadataNotifier,
-        geyser_plugin_manager::GeyserPluginManager,
+        geyser_plugin_delivery::GeyserNotification, geyser_plugin_manager::GeyserPluginManager,
     },
     log::*,
     solana_accounts_db::stake_rewards::RewardInfo,
@@ -52,6 +52,10 @@ impl BlockMetadataNotifier for BlockMetadataNotifierImpl {
                 executed_transaction_count,
                 entry_count,
             );
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::block_metadata(&block_info));
+                continue;
+            }
             let block_info = ReplicaBlockInfoVersions::V0_0_3(&block_info);
             match plugin.notify_block_metadata(block_info) {
                 Err(err) => {
@@ -0,0 +1,85 @@
+/// Module responsible for notifying plugins about committed bundles
+use {
+    crate::{
+        bundle_notifier_interface::BundleNotifier, geyser_plugin_delivery::GeyserNotification,
+        geyser_plugin_manager::GeyserPluginManager,
+    },
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaBundleInfo, ReplicaBundleInfoVersions,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature, transaction},
+    std::sync::{Arc, RwLock},
+};
+
+pub(crate) struct BundleNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl BundleNotifier for BundleNotifierImpl {
+    fn notify_bundle(
+        &self,
+        bundle_id: &str,
+        slot: Slot,
+        signatures: &[Signature],
+        transaction_results: &[transaction::Result<()>],
+        tips: &[(Pubkey, u64)],
+    ) {
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_bundle_info");
+
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let bundle_info = ReplicaBundleInfo {
+            bundle_id,
+            slot,
+            signatures,
+            transaction_results,
+            tips,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.bundle_notifications_enabled() {
+                continue;
+            }
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::bundle(&bundle_info));
+                continue;
+            }
+            match plugin.notify_bundle(ReplicaBundleInfoVersions::V0_0_1(&bundle_info)) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify bundle {}, error: ({}) to plugin {}",
+                        bundle_id,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified bundle {} to plugin {}",
+                        bundle_id,
+                        plugin.name()
+                    );
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_bundle_info-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
+}
+
+impl BundleNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+}
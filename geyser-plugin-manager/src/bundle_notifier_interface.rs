@@ -0,0 +1,19 @@
+use {
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature, transaction},
+    std::sync::Arc,
+};
+
+/// Interface for notifying committed bundles
+pub trait BundleNotifier {
+    /// Notify that the bundle with `bundle_id` was committed in `slot`
+    fn notify_bundle(
+        &self,
+        bundle_id: &str,
+        slot: Slot,
+        signatures: &[Signature],
+        transaction_results: &[transaction::Result<()>],
+        tips: &[(Pubkey, u64)],
+    );
+}
+
+pub type BundleNotifierArc = Arc<dyn BundleNotifier + Sync + Send>;
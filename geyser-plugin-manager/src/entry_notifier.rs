@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins about entries
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_delivery::GeyserNotification, geyser_plugin_manager::GeyserPluginManager,
+    },
     log::*,
     solana_entry::entry::EntrySummary,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
@@ -39,6 +41,10 @@ impl EntryNotifier for EntryNotifierImpl {
             if !plugin.entry_notifications_enabled() {
                 continue;
             }
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::entry(&entry_info));
+                continue;
+            }
             match plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_info)) {
                 Err(err) => {
                     error!(
@@ -0,0 +1,664 @@
+/// Module responsible for the asynchronous delivery of notifications to plugins, configured by the
+/// optional `async_delivery` section of the plugin's config file:
+///
+/// ```json5
+/// async_delivery: {
+///     queue_capacity: 10000,
+///     overflow_policy: "drop_oldest",
+/// }
+/// ```
+///
+/// Each such plugin gets a bounded queue, drained by a dedicated thread, so a slow plugin doesn't
+/// slow down replay and accounts-db. All notifications to the plugin go through its queue to
+/// preserve their ordering.
+///
+/// The queue is only as good as its overflow policy: with `overflow_policy: "block"`, a plugin
+/// that can't keep up blocks the notifying threads once its queue is full, backpressuring replay
+/// and accounts-db just like synchronous delivery would. The default is `"drop_oldest"`.
+use {
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaBlockInfoV3,
+        ReplicaBlockInfoVersions, ReplicaBundleInfo, ReplicaBundleInfoVersions, ReplicaEntryInfoV2,
+        ReplicaEntryInfoVersions, ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
+        Result as PluginResult, SlotStatus,
+    },
+    solana_metrics::*,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{self, SanitizedTransaction},
+    },
+    solana_transaction_status::{Reward, TransactionStatusMeta},
+    std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+pub const DEFAULT_ASYNC_DELIVERY_QUEUE_CAPACITY: usize = 10_000;
+
+const METRICS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What to do with a notification when the plugin's queue is full.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for the plugin to make room. This blocks the notifying thread, i.e. replay or
+    /// accounts-db, for as long as the plugin is behind.
+    Block,
+    /// Drop the oldest queued notification to make room.
+    #[default]
+    DropOldest,
+    /// Stop notifying the plugin until it is reloaded.
+    Disconnect,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsyncDeliveryConfig {
+    pub queue_capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for AsyncDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: DEFAULT_ASYNC_DELIVERY_QUEUE_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// An owned copy of a notification, queued for a plugin.
+#[derive(Debug)]
+pub(crate) enum GeyserNotification {
+    AccountUpdate {
+        slot: Slot,
+        is_startup: bool,
+        pubkey: Vec<u8>,
+        lamports: u64,
+        owner: Vec<u8>,
+        executable: bool,
+        rent_epoch: u64,
+        data: Vec<u8>,
+        write_version: u64,
+        txn: Option<SanitizedTransaction>,
+    },
+    EndOfStartup,
+    SlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+    Transaction {
+        slot: Slot,
+        index: usize,
+        signature: Signature,
+        is_vote: bool,
+        transaction: SanitizedTransaction,
+        transaction_status_meta: TransactionStatusMeta,
+    },
+    Entry {
+        slot: Slot,
+        index: usize,
+        num_hashes: u64,
+        hash: Vec<u8>,
+        executed_transaction_count: u64,
+        starting_transaction_index: usize,
+    },
+    BlockMetadata {
+        parent_slot: Slot,
+        parent_blockhash: String,
+        slot: Slot,
+        blockhash: String,
+        rewards: Vec<Reward>,
+        block_time: Option<UnixTimestamp>,
+        block_height: Option<u64>,
+        executed_transaction_count: u64,
+        entry_count: u64,
+    },
+    Bundle {
+        bundle_id: String,
+        slot: Slot,
+        signatures: Vec<Signature>,
+        transaction_results: Vec<transaction::Result<()>>,
+        tips: Vec<(Pubkey, u64)>,
+    },
+}
+
+impl GeyserNotification {
+    pub(crate) fn account_update(
+        account: &ReplicaAccountInfoV3,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Self {
+        Self::AccountUpdate {
+            slot,
+            is_startup,
+            pubkey: account.pubkey.to_vec(),
+            lamports: account.lamports,
+            owner: account.owner.to_vec(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+            write_version: account.write_version,
+            txn: account.txn.cloned(),
+        }
+    }
+
+    pub(crate) fn transaction(transaction: &ReplicaTransactionInfoV2, slot: Slot) -> Self {
+        Self::Transaction {
+            slot,
+            index: transaction.index,
+            signature: *transaction.signature,
+            is_vote: transaction.is_vote,
+            transaction: transaction.transaction.clone(),
+            transaction_status_meta: transaction.transaction_status_meta.clone(),
+        }
+    }
+
+    pub(crate) fn entry(entry: &ReplicaEntryInfoV2) -> Self {
+        Self::Entry {
+            slot: entry.slot,
+            index: entry.index,
+            num_hashes: entry.num_hashes,
+            hash: entry.hash.to_vec(),
+            executed_transaction_count: entry.executed_transaction_count,
+            starting_transaction_index: entry.starting_transaction_index,
+        }
+    }
+
+    pub(crate) fn block_metadata(block_info: &ReplicaBlockInfoV3) -> Self {
+        Self::BlockMetadata {
+            parent_slot: block_info.parent_slot,
+            parent_blockhash: block_info.parent_blockhash.to_string(),
+            slot: block_info.slot,
+            blockhash: block_info.blockhash.to_string(),
+            rewards: block_info.rewards.to_vec(),
+            block_time: block_info.block_time,
+            block_height: block_info.block_height,
+            executed_transaction_count: block_info.executed_transaction_count,
+            entry_count: block_info.entry_count,
+        }
+    }
+
+    pub(crate) fn bundle(bundle_info: &ReplicaBundleInfo) -> Self {
+        Self::Bundle {
+            bundle_id: bundle_info.bundle_id.to_string(),
+            slot: bundle_info.slot,
+            signatures: bundle_info.signatures.to_vec(),
+            transaction_results: bundle_info.transaction_results.to_vec(),
+            tips: bundle_info.tips.to_vec(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::AccountUpdate { .. } => "account update",
+            Self::EndOfStartup => "end of startup",
+            Self::SlotStatus { .. } => "slot status",
+            Self::Transaction { .. } => "transaction",
+            Self::Entry { .. } => "entry",
+            Self::BlockMetadata { .. } => "block metadata",
+            Self::Bundle { .. } => "bundle",
+        }
+    }
+
+    fn deliver(&self, plugin: &dyn GeyserPlugin) -> PluginResult<()> {
+        match self {
+            Self::AccountUpdate {
+                slot,
+                is_startup,
+                pubkey,
+                lamports,
+                owner,
+                executable,
+                rent_epoch,
+                data,
+                write_version,
+                txn,
+            } => {
+                let account = ReplicaAccountInfoV3 {
+                    pubkey,
+                    lamports: *lamports,
+                    owner,
+                    executable: *executable,
+                    rent_epoch: *rent_epoch,
+                    data,
+                    write_version: *write_version,
+                    txn: txn.as_ref(),
+                };
+                plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(&account),
+                    *slot,
+                    *is_startup,
+                )
+            }
+            Self::EndOfStartup => plugin.notify_end_of_startup(),
+            Self::SlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(*slot, *parent, *status),
+            Self::Transaction {
+                slot,
+                index,
+                signature,
+                is_vote,
+                transaction,
+                transaction_status_meta,
+            } => {
+                let transaction_info = ReplicaTransactionInfoV2 {
+                    signature,
+                    is_vote: *is_vote,
+                    transaction,
+                    transaction_status_meta,
+                    index: *index,
+                };
+                plugin.notify_transaction(
+                    ReplicaTransactionInfoVersions::V0_0_2(&transaction_info),
+                    *slot,
+                )
+            }
+            Self::Entry {
+                slot,
+                index,
+                num_hashes,
+                hash,
+                executed_transaction_count,
+                starting_transaction_index,
+            } => {
+                let entry_info = ReplicaEntryInfoV2 {
+                    slot: *slot,
+                    index: *index,
+                    num_hashes: *num_hashes,
+                    hash,
+                    executed_transaction_count: *executed_transaction_count,
+                    starting_transaction_index: *starting_transaction_index,
+                };
+                plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_info))
+            }
+            Self::BlockMetadata {
+                parent_slot,
+                parent_blockhash,
+                slot,
+                blockhash,
+                rewards,
+                block_time,
+                block_height,
+                executed_transaction_count,
+                entry_count,
+            } => {
+                let block_info = ReplicaBlockInfoV3 {
+                    parent_slot: *parent_slot,
+                    parent_blockhash,
+                    slot: *slot,
+                    blockhash,
+                    rewards,
+                    block_time: *block_time,
+                    block_height: *block_height,
+                    executed_transaction_count: *executed_transaction_count,
+                    entry_count: *entry_count,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_3(&block_info))
+            }
+            Self::Bundle {
+                bundle_id,
+                slot,
+                signatures,
+                transaction_results,
+                tips,
+            } => {
+                let bundle_info = ReplicaBundleInfo {
+                    bundle_id,
+                    slot: *slot,
+                    signatures,
+                    transaction_results,
+                    tips,
+                };
+                plugin.notify_bundle(ReplicaBundleInfoVersions::V0_0_1(&bundle_info))
+            }
+        }
+    }
+}
+
+/// The state of a plugin's asynchronous delivery, reported by the `listPlugins` admin RPC.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsyncDeliveryInfo {
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub blocked: u64,
+    /// Set once the queue overflowed under `OverflowPolicy::Disconnect`, until the plugin is
+    /// reloaded.
+    pub disconnected: bool,
+}
+
+/// Updated by the notifying threads and the delivery thread, reported by the metrics thread.
+#[derive(Debug, Default)]
+struct AsyncDeliveryStats {
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    blocked: AtomicU64,
+    disconnected: AtomicBool,
+}
+
+impl AsyncDeliveryStats {
+    fn info(&self, receiver: &Receiver<GeyserNotification>) -> AsyncDeliveryInfo {
+        AsyncDeliveryInfo {
+            queue_depth: receiver.len(),
+            queue_capacity: receiver.capacity().unwrap_or_default(),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+            disconnected: self.disconnected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The queue and delivery thread of a plugin configured for asynchronous delivery.
+///
+/// Metrics are reported by a separate thread, so they keep flowing while the plugin is stuck.
+#[derive(Debug)]
+pub(crate) struct AsyncPluginDelivery {
+    name: String,
+    overflow_policy: OverflowPolicy,
+    sender: Option<Sender<GeyserNotification>>,
+    // Used to make room in the queue under `OverflowPolicy::DropOldest`
+    receiver: Receiver<GeyserNotification>,
+    stats: Arc<AsyncDeliveryStats>,
+    thread_hdl: Option<JoinHandle<()>>,
+    metrics_exit_sender: Option<Sender<()>>,
+    metrics_thread_hdl: Option<JoinHandle<()>>,
+}
+
+impl AsyncPluginDelivery {
+    pub(crate) fn new(
+        name: String,
+        plugin: Arc<Box<dyn GeyserPlugin>>,
+        config: &AsyncDeliveryConfig,
+    ) -> Self {
+        let (sender, receiver) = bounded(config.queue_capacity);
+        let stats = Arc::new(AsyncDeliveryStats::default());
+        let thread_hdl = {
+            let name = name.clone();
+            let receiver = receiver.clone();
+            let stats = stats.clone();
+            Builder::new()
+                .name("solGeyserAsync".to_string())
+                .spawn(move || Self::run_delivery(&name, &**plugin, receiver, &stats))
+                .unwrap()
+        };
+        let (metrics_exit_sender, metrics_exit_receiver) = bounded(0);
+        let metrics_thread_hdl = {
+            let name = name.clone();
+            let receiver = receiver.clone();
+            let stats = stats.clone();
+            Builder::new()
+                .name("solGeyserAsyncMt".to_string())
+                .spawn(move || Self::run_metrics(&name, &receiver, &stats, metrics_exit_receiver))
+                .unwrap()
+        };
+        info!("Started asynchronous delivery to plugin {name}, config: {config:?}");
+        Self {
+            name,
+            overflow_policy: config.overflow_policy,
+            sender: Some(sender),
+            receiver,
+            stats,
+            thread_hdl: Some(thread_hdl),
+            metrics_exit_sender: Some(metrics_exit_sender),
+            metrics_thread_hdl: Some(metrics_thread_hdl),
+        }
+    }
+
+    pub(crate) fn info(&self) -> AsyncDeliveryInfo {
+        self.stats.info(&self.receiver)
+    }
+
+    /// Queues the notification, applying the overflow policy if the queue is full.
+    pub(crate) fn send(&self, notification: GeyserNotification) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if self.stats.disconnected.load(Ordering::Relaxed) {
+            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let mut notification = notification;
+        loop {
+            match sender.try_send(notification) {
+                Ok(()) => return,
+                Err(TrySendError::Disconnected(_)) => {
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Err(TrySendError::Full(full)) => match self.overflow_policy {
+                    OverflowPolicy::Block => {
+                        self.stats.blocked.fetch_add(1, Ordering::Relaxed);
+                        if sender.send(full).is_err() {
+                            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        return;
+                    }
+                    OverflowPolicy::DropOldest => {
+                        if self.receiver.try_recv().is_ok() {
+                            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        notification = full;
+                    }
+                    OverflowPolicy::Disconnect => {
+                        if !self.stats.disconnected.swap(true, Ordering::Relaxed) {
+                            error!(
+                                "The queue of plugin {} is full, disconnecting it until it is reloaded",
+                                self.name
+                            );
+                        }
+                        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Stops accepting notifications, and waits for the queued ones to be delivered.
+    pub(crate) fn join(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread_hdl) = self.thread_hdl.take() {
+            if thread_hdl.join().is_err() {
+                error!("The delivery thread of plugin {} panicked", self.name);
+            }
+        }
+        drop(self.metrics_exit_sender.take());
+        if let Some(metrics_thread_hdl) = self.metrics_thread_hdl.take() {
+            if metrics_thread_hdl.join().is_err() {
+                error!("The metrics thread of plugin {} panicked", self.name);
+            }
+        }
+    }
+
+    fn run_delivery(
+        name: &str,
+        plugin: &dyn GeyserPlugin,
+        receiver: Receiver<GeyserNotification>,
+        stats: &AsyncDeliveryStats,
+    ) {
+        for notification in receiver.iter() {
+            if let Err(err) = notification.deliver(plugin) {
+                error!(
+                    "Failed to notify {}, error: ({}) to plugin {}",
+                    notification.kind(),
+                    err,
+                    name
+                );
+            }
+            stats.delivered.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Reports the stats every `METRICS_REPORT_INTERVAL`, and once more when `exit` is dropped.
+    fn run_metrics(
+        name: &str,
+        receiver: &Receiver<GeyserNotification>,
+        stats: &AsyncDeliveryStats,
+        exit: Receiver<()>,
+    ) {
+        let mut reported = AsyncDeliveryInfo::default();
+        loop {
+            let exited = match exit.recv_timeout(METRICS_REPORT_INTERVAL) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => false,
+                Err(RecvTimeoutError::Disconnected) => true,
+            };
+            let info = stats.info(receiver);
+            datapoint_info!(
+                "geyser-plugin-async-delivery",
+                ("plugin", name, String),
+                ("queue_depth", info.queue_depth, i64),
+                ("queue_capacity", info.queue_capacity, i64),
+                ("delivered", info.delivered - reported.delivered, i64),
+                ("dropped", info.dropped - reported.dropped, i64),
+                ("blocked", info.blocked - reported.blocked, i64),
+                ("disconnected", info.disconnected, bool),
+            );
+            reported = info;
+            if exited {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crossbeam_channel::unbounded, std::sync::Mutex};
+
+    /// Records the slots it is notified of. Once `started` is signaled, waits for `gate` before
+    /// returning from each notification, until the gate's sender is dropped.
+    #[derive(Debug)]
+    struct SlowPlugin {
+        slots: Arc<Mutex<Vec<Slot>>>,
+        started: Sender<()>,
+        gate: Receiver<()>,
+    }
+
+    impl GeyserPlugin for SlowPlugin {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<Slot>,
+            _status: SlotStatus,
+        ) -> PluginResult<()> {
+            self.slots.lock().unwrap().push(slot);
+            let _ = self.started.send(());
+            let _ = self.gate.recv();
+            Ok(())
+        }
+    }
+
+    fn slot_status(slot: Slot) -> GeyserNotification {
+        GeyserNotification::SlotStatus {
+            slot,
+            parent: None,
+            status: SlotStatus::Processed,
+        }
+    }
+
+    /// Starts delivery to a `SlowPlugin`, stalled on slot 0 with an empty queue.
+    fn stalled_delivery(
+        overflow_policy: OverflowPolicy,
+    ) -> (AsyncPluginDelivery, Arc<Mutex<Vec<Slot>>>, Sender<()>) {
+        let slots = Arc::new(Mutex::new(vec![]));
+        let (started_sender, started_receiver) = unbounded();
+        let (gate_sender, gate_receiver) = unbounded();
+        let plugin: Box<dyn GeyserPlugin> = Box::new(SlowPlugin {
+            slots: slots.clone(),
+            started: started_sender,
+            gate: gate_receiver,
+        });
+        let delivery = AsyncPluginDelivery::new(
+            "slow".to_string(),
+            Arc::new(plugin),
+            &AsyncDeliveryConfig {
+                queue_capacity: 2,
+                overflow_policy,
+            },
+        );
+        delivery.send(slot_status(0));
+        started_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        (delivery, slots, gate_sender)
+    }
+
+    #[test]
+    fn test_overflow_drop_oldest() {
+        let (mut delivery, slots, gate_sender) = stalled_delivery(OverflowPolicy::DropOldest);
+        for slot in 1..=4 {
+            delivery.send(slot_status(slot));
+        }
+        assert_eq!(delivery.receiver.len(), 2);
+        assert_eq!(delivery.stats.dropped.load(Ordering::Relaxed), 2);
+
+        drop(gate_sender);
+        delivery.join();
+        assert_eq!(*slots.lock().unwrap(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_overflow_disconnect() {
+        let (mut delivery, slots, gate_sender) = stalled_delivery(OverflowPolicy::Disconnect);
+        for slot in 1..=3 {
+            delivery.send(slot_status(slot));
+        }
+        let info = delivery.info();
+        assert!(info.disconnected);
+        assert_eq!(info.queue_depth, 2);
+        assert_eq!(info.queue_capacity, 2);
+        assert_eq!(info.dropped, 1);
+
+        drop(gate_sender);
+        // Notifications are dropped once disconnected, even if the queue has room again
+        while !delivery.receiver.is_empty() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        delivery.send(slot_status(4));
+        delivery.join();
+        assert_eq!(*slots.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(delivery.stats.dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_default_overflow_policy() {
+        let config: AsyncDeliveryConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.overflow_policy, OverflowPolicy::DropOldest);
+        assert_eq!(config, AsyncDeliveryConfig::default());
+    }
+
+    #[test]
+    fn test_overflow_block() {
+        let (mut delivery, slots, gate_sender) = stalled_delivery(OverflowPolicy::Block);
+        delivery.send(slot_status(1));
+        delivery.send(slot_status(2));
+        drop(gate_sender);
+        for slot in 3..100 {
+            delivery.send(slot_status(slot));
+        }
+        delivery.join();
+        assert_eq!(*slots.lock().unwrap(), (0..100).collect::<Vec<_>>());
+        assert_eq!(delivery.stats.dropped.load(Ordering::Relaxed), 0);
+    }
+}
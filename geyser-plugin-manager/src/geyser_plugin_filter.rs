@@ -0,0 +1,281 @@
+/// Module responsible for filtering notifications before they reach a plugin, based on the
+/// optional `filters` section of the plugin's config file:
+///
+/// ```json5
+/// filters: {
+///     accounts: {
+///         owners: ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+///         pubkeys: [],
+///         data_sizes: [{ min: 165, max: 165 }],
+///     },
+///     transactions: {
+///         include_votes: false,
+///         include_failed: true,
+///     },
+/// }
+/// ```
+use {
+    crate::geyser_plugin_manager::GeyserPluginManagerError,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashSet,
+        str::FromStr,
+        sync::atomic::{AtomicU64, Ordering},
+    },
+};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeyserPluginFilterConfig {
+    pub accounts: Option<AccountFilterConfig>,
+    pub transactions: Option<TransactionFilterConfig>,
+}
+
+/// An account update is notified if its owner is in `owners` or its pubkey is in `pubkeys`,
+/// and its data size is in one of `data_sizes`. Empty lists don't filter anything.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountFilterConfig {
+    pub owners: Vec<String>,
+    pub pubkeys: Vec<String>,
+    pub data_sizes: Vec<DataSizeRange>,
+}
+
+/// An inclusive range of account data sizes, in bytes.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DataSizeRange {
+    #[serde(default)]
+    pub min: usize,
+    #[serde(default = "DataSizeRange::default_max")]
+    pub max: usize,
+}
+
+impl DataSizeRange {
+    fn default_max() -> usize {
+        usize::MAX
+    }
+
+    fn contains(&self, data_size: usize) -> bool {
+        (self.min..=self.max).contains(&data_size)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionFilterConfig {
+    pub include_votes: bool,
+    pub include_failed: bool,
+}
+
+impl Default for TransactionFilterConfig {
+    fn default() -> Self {
+        Self {
+            include_votes: true,
+            include_failed: true,
+        }
+    }
+}
+
+/// How many notifications a plugin's filter let through and how many it dropped, reported by the
+/// `listPlugins` admin RPC.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeyserPluginFilterStats {
+    pub accounts_notified: u64,
+    pub accounts_filtered: u64,
+    pub transactions_notified: u64,
+    pub transactions_filtered: u64,
+}
+
+#[derive(Debug)]
+struct AccountFilter {
+    owners: HashSet<Pubkey>,
+    pubkeys: HashSet<Pubkey>,
+    data_sizes: Vec<DataSizeRange>,
+}
+
+impl AccountFilter {
+    fn matches(&self, pubkey: &[u8], owner: &[u8], data_size: usize) -> bool {
+        fn contains(pubkeys: &HashSet<Pubkey>, pubkey: &[u8]) -> bool {
+            Pubkey::try_from(pubkey).map_or(false, |pubkey| pubkeys.contains(&pubkey))
+        }
+
+        let key_matches = (self.owners.is_empty() && self.pubkeys.is_empty())
+            || contains(&self.owners, owner)
+            || contains(&self.pubkeys, pubkey);
+        let data_size_matches = self.data_sizes.is_empty()
+            || self
+                .data_sizes
+                .iter()
+                .any(|data_sizes| data_sizes.contains(data_size));
+        key_matches && data_size_matches
+    }
+}
+
+/// The filter evaluated by the plugin manager before notifying a plugin. The default filter lets
+/// everything through.
+#[derive(Debug, Default)]
+pub struct GeyserPluginFilter {
+    accounts: Option<AccountFilter>,
+    transactions: Option<TransactionFilterConfig>,
+    accounts_notified: AtomicU64,
+    accounts_filtered: AtomicU64,
+    transactions_notified: AtomicU64,
+    transactions_filtered: AtomicU64,
+}
+
+impl GeyserPluginFilter {
+    pub fn new(config: GeyserPluginFilterConfig) -> Result<Self, GeyserPluginManagerError> {
+        fn parse_pubkeys(pubkeys: &[String]) -> Result<HashSet<Pubkey>, GeyserPluginManagerError> {
+            pubkeys
+                .iter()
+                .map(|pubkey| {
+                    Pubkey::from_str(pubkey).map_err(|err| {
+                        GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                            "Invalid pubkey {pubkey} in the plugin filters, error: {err:?}"
+                        ))
+                    })
+                })
+                .collect()
+        }
+
+        let accounts = config
+            .accounts
+            .map(|accounts| {
+                if let Some(range) = accounts.data_sizes.iter().find(|range| range.min > range.max)
+                {
+                    return Err(GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                        "Invalid data size range in the plugin filters: min {} is greater than max {}",
+                        range.min, range.max
+                    )));
+                }
+                Ok(AccountFilter {
+                    owners: parse_pubkeys(&accounts.owners)?,
+                    pubkeys: parse_pubkeys(&accounts.pubkeys)?,
+                    data_sizes: accounts.data_sizes,
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            accounts,
+            transactions: config.transactions,
+            ..Self::default()
+        })
+    }
+
+    /// Returns true if the account update should be notified, and counts the outcome.
+    pub fn account_matches(&self, pubkey: &[u8], owner: &[u8], data_size: usize) -> bool {
+        let matches = self
+            .accounts
+            .as_ref()
+            .map_or(true, |accounts| accounts.matches(pubkey, owner, data_size));
+        Self::count(matches, &self.accounts_notified, &self.accounts_filtered);
+        matches
+    }
+
+    /// Returns true if the transaction should be notified, and counts the outcome.
+    pub fn transaction_matches(&self, is_vote: bool, is_failed: bool) -> bool {
+        let matches = self.transactions.as_ref().map_or(true, |transactions| {
+            (transactions.include_votes || !is_vote) && (transactions.include_failed || !is_failed)
+        });
+        Self::count(
+            matches,
+            &self.transactions_notified,
+            &self.transactions_filtered,
+        );
+        matches
+    }
+
+    pub fn stats(&self) -> GeyserPluginFilterStats {
+        GeyserPluginFilterStats {
+            accounts_notified: self.accounts_notified.load(Ordering::Relaxed),
+            accounts_filtered: self.accounts_filtered.load(Ordering::Relaxed),
+            transactions_notified: self.transactions_notified.load(Ordering::Relaxed),
+            transactions_filtered: self.transactions_filtered.load(Ordering::Relaxed),
+        }
+    }
+
+    fn count(matches: bool, notified: &AtomicU64, filtered: &AtomicU64) {
+        if matches {
+            notified.fetch_add(1, Ordering::Relaxed);
+        } else {
+            filtered.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter_from_json(json: &str) -> Result<GeyserPluginFilter, GeyserPluginManagerError> {
+        let config: GeyserPluginFilterConfig = json5::from_str(json).unwrap();
+        GeyserPluginFilter::new(config)
+    }
+
+    #[test]
+    fn test_default_filter_matches_everything() {
+        let filter = GeyserPluginFilter::default();
+        assert!(filter.account_matches(
+            Pubkey::new_unique().as_ref(),
+            Pubkey::new_unique().as_ref(),
+            0
+        ));
+        assert!(filter.transaction_matches(true, true));
+        assert_eq!(
+            filter.stats(),
+            GeyserPluginFilterStats {
+                accounts_notified: 1,
+                transactions_notified: 1,
+                ..GeyserPluginFilterStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_account_filter() {
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let filter = filter_from_json(&format!(
+            "{{ accounts: {{ owners: ['{owner}'], pubkeys: ['{pubkey}'], \
+             data_sizes: [{{ min: 10, max: 20 }}, {{ min: 100 }}] }} }}"
+        ))
+        .unwrap();
+
+        let other = Pubkey::new_unique();
+        assert!(filter.account_matches(other.as_ref(), owner.as_ref(), 10));
+        assert!(filter.account_matches(pubkey.as_ref(), other.as_ref(), 1_000));
+        assert!(!filter.account_matches(other.as_ref(), other.as_ref(), 10));
+        assert!(!filter.account_matches(pubkey.as_ref(), owner.as_ref(), 50));
+        // Transactions aren't filtered without a transactions section
+        assert!(filter.transaction_matches(true, true));
+
+        let stats = filter.stats();
+        assert_eq!(stats.accounts_notified, 2);
+        assert_eq!(stats.accounts_filtered, 2);
+    }
+
+    #[test]
+    fn test_transaction_filter() {
+        let filter = filter_from_json("{ transactions: { include_votes: false } }").unwrap();
+        assert!(filter.transaction_matches(false, false));
+        assert!(filter.transaction_matches(false, true));
+        assert!(!filter.transaction_matches(true, false));
+
+        let filter = filter_from_json("{ transactions: { include_failed: false } }").unwrap();
+        assert!(filter.transaction_matches(true, false));
+        assert!(!filter.transaction_matches(false, true));
+        assert_eq!(filter.stats().transactions_filtered, 1);
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        assert!(filter_from_json("{ accounts: { owners: ['not a pubkey'] } }").is_err());
+        assert!(filter_from_json("{ accounts: { data_sizes: [{ min: 2, max: 1 }] } }").is_err());
+        assert!(
+            json5::from_str::<GeyserPluginFilterConfig>("{ accounts: { owner: [] } }").is_err()
+        );
+    }
+}
@@ -1,32 +1,86 @@
 use {
+    crate::{
+        geyser_plugin_delivery::{AsyncDeliveryConfig, AsyncDeliveryInfo, AsyncPluginDelivery},
+        geyser_plugin_filter::{GeyserPluginFilter, GeyserPluginFilterStats},
+    },
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     jsonrpc_server_utils::tokio::sync::oneshot::Sender as OneShotSender,
     libloading::Library,
     log::*,
+    serde_derive::{Deserialize, Serialize},
     solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
     std::{
         ops::{Deref, DerefMut},
         path::Path,
+        sync::Arc,
     },
 };
 
 #[derive(Debug)]
 pub struct LoadedGeyserPlugin {
     name: String,
-    plugin: Box<dyn GeyserPlugin>,
+    // Shared with the delivery thread while asynchronous delivery is running
+    plugin: Arc<Box<dyn GeyserPlugin>>,
+    filter: GeyserPluginFilter,
+    async_delivery_config: Option<AsyncDeliveryConfig>,
+    async_delivery: Option<AsyncPluginDelivery>,
 }
 
 impl LoadedGeyserPlugin {
     pub fn new(plugin: Box<dyn GeyserPlugin>, name: Option<String>) -> Self {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
-            plugin,
+            plugin: Arc::new(plugin),
+            filter: GeyserPluginFilter::default(),
+            async_delivery_config: None,
+            async_delivery: None,
         }
     }
 
+    pub fn with_filter(mut self, filter: GeyserPluginFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Notify the plugin from a dedicated thread once it is loaded, instead of from the
+    /// notifying threads
+    pub fn with_async_delivery(mut self, config: AsyncDeliveryConfig) -> Self {
+        self.async_delivery_config = Some(config);
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// The filter evaluated before notifying the plugin of account updates and transactions
+    pub fn filter(&self) -> &GeyserPluginFilter {
+        &self.filter
+    }
+
+    /// The queue to send notifications to, if the plugin is configured for asynchronous delivery
+    pub(crate) fn async_delivery(&self) -> Option<&AsyncPluginDelivery> {
+        self.async_delivery.as_ref()
+    }
+
+    /// Start the delivery thread, if configured. Must be called after `on_load`.
+    fn start_async_delivery(&mut self) {
+        if let Some(config) = &self.async_delivery_config {
+            self.async_delivery = Some(AsyncPluginDelivery::new(
+                self.name.clone(),
+                self.plugin.clone(),
+                config,
+            ));
+        }
+    }
+
+    /// Deliver the queued notifications and stop the delivery thread, if any. Must be called
+    /// before `on_unload`.
+    fn stop_async_delivery(&mut self) {
+        if let Some(mut async_delivery) = self.async_delivery.take() {
+            async_delivery.join();
+        }
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
@@ -39,10 +93,21 @@ impl Deref for LoadedGeyserPlugin {
 
 impl DerefMut for LoadedGeyserPlugin {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.plugin
+        Arc::get_mut(&mut self.plugin)
+            .expect("the plugin must not be mutated while its delivery thread is running")
     }
 }
 
+/// A loaded plugin, as reported by the `listPlugins` admin RPC
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeyserPluginInfo {
+    pub name: String,
+    pub filter_stats: GeyserPluginFilterStats,
+    /// Only set for plugins configured for asynchronous delivery
+    #[serde(default)]
+    pub async_delivery: Option<AsyncDeliveryInfo>,
+}
+
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
     pub plugins: Vec<LoadedGeyserPlugin>,
@@ -62,6 +127,7 @@ impl GeyserPluginManager {
     pub fn unload(&mut self) {
         for mut plugin in self.plugins.drain(..) {
             info!("Unloading plugin for {:?}", plugin.name());
+            plugin.stop_async_delivery();
             plugin.on_unload();
         }
 
@@ -100,9 +166,27 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in bundle data
+    pub fn bundle_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.bundle_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
-    pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
-        Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
+    pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<GeyserPluginInfo>> {
+        Ok(self
+            .plugins
+            .iter()
+            .map(|p| GeyserPluginInfo {
+                name: p.name().to_owned(),
+                filter_stats: p.filter().stats(),
+                async_delivery: p.async_delivery().map(AsyncPluginDelivery::info),
+            })
+            .collect())
     }
 
     /// Admin RPC request handler
@@ -143,7 +227,7 @@ impl GeyserPluginManager {
             });
         }
 
-        setup_logger_for_plugin(&*new_plugin.plugin)?;
+        setup_logger_for_plugin(&**new_plugin.plugin)?;
 
         // Call on_load and push plugin
         new_plugin
@@ -157,6 +241,7 @@ impl GeyserPluginManager {
                 data: None,
             })?;
         let name = new_plugin.name().to_string();
+        new_plugin.start_async_delivery();
         self.plugins.push(new_plugin);
         self.libs.push(new_lib);
 
@@ -231,12 +316,13 @@ impl GeyserPluginManager {
             });
         }
 
-        setup_logger_for_plugin(&*new_plugin.plugin)?;
+        setup_logger_for_plugin(&**new_plugin.plugin)?;
 
         // Attempt to on_load with new plugin
         match new_plugin.on_load(new_parsed_config_file, true) {
             // On success, push plugin and library
             Ok(()) => {
+                new_plugin.start_async_delivery();
                 self.plugins.push(new_plugin);
                 self.libs.push(new_lib);
             }
@@ -260,6 +346,7 @@ impl GeyserPluginManager {
         let current_lib = self.libs.remove(idx);
         let mut current_plugin = self.plugins.remove(idx);
         let name = current_plugin.name().to_string();
+        current_plugin.stop_async_delivery();
         current_plugin.on_unload();
         // The plugin must be dropped before the library to avoid a crash.
         drop(current_plugin);
@@ -298,7 +385,7 @@ pub enum GeyserPluginManagerRequest {
         response_sender: OneShotSender<JsonRpcResult<String>>,
     },
     ListPlugins {
-        response_sender: OneShotSender<JsonRpcResult<Vec<String>>>,
+        response_sender: OneShotSender<JsonRpcResult<Vec<GeyserPluginInfo>>>,
     },
 }
 
@@ -384,6 +471,36 @@ pub(crate) fn load_plugin_from_config(
 
     let plugin_name = result["name"].as_str().map(|s| s.to_owned());
 
+    let filter = match result.get("filters") {
+        Some(filters) => {
+            let filter_config = serde_json::from_value(filters.clone()).map_err(|err| {
+                GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                    "The filters in the config file {geyser_plugin_config_file:?} are invalid, error: {err:?}"
+                ))
+            })?;
+            GeyserPluginFilter::new(filter_config)?
+        }
+        None => GeyserPluginFilter::default(),
+    };
+
+    let async_delivery_config = match result.get("async_delivery") {
+        Some(async_delivery) => {
+            let async_delivery_config: AsyncDeliveryConfig =
+                serde_json::from_value(async_delivery.clone()).map_err(|err| {
+                    GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                        "The async_delivery section of the config file {geyser_plugin_config_file:?} is invalid, error: {err:?}"
+                    ))
+                })?;
+            if async_delivery_config.queue_capacity == 0 {
+                return Err(GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                    "The async_delivery queue_capacity in the config file {geyser_plugin_config_file:?} must be greater than 0"
+                )));
+            }
+            Some(async_delivery_config)
+        }
+        None => None,
+    };
+
     let config_file = geyser_plugin_config_file
         .as_os_str()
         .to_str()
@@ -398,11 +515,11 @@ pub(crate) fn load_plugin_from_config(
         let plugin_raw = constructor();
         (Box::from_raw(plugin_raw), lib)
     };
-    Ok((
-        LoadedGeyserPlugin::new(plugin, plugin_name),
-        lib,
-        config_file,
-    ))
+    let mut plugin = LoadedGeyserPlugin::new(plugin, plugin_name).with_filter(filter);
+    if let Some(async_delivery_config) = async_delivery_config {
+        plugin = plugin.with_async_delivery(async_delivery_config);
+    }
+    Ok((plugin, lib, config_file))
 }
 
 #[cfg(test)]
@@ -439,8 +556,11 @@ pub(crate) fn load_plugin_from_config(
 #[cfg(test)]
 mod tests {
     use {
-        crate::geyser_plugin_manager::{
-            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+        crate::{
+            geyser_plugin_delivery::AsyncDeliveryConfig,
+            geyser_plugin_manager::{
+                GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            },
         },
         libloading::Library,
         solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
@@ -516,9 +636,9 @@ mod tests {
 
         // The plugin is now replaced with ANOTHER_DUMMY_NAME
         let plugins = plugin_manager_lock.list_plugins().unwrap();
-        assert!(plugins.iter().any(|name| name.eq(ANOTHER_DUMMY_NAME)));
+        assert!(plugins.iter().any(|info| info.name.eq(ANOTHER_DUMMY_NAME)));
         // DUMMY_NAME should no longer be present.
-        assert!(!plugins.iter().any(|name| name.eq(DUMMY_NAME)));
+        assert!(!plugins.iter().any(|info| info.name.eq(DUMMY_NAME)));
     }
 
     #[test]
@@ -541,8 +661,8 @@ mod tests {
 
         // Check that both plugins are returned in the list
         let plugins = plugin_manager_lock.list_plugins().unwrap();
-        assert!(plugins.iter().any(|name| name.eq(DUMMY_NAME)));
-        assert!(plugins.iter().any(|name| name.eq(ANOTHER_DUMMY_NAME)));
+        assert!(plugins.iter().any(|info| info.name.eq(DUMMY_NAME)));
+        assert!(plugins.iter().any(|info| info.name.eq(ANOTHER_DUMMY_NAME)));
     }
 
     #[test]
@@ -561,4 +681,29 @@ mod tests {
         assert!(unload_result.is_ok());
         assert_eq!(plugin_manager_lock.plugins.len(), 0);
     }
+
+    #[test]
+    fn test_plugin_async_delivery_unload() {
+        let mut plugin_manager = GeyserPluginManager::new();
+
+        let (plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        let mut plugin = plugin.with_async_delivery(AsyncDeliveryConfig::default());
+        plugin.on_load(config, false).unwrap();
+        plugin.start_async_delivery();
+        assert!(plugin.async_delivery().is_some());
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+
+        let plugins = plugin_manager.list_plugins().unwrap();
+        let async_delivery = plugins[0].async_delivery.as_ref().unwrap();
+        assert_eq!(
+            async_delivery.queue_capacity,
+            AsyncDeliveryConfig::default().queue_capacity
+        );
+        assert!(!async_delivery.disconnected);
+
+        // The delivery thread is stopped before the plugin is unloaded
+        assert!(plugin_manager.unload_plugin(DUMMY_NAME).is_ok());
+        assert!(plugin_manager.plugins.is_empty());
+    }
 }
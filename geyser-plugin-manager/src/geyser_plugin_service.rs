@@ -3,6 +3,8 @@ use {
         accounts_update_notifier::AccountsUpdateNotifierImpl,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
+        bundle_notifier::BundleNotifierImpl,
+        bundle_notifier_interface::BundleNotifierArc,
         entry_notifier::EntryNotifierImpl,
         geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
         slot_status_notifier::SlotStatusNotifierImpl,
@@ -37,6 +39,7 @@ pub struct GeyserPluginService {
     transaction_notifier: Option<TransactionNotifierArc>,
     entry_notifier: Option<EntryNotifierArc>,
     block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+    bundle_notifier: Option<BundleNotifierArc>,
 }
 
 impl GeyserPluginService {
@@ -81,6 +84,7 @@ impl GeyserPluginService {
             plugin_manager.account_data_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
+        let bundle_notifications_enabled = plugin_manager.bundle_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
@@ -107,12 +111,20 @@ impl GeyserPluginService {
             None
         };
 
+        let bundle_notifier: Option<BundleNotifierArc> = if bundle_notifications_enabled {
+            let bundle_notifier = BundleNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(bundle_notifier))
+        } else {
+            None
+        };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierArc>,
         ) = if account_data_notifications_enabled
             || transaction_notifications_enabled
             || entry_notifications_enabled
+            || bundle_notifications_enabled
         {
             let slot_status_notifier = SlotStatusNotifierImpl::new(plugin_manager.clone());
             let slot_status_notifier = Arc::new(RwLock::new(slot_status_notifier));
@@ -143,6 +155,7 @@ impl GeyserPluginService {
             transaction_notifier,
             entry_notifier,
             block_metadata_notifier,
+            bundle_notifier,
         })
     }
 
@@ -168,6 +181,10 @@ impl GeyserPluginService {
         self.entry_notifier.clone()
     }
 
+    pub fn get_bundle_notifier(&self) -> Option<BundleNotifierArc> {
+        self.bundle_notifier.clone()
+    }
+
     pub fn get_block_metadata_notifier(&self) -> Option<BlockMetadataNotifierArc> {
         self.block_metadata_notifier.clone()
     }
@@ -1,11 +1,15 @@
 pub mod accounts_update_notifier;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
+pub mod bundle_notifier;
+pub mod bundle_notifier_interface;
 pub mod entry_notifier;
+pub mod geyser_plugin_delivery;
+pub mod geyser_plugin_filter;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
 
-pub use geyser_plugin_manager::GeyserPluginManagerRequest;
+pub use geyser_plugin_manager::{GeyserPluginInfo, GeyserPluginManagerRequest};
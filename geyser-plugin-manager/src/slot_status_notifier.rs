@@ -1,5 +1,7 @@
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_delivery::GeyserNotification, geyser_plugin_manager::GeyserPluginManager,
+    },
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
     solana_measure::measure::Measure,
@@ -51,6 +53,14 @@ impl SlotStatusNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::SlotStatus {
+                    slot,
+                    parent,
+                    status: slot_status,
+                });
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-slot");
             match plugin.update_slot_status(slot, parent, slot_status) {
                 Err(err) => {
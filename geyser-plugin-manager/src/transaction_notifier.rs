@@ -1,6 +1,8 @@
 /// Module responsible for notifying plugins of transactions
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_delivery::GeyserNotification, geyser_plugin_manager::GeyserPluginManager,
+    },
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
@@ -45,7 +47,16 @@ impl TransactionNotifier for TransactionNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.transaction_notifications_enabled() {
+            if !plugin.transaction_notifications_enabled()
+                || !plugin.filter().transaction_matches(
+                    transaction_log_info.is_vote,
+                    transaction_status_meta.status.is_err(),
+                )
+            {
+                continue;
+            }
+            if let Some(async_delivery) = plugin.async_delivery() {
+                async_delivery.send(GeyserNotification::transaction(&transaction_log_info, slot));
                 continue;
             }
             match plugin.notify_transaction(
@@ -24,7 +24,7 @@ use {
         blockstore_options::AccessType,
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, LedgerArchive, LocalLedgerStorage},
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, EncodeError, EncodedConfirmedBlock,
         TransactionDetails, UiTransactionEncoding, VersionedConfirmedBlock,
@@ -32,7 +32,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -45,11 +45,20 @@ async fn upload(
     starting_slot: Option<Slot>,
     ending_slot: Option<Slot>,
     force_reupload: bool,
+    local_archive_path: Option<PathBuf>,
     config: solana_storage_bigtable::LedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
-        .await
-        .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
+    let ledger_archive: Arc<dyn LedgerArchive> = match local_archive_path {
+        Some(local_archive_path) => Arc::new(
+            LocalLedgerStorage::open(&local_archive_path)
+                .map_err(|err| format!("Failed to open local archive: {err:?}"))?,
+        ),
+        None => Arc::new(
+            solana_storage_bigtable::LedgerStorage::new_with_config(config)
+                .await
+                .map_err(|err| format!("Failed to connect to storage: {err:?}"))?,
+        ),
+    };
 
     let config = ConfirmedBlockUploadConfig {
         force_reupload,
@@ -75,7 +84,7 @@ async fn upload(
         );
         let last_slot_checked = solana_ledger::bigtable_upload::upload_confirmed_blocks(
             blockstore.clone(),
-            bigtable.clone(),
+            ledger_archive.clone(),
             starting_slot,
             current_ending_slot,
             config.clone(),
@@ -721,6 +730,16 @@ impl BigTableSubCommand for App<'_, '_> {
                                      instance. Note: reupload will *not* delete any data from the \
                                      tx-by-addr table; Use with care.",
                                 ),
+                        )
+                        .arg(
+                            Arg::with_name("local_archive")
+                                .long("local-archive")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .help(
+                                    "Upload to a local ledger archive in this directory instead \
+                                     of BigTable. The archive is created if it doesn't exist",
+                                ),
                         ),
                 )
                 .subcommand(
@@ -1097,6 +1116,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
             let starting_slot = value_t!(arg_matches, "starting_slot", Slot).ok();
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).ok();
             let force_reupload = arg_matches.is_present("force_reupload");
+            let local_archive_path = value_t!(arg_matches, "local_archive", PathBuf).ok();
             let blockstore = crate::open_blockstore(
                 &canonicalize_ledger_path(ledger_path),
                 arg_matches,
@@ -1113,6 +1133,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 starting_slot,
                 ending_slot,
                 force_reupload,
+                local_archive_path,
                 config,
             ))
         }
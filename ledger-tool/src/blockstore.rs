@@ -133,6 +133,9 @@ fn analyze_storage(database: &Database) {
     analyze_column::<BlockHeight>(database, "BlockHeight");
     analyze_column::<ProgramCosts>(database, "ProgramCosts");
     analyze_column::<OptimisticSlots>(database, "OptimisticSlots");
+    analyze_column::<BundleStatus>(database, "BundleStatus");
+    analyze_column::<BundleSlots>(database, "BundleSlots");
+    analyze_column::<TipRevenue>(database, "TipRevenue");
 }
 
 fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
@@ -164,6 +167,9 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::OptimisticSlots::NAME => {
             Some(cf::OptimisticSlots::slot(cf::OptimisticSlots::index(key)))
         }
+        cf::BundleStatus::NAME => Some(cf::BundleStatus::slot(cf::BundleStatus::index(key))),
+        cf::BundleSlots::NAME => Some(cf::BundleSlots::slot(cf::BundleSlots::index(key))),
+        cf::TipRevenue::NAME => Some(cf::TipRevenue::slot(cf::TipRevenue::index(key))),
         &_ => None,
     }
 }
@@ -0,0 +1,646 @@
+//! The `bundles` subcommand finds the bundles in a slot and re-executes them with
+//! `load_and_execute_bundle` so their behavior can be compared against what landed.
+use {
+    crate::{args::*, canonicalize_ledger_path, ledger_utils::*},
+    clap::{value_t, value_t_or_exit, App, Arg, ArgMatches, SubCommand},
+    log::*,
+    serde::Serialize,
+    solana_bundle::bundle_execution::{load_and_execute_bundle, LoadAndExecuteBundleError},
+    solana_clap_utils::{
+        input_parsers::pubkey_of,
+        input_validators::{is_pubkey, is_slot},
+    },
+    solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_core::tip_manager::{TipManager, TipManagerConfig},
+    solana_entry::entry::Entry,
+    solana_ledger::{
+        blockstore::Blockstore, blockstore_options::AccessType,
+        leader_schedule_cache::LeaderScheduleCache, use_snapshot_archives_at_startup,
+    },
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        bundle::{derive_bundle_id, SanitizedBundle},
+        clock::{Slot, MAX_PROCESSING_AGE},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{MessageHash, SanitizedTransaction, VersionedTransaction},
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        fmt::{self, Display, Formatter},
+        path::{Path, PathBuf},
+        process::exit,
+        sync::Arc,
+        time::Duration,
+    },
+};
+
+/// Bundles are never longer than this, larger entries come from regular banking
+const MAX_BUNDLE_LENGTH: usize = 5;
+
+/// Upper bound on how long re-executing a single bundle may take
+const MAX_BUNDLE_EXECUTION_TIME: Duration = Duration::from_secs(10);
+
+pub trait BundlesSubCommand {
+    fn bundles_subcommand(self) -> Self;
+}
+
+impl BundlesSubCommand for App<'_, '_> {
+    fn bundles_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("bundles")
+                .about(
+                    "Find the bundles in a slot and re-execute them against the parent bank, \
+                     reporting compute units, tips and account changes for each bundle",
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .index(1)
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Slot to find and re-execute bundles in"),
+                )
+                .arg(
+                    Arg::with_name("tip_payment_program_pubkey")
+                        .long("tip-payment-program-pubkey")
+                        .value_name("TIP_PAYMENT_PROGRAM_PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "The public key of the tip-payment program, used to derive the tip \
+                             accounts that identify bundles",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("isolated")
+                        .long("isolated")
+                        .takes_value(false)
+                        .help(
+                            "Execute every bundle against the parent bank alone instead of after \
+                             the transactions that precede it in the slot. This shows how each \
+                             bundle would have behaved had it been placed first in the block",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("max_genesis_archive_unpacked_size")
+                        .long("max-genesis-archive-unpacked-size")
+                        .value_name("NUMBER")
+                        .takes_value(true)
+                        .default_value("10485760")
+                        .help("maximum total uncompressed size of unpacked genesis archive"),
+                )
+                .arg(
+                    Arg::with_name(use_snapshot_archives_at_startup::cli::NAME)
+                        .long(use_snapshot_archives_at_startup::cli::LONG_ARG)
+                        .takes_value(true)
+                        .possible_values(use_snapshot_archives_at_startup::cli::POSSIBLE_VALUES)
+                        .default_value(
+                            use_snapshot_archives_at_startup::cli::default_value_for_ledger_tool(),
+                        )
+                        .help(use_snapshot_archives_at_startup::cli::HELP)
+                        .long_help(use_snapshot_archives_at_startup::cli::LONG_HELP),
+                ),
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountDiff {
+    pubkey: String,
+    pre_lamports: u64,
+    post_lamports: u64,
+    data_changed: bool,
+    owner_changed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionReplay {
+    signature: String,
+    /// Error and compute units recorded when the transaction landed, if transaction history was
+    /// enabled on the node that produced the ledger
+    landed_err: Option<String>,
+    landed_compute_units: Option<u64>,
+    replayed_err: Option<String>,
+    replayed_compute_units: Option<u64>,
+    account_diffs: Vec<AccountDiff>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleReplay {
+    entry_index: usize,
+    bundle_id: String,
+    result: String,
+    compute_units: u64,
+    tip_lamports: u64,
+    transactions: Vec<TransactionReplay>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundlesReplay {
+    slot: Slot,
+    parent_slot: Slot,
+    isolated: bool,
+    bundles: Vec<BundleReplay>,
+}
+
+impl Display for BundlesReplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Slot {} (parent {}): {} bundles{}",
+            self.slot,
+            self.parent_slot,
+            self.bundles.len(),
+            if self.isolated {
+                ", each executed against the parent bank"
+            } else {
+                ""
+            },
+        )?;
+        for bundle in &self.bundles {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Bundle {} (entry {}): {}",
+                bundle.bundle_id, bundle.entry_index, bundle.result
+            )?;
+            writeln!(f, "  Compute units: {}", bundle.compute_units)?;
+            writeln!(f, "  Tips: {} lamports", bundle.tip_lamports)?;
+            for transaction in &bundle.transactions {
+                writeln!(f, "  Transaction {}", transaction.signature)?;
+                writeln!(
+                    f,
+                    "    Landed:   err={} compute_units={}",
+                    transaction.landed_err.as_deref().unwrap_or("none"),
+                    display_option(transaction.landed_compute_units),
+                )?;
+                writeln!(
+                    f,
+                    "    Replayed: err={} compute_units={}",
+                    transaction.replayed_err.as_deref().unwrap_or("none"),
+                    display_option(transaction.replayed_compute_units),
+                )?;
+                for diff in &transaction.account_diffs {
+                    writeln!(
+                        f,
+                        "    {}: {} -> {} lamports{}{}",
+                        diff.pubkey,
+                        diff.pre_lamports,
+                        diff.post_lamports,
+                        if diff.data_changed {
+                            ", data changed"
+                        } else {
+                            ""
+                        },
+                        if diff.owner_changed {
+                            ", owner changed"
+                        } else {
+                            ""
+                        },
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl QuietDisplay for BundlesReplay {}
+impl VerboseDisplay for BundlesReplay {}
+
+fn display_option(value: Option<u64>) -> String {
+    value.map_or_else(|| "unknown".to_string(), |value| value.to_string())
+}
+
+pub fn bundles(ledger_path: &Path, arg_matches: &ArgMatches<'_>) {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    let slot = value_t_or_exit!(arg_matches, "slot", Slot);
+    let tip_payment_program_id = pubkey_of(arg_matches, "tip_payment_program_pubkey").unwrap();
+    let isolated = arg_matches.is_present("isolated");
+
+    let blockstore = Arc::new(open_blockstore(
+        &ledger_path,
+        arg_matches,
+        AccessType::Secondary,
+    ));
+    let parent_slot = blockstore
+        .meta(slot)
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.parent_slot)
+        .unwrap_or_else(|| {
+            eprintln!("Slot {slot} or its parent is not in the blockstore");
+            exit(1);
+        });
+    let entries = blockstore.get_slot_entries(slot, 0).unwrap_or_else(|err| {
+        eprintln!("Failed to load entries for slot {slot}: {err}");
+        exit(1);
+    });
+
+    let tip_manager = TipManager::new(TipManagerConfig {
+        tip_payment_program_id,
+        ..TipManagerConfig::default()
+    });
+    let tip_accounts = tip_manager.get_tip_accounts();
+    let bundle_entries = find_bundle_entries(&entries, &tip_accounts);
+    info!(
+        "found {} bundles in {} entries of slot {slot}",
+        bundle_entries.len(),
+        entries.len()
+    );
+
+    let bank = load_child_bank(
+        &ledger_path,
+        arg_matches,
+        blockstore.clone(),
+        parent_slot,
+        slot,
+    );
+    let mut replays = Vec::with_capacity(bundle_entries.len());
+    for (entry_index, entry) in entries.into_iter().enumerate() {
+        if bundle_entries.contains(&entry_index) {
+            replays.push(replay_bundle(
+                &bank,
+                &blockstore,
+                &tip_accounts,
+                entry_index,
+                &entry.transactions,
+            ));
+        }
+        if !isolated && !entry.transactions.is_empty() {
+            if let Err(err) = bank.try_process_entry_transactions(entry.transactions) {
+                eprintln!("Failed to process entry {entry_index} of slot {slot}: {err}");
+                exit(1);
+            }
+        }
+    }
+
+    let output = BundlesReplay {
+        slot,
+        parent_slot,
+        isolated,
+        bundles: replays,
+    };
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    println!("{}", output_format.formatted_string(&output));
+}
+
+/// Returns the indexes of the entries that look like a bundle: a handful of transactions,
+/// recorded together, where at least one pays into a tip account.
+fn find_bundle_entries(entries: &[Entry], tip_accounts: &HashSet<Pubkey>) -> HashSet<usize> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            !entry.transactions.is_empty()
+                && entry.transactions.len() <= MAX_BUNDLE_LENGTH
+                && entry
+                    .transactions
+                    .iter()
+                    .any(|tx| writes_tip_account(tx, tip_accounts))
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn writes_tip_account(transaction: &VersionedTransaction, tip_accounts: &HashSet<Pubkey>) -> bool {
+    transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .any(|(index, key)| {
+            tip_accounts.contains(key) && transaction.message.is_maybe_writable(index)
+        })
+}
+
+/// Replays the ledger up to `parent_slot` and returns a new bank for `slot` on top of it.
+fn load_child_bank(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches<'_>,
+    blockstore: Arc<Blockstore>,
+    parent_slot: Slot,
+    slot: Slot,
+) -> Arc<Bank> {
+    let mut process_options = parse_process_options(ledger_path, arg_matches);
+    process_options.halt_at_slot = Some(parent_slot);
+    let snapshot_archive_path = value_t!(arg_matches, "snapshot_archive_path", String)
+        .ok()
+        .map(PathBuf::from);
+    let incremental_snapshot_archive_path =
+        value_t!(arg_matches, "incremental_snapshot_archive_path", String)
+            .ok()
+            .map(PathBuf::from);
+
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    let (bank_forks, ..) = load_and_process_ledger_or_exit(
+        arg_matches,
+        &genesis_config,
+        blockstore,
+        process_options,
+        snapshot_archive_path,
+        incremental_snapshot_archive_path,
+        false,
+    );
+    let parent = bank_forks
+        .read()
+        .unwrap()
+        .get(parent_slot)
+        .unwrap_or_else(|| {
+            eprintln!("Unable to replay the ledger up to parent slot {parent_slot}");
+            exit(1);
+        });
+
+    let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&parent);
+    let leader = leader_schedule_cache
+        .slot_leader_at(slot, Some(parent.as_ref()))
+        .unwrap_or_else(|| {
+            eprintln!("No leader found for slot {slot}");
+            exit(1);
+        });
+    Arc::new(Bank::new_from_parent(parent, &leader, slot))
+}
+
+fn replay_bundle(
+    bank: &Bank,
+    blockstore: &Blockstore,
+    tip_accounts: &HashSet<Pubkey>,
+    entry_index: usize,
+    transactions: &[VersionedTransaction],
+) -> BundleReplay {
+    let bundle_id = derive_bundle_id(transactions);
+    let sanitized_transactions: Result<Vec<_>, _> = transactions
+        .iter()
+        .map(|tx| SanitizedTransaction::try_create(tx.clone(), MessageHash::Compute, None, bank))
+        .collect();
+    let sanitized_transactions = match sanitized_transactions {
+        Ok(sanitized_transactions) => sanitized_transactions,
+        Err(err) => {
+            return BundleReplay {
+                entry_index,
+                bundle_id,
+                result: format!("failed to sanitize transactions: {err}"),
+                compute_units: 0,
+                tip_lamports: 0,
+                transactions: vec![],
+            };
+        }
+    };
+
+    let execution_accounts: Vec<_> = sanitized_transactions
+        .iter()
+        .map(|tx| {
+            let message = tx.message();
+            Some(
+                message
+                    .account_keys()
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| message.is_writable(*index))
+                    .map(|(_, key)| *key)
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    let bundle = SanitizedBundle {
+        transactions: sanitized_transactions,
+        bundle_id: bundle_id.clone(),
+    };
+
+    let output = load_and_execute_bundle(
+        bank,
+        &bundle,
+        MAX_PROCESSING_AGE,
+        &MAX_BUNDLE_EXECUTION_TIME,
+        false,
+        false,
+        false,
+        false,
+        &None,
+        true,
+        None,
+        &execution_accounts,
+        &execution_accounts,
+    );
+
+    let mut replayed: HashMap<Signature, TransactionReplay> = HashMap::default();
+    for transactions_output in output.bundle_transaction_results() {
+        for (index, (transaction, execution_result)) in transactions_output
+            .transactions()
+            .iter()
+            .zip(transactions_output.execution_results())
+            .enumerate()
+            .filter(|(_, (_, result))| result.was_executed())
+        {
+            let account_diffs = match (
+                transactions_output.pre_tx_execution_accounts().get(index),
+                transactions_output.post_tx_execution_accounts().get(index),
+            ) {
+                (Some(Some(pre_accounts)), Some(Some(post_accounts))) => {
+                    diff_accounts(pre_accounts, post_accounts)
+                }
+                _ => vec![],
+            };
+            replayed.insert(
+                *transaction.signature(),
+                TransactionReplay {
+                    signature: transaction.signature().to_string(),
+                    landed_err: None,
+                    landed_compute_units: None,
+                    replayed_err: execution_result
+                        .flattened_result()
+                        .err()
+                        .map(|err| err.to_string()),
+                    replayed_compute_units: execution_result
+                        .details()
+                        .map(|details| details.executed_units),
+                    account_diffs,
+                },
+            );
+        }
+    }
+    if let Err(LoadAndExecuteBundleError::TransactionError {
+        signature,
+        execution_result,
+    }) = output.result()
+    {
+        replayed.insert(
+            *signature,
+            TransactionReplay {
+                signature: signature.to_string(),
+                landed_err: None,
+                landed_compute_units: None,
+                replayed_err: execution_result
+                    .flattened_result()
+                    .err()
+                    .map(|err| err.to_string()),
+                replayed_compute_units: execution_result
+                    .details()
+                    .map(|details| details.executed_units),
+                account_diffs: vec![],
+            },
+        );
+    }
+
+    // keep the bundle's order and fill in how each transaction behaved when it landed
+    let transactions: Vec<_> = bundle
+        .transactions
+        .iter()
+        .map(|transaction| {
+            let signature = *transaction.signature();
+            let mut replay = replayed.remove(&signature).unwrap_or(TransactionReplay {
+                signature: signature.to_string(),
+                landed_err: None,
+                landed_compute_units: None,
+                replayed_err: Some("not executed".to_string()),
+                replayed_compute_units: None,
+                account_diffs: vec![],
+            });
+            if let Ok(Some(status)) = blockstore.read_transaction_status((signature, bank.slot())) {
+                replay.landed_err = status.status.err().map(|err| err.to_string());
+                replay.landed_compute_units = status.compute_units_consumed;
+            }
+            replay
+        })
+        .collect();
+
+    let compute_units = transactions
+        .iter()
+        .filter_map(|transaction| transaction.replayed_compute_units)
+        .sum();
+    let tip_lamports = transactions
+        .iter()
+        .flat_map(|transaction| &transaction.account_diffs)
+        .filter(|diff| {
+            Pubkey::try_from(diff.pubkey.as_str())
+                .map(|pubkey| tip_accounts.contains(&pubkey))
+                .unwrap_or_default()
+        })
+        .map(|diff| diff.post_lamports.saturating_sub(diff.pre_lamports))
+        .sum();
+
+    BundleReplay {
+        entry_index,
+        bundle_id,
+        result: match output.result() {
+            Ok(()) => "ok".to_string(),
+            Err(err) => err.to_string(),
+        },
+        compute_units,
+        tip_lamports,
+        transactions,
+    }
+}
+
+/// Returns the accounts whose lamports, data or owner changed between the two snapshots.
+fn diff_accounts(
+    pre_accounts: &[(Pubkey, AccountSharedData)],
+    post_accounts: &[(Pubkey, AccountSharedData)],
+) -> Vec<AccountDiff> {
+    let pre_accounts: HashMap<_, _> = pre_accounts.iter().cloned().collect();
+    post_accounts
+        .iter()
+        .filter_map(|(pubkey, post_account)| {
+            let pre_account = pre_accounts.get(pubkey).cloned().unwrap_or_default();
+            let data_changed = pre_account.data() != post_account.data();
+            let owner_changed = pre_account.owner() != post_account.owner();
+            (pre_account.lamports() != post_account.lamports() || data_changed || owner_changed)
+                .then(|| AccountDiff {
+                    pubkey: pubkey.to_string(),
+                    pre_lamports: pre_account.lamports(),
+                    post_lamports: post_account.lamports(),
+                    data_changed,
+                    owner_changed,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, system_transaction, transaction::Transaction,
+        },
+    };
+
+    fn entry(transactions: Vec<Transaction>) -> Entry {
+        Entry {
+            num_hashes: 1,
+            hash: Hash::new_unique(),
+            transactions: transactions.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_bundle_entries() {
+        let payer = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let tip_accounts = HashSet::from([tip_account]);
+        let transfer = |to: &Pubkey| system_transaction::transfer(&payer, to, 1, Hash::default());
+
+        let entries = vec![
+            // tick
+            entry(vec![]),
+            // no tip
+            entry(vec![transfer(&Pubkey::new_unique())]),
+            // bundle tipping in its last transaction
+            entry(vec![
+                transfer(&Pubkey::new_unique()),
+                transfer(&Pubkey::new_unique()),
+                transfer(&tip_account),
+            ]),
+            // too many transactions to be a bundle
+            entry(
+                (0..=MAX_BUNDLE_LENGTH)
+                    .map(|_| transfer(&tip_account))
+                    .collect(),
+            ),
+            // single transaction tipping
+            entry(vec![transfer(&tip_account)]),
+        ];
+
+        assert_eq!(
+            find_bundle_entries(&entries, &tip_accounts),
+            HashSet::from([2, 4])
+        );
+    }
+
+    #[test]
+    fn test_diff_accounts() {
+        let owner = Pubkey::new_unique();
+        let unchanged = (Pubkey::new_unique(), AccountSharedData::new(10, 0, &owner));
+        let debited = Pubkey::new_unique();
+        let created = Pubkey::new_unique();
+
+        let diffs = diff_accounts(
+            &[
+                unchanged.clone(),
+                (debited, AccountSharedData::new(10, 0, &owner)),
+            ],
+            &[
+                unchanged,
+                (debited, AccountSharedData::new(5, 0, &owner)),
+                (created, AccountSharedData::new(5, 8, &owner)),
+            ],
+        );
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].pubkey, debited.to_string());
+        assert_eq!((diffs[0].pre_lamports, diffs[0].post_lamports), (10, 5));
+        assert!(!diffs[0].data_changed);
+        assert_eq!(diffs[1].pubkey, created.to_string());
+        assert_eq!((diffs[1].pre_lamports, diffs[1].post_lamports), (0, 5));
+        assert!(diffs[1].data_changed);
+        assert!(diffs[1].owner_changed);
+    }
+}
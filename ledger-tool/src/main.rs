@@ -4,6 +4,7 @@ use {
         args::*,
         bigtable::*,
         blockstore::*,
+        bundles::*,
         ledger_path::*,
         ledger_utils::*,
         output::{output_account, AccountsOutputConfig, AccountsOutputStreamer},
@@ -26,7 +27,7 @@ use {
         input_parsers::{cluster_type_of, pubkey_of, pubkeys_of},
         input_validators::{
             is_parsable, is_pow2, is_pubkey, is_pubkey_or_keypair, is_slot, is_valid_percentage,
-            validate_maximum_full_snapshot_archives_to_retain,
+            is_within_range, validate_maximum_full_snapshot_archives_to_retain,
             validate_maximum_incremental_snapshot_archives_to_retain,
         },
     },
@@ -93,6 +94,7 @@ use {
 mod args;
 mod bigtable;
 mod blockstore;
+mod bundles;
 mod ledger_path;
 mod ledger_utils;
 mod output;
@@ -844,6 +846,20 @@ fn main() {
                 .hidden(hidden_unless_forced())
                 .help(BlockVerificationMethod::cli_message()),
         )
+        .arg(
+            Arg::with_name("unified_scheduler_handler_threads")
+                .long("unified-scheduler-handler-threads")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1usize..))
+                .global(true)
+                .hidden(hidden_unless_forced())
+                .help(
+                    "Change the number of the unified scheduler's transaction execution threads \
+                     dedicated to each block, otherwise calculated as cpu_cores/4 [default: \
+                     cpu_cores/4]",
+                ),
+        )
         .arg(
             Arg::with_name("output_format")
                 .long("output")
@@ -853,7 +869,7 @@ fn main() {
                 .possible_values(&["json", "json-compact"])
                 .help(
                     "Return information in specified output format, currently only available for \
-                     bigtable and program subcommands",
+                     bigtable, program and bundles subcommands",
                 ),
         )
         .arg(
@@ -1415,6 +1431,7 @@ fn main() {
                 ),
         )
         .program_subcommand()
+        .bundles_subcommand()
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -1434,6 +1451,7 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("bundles", Some(arg_matches)) => bundles(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -4,6 +4,7 @@ use {
     log::*,
     solana_measure::measure::Measure,
     solana_sdk::clock::Slot,
+    solana_storage_bigtable::LedgerArchive,
     std::{
         cmp::{max, min},
         collections::HashSet,
@@ -41,12 +42,13 @@ struct BlockstoreLoadStats {
     pub elapsed: Duration,
 }
 
-/// Uploads a range of blocks from a Blockstore to bigtable LedgerStorage
+/// Uploads a range of blocks from a Blockstore to a long-term ledger archive, such as bigtable
+/// LedgerStorage
 /// Returns the Slot of the last block checked. If no blocks in the range `[staring_slot,
 /// ending_slot]` are found in Blockstore, this value is equal to `ending_slot`.
 pub async fn upload_confirmed_blocks(
     blockstore: Arc<Blockstore>,
-    bigtable: solana_storage_bigtable::LedgerStorage,
+    ledger_archive: Arc<dyn LedgerArchive>,
     starting_slot: Slot,
     ending_slot: Slot,
     config: ConfirmedBlockUploadConfig,
@@ -80,20 +82,20 @@ pub async fn upload_confirmed_blocks(
         last_blockstore_slot,
     );
 
-    // Gather the blocks that are already present in bigtable, by slot
-    let bigtable_slots = if !config.force_reupload {
-        let mut bigtable_slots = vec![];
+    // Gather the blocks that are already present in the ledger archive, by slot
+    let archived_slots = if !config.force_reupload {
+        let mut archived_slots = vec![];
         info!(
-            "Loading list of bigtable blocks between slots {} and {}...",
+            "Loading list of archived blocks between slots {} and {}...",
             first_blockstore_slot, last_blockstore_slot
         );
 
         let mut start_slot = first_blockstore_slot;
         while start_slot <= last_blockstore_slot {
-            let mut next_bigtable_slots = loop {
-                let num_bigtable_blocks = min(1000, config.max_num_slots_to_check * 2);
-                match bigtable
-                    .get_confirmed_blocks(start_slot, num_bigtable_blocks)
+            let mut next_archived_slots = loop {
+                let num_archived_blocks = min(1000, config.max_num_slots_to_check * 2);
+                match ledger_archive
+                    .get_confirmed_blocks(start_slot, num_archived_blocks)
                     .await
                 {
                     Ok(slots) => break slots,
@@ -104,13 +106,13 @@ pub async fn upload_confirmed_blocks(
                     }
                 }
             };
-            if next_bigtable_slots.is_empty() {
+            if next_archived_slots.is_empty() {
                 break;
             }
-            bigtable_slots.append(&mut next_bigtable_slots);
-            start_slot = bigtable_slots.last().unwrap() + 1;
+            archived_slots.append(&mut next_archived_slots);
+            start_slot = archived_slots.last().unwrap() + 1;
         }
-        bigtable_slots
+        archived_slots
             .into_iter()
             .filter(|slot| *slot <= last_blockstore_slot)
             .collect::<Vec<_>>()
@@ -119,13 +121,13 @@ pub async fn upload_confirmed_blocks(
     };
 
     // The blocks that still need to be uploaded is the difference between what's already in the
-    // bigtable and what's in blockstore...
+    // ledger archive and what's in blockstore...
     let blocks_to_upload = {
         let blockstore_slots = blockstore_slots.into_iter().collect::<HashSet<_>>();
-        let bigtable_slots = bigtable_slots.into_iter().collect::<HashSet<_>>();
+        let archived_slots = archived_slots.into_iter().collect::<HashSet<_>>();
 
         let mut blocks_to_upload = blockstore_slots
-            .difference(&bigtable_slots)
+            .difference(&archived_slots)
             .cloned()
             .collect::<Vec<_>>();
         blocks_to_upload.sort_unstable();
@@ -135,7 +137,7 @@ pub async fn upload_confirmed_blocks(
 
     if blocks_to_upload.is_empty() {
         info!(
-            "No blocks between {} and {} need to be uploaded to bigtable",
+            "No blocks between {} and {} need to be uploaded to the ledger archive",
             starting_slot, ending_slot
         );
         return Ok(ending_slot);
@@ -148,7 +150,7 @@ pub async fn upload_confirmed_blocks(
         last_slot
     );
 
-    // Distribute the blockstore reading across a few background threads to speed up the bigtable uploading
+    // Distribute the blockstore reading across a few background threads to speed up the uploading
     let (loader_threads, receiver): (Vec<_>, _) = {
         let exit = exit.clone();
 
@@ -225,9 +227,10 @@ pub async fn upload_confirmed_blocks(
                 None
             }
             Some(confirmed_block) => {
-                let bt = bigtable.clone();
+                let ledger_archive = ledger_archive.clone();
                 Some(tokio::spawn(async move {
-                    bt.upload_confirmed_block_with_entries(slot, confirmed_block)
+                    ledger_archive
+                        .upload_confirmed_block_with_entries(slot, confirmed_block)
                         .await
                 }))
             }
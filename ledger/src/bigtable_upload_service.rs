@@ -4,6 +4,7 @@ use {
         blockstore::Blockstore,
     },
     solana_runtime::commitment::BlockCommitmentCache,
+    solana_storage_bigtable::LedgerArchive,
     std::{
         cmp::min,
         sync::{
@@ -15,14 +16,17 @@ use {
     tokio::runtime::Runtime,
 };
 
-pub struct BigTableUploadService {
+pub struct LedgerArchiveUploadService {
     thread: JoinHandle<()>,
 }
 
-impl BigTableUploadService {
+/// Former name of [`LedgerArchiveUploadService`], kept for existing callers
+pub type BigTableUploadService = LedgerArchiveUploadService;
+
+impl LedgerArchiveUploadService {
     pub fn new(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        ledger_archive: Arc<dyn LedgerArchive>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -31,7 +35,7 @@ impl BigTableUploadService {
     ) -> Self {
         Self::new_with_config(
             runtime,
-            bigtable_ledger_storage,
+            ledger_archive,
             blockstore,
             block_commitment_cache,
             max_complete_transaction_status_slot,
@@ -43,7 +47,7 @@ impl BigTableUploadService {
 
     pub fn new_with_config(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        ledger_archive: Arc<dyn LedgerArchive>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -51,13 +55,13 @@ impl BigTableUploadService {
         config: ConfirmedBlockUploadConfig,
         exit: Arc<AtomicBool>,
     ) -> Self {
-        info!("Starting BigTable upload service");
+        info!("Starting ledger archive upload service");
         let thread = Builder::new()
             .name("solBigTUpload".to_string())
             .spawn(move || {
                 Self::run(
                     runtime,
-                    bigtable_ledger_storage,
+                    ledger_archive,
                     blockstore,
                     block_commitment_cache,
                     max_complete_transaction_status_slot,
@@ -73,7 +77,7 @@ impl BigTableUploadService {
 
     fn run(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        ledger_archive: Arc<dyn LedgerArchive>,
         blockstore: Arc<Blockstore>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -109,7 +113,7 @@ impl BigTableUploadService {
 
             let result = runtime.block_on(bigtable_upload::upload_confirmed_blocks(
                 blockstore.clone(),
-                bigtable_ledger_storage.clone(),
+                ledger_archive.clone(),
                 start_slot,
                 end_slot,
                 config.clone(),
@@ -119,7 +123,7 @@ impl BigTableUploadService {
             match result {
                 Ok(last_slot_uploaded) => start_slot = last_slot_uploaded.saturating_add(1),
                 Err(err) => {
-                    warn!("bigtable: upload_confirmed_blocks: {}", err);
+                    warn!("ledger archive: upload_confirmed_blocks: {}", err);
                     std::thread::sleep(std::time::Duration::from_secs(2));
                     if start_slot == 0 {
                         start_slot = blockstore.get_first_available_block().unwrap_or_default();
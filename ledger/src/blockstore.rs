@@ -240,6 +240,9 @@ pub struct Blockstore {
     optimistic_slots_cf: LedgerColumn<cf::OptimisticSlots>,
     max_root: AtomicU64,
     merkle_root_meta_cf: LedgerColumn<cf::MerkleRootMeta>,
+    bundle_status_cf: LedgerColumn<cf::BundleStatus>,
+    bundle_slots_cf: LedgerColumn<cf::BundleSlots>,
+    tip_revenue_cf: LedgerColumn<cf::TipRevenue>,
     insert_shreds_lock: Mutex<()>,
     new_shreds_signals: Mutex<Vec<Sender<bool>>>,
     completed_slots_senders: Mutex<Vec<CompletedSlotsSender>>,
@@ -342,6 +345,9 @@ impl Blockstore {
         let bank_hash_cf = db.column();
         let optimistic_slots_cf = db.column();
         let merkle_root_meta_cf = db.column();
+        let bundle_status_cf = db.column();
+        let bundle_slots_cf = db.column();
+        let tip_revenue_cf = db.column();
 
         let db = Arc::new(db);
 
@@ -380,6 +386,9 @@ impl Blockstore {
             bank_hash_cf,
             optimistic_slots_cf,
             merkle_root_meta_cf,
+            bundle_status_cf,
+            bundle_slots_cf,
+            tip_revenue_cf,
             new_shreds_signals: Mutex::default(),
             completed_slots_senders: Mutex::default(),
             shred_timing_point_sender: None,
@@ -754,6 +763,9 @@ impl Blockstore {
         self.bank_hash_cf.submit_rocksdb_cf_metrics();
         self.optimistic_slots_cf.submit_rocksdb_cf_metrics();
         self.merkle_root_meta_cf.submit_rocksdb_cf_metrics();
+        self.bundle_status_cf.submit_rocksdb_cf_metrics();
+        self.bundle_slots_cf.submit_rocksdb_cf_metrics();
+        self.tip_revenue_cf.submit_rocksdb_cf_metrics();
     }
 
     /// Report the accumulated RPC API metrics
@@ -2529,6 +2541,59 @@ impl Blockstore {
         self.transaction_memos_cf.put((*signature, slot), &memos)
     }
 
+    /// Returns every slot the bundle was committed in, along with the
+    /// [`BundleStatusMeta`] recorded for it, in ascending slot order. A bundle
+    /// may land in more than one slot if those slots are on different forks.
+    pub fn read_bundle_statuses(&self, bundle_id: &str) -> Result<Vec<(Slot, BundleStatusMeta)>> {
+        let mut statuses = vec![];
+        for ((id, slot), _) in self.bundle_slots_cf.iter(IteratorMode::From(
+            (bundle_id.to_string(), 0),
+            IteratorDirection::Forward,
+        ))? {
+            if id != bundle_id {
+                break;
+            }
+            // The slot may have been purged since, in which case only the
+            // BundleSlots entry remains until compaction
+            if let Some(status) = self.bundle_status_cf.get((slot, id))? {
+                statuses.push((slot, status));
+            }
+        }
+        Ok(statuses)
+    }
+
+    pub fn write_bundle_status(
+        &self,
+        bundle_id: &str,
+        slot: Slot,
+        status: &BundleStatusMeta,
+    ) -> Result<()> {
+        let mut write_batch = self.db.batch()?;
+        write_batch.put::<cf::BundleStatus>((slot, bundle_id.to_string()), status)?;
+        write_batch.put_bytes::<cf::BundleSlots>((bundle_id.to_string(), slot), &[])?;
+        self.db.write(write_batch)
+    }
+
+    /// Returns the tip revenue recorded for this validator's leader slots in
+    /// \[`start_slot`, `end_slot`\], in ascending slot order.
+    pub fn read_tip_revenue(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<(Slot, TipRevenueMeta)>> {
+        self.tip_revenue_cf
+            .iter(IteratorMode::From(start_slot, IteratorDirection::Forward))?
+            .take_while(|(slot, _bytes)| *slot <= end_slot)
+            .map(|(slot, bytes)| -> Result<(Slot, TipRevenueMeta)> {
+                Ok((slot, deserialize(&bytes)?))
+            })
+            .collect()
+    }
+
+    pub fn write_tip_revenue(&self, slot: Slot, tip_revenue: &TipRevenueMeta) -> Result<()> {
+        self.tip_revenue_cf.put(slot, tip_revenue)
+    }
+
     /// Acquires the `lowest_cleanup_slot` lock and returns a tuple of the held lock
     /// and lowest available slot.
     ///
@@ -10788,4 +10853,78 @@ pub mod tests {
             assert_eq!(read_cost, *cost_table.get(&read_key).unwrap());
         }
     }
+
+    #[test]
+    fn test_read_write_bundle_status() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let status = |tip_lamports| BundleStatusMeta {
+            signatures: vec![Signature::new_unique(), Signature::new_unique()],
+            tip_lamports,
+        };
+        let landed_on_fork = status(1_000);
+        let landed = status(2_000);
+        let other = status(3_000);
+        blockstore
+            .write_bundle_status("bundle", 12, &landed)
+            .unwrap();
+        blockstore
+            .write_bundle_status("bundle", 11, &landed_on_fork)
+            .unwrap();
+        // shares a prefix with the first bundle id, and must not be returned for it
+        blockstore
+            .write_bundle_status("bundle2", 11, &other)
+            .unwrap();
+
+        assert_eq!(
+            blockstore.read_bundle_statuses("bundle").unwrap(),
+            vec![(11, landed_on_fork), (12, landed)]
+        );
+        assert_eq!(
+            blockstore.read_bundle_statuses("bundle2").unwrap(),
+            vec![(11, other)]
+        );
+        assert!(blockstore.read_bundle_statuses("bund").unwrap().is_empty());
+        assert!(blockstore
+            .read_bundle_statuses("unknown")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_read_write_tip_revenue() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let tip_revenue = |total_tips| TipRevenueMeta {
+            epoch: 1,
+            tip_accounts: vec![(Pubkey::new_unique(), total_tips)],
+            total_tips,
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission_pct: 5,
+            block_builder_commission: total_tips * 5 / 100,
+            tip_distribution_account: Pubkey::new_unique(),
+            net_tip_distribution: total_tips - total_tips * 5 / 100,
+        };
+        let revenues = vec![
+            (10, tip_revenue(1_000)),
+            (11, tip_revenue(2_000)),
+            (20, tip_revenue(0)),
+        ];
+        for (slot, revenue) in &revenues {
+            blockstore.write_tip_revenue(*slot, revenue).unwrap();
+        }
+
+        assert_eq!(blockstore.read_tip_revenue(0, 100).unwrap(), revenues);
+        assert_eq!(
+            blockstore.read_tip_revenue(11, 19).unwrap(),
+            revenues[1..2].to_vec()
+        );
+        assert_eq!(
+            blockstore.read_tip_revenue(10, 10).unwrap(),
+            revenues[..1].to_vec()
+        );
+        assert!(blockstore.read_tip_revenue(21, 100).unwrap().is_empty());
+    }
 }
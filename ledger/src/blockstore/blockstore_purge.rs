@@ -224,6 +224,14 @@ impl Blockstore {
             & self
                 .db
                 .delete_range_cf::<cf::MerkleRootMeta>(&mut write_batch, from_slot, to_slot)
+                .is_ok()
+            & self
+                .db
+                .delete_range_cf::<cf::BundleStatus>(&mut write_batch, from_slot, to_slot)
+                .is_ok()
+            & self
+                .db
+                .delete_range_cf::<cf::TipRevenue>(&mut write_batch, from_slot, to_slot)
                 .is_ok();
         match purge_type {
             PurgeType::Exact => {
@@ -337,6 +345,14 @@ impl Blockstore {
                 .db
                 .delete_file_in_range_cf::<cf::MerkleRootMeta>(from_slot, to_slot)
                 .is_ok()
+            & self
+                .db
+                .delete_file_in_range_cf::<cf::BundleStatus>(from_slot, to_slot)
+                .is_ok()
+            & self
+                .db
+                .delete_file_in_range_cf::<cf::TipRevenue>(from_slot, to_slot)
+                .is_ok()
     }
 
     /// Returns true if the special columns, TransactionStatus and
@@ -510,6 +526,41 @@ pub mod tests {
             });
     }
 
+    #[test]
+    fn test_purge_bundle_statuses() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let status = |tip_lamports| BundleStatusMeta {
+            signatures: vec![Signature::new_unique()],
+            tip_lamports,
+        };
+        for slot in 0..10 {
+            blockstore
+                .write_bundle_status("bundle", slot, &status(slot))
+                .unwrap();
+        }
+        blockstore
+            .write_bundle_status("other", 3, &status(100))
+            .unwrap();
+
+        blockstore.purge_slots(0, 4, PurgeType::Exact);
+
+        let slots: Vec<Slot> = blockstore
+            .read_bundle_statuses("bundle")
+            .unwrap()
+            .into_iter()
+            .map(|(slot, _)| slot)
+            .collect();
+        assert_eq!(slots, (5..10).collect::<Vec<_>>());
+        assert!(blockstore.read_bundle_statuses("other").unwrap().is_empty());
+        assert!(blockstore
+            .bundle_status_cf
+            .iter(IteratorMode::Start)
+            .unwrap()
+            .all(|((slot, _), _)| slot >= 5));
+    }
+
     #[test]
     fn test_purge_front_of_ledger() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
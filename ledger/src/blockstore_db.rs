@@ -106,6 +106,12 @@ const PROGRAM_COSTS_CF: &str = "program_costs";
 const OPTIMISTIC_SLOTS_CF: &str = "optimistic_slots";
 /// Column family for merkle roots
 const MERKLE_ROOT_META_CF: &str = "merkle_root_meta";
+/// Column family for committed bundles
+const BUNDLE_STATUS_CF: &str = "bundle_status";
+/// Column family for the slots of committed bundles, by bundle id
+const BUNDLE_SLOTS_CF: &str = "bundle_slots";
+/// Column family for tip revenue
+const TIP_REVENUE_CF: &str = "tip_revenue";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -355,6 +361,37 @@ pub mod columns {
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
 
+    #[derive(Debug)]
+    /// The bundle status column
+    ///
+    /// This column family records the bundles committed by this validator while
+    /// leader, keyed by the slot the bundle landed in and the bundle id.
+    ///
+    /// * index type: `(`[`Slot`]`, `[`String`]`)`
+    /// * value type: [`blockstore_meta::BundleStatusMeta`]
+    pub struct BundleStatus;
+
+    #[derive(Debug)]
+    /// The bundle slots column
+    ///
+    /// This column family indexes the [`BundleStatus`] column by bundle id. Its
+    /// entries are only removed by compaction, so an entry whose slot has been
+    /// purged from [`BundleStatus`] must be ignored.
+    ///
+    /// * index type: `(`[`String`]`, `[`Slot`]`)`
+    /// * value type: empty
+    pub struct BundleSlots;
+
+    #[derive(Debug)]
+    /// The tip revenue column
+    ///
+    /// This column family records the MEV tips collected in this validator's
+    /// rooted leader slots.
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: [`blockstore_meta::TipRevenueMeta`]
+    pub struct TipRevenue;
+
     // When adding a new column ...
     // - Add struct below and implement `Column` and `ColumnName` traits
     // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
@@ -494,6 +531,9 @@ impl Rocks {
             new_cf_descriptor::<ProgramCosts>(options, oldest_slot),
             new_cf_descriptor::<OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<BundleStatus>(options, oldest_slot),
+            new_cf_descriptor::<BundleSlots>(options, oldest_slot),
+            new_cf_descriptor::<TipRevenue>(options, oldest_slot),
         ];
 
         // If the access type is Secondary, we don't need to open all of the
@@ -567,6 +607,9 @@ impl Rocks {
             ProgramCosts::NAME,
             OptimisticSlots::NAME,
             MerkleRootMeta::NAME,
+            BundleStatus::NAME,
+            BundleSlots::NAME,
+            TipRevenue::NAME,
         ]
     }
 
@@ -1322,6 +1365,79 @@ impl TypedColumn for columns::MerkleRootMeta {
     type Type = MerkleRootMeta;
 }
 
+impl Column for columns::BundleStatus {
+    type Index = (Slot, String);
+
+    fn key((slot, bundle_id): Self::Index) -> Vec<u8> {
+        let mut key = Vec::with_capacity(8 + bundle_id.len());
+        key.extend_from_slice(&slot.to_be_bytes());
+        key.extend_from_slice(bundle_id.as_bytes());
+        key
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        let (slot, bundle_id) = key.split_at(8.min(key.len()));
+        (
+            BigEndian::read_u64(slot),
+            String::from_utf8_lossy(bundle_id).into_owned(),
+        )
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        (slot, String::default())
+    }
+}
+impl ColumnName for columns::BundleStatus {
+    const NAME: &'static str = BUNDLE_STATUS_CF;
+}
+impl TypedColumn for columns::BundleStatus {
+    type Type = blockstore_meta::BundleStatusMeta;
+}
+
+impl Column for columns::BundleSlots {
+    type Index = (String, Slot);
+
+    fn key((bundle_id, slot): Self::Index) -> Vec<u8> {
+        let mut key = Vec::with_capacity(bundle_id.len() + 8);
+        key.extend_from_slice(bundle_id.as_bytes());
+        key.extend_from_slice(&slot.to_be_bytes());
+        key
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        let (bundle_id, slot) = key.split_at(key.len().saturating_sub(8));
+        (
+            String::from_utf8_lossy(bundle_id).into_owned(),
+            BigEndian::read_u64(slot),
+        )
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    // The BundleSlots column is not keyed by slot so this method is meaningless
+    // See Column::as_index() declaration for more details
+    fn as_index(_index: u64) -> Self::Index {
+        (String::default(), 0)
+    }
+}
+impl ColumnName for columns::BundleSlots {
+    const NAME: &'static str = BUNDLE_SLOTS_CF;
+}
+
+impl SlotColumn for columns::TipRevenue {}
+impl ColumnName for columns::TipRevenue {
+    const NAME: &'static str = TIP_REVENUE_CF;
+}
+impl TypedColumn for columns::TipRevenue {
+    type Type = blockstore_meta::TipRevenueMeta;
+}
+
 #[derive(Debug)]
 pub struct Database {
     backend: Arc<Rocks>,
@@ -2209,6 +2325,7 @@ fn should_enable_cf_compaction(cf_name: &str) -> bool {
         columns::TransactionStatus::NAME
             | columns::TransactionMemos::NAME
             | columns::AddressSignatures::NAME
+            | columns::BundleSlots::NAME
     )
 }
 
@@ -2300,6 +2417,7 @@ pub mod tests {
         let columns_to_compact = [
             columns::TransactionStatus::NAME,
             columns::AddressSignatures::NAME,
+            columns::BundleSlots::NAME,
         ];
         columns_to_compact.iter().for_each(|cf_name| {
             assert!(should_enable_cf_compaction(cf_name));
@@ -3,8 +3,10 @@ use {
     bitflags::bitflags,
     serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_sdk::{
-        clock::{Slot, UnixTimestamp},
+        clock::{Epoch, Slot, UnixTimestamp},
         hash::Hash,
+        pubkey::Pubkey,
+        signature::Signature,
     },
     std::{
         collections::BTreeSet,
@@ -505,6 +507,34 @@ pub struct ProgramCost {
     pub cost: u64,
 }
 
+/// Records that a bundle was committed in a slot.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BundleStatusMeta {
+    /// First signature of each transaction in the bundle, in execution order
+    pub signatures: Vec<Signature>,
+    /// Lamports the bundle paid into the tip accounts
+    pub tip_lamports: u64,
+}
+
+/// MEV tips collected in one of this validator's leader slots.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TipRevenueMeta {
+    pub epoch: Epoch,
+    /// Lamports collected by each tip payment account during the slot
+    pub tip_accounts: Vec<(Pubkey, u64)>,
+    /// Sum of the lamports collected by all tip payment accounts
+    pub total_tips: u64,
+    pub block_builder: Pubkey,
+    /// Percent of the tips paid to the block builder
+    pub block_builder_commission_pct: u64,
+    /// Lamports paid to the block builder
+    pub block_builder_commission: u64,
+    /// The tip distribution account the remaining tips are paid to
+    pub tip_distribution_account: Pubkey,
+    /// Lamports paid to the tip distribution account
+    pub net_tip_distribution: u64,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct OptimisticSlotMetaV0 {
     pub hash: Hash,
@@ -45,6 +45,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         no_os_network_stats_reporting: config.no_os_network_stats_reporting,
         no_os_cpu_stats_reporting: config.no_os_cpu_stats_reporting,
         no_os_disk_stats_reporting: config.no_os_disk_stats_reporting,
+        prometheus_bind_address: config.prometheus_bind_address,
         poh_pinned_cpu_core: config.poh_pinned_cpu_core,
         account_indexes: config.account_indexes.clone(),
         warp_slot: config.warp_slot,
@@ -66,14 +67,21 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         banking_trace_dir_byte_limit: config.banking_trace_dir_byte_limit,
         block_verification_method: config.block_verification_method.clone(),
         block_production_method: config.block_production_method.clone(),
+        unified_scheduler_handler_threads: config.unified_scheduler_handler_threads,
         generator_config: config.generator_config.clone(),
         use_snapshot_archives_at_startup: config.use_snapshot_archives_at_startup,
         wen_restart_proto_path: config.wen_restart_proto_path.clone(),
         relayer_config: config.relayer_config.clone(),
         block_engine_config: config.block_engine_config.clone(),
         shred_receiver_address: config.shred_receiver_address.clone(),
+        relayer_grace_window: config.relayer_grace_window,
+        fetch_stage_mode_override: config.fetch_stage_mode_override.clone(),
         tip_manager_config: config.tip_manager_config.clone(),
         preallocated_bundle_cost: config.preallocated_bundle_cost,
+        bundle_ordering_method: config.bundle_ordering_method,
+        bundle_execution_threads: config.bundle_execution_threads,
+        bundle_denylist: config.bundle_denylist.clone(),
+        runtime_plugin_submission_config: config.runtime_plugin_submission_config.clone(),
     }
 }
 
@@ -15,6 +15,11 @@ use {
             tower_storage::FileTowerStorage, Tower, SWITCH_FORK_THRESHOLD, VOTE_THRESHOLD_DEPTH,
         },
         optimistic_confirmation_verifier::OptimisticConfirmationVerifier,
+        packet_bundle::PacketBundle,
+        proxy::{
+            block_engine_stage::BlockEngineConfig,
+            mock_server::{MockProxyServer, MockProxyServerConfig},
+        },
         replay_stage::DUPLICATE_THRESHOLD,
         validator::{BlockProductionMethod, BlockVerificationMethod, ValidatorConfig},
     },
@@ -46,6 +51,7 @@ use {
         local_cluster::{ClusterConfig, LocalCluster},
         validator_configs::*,
     },
+    solana_perf::packet::PacketBatch,
     solana_pubsub_client::pubsub_client::PubsubClient,
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::{
@@ -72,6 +78,7 @@ use {
         genesis_config::ClusterType,
         hard_forks::HardForks,
         hash::Hash,
+        packet::Packet,
         poh_config::PohConfig,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
@@ -200,6 +207,76 @@ fn test_spend_and_verify_all_nodes_3() {
     );
 }
 
+#[test]
+#[serial]
+fn test_bundle_from_mock_block_engine_lands() {
+    solana_logger::setup_with_default(RUST_LOG_FILTER);
+    let mock_server = MockProxyServer::new(MockProxyServerConfig::default());
+
+    let validator_config = ValidatorConfig::default_for_test();
+    *validator_config.block_engine_config.lock().unwrap() = BlockEngineConfig {
+        block_engine_urls: vec![mock_server.url()],
+        trust_packets: false,
+    };
+    let mut config = ClusterConfig {
+        cluster_lamports: DEFAULT_CLUSTER_LAMPORTS,
+        node_stakes: vec![DEFAULT_NODE_STAKE],
+        validator_configs: vec![validator_config],
+        ..ClusterConfig::default()
+    };
+    let cluster = LocalCluster::new(&mut config, SocketAddrSpace::Unspecified);
+    let client = RpcClient::new_socket(cluster.entry_point_info.rpc().unwrap());
+
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .unwrap()
+        .0;
+    let transactions: Vec<_> = (0..3)
+        .map(|lamports| {
+            system_transaction::transfer(
+                &cluster.funding_keypair,
+                &solana_sdk::pubkey::new_rand(),
+                lamports + 1,
+                blockhash,
+            )
+        })
+        .collect();
+    let signatures: Vec<_> = transactions.iter().map(|tx| tx.signatures[0]).collect();
+    mock_server.send_bundles(&[PacketBundle {
+        batch: PacketBatch::new(
+            transactions
+                .iter()
+                .map(|tx| Packet::from_data(None, tx).unwrap())
+                .collect(),
+        ),
+        bundle_id: "mock-bundle".to_string(),
+    }]);
+
+    let timer = Instant::now();
+    let landed_slots = loop {
+        let statuses = client.get_signature_statuses(&signatures).unwrap().value;
+        if statuses.iter().all(|status| status.is_some()) {
+            break statuses
+                .into_iter()
+                .map(|status| status.unwrap().slot)
+                .collect::<HashSet<_>>();
+        }
+        assert!(
+            timer.elapsed() < Duration::from_secs(30),
+            "bundle did not land in 30 seconds, mock server stats: {:?}",
+            mock_server.stats()
+        );
+        sleep(Duration::from_millis(100));
+    };
+
+    // all-or-nothing and atomic: every transaction landed, all in the same slot
+    assert_eq!(landed_slots.len(), 1);
+    let stats = mock_server.stats();
+    assert!(stats.num_tokens_generated >= 1);
+    assert!(stats.num_bundle_subscriptions >= 1);
+    assert_eq!(stats.num_bundles_sent, 1);
+}
+
 #[test]
 #[serial]
 #[ignore]
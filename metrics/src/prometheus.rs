@@ -0,0 +1,344 @@
+//! The `prometheus` module serves the latest value of each datapoint field and the total of each
+//! counter in the Prometheus text exposition format, over HTTP at `/metrics`.
+//!
+//! Numeric and bool fields of a datapoint `name` are exposed as gauges named
+//! `solana_<name>_<field>`, labeled with the datapoint's tags. String fields are not exposed.
+//! Counters are exposed as `solana_<name>_total`.
+//!
+//! Values are only recorded once a [`PrometheusService`] is started.
+
+use {
+    crate::{counter::CounterPoint, datapoint::DataPoint},
+    lazy_static::lazy_static,
+    log::*,
+    std::{
+        collections::BTreeMap,
+        fmt::Write as _,
+        io::{self, BufRead, BufReader, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+const METRIC_NAME_PREFIX: &str = "solana_";
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref REGISTRY: Mutex<PrometheusRegistry> = Mutex::new(PrometheusRegistry::default());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MetricFamily {
+    kind: MetricKind,
+    /// Sample values, keyed by their rendered labels
+    samples: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Default)]
+struct PrometheusRegistry {
+    families: BTreeMap<String, MetricFamily>,
+}
+
+impl PrometheusRegistry {
+    fn record_point(&mut self, point: &DataPoint) {
+        let labels = render_labels(&point.tags);
+        for (field, value) in &point.fields {
+            if let Some(value) = parse_field_value(value) {
+                let name = metric_name(&[point.name, field]);
+                self.set(name, MetricKind::Gauge, &labels, |sample| *sample = value);
+            }
+        }
+    }
+
+    fn record_counter(&mut self, counter: &CounterPoint) {
+        let name = metric_name(&[counter.name, "total"]);
+        self.set(name, MetricKind::Counter, "", |sample| {
+            *sample += counter.count as f64
+        });
+    }
+
+    fn set(&mut self, name: String, kind: MetricKind, labels: &str, update: impl FnOnce(&mut f64)) {
+        let family = self.families.entry(name).or_insert_with(|| MetricFamily {
+            kind,
+            samples: BTreeMap::new(),
+        });
+        // A datapoint and a counter may map to the same name; keep the first one.
+        if family.kind == kind {
+            update(family.samples.entry(labels.to_string()).or_insert(0.0));
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, family) in &self.families {
+            let _ = writeln!(text, "# TYPE {name} {}", family.kind.as_str());
+            for (labels, value) in &family.samples {
+                let _ = writeln!(text, "{name}{labels} {}", render_value(*value));
+            }
+        }
+        text
+    }
+}
+
+/// Records the fields of `point`, if a [`PrometheusService`] is running.
+pub(crate) fn record_point(point: &DataPoint) {
+    if ENABLED.load(Ordering::Relaxed) {
+        REGISTRY.lock().unwrap().record_point(point);
+    }
+}
+
+/// Adds the count of `counter` to its total, if a [`PrometheusService`] is running.
+pub(crate) fn record_counter(counter: &CounterPoint) {
+    if ENABLED.load(Ordering::Relaxed) {
+        REGISTRY.lock().unwrap().record_counter(counter);
+    }
+}
+
+/// Returns the recorded metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    REGISTRY.lock().unwrap().render()
+}
+
+/// Parses a field value as serialized by `DataPoint`: `<int>i`, `true`/`false`, a float, or a
+/// quoted string, which isn't a valid sample value.
+fn parse_field_value(value: &str) -> Option<f64> {
+    if let Some(value) = value.strip_suffix('i') {
+        return value.parse::<i64>().ok().map(|value| value as f64);
+    }
+    match value {
+        "true" => Some(1.0),
+        "false" => Some(0.0),
+        _ => value.parse().ok(),
+    }
+}
+
+fn metric_name(parts: &[&str]) -> String {
+    let mut name = METRIC_NAME_PREFIX.to_string();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            name.push('_');
+        }
+        name.extend(part.chars().map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        }));
+    }
+    name
+}
+
+fn render_labels(tags: &[(&'static str, String)]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<_> = tags
+        .iter()
+        .map(|(name, value)| {
+            let name: String = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+fn render_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serves the recorded metrics at `http://<bind_address>/metrics`.
+pub struct PrometheusService {
+    local_addr: SocketAddr,
+    thread_hdl: JoinHandle<()>,
+}
+
+impl PrometheusService {
+    pub fn new(bind_address: SocketAddr, exit: Arc<AtomicBool>) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind_address)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        ENABLED.store(true, Ordering::Relaxed);
+        info!("serving Prometheus metrics at http://{local_addr}/metrics");
+
+        let thread_hdl = Builder::new()
+            .name("solPrometheus".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            if let Err(err) = Self::handle_connection(stream) {
+                                debug!("Prometheus request failed: {err}");
+                            }
+                        }
+                        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(ACCEPT_POLL_INTERVAL);
+                        }
+                        Err(err) => {
+                            warn!("Prometheus endpoint failed to accept a connection: {err}");
+                            thread::sleep(ACCEPT_POLL_INTERVAL);
+                        }
+                    }
+                }
+            })
+            .unwrap();
+        Ok(Self {
+            local_addr,
+            thread_hdl,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn handle_connection(mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Skip the headers
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+            header.clear();
+        }
+
+        let mut request = request_line.split_whitespace();
+        let (status, body) = match (request.next(), request.next()) {
+            (Some("GET"), Some("/metrics")) => ("200 OK", render()),
+            _ => ("404 Not Found", "Not Found\n".to_string()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Read};
+
+    #[test]
+    fn test_render_datapoints() {
+        let mut registry = PrometheusRegistry::default();
+        registry.record_point(
+            DataPoint::new("bank-process")
+                .add_tag("leader", "a\"b")
+                .add_field_i64("txs", 10)
+                .add_field_bool("voting", true)
+                .add_field_f64("ratio", 0.5)
+                .add_field_str("mode", "relayer"),
+        );
+        // Only the latest value of a field is kept
+        registry.record_point(
+            DataPoint::new("bank-process")
+                .add_tag("leader", "a\"b")
+                .add_field_i64("txs", 12),
+        );
+        registry.record_point(DataPoint::new("bank-process").add_field_i64("txs", 1));
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE solana_bank_process_ratio gauge\n\
+             solana_bank_process_ratio{leader=\"a\\\"b\"} 0.5\n\
+             # TYPE solana_bank_process_txs gauge\n\
+             solana_bank_process_txs 1\n\
+             solana_bank_process_txs{leader=\"a\\\"b\"} 12\n\
+             # TYPE solana_bank_process_voting gauge\n\
+             solana_bank_process_voting{leader=\"a\\\"b\"} 1\n"
+        );
+    }
+
+    #[test]
+    fn test_render_counters() {
+        let mut registry = PrometheusRegistry::default();
+        let mut counter = CounterPoint::new("packets-received");
+        counter.count = 3;
+        registry.record_counter(&counter);
+        counter.count = 4;
+        registry.record_counter(&counter);
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE solana_packets_received_total counter\n\
+             solana_packets_received_total 7\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        assert_eq!(parse_field_value("-3i"), Some(-3.0));
+        assert_eq!(parse_field_value("false"), Some(0.0));
+        assert_eq!(parse_field_value("1.25"), Some(1.25));
+        assert_eq!(parse_field_value("\"1i\""), None);
+        assert_eq!(parse_field_value("\"text\""), None);
+    }
+
+    #[test]
+    fn test_prometheus_service() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let service = PrometheusService::new("127.0.0.1:0".parse().unwrap(), exit.clone()).unwrap();
+        record_point(DataPoint::new("prometheus-service-test").add_field_i64("value", 42));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(service.local_addr()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\nsolana_prometheus_service_test_value 42\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        exit.store(true, Ordering::Relaxed);
+        service.join().unwrap();
+    }
+}
@@ -841,6 +841,11 @@ impl ProgramTest {
             bank.store_account(program_id, account);
         }
 
+        // Preload the tip accounts so bundles can pay tips like they would on a Jito validator
+        for (tip_account, account) in programs::jito_tip_accounts(&Rent::default()).iter() {
+            bank.store_account(tip_account, account);
+        }
+
         // User-supplied additional builtins
         let mut builtin_programs = Vec::new();
         std::mem::swap(&mut self.builtin_programs, &mut builtin_programs);
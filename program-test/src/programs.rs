@@ -1,8 +1,15 @@
-use solana_sdk::{
-    account::{Account, AccountSharedData},
-    bpf_loader_upgradeable::UpgradeableLoaderState,
-    pubkey::Pubkey,
-    rent::Rent,
+use {
+    ::jito_tip_payment::{
+        TIP_ACCOUNT_SEED_0, TIP_ACCOUNT_SEED_1, TIP_ACCOUNT_SEED_2, TIP_ACCOUNT_SEED_3,
+        TIP_ACCOUNT_SEED_4, TIP_ACCOUNT_SEED_5, TIP_ACCOUNT_SEED_6, TIP_ACCOUNT_SEED_7,
+    },
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        bpf_loader_upgradeable::UpgradeableLoaderState,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
 };
 
 mod spl_token {
@@ -21,10 +28,10 @@ mod spl_associated_token_account {
     solana_sdk::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 }
 
-mod jito_tip_payment {
+pub mod jito_tip_payment {
     solana_sdk::declare_id!("T1pyyaTNZsKv2WcRAB8oVnk93mLJw2XzjtVYqCsaHqt");
 }
-mod jito_tip_distribution {
+pub mod jito_tip_distribution {
     solana_sdk::declare_id!("4R3gSG8BpU4t19KYj8CfnbtRpnT8gtk4dvTHxVRwc2r7");
 }
 
@@ -111,3 +118,28 @@ pub fn spl_programs(rent: &Rent) -> Vec<(Pubkey, AccountSharedData)> {
         })
         .collect()
 }
+
+/// The tip accounts of the bundled tip payment program, derived the same way as the validator's
+/// `TipManager`. They're created as rent-exempt system accounts so bundles can tip them with a
+/// plain transfer before the tip payment program has been initialized.
+pub fn jito_tip_accounts(rent: &Rent) -> Vec<(Pubkey, AccountSharedData)> {
+    [
+        TIP_ACCOUNT_SEED_0,
+        TIP_ACCOUNT_SEED_1,
+        TIP_ACCOUNT_SEED_2,
+        TIP_ACCOUNT_SEED_3,
+        TIP_ACCOUNT_SEED_4,
+        TIP_ACCOUNT_SEED_5,
+        TIP_ACCOUNT_SEED_6,
+        TIP_ACCOUNT_SEED_7,
+    ]
+    .iter()
+    .map(|seed| {
+        let (tip_account, _) = Pubkey::find_program_address(&[*seed], &jito_tip_payment::ID);
+        (
+            tip_account,
+            AccountSharedData::new(rent.minimum_balance(0), 0, &system_program::ID),
+        )
+    })
+    .collect()
+}
@@ -0,0 +1,96 @@
+use {
+    solana_program_test::{programs::jito_tip_accounts, ProgramTest},
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::{Keypair, Signer},
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+};
+
+#[tokio::test]
+async fn bundle_with_tip_lands_atomically() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let recipient = Keypair::new();
+    let tip_account = jito_tip_accounts(&rent)[0].0;
+    let tip_account_balance = context.banks_client.get_balance(tip_account).await.unwrap();
+    assert!(tip_account_balance > 0);
+
+    let transfer_amount = rent.minimum_balance(0);
+    let transfer = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &recipient.pubkey(),
+            transfer_amount,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let tip = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &tip_account,
+            1_000,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    // the payer can't afford this, which must revert the whole bundle
+    let overdraft = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &recipient.pubkey(),
+            u64::MAX,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let result = context
+        .banks_client
+        .process_bundle_with_metadata(vec![transfer.clone(), tip.clone(), overdraft.clone()])
+        .await
+        .unwrap();
+    assert_eq!(
+        result.result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(1)
+        ))
+    );
+    assert_eq!(result.failed_transaction, Some(overdraft.signatures[0]));
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(recipient.pubkey())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        context.banks_client.get_balance(tip_account).await.unwrap(),
+        tip_account_balance
+    );
+
+    context
+        .banks_client
+        .process_bundle(vec![transfer, tip])
+        .await
+        .unwrap();
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(recipient.pubkey())
+            .await
+            .unwrap(),
+        transfer_amount
+    );
+    assert_eq!(
+        context.banks_client.get_balance(tip_account).await.unwrap(),
+        tip_account_balance + 1_000
+    );
+}
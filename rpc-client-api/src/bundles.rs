@@ -6,15 +6,22 @@ use {
     solana_accounts_db::transaction_results::TransactionExecutionResult,
     solana_bundle::{bundle_execution::LoadAndExecuteBundleError, BundleExecutionError},
     solana_sdk::{
-        clock::Slot,
+        clock::{Epoch, Slot},
         commitment_config::{CommitmentConfig, CommitmentLevel},
         signature::Signature,
         transaction::TransactionError,
     },
-    solana_transaction_status::{UiTransactionEncoding, UiTransactionReturnData},
+    solana_transaction_status::{
+        TransactionConfirmationStatus, UiTransactionEncoding, UiTransactionReturnData,
+    },
+    std::collections::HashMap,
     thiserror::Error,
 };
 
+pub const MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS: usize = 256;
+pub const MAX_SIMULATE_BUNDLES_QUERY_ITEMS: usize = 16;
+pub const MAX_GET_TIP_REVENUE_SLOT_RANGE: u64 = 500_000;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcBundleSimulationSummary {
@@ -51,6 +58,9 @@ pub enum RpcBundleExecutionError {
 
     #[error("A transaction in the bundle failed to execute: [signature={0}, error={1}]")]
     TransactionFailure(Signature, String),
+
+    #[error("Bundle denied by policy rule {0}")]
+    DeniedByPolicy(String),
 }
 
 impl From<BundleExecutionError> for RpcBundleExecutionError {
@@ -93,6 +103,7 @@ impl From<BundleExecutionError> for RpcBundleExecutionError {
             BundleExecutionError::LockError => Self::BundleLockError,
             BundleExecutionError::PohRecordError(e) => Self::PohRecordError(e.to_string()),
             BundleExecutionError::TipError(e) => Self::TipError(e.to_string()),
+            BundleExecutionError::DeniedByPolicy(rule) => Self::DeniedByPolicy(rule),
         }
     }
 }
@@ -138,6 +149,36 @@ pub struct RpcSimulateBundleConfig {
     pub replace_recent_blockhash: bool,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundlesConfig {
+    /// Gives the state of accounts pre/post transaction execution, one entry per bundle.
+    /// Either empty, or equal in length to the number of bundles, in which case each entry must
+    /// be equal in length to the number of transactions in its bundle.
+    #[serde(default)]
+    pub pre_execution_accounts_configs: Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+    #[serde(default)]
+    pub post_execution_accounts_configs: Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+
+    /// Specifies the encoding scheme of the contained transactions.
+    pub transaction_encoding: Option<UiTransactionEncoding>,
+
+    /// Specifies the bank to run simulation against.
+    pub simulation_bank: Option<SimulationSlotConfig>,
+
+    /// Opt to skip sig-verify for faster performance.
+    #[serde(default)]
+    pub skip_sig_verify: bool,
+
+    /// Replace recent blockhash to simulate old transactions without resigning.
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+
+    /// Account state, keyed by address, used in place of the bank's before the first bundle
+    /// is simulated.
+    pub account_overrides: Option<HashMap<String, UiAccount>>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum SimulationSlotConfig {
@@ -164,3 +205,55 @@ impl Default for SimulationSlotConfig {
 pub struct RpcBundleRequest {
     pub encoded_transactions: Vec<String>,
 }
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSendBundleConfig {
+    /// Skip simulating the bundle before forwarding it.
+    #[serde(default)]
+    pub skip_preflight: bool,
+
+    /// Specifies the commitment of the bank the bundle is simulated against.
+    pub preflight_commitment: Option<CommitmentLevel>,
+
+    /// Specifies the encoding scheme of the contained transactions.
+    pub encoding: Option<UiTransactionEncoding>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleStatus {
+    pub bundle_id: String,
+    /// The slot the bundle landed in.
+    pub slot: Slot,
+    /// Signatures of the bundle's transactions, in execution order.
+    pub transactions: Vec<String>,
+    /// Lamports the bundle paid into the tip accounts.
+    pub tip_lamports: u64,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTipAccountRevenue {
+    pub tip_account: String,
+    /// Lamports collected by the tip account during the slot.
+    pub lamports: u64,
+}
+
+/// MEV tips collected in one of the node's rooted leader slots.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTipRevenue {
+    pub slot: Slot,
+    pub epoch: Epoch,
+    pub tip_accounts: Vec<RpcTipAccountRevenue>,
+    pub total_tips: u64,
+    pub block_builder: String,
+    pub block_builder_commission_pct: u64,
+    /// Lamports paid to the block builder.
+    pub block_builder_commission: u64,
+    pub tip_distribution_account: String,
+    /// Lamports paid to the tip distribution account.
+    pub net_tip_distribution: u64,
+}
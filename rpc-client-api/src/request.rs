@@ -20,6 +20,7 @@ pub enum RpcRequest {
     GetBlocks,
     GetBlocksWithLimit,
     GetBlockTime,
+    GetBundleStatuses,
     GetClusterNodes,
     #[deprecated(since = "1.7.0", note = "Please use RpcRequest::GetBlock instead")]
     GetConfirmedBlock,
@@ -98,6 +99,7 @@ pub enum RpcRequest {
     GetStakeMinimumDelegation,
     GetStoragePubkeysForSlot,
     GetSupply,
+    GetTipRevenue,
     GetTokenAccountBalance,
     GetTokenAccountsByDelegate,
     GetTokenAccountsByOwner,
@@ -114,6 +116,7 @@ pub enum RpcRequest {
     SendTransaction,
     SimulateTransaction,
     SimulateBundle,
+    SimulateBundles,
     SignVote,
 }
 
@@ -131,6 +134,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetBlocks => "getBlocks",
             RpcRequest::GetBlocksWithLimit => "getBlocksWithLimit",
             RpcRequest::GetBlockTime => "getBlockTime",
+            RpcRequest::GetBundleStatuses => "getBundleStatuses",
             RpcRequest::GetClusterNodes => "getClusterNodes",
             RpcRequest::GetConfirmedBlock => "getConfirmedBlock",
             RpcRequest::GetConfirmedBlocks => "getConfirmedBlocks",
@@ -175,6 +179,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetSlotsPerSegment => "getSlotsPerSegment",
             RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
             RpcRequest::GetSupply => "getSupply",
+            RpcRequest::GetTipRevenue => "getTipRevenue",
             RpcRequest::GetTokenAccountBalance => "getTokenAccountBalance",
             RpcRequest::GetTokenAccountsByDelegate => "getTokenAccountsByDelegate",
             RpcRequest::GetTokenAccountsByOwner => "getTokenAccountsByOwner",
@@ -191,6 +196,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::SendTransaction => "sendTransaction",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SimulateBundle => "simulateBundle",
+            RpcRequest::SimulateBundles => "simulateBundles",
             RpcRequest::SignVote => "signVote",
         };
 
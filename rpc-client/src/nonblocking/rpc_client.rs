@@ -34,8 +34,8 @@ use {
     },
     solana_rpc_client_api::{
         bundles::{
-            RpcBundleRequest, RpcSimulateBundleConfig, RpcSimulateBundleResult,
-            SimulationSlotConfig,
+            RpcBundleRequest, RpcBundleStatus, RpcSimulateBundleConfig, RpcSimulateBundleResult,
+            RpcSimulateBundlesConfig, RpcTipRevenue, SimulationSlotConfig,
         },
         client_error::{
             Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult,
@@ -1525,6 +1525,77 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates a sequence of bundles against the same bank, where each bundle sees the account
+    /// state left behind by the successful bundles before it.
+    ///
+    /// Accounts in `config.account_overrides` replace the bank's accounts for the whole sequence.
+    pub async fn simulate_bundles(
+        &self,
+        bundles: &[VersionedBundle],
+        config: RpcSimulateBundlesConfig,
+    ) -> RpcResult<Vec<RpcSimulateBundleResult>> {
+        let transaction_encoding = if let Some(enc) = config.transaction_encoding {
+            enc
+        } else {
+            self.default_cluster_transaction_encoding().await?
+        };
+        let simulation_bank = Some(config.simulation_bank.unwrap_or_default());
+
+        let rpc_bundle_requests = bundles
+            .iter()
+            .map(|bundle| {
+                let encoded_transactions = bundle
+                    .transactions
+                    .iter()
+                    .map(|tx| {
+                        serialize_and_encode::<VersionedTransaction>(tx, transaction_encoding)
+                    })
+                    .collect::<ClientResult<Vec<String>>>()?;
+                Ok(RpcBundleRequest {
+                    encoded_transactions,
+                })
+            })
+            .collect::<ClientResult<Vec<RpcBundleRequest>>>()?;
+
+        let config = RpcSimulateBundlesConfig {
+            transaction_encoding: Some(transaction_encoding),
+            simulation_bank,
+            ..config
+        };
+
+        self.send(
+            RpcRequest::SimulateBundles,
+            json!([rpc_bundle_requests, config]),
+        )
+        .await
+    }
+
+    /// Returns where each of the given bundles landed, as recorded by the node while it was
+    /// leader.
+    ///
+    /// The returned vector has the same length as the input slice, with `None` for bundles that
+    /// haven't landed on the fork the node has processed.
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> RpcResult<Vec<Option<RpcBundleStatus>>> {
+        self.send(RpcRequest::GetBundleStatuses, json!([bundle_ids]))
+            .await
+    }
+
+    /// Returns the tip revenue the node recorded for its rooted leader slots between `start_slot`
+    /// and `end_slot`, inclusive.
+    ///
+    /// If `end_slot` is not given, the node's highest root is used.
+    pub async fn get_tip_revenue(
+        &self,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+    ) -> ClientResult<Vec<RpcTipRevenue>> {
+        self.send(RpcRequest::GetTipRevenue, json!([start_slot, end_slot]))
+            .await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -28,7 +28,10 @@ use {
         UiAccount, UiAccountEncoding,
     },
     solana_rpc_client_api::{
-        bundles::{RpcSimulateBundleConfig, RpcSimulateBundleResult},
+        bundles::{
+            RpcBundleStatus, RpcSimulateBundleConfig, RpcSimulateBundleResult,
+            RpcSimulateBundlesConfig, RpcTipRevenue,
+        },
         client_error::{Error as ClientError, ErrorKind, Result as ClientResult},
         config::{RpcAccountInfoConfig, *},
         request::{RpcRequest, TokenAccountsFilter},
@@ -1181,6 +1184,42 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).simulate_bundle_with_config(bundle, config))
     }
 
+    /// Simulates a sequence of bundles against the same bank, where each bundle sees the account
+    /// state left behind by the successful bundles before it.
+    ///
+    /// Accounts in `config.account_overrides` replace the bank's accounts for the whole sequence.
+    pub fn simulate_bundles(
+        &self,
+        bundles: &[VersionedBundle],
+        config: RpcSimulateBundlesConfig,
+    ) -> RpcResult<Vec<RpcSimulateBundleResult>> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundles(bundles, config))
+    }
+
+    /// Returns where each of the given bundles landed, as recorded by the node while it was
+    /// leader.
+    ///
+    /// The returned vector has the same length as the input slice, with `None` for bundles that
+    /// haven't landed on the fork the node has processed.
+    pub fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> RpcResult<Vec<Option<RpcBundleStatus>>> {
+        self.invoke((self.rpc_client.as_ref()).get_bundle_statuses(bundle_ids))
+    }
+
+    /// Returns the tip revenue the node recorded for its rooted leader slots between `start_slot`
+    /// and `end_slot`, inclusive.
+    ///
+    /// If `end_slot` is not given, the node's highest root is used.
+    pub fn get_tip_revenue(
+        &self,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+    ) -> ClientResult<Vec<RpcTipRevenue>> {
+        self.invoke((self.rpc_client.as_ref()).get_tip_revenue(start_slot, end_slot))
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
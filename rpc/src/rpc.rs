@@ -59,6 +59,7 @@ use {
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
         account_utils::StateMut,
+        bundle::VersionedBundle,
         clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
         epoch_info::EpochInfo,
@@ -88,7 +89,7 @@ use {
         tpu_info::NullTpuInfo,
     },
     solana_stake_program,
-    solana_storage_bigtable::Error as StorageError,
+    solana_storage_bigtable::{Error as StorageError, LedgerArchive},
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
         map_inner_instructions, BlockEncodingOptions, ConfirmedBlock,
@@ -108,6 +109,7 @@ use {
         collections::{HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -148,6 +150,8 @@ pub struct JsonRpcConfig {
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
+    /// Use a local ledger archive instead of BigTable, see `RpcLocalArchiveConfig`
+    pub rpc_local_archive_config: Option<RpcLocalArchiveConfig>,
     pub max_multiple_accounts: Option<usize>,
     pub account_indexes: AccountSecondaryIndexes,
     pub rpc_threads: usize,
@@ -158,6 +162,8 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Accept bundles over `sendBundle` and forward them to the BundleStage, used by TestValidator
+    pub enable_send_bundle: bool,
 }
 
 impl JsonRpcConfig {
@@ -193,6 +199,13 @@ impl Default for RpcBigtableConfig {
     }
 }
 
+/// A ledger archive kept in a local RocksDB database, in the same format as BigTable
+#[derive(Debug, Clone, Default)]
+pub struct RpcLocalArchiveConfig {
+    pub enable_local_archive_upload: bool,
+    pub archive_path: PathBuf,
+}
+
 #[derive(Clone)]
 pub struct JsonRpcRequestProcessor {
     bank_forks: Arc<RwLock<BankForks>>,
@@ -206,7 +219,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    ledger_archive: Option<Arc<dyn LedgerArchive>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
@@ -214,6 +227,7 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    bundle_sender: Option<Sender<VersionedBundle>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -320,7 +334,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        ledger_archive: Option<Arc<dyn LedgerArchive>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -328,6 +342,7 @@ impl JsonRpcRequestProcessor {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_sender: Option<Sender<VersionedBundle>>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
         (
@@ -342,7 +357,7 @@ impl JsonRpcRequestProcessor {
                 cluster_info,
                 genesis_hash,
                 transaction_sender: Arc::new(Mutex::new(sender)),
-                bigtable_ledger_storage,
+                ledger_archive,
                 optimistically_confirmed_bank,
                 largest_accounts_cache,
                 max_slots,
@@ -350,6 +365,7 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                bundle_sender,
             },
             receiver,
         )
@@ -413,7 +429,7 @@ impl JsonRpcRequestProcessor {
             cluster_info,
             genesis_hash,
             transaction_sender: Arc::new(Mutex::new(sender)),
-            bigtable_ledger_storage: None,
+            ledger_archive: None,
             optimistically_confirmed_bank,
             largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::new(30))),
             max_slots: Arc::new(MaxSlots::default()),
@@ -421,6 +437,7 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            bundle_sender: None,
         }
     }
 
@@ -582,7 +599,7 @@ impl JsonRpcRequestProcessor {
         let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(rewards_epoch);
 
         if first_slot_in_epoch < first_available_block {
-            if self.bigtable_ledger_storage.is_some() {
+            if self.ledger_archive.is_some() {
                 return Err(RpcCustomError::LongTermStorageSlotSkipped {
                     slot: first_slot_in_epoch,
                 }
@@ -1145,7 +1162,7 @@ impl JsonRpcRequestProcessor {
         Ok(())
     }
 
-    fn check_bigtable_result<T>(
+    fn check_archive_result<T>(
         &self,
         result: &std::result::Result<T, solana_storage_bigtable::Error>,
     ) -> Result<()> {
@@ -1208,11 +1225,10 @@ impl JsonRpcRequestProcessor {
                     Ok(encoded_block)
                 };
                 if result.is_err() {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        let bigtable_result =
-                            bigtable_ledger_storage.get_confirmed_block(slot).await;
-                        self.check_bigtable_result(&bigtable_result)?;
-                        return bigtable_result.ok().map(encode_block).transpose();
+                    if let Some(ledger_archive) = &self.ledger_archive {
+                        let archive_result = ledger_archive.get_confirmed_block(slot).await;
+                        self.check_archive_result(&archive_result)?;
+                        return archive_result.ok().map(encode_block).transpose();
                     }
                 }
                 self.check_slot_cleaned_up(&result, slot)?;
@@ -1299,13 +1315,13 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // [start_slot..end_slot] can be fetched from BigTable. This range should not ever run
             // into unfinalized confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return bigtable_ledger_storage
+            if let Some(ledger_archive) = &self.ledger_archive {
+                return ledger_archive
                     .get_confirmed_blocks(start_slot, (end_slot - start_slot) as usize + 1) // increment limit by 1 to ensure returned range is inclusive of both start_slot and end_slot
                     .await
-                    .map(|mut bigtable_blocks| {
-                        bigtable_blocks.retain(|&slot| slot <= end_slot);
-                        bigtable_blocks
+                    .map(|mut archive_blocks| {
+                        archive_blocks.retain(|&slot| slot <= end_slot);
+                        archive_blocks
                     })
                     .map_err(|_| {
                         Error::invalid_params(
@@ -1366,8 +1382,8 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // range can be fetched from BigTable. This range should not ever run into unfinalized
             // confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return Ok(bigtable_ledger_storage
+            if let Some(ledger_archive) = &self.ledger_archive {
+                return Ok(ledger_archive
                     .get_confirmed_blocks(start_slot, limit)
                     .await
                     .unwrap_or_default());
@@ -1422,10 +1438,10 @@ impl JsonRpcRequestProcessor {
             let result = self.blockstore.get_rooted_block_time(slot);
             self.check_blockstore_root(&result, slot)?;
             if result.is_err() {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let bigtable_result = bigtable_ledger_storage.get_confirmed_block(slot).await;
-                    self.check_bigtable_result(&bigtable_result)?;
-                    return Ok(bigtable_result
+                if let Some(ledger_archive) = &self.ledger_archive {
+                    let archive_result = ledger_archive.get_confirmed_block(slot).await;
+                    self.check_archive_result(&archive_result)?;
+                    return Ok(archive_result
                         .ok()
                         .and_then(|confirmed_block| confirmed_block.block_time));
                 }
@@ -1515,8 +1531,8 @@ impl JsonRpcRequestProcessor {
                     })
                 {
                     Some(status)
-                } else if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    bigtable_ledger_storage
+                } else if let Some(ledger_archive) = &self.ledger_archive {
+                    ledger_archive
                         .get_signature_status(&signature)
                         .await
                         .map(Some)
@@ -1624,8 +1640,8 @@ impl JsonRpcRequestProcessor {
                     }
                 }
                 None => {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        return bigtable_ledger_storage
+                    if let Some(ledger_archive) = &self.ledger_archive {
+                        return ledger_archive
                             .get_confirmed_transaction(&signature)
                             .await
                             .unwrap_or(None)
@@ -1727,23 +1743,23 @@ impl JsonRpcRequestProcessor {
             };
 
             if results.len() < limit {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let mut bigtable_before = before;
+                if let Some(ledger_archive) = &self.ledger_archive {
+                    let mut archive_before = before;
                     if !results.is_empty() {
                         limit -= results.len();
-                        bigtable_before = results.last().map(|x| x.signature);
+                        archive_before = results.last().map(|x| x.signature);
                     }
 
                     // If the oldest address-signature found in Blockstore has not yet been
                     // uploaded to long-term storage, modify the storage query to return all latest
                     // signatures to prevent erroring on RowNotFound. This can race with upload.
-                    if found_before && bigtable_before.is_some() {
-                        match bigtable_ledger_storage
-                            .get_signature_status(&bigtable_before.unwrap())
+                    if found_before && archive_before.is_some() {
+                        match ledger_archive
+                            .get_signature_status(&archive_before.unwrap())
                             .await
                         {
                             Err(StorageError::SignatureNotFound) => {
-                                bigtable_before = None;
+                                archive_before = None;
                             }
                             Err(err) => {
                                 warn!("{:?}", err);
@@ -1753,26 +1769,26 @@ impl JsonRpcRequestProcessor {
                         }
                     }
 
-                    let bigtable_results = bigtable_ledger_storage
+                    let archive_results = ledger_archive
                         .get_confirmed_signatures_for_address(
                             &address,
-                            bigtable_before.as_ref(),
+                            archive_before.as_ref(),
                             until.as_ref(),
                             limit,
                         )
                         .await;
-                    match bigtable_results {
-                        Ok(bigtable_results) => {
+                    match archive_results {
+                        Ok(archive_results) => {
                             let results_set: HashSet<_> =
                                 results.iter().map(|result| result.signature).collect();
-                            for (bigtable_result, _) in bigtable_results {
+                            for (archive_result, _) in archive_results {
                                 // In the upload race condition, latest address-signatures in
                                 // long-term storage may include original `before` signature...
-                                if before != Some(bigtable_result.signature)
+                                if before != Some(archive_result.signature)
                                     // ...or earlier Blockstore signatures
-                                    && !results_set.contains(&bigtable_result.signature)
+                                    && !results_set.contains(&archive_result.signature)
                                 {
-                                    results.push(bigtable_result);
+                                    results.push(archive_result);
                                 }
                             }
                         }
@@ -1795,15 +1811,15 @@ impl JsonRpcRequestProcessor {
             .get_first_available_block()
             .unwrap_or_default();
 
-        if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-            let bigtable_slot = bigtable_ledger_storage
+        if let Some(ledger_archive) = &self.ledger_archive {
+            let archive_slot = ledger_archive
                 .get_first_available_block()
                 .await
                 .unwrap_or(None)
                 .unwrap_or(slot);
 
-            if bigtable_slot < slot {
-                return bigtable_slot;
+            if archive_slot < slot {
+                return archive_slot;
             }
         }
         slot
@@ -3371,14 +3387,16 @@ pub mod utils {
             bundle_execution::{LoadAndExecuteBundleError, LoadAndExecuteBundleOutput},
             BundleExecutionError,
         },
+        solana_ledger::blockstore::Blockstore,
         solana_rpc_client_api::{
             bundles::{
                 RpcBundleExecutionError, RpcBundleSimulationSummary, RpcSimulateBundleConfig,
-                RpcSimulateBundleResult, RpcSimulateBundleTransactionResult,
+                RpcSimulateBundleResult, RpcSimulateBundleTransactionResult, RpcTipAccountRevenue,
+                RpcTipRevenue, MAX_GET_TIP_REVENUE_SLOT_RANGE,
             },
             config::RpcSimulateTransactionAccountsConfig,
         },
-        solana_sdk::{account::AccountSharedData, pubkey::Pubkey},
+        solana_sdk::{account::AccountSharedData, clock::Slot, pubkey::Pubkey},
         std::str::FromStr,
     };
 
@@ -3503,6 +3521,47 @@ pub mod utils {
         }
         Ok(execution_accounts)
     }
+
+    /// Returns the tip revenue recorded for this node's rooted leader slots in
+    /// \[`start_slot`, `end_slot`\]
+    pub fn get_tip_revenue(
+        blockstore: &Blockstore,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<RpcTipRevenue>, Error> {
+        if end_slot < start_slot {
+            return Ok(vec![]);
+        }
+        if end_slot - start_slot > MAX_GET_TIP_REVENUE_SLOT_RANGE {
+            return Err(Error::invalid_params(format!(
+                "Slot range too large; max {MAX_GET_TIP_REVENUE_SLOT_RANGE}"
+            )));
+        }
+
+        Ok(blockstore
+            .read_tip_revenue(start_slot, end_slot)
+            .map_err(|_| Error::internal_error())?
+            .into_iter()
+            .map(|(slot, tip_revenue)| RpcTipRevenue {
+                slot,
+                epoch: tip_revenue.epoch,
+                tip_accounts: tip_revenue
+                    .tip_accounts
+                    .into_iter()
+                    .map(|(tip_account, lamports)| RpcTipAccountRevenue {
+                        tip_account: tip_account.to_string(),
+                        lamports,
+                    })
+                    .collect(),
+                total_tips: tip_revenue.total_tips,
+                block_builder: tip_revenue.block_builder.to_string(),
+                block_builder_commission_pct: tip_revenue.block_builder_commission_pct,
+                block_builder_commission: tip_revenue.block_builder_commission,
+                tip_distribution_account: tip_revenue.tip_distribution_account.to_string(),
+                net_tip_distribution: tip_revenue.net_tip_distribution,
+            })
+            .collect())
+    }
 }
 
 // Full RPC interface that an API node is expected to provide
@@ -3512,10 +3571,16 @@ pub mod rpc_full {
         super::*,
         crate::rpc::utils::{account_configs_to_accounts, rpc_bundle_result_from_bank_result},
         jsonrpc_core::ErrorCode,
+        solana_accounts_db::account_overrides::AccountOverrides,
         solana_bundle::bundle_execution::{load_and_execute_bundle, LoadAndExecuteBundleError},
-        solana_rpc_client_api::bundles::{
-            RpcBundleRequest, RpcSimulateBundleConfig, RpcSimulateBundleResult,
-            SimulationSlotConfig,
+        solana_rpc_client_api::{
+            bundles::{
+                RpcBundleRequest, RpcBundleStatus, RpcSendBundleConfig, RpcSimulateBundleConfig,
+                RpcSimulateBundleResult, RpcSimulateBundlesConfig, RpcTipRevenue,
+                SimulationSlotConfig, MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS,
+                MAX_SIMULATE_BUNDLES_QUERY_ITEMS,
+            },
+            custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
         },
         solana_sdk::{
             bundle::{derive_bundle_id, SanitizedBundle},
@@ -3525,6 +3590,137 @@ pub mod rpc_full {
         solana_transaction_status::UiInnerInstructions,
     };
 
+    const MAX_BUNDLE_SIMULATION_TIME: Duration = Duration::from_millis(500);
+
+    fn simulation_bank(
+        meta: &JsonRpcRequestProcessor,
+        simulation_bank: Option<SimulationSlotConfig>,
+    ) -> Result<Arc<Bank>> {
+        match simulation_bank.unwrap_or_default() {
+            SimulationSlotConfig::Commitment(commitment) => Ok(meta.bank(Some(commitment))),
+            SimulationSlotConfig::Slot(slot) => meta.bank_from_slot(slot).ok_or_else(|| {
+                Error::invalid_params(format!("bank not found for the provided slot: {}", slot))
+            }),
+            SimulationSlotConfig::Tip => Ok(meta.bank_forks.read().unwrap().working_bank()),
+        }
+    }
+
+    /// Simulates a bundle against `bank` with `account_overrides` layered on top. When the bundle
+    /// executes successfully, `account_overrides` is updated with the account state it left behind.
+    fn simulate_bundle_with_overrides(
+        bank: &Bank,
+        rpc_bundle_request: RpcBundleRequest,
+        config: RpcSimulateBundleConfig,
+        account_overrides: &mut AccountOverrides,
+    ) -> Result<RpcSimulateBundleResult> {
+        // Run some request validations
+        if !(config.pre_execution_accounts_configs.len()
+            == rpc_bundle_request.encoded_transactions.len()
+            && config.post_execution_accounts_configs.len()
+                == rpc_bundle_request.encoded_transactions.len())
+        {
+            return Err(Error::invalid_params(
+                "pre/post_execution_accounts_configs must be equal in length to the number of transactions",
+            ));
+        }
+
+        let tx_encoding = config
+            .transaction_encoding
+            .unwrap_or(UiTransactionEncoding::Base64);
+        let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+            Error::invalid_params(format!(
+                "Unsupported encoding: {}. Supported encodings are: base58 & base64",
+                tx_encoding
+            ))
+        })?;
+        let mut decoded_transactions = rpc_bundle_request
+            .encoded_transactions
+            .into_iter()
+            .map(|encoded_tx| {
+                decode_and_deserialize::<VersionedTransaction>(encoded_tx, binary_encoding)
+                    .map(|de| de.1)
+            })
+            .collect::<Result<Vec<VersionedTransaction>>>()?;
+
+        if config.replace_recent_blockhash {
+            if !config.skip_sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            decoded_transactions.iter_mut().for_each(|tx| {
+                tx.message.set_recent_blockhash(bank.last_blockhash());
+            });
+        }
+
+        let bundle_id = derive_bundle_id(&decoded_transactions);
+        let sanitized_bundle = SanitizedBundle {
+            transactions: decoded_transactions
+                .into_iter()
+                .map(|tx| sanitize_transaction(tx, bank))
+                .collect::<Result<Vec<SanitizedTransaction>>>()?,
+            bundle_id,
+        };
+
+        if !config.skip_sig_verify {
+            for tx in &sanitized_bundle.transactions {
+                verify_transaction(tx, &bank.feature_set)?;
+            }
+        }
+
+        let pre_execution_accounts =
+            account_configs_to_accounts(&config.pre_execution_accounts_configs)?;
+        let post_execution_accounts =
+            account_configs_to_accounts(&config.post_execution_accounts_configs)?;
+
+        // a failed bundle can leave partially executed state behind, so it runs against a copy
+        let mut bundle_account_overrides = account_overrides.clone();
+        let bundle_execution_result = load_and_execute_bundle(
+            bank,
+            &sanitized_bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_BUNDLE_SIMULATION_TIME,
+            true,
+            true,
+            true,
+            true,
+            &None,
+            true,
+            Some(&mut bundle_account_overrides),
+            &pre_execution_accounts,
+            &post_execution_accounts,
+        );
+
+        // only return error if irrecoverable (timeout or tx malformed)
+        // bundle execution failures w/ context are returned to client
+        match bundle_execution_result.result() {
+            Ok(()) => *account_overrides = bundle_account_overrides,
+            Err(LoadAndExecuteBundleError::TransactionError { .. }) => {}
+            Err(LoadAndExecuteBundleError::ProcessingTimeExceeded(elapsed)) => {
+                let mut error = Error::new(ErrorCode::ServerError(10_000));
+                error.message = format!(
+                    "simulation time exceeded max allowed time: {:?}ms",
+                    elapsed.as_millis()
+                );
+                return Err(error);
+            }
+            Err(LoadAndExecuteBundleError::InvalidPreOrPostAccounts) => {
+                return Err(Error::invalid_params("invalid pre or post account data"));
+            }
+            Err(LoadAndExecuteBundleError::LockError {
+                signature,
+                transaction_error,
+            }) => {
+                return Err(Error::invalid_params(format!(
+                    "error locking transaction with signature: {}, error: {:?}",
+                    signature, transaction_error
+                )));
+            }
+        }
+
+        rpc_bundle_result_from_bank_result(bundle_execution_result, config)
+    }
+
     #[rpc]
     pub trait Full {
         type Metadata;
@@ -3594,6 +3790,37 @@ pub mod rpc_full {
             config: Option<RpcSimulateBundleConfig>,
         ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
 
+        #[rpc(meta, name = "simulateBundles")]
+        fn simulate_bundles(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_requests: Vec<RpcBundleRequest>,
+            config: Option<RpcSimulateBundlesConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateBundleResult>>>;
+
+        #[rpc(meta, name = "sendBundle")]
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<String>;
+
+        #[rpc(meta, name = "getBundleStatuses")]
+        fn get_bundle_statuses(
+            &self,
+            meta: Self::Metadata,
+            bundle_ids: Vec<String>,
+        ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>>;
+
+        #[rpc(meta, name = "getTipRevenue")]
+        fn get_tip_revenue(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Slot,
+            end_slot: Option<Slot>,
+        ) -> Result<Vec<RpcTipRevenue>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -4108,8 +4335,6 @@ pub mod rpc_full {
             rpc_bundle_request: RpcBundleRequest,
             config: Option<RpcSimulateBundleConfig>,
         ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
-            const MAX_BUNDLE_SIMULATION_TIME: Duration = Duration::from_millis(500);
-
             debug!("simulate_bundle rpc request received");
 
             let config = config.unwrap_or_else(|| RpcSimulateBundleConfig {
@@ -4124,120 +4349,267 @@ pub mod rpc_full {
                 ..RpcSimulateBundleConfig::default()
             });
 
-            // Run some request validations
-            if !(config.pre_execution_accounts_configs.len()
-                == rpc_bundle_request.encoded_transactions.len()
-                && config.post_execution_accounts_configs.len()
-                    == rpc_bundle_request.encoded_transactions.len())
+            let bank = simulation_bank(&meta, config.simulation_bank)?;
+            let rpc_bundle_result = simulate_bundle_with_overrides(
+                &bank,
+                rpc_bundle_request,
+                config,
+                &mut AccountOverrides::default(),
+            )?;
+
+            Ok(new_response(&bank, rpc_bundle_result))
+        }
+
+        fn simulate_bundles(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_requests: Vec<RpcBundleRequest>,
+            config: Option<RpcSimulateBundlesConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateBundleResult>>> {
+            debug!(
+                "simulate_bundles rpc request received: {:?}",
+                rpc_bundle_requests.len()
+            );
+            if rpc_bundle_requests.len() > MAX_SIMULATE_BUNDLES_QUERY_ITEMS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_SIMULATE_BUNDLES_QUERY_ITEMS}"
+                )));
+            }
+
+            let RpcSimulateBundlesConfig {
+                pre_execution_accounts_configs,
+                post_execution_accounts_configs,
+                transaction_encoding,
+                simulation_bank: simulation_bank_config,
+                skip_sig_verify,
+                replace_recent_blockhash,
+                account_overrides: encoded_account_overrides,
+            } = config.unwrap_or_default();
+            if [
+                &pre_execution_accounts_configs,
+                &post_execution_accounts_configs,
+            ]
+            .iter()
+            .any(|configs| !configs.is_empty() && configs.len() != rpc_bundle_requests.len())
             {
                 return Err(Error::invalid_params(
-                    "pre/post_execution_accounts_configs must be equal in length to the number of transactions",
+                    "pre/post_execution_accounts_configs must be empty or equal in length to the number of bundles",
                 ));
             }
 
-            let bank = match config.simulation_bank.unwrap_or_default() {
-                SimulationSlotConfig::Commitment(commitment) => Ok(meta.bank(Some(commitment))),
-                SimulationSlotConfig::Slot(slot) => meta.bank_from_slot(slot).ok_or_else(|| {
-                    Error::invalid_params(format!("bank not found for the provided slot: {}", slot))
-                }),
-                SimulationSlotConfig::Tip => Ok(meta.bank_forks.read().unwrap().working_bank()),
-            }?;
+            let mut account_overrides = AccountOverrides::default();
+            for (address, ui_account) in encoded_account_overrides.unwrap_or_default() {
+                let pubkey = verify_pubkey(&address)?;
+                let account = ui_account.decode::<AccountSharedData>().ok_or_else(|| {
+                    Error::invalid_params(format!("invalid account override for {address}"))
+                })?;
+                account_overrides.set_account(&pubkey, Some(account));
+            }
+
+            let bank = simulation_bank(&meta, simulation_bank_config)?;
+            // each bundle sees the account state left behind by the successful bundles before it
+            let rpc_bundle_results = rpc_bundle_requests
+                .into_iter()
+                .enumerate()
+                .map(|(index, rpc_bundle_request)| {
+                    let num_transactions = rpc_bundle_request.encoded_transactions.len();
+                    let config = RpcSimulateBundleConfig {
+                        pre_execution_accounts_configs: pre_execution_accounts_configs
+                            .get(index)
+                            .cloned()
+                            .unwrap_or_else(|| vec![None; num_transactions]),
+                        post_execution_accounts_configs: post_execution_accounts_configs
+                            .get(index)
+                            .cloned()
+                            .unwrap_or_else(|| vec![None; num_transactions]),
+                        transaction_encoding,
+                        simulation_bank: simulation_bank_config,
+                        skip_sig_verify,
+                        replace_recent_blockhash,
+                    };
+                    simulate_bundle_with_overrides(
+                        &bank,
+                        rpc_bundle_request,
+                        config,
+                        &mut account_overrides,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(&bank, rpc_bundle_results))
+        }
 
-            let tx_encoding = config
-                .transaction_encoding
-                .unwrap_or(UiTransactionEncoding::Base64);
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<String> {
+            debug!("send_bundle rpc request received");
+            let bundle_sender = meta
+                .bundle_sender
+                .clone()
+                .ok_or_else(Error::method_not_found)?;
+            let RpcSendBundleConfig {
+                skip_preflight,
+                preflight_commitment,
+                encoding,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params(
+                    "bundle must contain at least one transaction",
+                ));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base64);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
                 Error::invalid_params(format!(
-                    "Unsupported encoding: {}. Supported encodings are: base58 & base64",
-                    tx_encoding
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
                 ))
             })?;
-            let mut decoded_transactions = rpc_bundle_request
-                .encoded_transactions
+            let transactions = data
                 .into_iter()
                 .map(|encoded_tx| {
                     decode_and_deserialize::<VersionedTransaction>(encoded_tx, binary_encoding)
                         .map(|de| de.1)
                 })
                 .collect::<Result<Vec<VersionedTransaction>>>()?;
+            let bundle_id = derive_bundle_id(&transactions);
 
-            if config.replace_recent_blockhash {
-                if !config.skip_sig_verify {
-                    return Err(Error::invalid_params(
-                        "sigVerify may not be used with replaceRecentBlockhash",
-                    ));
+            if !skip_preflight {
+                let preflight_bank = meta
+                    .bank(preflight_commitment.map(|commitment| CommitmentConfig { commitment }));
+                let sanitized_bundle = SanitizedBundle {
+                    transactions: transactions
+                        .iter()
+                        .cloned()
+                        .map(|tx| sanitize_transaction(tx, preflight_bank.as_ref()))
+                        .collect::<Result<Vec<SanitizedTransaction>>>()?,
+                    bundle_id: bundle_id.clone(),
+                };
+                for tx in &sanitized_bundle.transactions {
+                    verify_transaction(tx, &preflight_bank.feature_set)?;
                 }
-                decoded_transactions.iter_mut().for_each(|tx| {
-                    tx.message.set_recent_blockhash(bank.last_blockhash());
-                });
-            }
-
-            let bundle_id = derive_bundle_id(&decoded_transactions);
-            let sanitized_bundle = SanitizedBundle {
-                transactions: decoded_transactions
-                    .into_iter()
-                    .map(|tx| sanitize_transaction(tx, bank.as_ref()))
-                    .collect::<Result<Vec<SanitizedTransaction>>>()?,
-                bundle_id,
-            };
 
-            if !config.skip_sig_verify {
-                for tx in &sanitized_bundle.transactions {
-                    verify_transaction(tx, &bank.feature_set)?;
+                let execution_accounts = vec![None; sanitized_bundle.transactions.len()];
+                let bundle_execution_result = load_and_execute_bundle(
+                    &preflight_bank,
+                    &sanitized_bundle,
+                    MAX_PROCESSING_AGE,
+                    &MAX_BUNDLE_SIMULATION_TIME,
+                    false,
+                    true,
+                    true,
+                    false,
+                    &None,
+                    true,
+                    None,
+                    &execution_accounts,
+                    &execution_accounts,
+                );
+                if let Err(err) = bundle_execution_result.result() {
+                    let message = format!("Bundle simulation failed: {err}");
+                    let rpc_bundle_result = rpc_bundle_result_from_bank_result(
+                        bundle_execution_result,
+                        RpcSimulateBundleConfig {
+                            pre_execution_accounts_configs: vec![None; transactions.len()],
+                            post_execution_accounts_configs: vec![None; transactions.len()],
+                            ..RpcSimulateBundleConfig::default()
+                        },
+                    )?;
+                    let mut error = Error::new(ErrorCode::ServerError(
+                        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+                    ));
+                    error.message = message;
+                    error.data = serde_json::to_value(rpc_bundle_result).ok();
+                    return Err(error);
                 }
             }
 
-            let pre_execution_accounts =
-                account_configs_to_accounts(&config.pre_execution_accounts_configs)?;
-            let post_execution_accounts =
-                account_configs_to_accounts(&config.post_execution_accounts_configs)?;
+            bundle_sender
+                .send(VersionedBundle { transactions })
+                .map_err(|_| Error::internal_error())?;
+            Ok(bundle_id)
+        }
 
-            let bundle_execution_result = load_and_execute_bundle(
-                &bank,
-                &sanitized_bundle,
-                MAX_PROCESSING_AGE,
-                &MAX_BUNDLE_SIMULATION_TIME,
-                true,
-                true,
-                true,
-                true,
-                &None,
-                true,
-                None,
-                &pre_execution_accounts,
-                &post_execution_accounts,
+        fn get_bundle_statuses(
+            &self,
+            meta: Self::Metadata,
+            bundle_ids: Vec<String>,
+        ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>> {
+            debug!(
+                "get_bundle_statuses rpc request received: {:?}",
+                bundle_ids.len()
             );
-
-            // only return error if irrecoverable (timeout or tx malformed)
-            // bundle execution failures w/ context are returned to client
-            match bundle_execution_result.result() {
-                Ok(()) | Err(LoadAndExecuteBundleError::TransactionError { .. }) => {}
-                Err(LoadAndExecuteBundleError::ProcessingTimeExceeded(elapsed)) => {
-                    let mut error = Error::new(ErrorCode::ServerError(10_000));
-                    error.message = format!(
-                        "simulation time exceeded max allowed time: {:?}ms",
-                        elapsed.as_millis()
-                    );
-                    return Err(error);
-                }
-                Err(LoadAndExecuteBundleError::InvalidPreOrPostAccounts) => {
-                    return Err(Error::invalid_params("invalid pre or post account data"));
-                }
-                Err(LoadAndExecuteBundleError::LockError {
-                    signature,
-                    transaction_error,
-                }) => {
-                    return Err(Error::invalid_params(format!(
-                        "error locking transaction with signature: {}, error: {:?}",
-                        signature, transaction_error
-                    )));
-                }
+            if bundle_ids.len() > MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS}"
+                )));
             }
 
-            let rpc_bundle_result =
-                rpc_bundle_result_from_bank_result(bundle_execution_result, config)?;
+            let bank = meta.bank(Some(CommitmentConfig::processed()));
+            let processed_ancestors = bank.status_cache_ancestors();
+            let confirmed_ancestors = meta
+                .bank(Some(CommitmentConfig::confirmed()))
+                .status_cache_ancestors();
+            let r_block_commitment_cache = meta.block_commitment_cache.read().unwrap();
 
-            Ok(new_response(&bank, rpc_bundle_result))
+            let statuses = bundle_ids
+                .into_iter()
+                .map(|bundle_id| {
+                    // A bundle may have landed on more than one fork, only report the landing on
+                    // the fork this node has processed
+                    let landing = meta
+                        .blockstore
+                        .read_bundle_statuses(&bundle_id)
+                        .map_err(|_| Error::internal_error())?
+                        .into_iter()
+                        .rev()
+                        .find(|(slot, _)| {
+                            processed_ancestors.contains(slot) || meta.blockstore.is_root(*slot)
+                        });
+                    Ok(landing.map(|(slot, status)| {
+                        let confirmation_status = if is_finalized(
+                            &r_block_commitment_cache,
+                            &bank,
+                            &meta.blockstore,
+                            slot,
+                        ) {
+                            TransactionConfirmationStatus::Finalized
+                        } else if confirmed_ancestors.contains(&slot) {
+                            TransactionConfirmationStatus::Confirmed
+                        } else {
+                            TransactionConfirmationStatus::Processed
+                        };
+                        RpcBundleStatus {
+                            bundle_id,
+                            slot,
+                            transactions: status
+                                .signatures
+                                .iter()
+                                .map(|signature| signature.to_string())
+                                .collect(),
+                            tip_lamports: status.tip_lamports,
+                            confirmation_status: Some(confirmation_status),
+                        }
+                    }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(&bank, statuses))
+        }
+
+        fn get_tip_revenue(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Slot,
+            end_slot: Option<Slot>,
+        ) -> Result<Vec<RpcTipRevenue>> {
+            debug!(
+                "get_tip_revenue rpc request received: {:?}-{:?}",
+                start_slot, end_slot
+            );
+            let end_slot = end_slot.unwrap_or_else(|| meta.blockstore.max_root());
+            utils::get_tip_revenue(&meta.blockstore, start_slot, end_slot)
         }
 
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
@@ -5063,13 +5435,18 @@ pub mod tests {
         solana_entry::entry::next_versioned_entry,
         solana_gossip::socketaddr,
         solana_ledger::{
-            blockstore_meta::PerfSampleV2,
+            blockstore_meta::{BundleStatusMeta, PerfSampleV2},
             blockstore_processor::fill_blockstore_slot_with_ticks,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
         solana_rpc_client_api::{
+            bundles::{
+                RpcBundleSimulationSummary, RpcBundleStatus, RpcSimulateBundleResult,
+                MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS, MAX_SIMULATE_BUNDLES_QUERY_ITEMS,
+            },
             custom_error::{
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -5085,6 +5462,7 @@ pub mod tests {
                 self,
                 state::{AddressLookupTable, LookupTableMeta},
             },
+            bundle::derive_bundle_id,
             clock::MAX_RECENT_BLOCKHASHES,
             compute_budget::ComputeBudgetInstruction,
             fee_calculator::{FeeRateGovernor, DEFAULT_BURN_PERCENT},
@@ -5238,6 +5616,7 @@ pub mod tests {
                 max_complete_transaction_status_slot.clone(),
                 max_complete_rewards_slot,
                 Arc::new(PrioritizationFeeCache::default()),
+                None,
             )
             .0;
 
@@ -6415,6 +6794,127 @@ pub mod tests {
         assert_eq!(expected_response, actual_response);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundles_chained() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        bank.freeze();
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let rent_exempt_lamports = bank.get_minimum_balance_for_rent_exemption(0);
+        let encode_bundle = |transactions: &[Transaction]| {
+            json!({
+                "encodedTransactions": transactions
+                    .iter()
+                    .map(|tx| general_purpose::STANDARD.encode(serialize(tx).unwrap()))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // the second bundle spends lamports that only exist after the first one executed
+        let searcher = Keypair::new();
+        let recipient = solana_sdk::pubkey::new_rand();
+        let fund_searcher = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &searcher.pubkey(),
+            10 * rent_exempt_lamports,
+            recent_blockhash,
+        );
+        let searcher_transfer = system_transaction::transfer(
+            &searcher,
+            &recipient,
+            rent_exempt_lamports,
+            recent_blockhash,
+        );
+
+        // state left by a failed bundle is not visible to the bundles after it
+        let unfunded = Keypair::new();
+        let fund_unfunded = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &unfunded.pubkey(),
+            10 * rent_exempt_lamports,
+            recent_blockhash,
+        );
+        let overdraft =
+            system_transaction::transfer(&rpc.mint_keypair, &recipient, u64::MAX, recent_blockhash);
+        let unfunded_transfer = system_transaction::transfer(
+            &unfunded,
+            &recipient,
+            rent_exempt_lamports,
+            recent_blockhash,
+        );
+
+        let request = create_test_request(
+            "simulateBundles",
+            Some(json!([
+                [
+                    encode_bundle(&[fund_searcher]),
+                    encode_bundle(&[searcher_transfer.clone()]),
+                    encode_bundle(&[fund_unfunded, overdraft]),
+                    encode_bundle(&[unfunded_transfer]),
+                ],
+                {
+                    "postExecutionAccountsConfigs": [
+                        [null],
+                        [{ "encoding": "base64", "addresses": [recipient.to_string()] }],
+                        [null, null],
+                        [null],
+                    ],
+                },
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateBundleResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.len(), 4);
+        assert!(matches!(
+            result.value[0].summary,
+            RpcBundleSimulationSummary::Succeeded
+        ));
+        assert!(matches!(
+            result.value[1].summary,
+            RpcBundleSimulationSummary::Succeeded
+        ));
+        let recipient_account = &result.value[1].transaction_results[0]
+            .post_execution_accounts
+            .as_ref()
+            .unwrap()[0];
+        assert_eq!(recipient_account.lamports, rent_exempt_lamports);
+        assert!(matches!(
+            result.value[2].summary,
+            RpcBundleSimulationSummary::Failed { .. }
+        ));
+        assert!(matches!(
+            result.value[3].summary,
+            RpcBundleSimulationSummary::Failed { .. }
+        ));
+
+        // accounts supplied by the request are used in place of the bank's
+        let searcher_account = UiAccount::encode(
+            &searcher.pubkey(),
+            &AccountSharedData::new(10 * rent_exempt_lamports, 0, &system_program::id()),
+            UiAccountEncoding::Base64,
+            None,
+            None,
+        );
+        let request = create_test_request(
+            "simulateBundles",
+            Some(json!([
+                [encode_bundle(&[searcher_transfer])],
+                { "accountOverrides": { searcher.pubkey().to_string(): searcher_account } },
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateBundleResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(matches!(
+            result.value[0].summary,
+            RpcBundleSimulationSummary::Succeeded
+        ));
+
+        let bundles = vec![encode_bundle(&[]); MAX_SIMULATE_BUNDLES_QUERY_ITEMS + 1];
+        let request = create_test_request("simulateBundles", Some(json!([bundles])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -7328,6 +7828,121 @@ pub mod tests {
         assert_eq!(error["code"], ErrorCode::InvalidParams.code());
     }
 
+    #[test]
+    fn test_rpc_send_bundle() {
+        let mut rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let recent_blockhash = bank.last_blockhash();
+        let lamports = bank.get_minimum_balance_for_rent_exemption(0);
+        let encode = |transactions: &[VersionedTransaction]| -> Vec<String> {
+            transactions
+                .iter()
+                .map(|tx| general_purpose::STANDARD.encode(serialize(tx).unwrap()))
+                .collect()
+        };
+        let transactions: Vec<_> = (1..=2)
+            .map(|i| {
+                VersionedTransaction::from(system_transaction::transfer(
+                    &rpc.mint_keypair,
+                    &solana_sdk::pubkey::new_rand(),
+                    lamports * i,
+                    recent_blockhash,
+                ))
+            })
+            .collect();
+
+        // sendBundle is only served when the node forwards bundles to the BundleStage
+        let request = create_test_request("sendBundle", Some(json!([encode(&transactions)])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::MethodNotFound.code());
+
+        let (bundle_sender, bundle_receiver) = unbounded();
+        rpc.meta.bundle_sender = Some(bundle_sender);
+
+        let request = create_test_request("sendBundle", Some(json!([encode(&transactions)])));
+        let bundle_id: String = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(bundle_id, derive_bundle_id(&transactions));
+        assert_eq!(
+            bundle_receiver.try_recv().unwrap(),
+            VersionedBundle {
+                transactions: transactions.clone()
+            }
+        );
+
+        // a bundle that fails simulation isn't forwarded unless preflight is skipped
+        let overdraft = VersionedTransaction::from(system_transaction::transfer(
+            &rpc.mint_keypair,
+            &solana_sdk::pubkey::new_rand(),
+            TEST_MINT_LAMPORTS * 2,
+            recent_blockhash,
+        ));
+        let failing_transactions = vec![transactions[0].clone(), overdraft];
+        let request =
+            create_test_request("sendBundle", Some(json!([encode(&failing_transactions)])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            code,
+            JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE
+        );
+        assert!(bundle_receiver.try_recv().is_err());
+
+        let request = create_test_request(
+            "sendBundle",
+            Some(json!([encode(&failing_transactions), {"skipPreflight": true}])),
+        );
+        let _: String = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            bundle_receiver.try_recv().unwrap().transactions,
+            failing_transactions
+        );
+    }
+
+    #[test]
+    fn test_rpc_get_bundle_statuses() {
+        let rpc = RpcHandler::start();
+        let status = BundleStatusMeta {
+            signatures: vec![Signature::new_unique(), Signature::new_unique()],
+            tip_lamports: 1_000,
+        };
+        rpc.blockstore
+            .write_bundle_status("landed", 0, &status)
+            .unwrap();
+        // landed in a slot that isn't on the fork this node processed
+        rpc.blockstore
+            .write_bundle_status("forked", 1_000, &status)
+            .unwrap();
+
+        let request = create_test_request(
+            "getBundleStatuses",
+            Some(json!([["landed", "forked", "unknown"]])),
+        );
+        let result: RpcResponse<Vec<Option<RpcBundleStatus>>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value,
+            vec![
+                Some(RpcBundleStatus {
+                    bundle_id: "landed".to_string(),
+                    slot: 0,
+                    transactions: status
+                        .signatures
+                        .iter()
+                        .map(|signature| signature.to_string())
+                        .collect(),
+                    tip_lamports: 1_000,
+                    confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+                }),
+                None,
+                None,
+            ]
+        );
+
+        let bundle_ids = vec!["bundle"; MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS + 1];
+        let request = create_test_request("getBundleStatuses", Some(json!([bundle_ids])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_rpc_send_transaction_preflight() {
         let exit = Arc::new(AtomicBool::new(false));
@@ -7375,6 +7990,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
         SendTransactionService::new::<NullTpuInfo>(
             cluster_info,
@@ -7647,6 +8263,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
         SendTransactionService::new::<NullTpuInfo>(
             cluster_info,
@@ -9279,6 +9896,7 @@ pub mod tests {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         );
 
         let mut io = MetaIoHandler::default();
@@ -12,7 +12,7 @@ use {
         rpc_cache::LargestAccountsCache,
         rpc_health::*,
     },
-    crossbeam_channel::unbounded,
+    crossbeam_channel::{unbounded, Sender},
     jsonrpc_core::{futures::prelude::*, MetaIoHandler},
     jsonrpc_http_server::{
         hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, RequestMiddleware,
@@ -23,7 +23,7 @@ use {
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         bigtable_upload::ConfirmedBlockUploadConfig,
-        bigtable_upload_service::BigTableUploadService, blockstore::Blockstore,
+        bigtable_upload_service::LedgerArchiveUploadService, blockstore::Blockstore,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_metrics::inc_new_counter_info,
@@ -36,11 +36,11 @@ use {
         snapshot_utils,
     },
     solana_sdk::{
-        exit::Exit, genesis_config::DEFAULT_GENESIS_DOWNLOAD_PATH, hash::Hash,
-        native_token::lamports_to_sol,
+        bundle::VersionedBundle, exit::Exit, genesis_config::DEFAULT_GENESIS_DOWNLOAD_PATH,
+        hash::Hash, native_token::lamports_to_sol,
     },
     solana_send_transaction_service::send_transaction_service::{self, SendTransactionService},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, LedgerArchive, LocalLedgerStorage},
     std::{
         net::SocketAddr,
         path::{Path, PathBuf},
@@ -361,6 +361,7 @@ impl JsonRpcService {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_sender: Option<Sender<VersionedBundle>>,
     ) -> Result<Self, String> {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
@@ -395,10 +396,26 @@ impl JsonRpcService {
                 .expect("Runtime"),
         );
 
-        let exit_bigtable_ledger_upload_service = Arc::new(AtomicBool::new(false));
+        let exit_ledger_archive_upload_service = Arc::new(AtomicBool::new(false));
 
-        let (bigtable_ledger_storage, _bigtable_ledger_upload_service) =
-            if let Some(RpcBigtableConfig {
+        let (ledger_archive, enable_ledger_upload) =
+            if let Some(local_archive_config) = &config.rpc_local_archive_config {
+                match LocalLedgerStorage::open(&local_archive_config.archive_path) {
+                    Ok(local_ledger_storage) => {
+                        info!("Local ledger archive initialized");
+                        let local_ledger_storage: Arc<dyn LedgerArchive> =
+                            Arc::new(local_ledger_storage);
+                        (
+                            Some(local_ledger_storage),
+                            local_archive_config.enable_local_archive_upload,
+                        )
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize local ledger archive: {:?}", err);
+                        (None, false)
+                    }
+                }
+            } else if let Some(RpcBigtableConfig {
                 enable_bigtable_ledger_upload,
                 ref bigtable_instance_name,
                 ref bigtable_app_profile_id,
@@ -420,35 +437,34 @@ impl JsonRpcService {
                     ))
                     .map(|bigtable_ledger_storage| {
                         info!("BigTable ledger storage initialized");
-
-                        let bigtable_ledger_upload_service = if enable_bigtable_ledger_upload {
-                            Some(Arc::new(BigTableUploadService::new_with_config(
-                                runtime.clone(),
-                                bigtable_ledger_storage.clone(),
-                                blockstore.clone(),
-                                block_commitment_cache.clone(),
-                                max_complete_transaction_status_slot.clone(),
-                                max_complete_rewards_slot.clone(),
-                                ConfirmedBlockUploadConfig::default(),
-                                exit_bigtable_ledger_upload_service.clone(),
-                            )))
-                        } else {
-                            None
-                        };
-
-                        (
-                            Some(bigtable_ledger_storage),
-                            bigtable_ledger_upload_service,
-                        )
+                        let bigtable_ledger_storage: Arc<dyn LedgerArchive> =
+                            Arc::new(bigtable_ledger_storage);
+                        (Some(bigtable_ledger_storage), enable_bigtable_ledger_upload)
                     })
                     .unwrap_or_else(|err| {
                         error!("Failed to initialize BigTable ledger storage: {:?}", err);
-                        (None, None)
+                        (None, false)
                     })
             } else {
-                (None, None)
+                (None, false)
             };
 
+        let _ledger_archive_upload_service = ledger_archive
+            .as_ref()
+            .filter(|_| enable_ledger_upload)
+            .map(|ledger_archive| {
+                Arc::new(LedgerArchiveUploadService::new_with_config(
+                    runtime.clone(),
+                    ledger_archive.clone(),
+                    blockstore.clone(),
+                    block_commitment_cache.clone(),
+                    max_complete_transaction_status_slot.clone(),
+                    max_complete_rewards_slot.clone(),
+                    ConfirmedBlockUploadConfig::default(),
+                    exit_ledger_archive_upload_service.clone(),
+                ))
+            });
+
         let full_api = config.full_api;
         let obsolete_v1_7_api = config.obsolete_v1_7_api;
         let max_request_body_size = config
@@ -464,7 +480,7 @@ impl JsonRpcService {
             health.clone(),
             cluster_info.clone(),
             genesis_hash,
-            bigtable_ledger_storage,
+            ledger_archive,
             optimistically_confirmed_bank,
             largest_accounts_cache,
             max_slots,
@@ -472,6 +488,7 @@ impl JsonRpcService {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             prioritization_fee_cache,
+            bundle_sender,
         );
 
         let leader_info =
@@ -546,7 +563,7 @@ impl JsonRpcService {
                 let server = server.unwrap();
                 close_handle_sender.send(Ok(server.close_handle())).unwrap();
                 server.wait();
-                exit_bigtable_ledger_upload_service.store(true, Ordering::Relaxed);
+                exit_ledger_archive_upload_service.store(true, Ordering::Relaxed);
             })
             .unwrap();
 
@@ -650,6 +667,7 @@ mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         )
         .expect("assume successful JsonRpcService start");
         let thread = rpc_service.thread_hdl.thread();
@@ -1,3 +1,4 @@
+pub mod plugin_submitter;
 pub mod runtime_plugin;
 pub mod runtime_plugin_admin_rpc_service;
 pub mod runtime_plugin_manager;
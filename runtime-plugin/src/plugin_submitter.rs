@@ -0,0 +1,357 @@
+//! Lets runtime plugins submit transactions and bundles into the validator's own TPU and
+//! BundleStage while the validator is leader, e.g. to crank on-chain programs without a round
+//! trip through RPC.
+
+use {
+    crossbeam_channel::{bounded, Receiver, Sender, TrySendError},
+    solana_gossip::cluster_info::ClusterInfo,
+    solana_runtime::bank_forks::BankForks,
+    solana_sdk::{bundle::VersionedBundle, transaction::VersionedTransaction},
+    std::{
+        fmt,
+        sync::{Arc, Mutex, RwLock},
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+pub const DEFAULT_MAX_PLUGIN_SUBMISSIONS_PER_SECOND: u64 = 100;
+/// Submissions are rejected once this many are waiting to be forwarded into the TPU.
+pub const MAX_PLUGIN_SUBMISSIONS_IN_CHANNEL: usize = 10_000;
+
+#[derive(Clone, Debug)]
+pub struct PluginSubmissionConfig {
+    /// The maximum number of transactions and bundles a single plugin may submit per second.
+    pub max_submissions_per_second: u64,
+}
+
+impl Default for PluginSubmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_submissions_per_second: DEFAULT_MAX_PLUGIN_SUBMISSIONS_PER_SECOND,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PluginSubmissionError {
+    #[error("the validator is not producing a block")]
+    NotLeader,
+
+    #[error("exceeded the limit of {0} submissions per second")]
+    RateLimited(u64),
+
+    #[error("too many submissions are waiting to be forwarded")]
+    QueueFull,
+
+    #[error("the validator stopped accepting submissions")]
+    Disconnected,
+}
+
+/// The receiving ends of the plugin submission channels, drained by the TPU.
+pub struct PluginSubmissionReceivers {
+    pub transaction_receiver: Receiver<VersionedTransaction>,
+    pub bundle_receiver: Receiver<VersionedBundle>,
+}
+
+/// Hands out a [`PluginSubmitter`] to every runtime plugin that gets loaded.
+#[derive(Clone)]
+pub struct PluginSubmissionContext {
+    config: PluginSubmissionConfig,
+    cluster_info: Arc<ClusterInfo>,
+    transaction_sender: Sender<VersionedTransaction>,
+    bundle_sender: Sender<VersionedBundle>,
+}
+
+impl PluginSubmissionContext {
+    /// Creates the submission channels for the validator whose identity is kept in `cluster_info`.
+    pub fn new(
+        config: PluginSubmissionConfig,
+        cluster_info: Arc<ClusterInfo>,
+    ) -> (Self, PluginSubmissionReceivers) {
+        let (transaction_sender, transaction_receiver) = bounded(MAX_PLUGIN_SUBMISSIONS_IN_CHANNEL);
+        let (bundle_sender, bundle_receiver) = bounded(MAX_PLUGIN_SUBMISSIONS_IN_CHANNEL);
+        (
+            Self {
+                config,
+                cluster_info,
+                transaction_sender,
+                bundle_sender,
+            },
+            PluginSubmissionReceivers {
+                transaction_receiver,
+                bundle_receiver,
+            },
+        )
+    }
+
+    /// Returns a submitter with its own rate limit, so one plugin can't starve the others.
+    pub fn new_submitter(&self, bank_forks: Arc<RwLock<BankForks>>) -> PluginSubmitter {
+        PluginSubmitter {
+            transaction_sender: self.transaction_sender.clone(),
+            bundle_sender: self.bundle_sender.clone(),
+            bank_forks,
+            cluster_info: self.cluster_info.clone(),
+            rate_limiter: Arc::new(Mutex::new(RateLimiter::new(
+                self.config.max_submissions_per_second,
+            ))),
+        }
+    }
+}
+
+/// Submits transactions and bundles on behalf of a runtime plugin.
+///
+/// Submissions are only accepted while the validator is producing a block, and count against
+/// the plugin's per-second limit. Transactions go through sigverify like any TPU transaction;
+/// bundles go to the BundleStage like bundles received from a block engine.
+#[derive(Clone)]
+pub struct PluginSubmitter {
+    transaction_sender: Sender<VersionedTransaction>,
+    bundle_sender: Sender<VersionedBundle>,
+    bank_forks: Arc<RwLock<BankForks>>,
+    cluster_info: Arc<ClusterInfo>,
+    rate_limiter: Arc<Mutex<RateLimiter>>,
+}
+
+impl fmt::Debug for PluginSubmitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginSubmitter")
+            .field("identity", &self.cluster_info.id())
+            .field("rate_limiter", &self.rate_limiter)
+            .finish()
+    }
+}
+
+impl PluginSubmitter {
+    pub fn submit_transaction(
+        &self,
+        transaction: VersionedTransaction,
+    ) -> Result<(), PluginSubmissionError> {
+        self.check_can_submit()?;
+        self.transaction_sender
+            .try_send(transaction)
+            .map_err(PluginSubmissionError::from)
+    }
+
+    pub fn submit_bundle(&self, bundle: VersionedBundle) -> Result<(), PluginSubmissionError> {
+        self.check_can_submit()?;
+        self.bundle_sender
+            .try_send(bundle)
+            .map_err(PluginSubmissionError::from)
+    }
+
+    /// Returns true if the working bank is an unfrozen bank produced by this validator. The
+    /// identity is read on every call since it can be changed at runtime through the admin rpc.
+    pub fn is_leader(&self) -> bool {
+        let working_bank = self.bank_forks.read().unwrap().working_bank();
+        *working_bank.collector_id() == self.cluster_info.id() && !working_bank.is_frozen()
+    }
+
+    fn check_can_submit(&self) -> Result<(), PluginSubmissionError> {
+        if !self.is_leader() {
+            return Err(PluginSubmissionError::NotLeader);
+        }
+        self.rate_limiter
+            .lock()
+            .unwrap()
+            .try_acquire(Instant::now())
+    }
+}
+
+impl<T> From<TrySendError<T>> for PluginSubmissionError {
+    fn from(err: TrySendError<T>) -> Self {
+        match err {
+            TrySendError::Full(_) => Self::QueueFull,
+            TrySendError::Disconnected(_) => Self::Disconnected,
+        }
+    }
+}
+
+/// Fixed one-second window rate limiter.
+#[derive(Debug)]
+struct RateLimiter {
+    max_per_second: u64,
+    window_start: Instant,
+    count: u64,
+}
+
+impl RateLimiter {
+    fn new(max_per_second: u64) -> Self {
+        Self {
+            max_per_second,
+            window_start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), PluginSubmissionError> {
+        if now.saturating_duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.count = 0;
+        }
+        if self.count >= self.max_per_second {
+            return Err(PluginSubmissionError::RateLimited(self.max_per_second));
+        }
+        self.count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_gossip::contact_info::ContactInfo,
+        solana_runtime::{
+            bank::Bank,
+            genesis_utils::{
+                bootstrap_validator_stake_lamports, create_genesis_config_with_leader,
+                GenesisConfigInfo,
+            },
+        },
+        solana_sdk::{hash::Hash, signature::Keypair, signer::Signer, system_transaction},
+        solana_streamer::socket::SocketAddrSpace,
+    };
+
+    fn test_transaction() -> VersionedTransaction {
+        let keypair = Keypair::new();
+        VersionedTransaction::from(system_transaction::transfer(
+            &keypair,
+            &keypair.pubkey(),
+            1,
+            Hash::default(),
+        ))
+    }
+
+    fn test_cluster_info(keypair: Arc<Keypair>) -> Arc<ClusterInfo> {
+        Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&keypair.pubkey(), 0),
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ))
+    }
+
+    /// Returns the bank forks of a leader bank produced by `leader`.
+    fn test_bank_forks(leader: &Keypair) -> (Arc<Bank>, Arc<RwLock<BankForks>>) {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config_with_leader(
+            1_000_000,
+            &leader.pubkey(),
+            bootstrap_validator_stake_lamports(),
+        );
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        assert_eq!(bank.collector_id(), &leader.pubkey());
+        (bank, bank_forks)
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut rate_limiter = RateLimiter::new(2);
+        let start = rate_limiter.window_start;
+        assert_eq!(rate_limiter.try_acquire(start), Ok(()));
+        assert_eq!(rate_limiter.try_acquire(start), Ok(()));
+        assert_eq!(
+            rate_limiter.try_acquire(start + Duration::from_millis(999)),
+            Err(PluginSubmissionError::RateLimited(2))
+        );
+        assert_eq!(
+            rate_limiter.try_acquire(start + Duration::from_secs(1)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_submit_only_while_leader() {
+        let leader = Arc::new(Keypair::new());
+        let (bank, bank_forks) = test_bank_forks(&leader);
+
+        let cluster_info = test_cluster_info(Arc::new(Keypair::new()));
+        let (context, receivers) =
+            PluginSubmissionContext::new(PluginSubmissionConfig::default(), cluster_info.clone());
+        let submitter = context.new_submitter(bank_forks);
+        assert_eq!(
+            submitter.submit_transaction(test_transaction()),
+            Err(PluginSubmissionError::NotLeader)
+        );
+
+        // the identity is picked up as soon as it's changed
+        cluster_info.set_keypair(leader);
+        let transaction = test_transaction();
+        assert_eq!(submitter.submit_transaction(transaction.clone()), Ok(()));
+        assert_eq!(
+            receivers.transaction_receiver.try_recv().unwrap(),
+            transaction
+        );
+
+        bank.freeze();
+        assert_eq!(
+            submitter.submit_bundle(VersionedBundle {
+                transactions: vec![test_transaction()],
+            }),
+            Err(PluginSubmissionError::NotLeader)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_is_per_submitter() {
+        let leader = Arc::new(Keypair::new());
+        let (_bank, bank_forks) = test_bank_forks(&leader);
+        let (context, receivers) = PluginSubmissionContext::new(
+            PluginSubmissionConfig {
+                max_submissions_per_second: 1,
+            },
+            test_cluster_info(leader),
+        );
+
+        let submitter = context.new_submitter(bank_forks.clone());
+        assert_eq!(submitter.submit_transaction(test_transaction()), Ok(()));
+        assert_eq!(
+            submitter.submit_transaction(test_transaction()),
+            Err(PluginSubmissionError::RateLimited(1))
+        );
+        // Clones share the limit of the plugin they were handed to.
+        assert_eq!(
+            submitter.clone().submit_transaction(test_transaction()),
+            Err(PluginSubmissionError::RateLimited(1))
+        );
+
+        let other_submitter = context.new_submitter(bank_forks);
+        assert_eq!(
+            other_submitter.submit_transaction(test_transaction()),
+            Ok(())
+        );
+        assert_eq!(receivers.transaction_receiver.len(), 2);
+    }
+
+    #[test]
+    fn test_submit_queue_full() {
+        let leader = Arc::new(Keypair::new());
+        let (_bank, bank_forks) = test_bank_forks(&leader);
+        let (context, receivers) = PluginSubmissionContext::new(
+            PluginSubmissionConfig {
+                max_submissions_per_second: u64::MAX,
+            },
+            test_cluster_info(leader),
+        );
+        let submitter = context.new_submitter(bank_forks);
+
+        let transaction = test_transaction();
+        for _ in 0..MAX_PLUGIN_SUBMISSIONS_IN_CHANNEL {
+            assert_eq!(submitter.submit_transaction(transaction.clone()), Ok(()));
+        }
+        assert_eq!(
+            submitter.submit_transaction(transaction.clone()),
+            Err(PluginSubmissionError::QueueFull)
+        );
+
+        receivers.transaction_receiver.try_recv().unwrap();
+        assert_eq!(submitter.submit_transaction(transaction), Ok(()));
+
+        drop(receivers);
+        assert_eq!(
+            submitter.submit_bundle(VersionedBundle {
+                transactions: vec![test_transaction()],
+            }),
+            Err(PluginSubmissionError::Disconnected)
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use {
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    crate::plugin_submitter::PluginSubmitter,
+    solana_runtime::{bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache},
     std::{
         any::Any,
         error,
@@ -32,10 +33,22 @@ pub struct PluginDependencies {
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     pub exit: Arc<AtomicBool>,
+    /// Submits transactions and bundles while the validator is leader; `None` unless the
+    /// validator enabled plugin submissions.
+    pub submitter: Option<PluginSubmitter>,
 }
 
 pub trait RuntimePlugin: Any + Debug + Send + Sync {
     fn name(&self) -> &'static str;
     fn on_load(&mut self, config_file: &str, dependencies: PluginDependencies) -> Result<()>;
     fn on_unload(&mut self);
+
+    /// Called after `bank` is frozen.
+    fn notify_bank_frozen(&self, _bank: &Arc<Bank>) {}
+
+    /// Called after `bank` becomes the new root.
+    fn notify_bank_rooted(&self, _bank: &Arc<Bank>) {}
+
+    /// Called when the validator starts producing a block in one of its leader slots.
+    fn notify_new_leader_slot(&self, _bank: &Arc<Bank>) {}
 }
@@ -1,5 +1,9 @@
 use {
-    crate::runtime_plugin::{PluginDependencies, RuntimePlugin},
+    crate::{
+        plugin_submitter::PluginSubmissionContext,
+        runtime_plugin::{PluginDependencies, RuntimePlugin},
+        runtime_plugin_service::BankLifecycleEvent,
+    },
     jsonrpc_core::{serde_json, ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
@@ -45,6 +49,7 @@ pub struct RuntimePluginManager {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     exit: Arc<AtomicBool>,
+    submission_context: Option<PluginSubmissionContext>,
 }
 
 impl RuntimePluginManager {
@@ -52,6 +57,7 @@ impl RuntimePluginManager {
         bank_forks: Arc<RwLock<BankForks>>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         exit: Arc<AtomicBool>,
+        submission_context: Option<PluginSubmissionContext>,
     ) -> Self {
         Self {
             plugins: vec![],
@@ -59,6 +65,7 @@ impl RuntimePluginManager {
             bank_forks,
             block_commitment_cache,
             exit,
+            submission_context,
         }
     }
 
@@ -92,14 +99,7 @@ impl RuntimePluginManager {
         }
 
         new_plugin
-            .on_load(
-                config_file,
-                PluginDependencies {
-                    bank_forks: self.bank_forks.clone(),
-                    block_commitment_cache: self.block_commitment_cache.clone(),
-                    exit: self.exit.clone(),
-                },
-            )
+            .on_load(config_file, self.plugin_dependencies())
             .map_err(|on_load_err| jsonrpc_core::Error {
                 code: ErrorCode::InvalidRequest,
                 message: format!(
@@ -172,14 +172,7 @@ impl RuntimePluginManager {
             })?;
 
         // Attempt to on_load with new plugin
-        match new_plugin.on_load(
-            new_parsed_config_file,
-            PluginDependencies {
-                bank_forks: self.bank_forks.clone(),
-                block_commitment_cache: self.block_commitment_cache.clone(),
-                exit: self.exit.clone(),
-            },
-        ) {
+        match new_plugin.on_load(new_parsed_config_file, self.plugin_dependencies()) {
             // On success, push plugin and library
             Ok(()) => {
                 self.plugins.push(new_plugin);
@@ -201,6 +194,29 @@ impl RuntimePluginManager {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
     }
 
+    pub(crate) fn notify_bank_lifecycle_event(&self, event: &BankLifecycleEvent) {
+        for plugin in &self.plugins {
+            match event {
+                BankLifecycleEvent::Frozen(bank) => plugin.notify_bank_frozen(bank),
+                BankLifecycleEvent::Rooted(bank) => plugin.notify_bank_rooted(bank),
+                BankLifecycleEvent::NewLeaderSlot(bank) => plugin.notify_new_leader_slot(bank),
+            }
+        }
+    }
+
+    /// Every plugin gets its own submitter, and with it its own submission rate limit.
+    fn plugin_dependencies(&self) -> PluginDependencies {
+        PluginDependencies {
+            bank_forks: self.bank_forks.clone(),
+            block_commitment_cache: self.block_commitment_cache.clone(),
+            exit: self.exit.clone(),
+            submitter: self
+                .submission_context
+                .as_ref()
+                .map(|context| context.new_submitter(self.bank_forks.clone())),
+        }
+    }
+
     fn try_drop_plugin(&mut self, idx: usize) {
         if idx < self.plugins.len() {
             let mut plugin = self.plugins.remove(idx);
@@ -273,3 +289,117 @@ fn load_plugin_from_config(
 
     Ok((plugin, lib, config_file))
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::plugin_submitter::PluginSubmissionConfig,
+        solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+        solana_runtime::{
+            bank::Bank,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        },
+        solana_sdk::{
+            clock::Slot,
+            pubkey::Pubkey,
+            signature::{Keypair, Signer},
+        },
+        solana_streamer::socket::SocketAddrSpace,
+        std::sync::Mutex,
+    };
+
+    #[derive(Debug, Default)]
+    struct TestPlugin {
+        events: Arc<Mutex<Vec<(&'static str, Slot)>>>,
+    }
+
+    impl RuntimePlugin for TestPlugin {
+        fn name(&self) -> &'static str {
+            "test_plugin"
+        }
+
+        fn on_load(
+            &mut self,
+            _config_file: &str,
+            _dependencies: PluginDependencies,
+        ) -> crate::runtime_plugin::Result<()> {
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {}
+
+        fn notify_bank_frozen(&self, bank: &Arc<Bank>) {
+            self.events.lock().unwrap().push(("frozen", bank.slot()));
+        }
+
+        fn notify_bank_rooted(&self, bank: &Arc<Bank>) {
+            self.events.lock().unwrap().push(("rooted", bank.slot()));
+        }
+
+        fn notify_new_leader_slot(&self, bank: &Arc<Bank>) {
+            self.events.lock().unwrap().push(("leader", bank.slot()));
+        }
+    }
+
+    #[test]
+    fn test_notify_bank_lifecycle_event() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000);
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let mut plugin_manager = RuntimePluginManager::new(
+            bank_forks,
+            Arc::<RwLock<BlockCommitmentCache>>::default(),
+            Arc::<AtomicBool>::default(),
+            None,
+        );
+        let events = Arc::<Mutex<Vec<_>>>::default();
+        plugin_manager.plugins.push(Box::new(TestPlugin {
+            events: events.clone(),
+        }));
+
+        let child = Arc::new(Bank::new_from_parent(
+            bank.clone(),
+            &Pubkey::new_unique(),
+            1,
+        ));
+        plugin_manager
+            .notify_bank_lifecycle_event(&BankLifecycleEvent::NewLeaderSlot(child.clone()));
+        plugin_manager.notify_bank_lifecycle_event(&BankLifecycleEvent::Frozen(child.clone()));
+        plugin_manager.notify_bank_lifecycle_event(&BankLifecycleEvent::Rooted(child));
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![("leader", 1), ("frozen", 1), ("rooted", 1)]
+        );
+    }
+
+    #[test]
+    fn test_plugin_dependencies_submitter() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000);
+        let (_bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+
+        let plugin_manager = RuntimePluginManager::new(
+            bank_forks.clone(),
+            Arc::<RwLock<BlockCommitmentCache>>::default(),
+            Arc::<AtomicBool>::default(),
+            None,
+        );
+        assert!(plugin_manager.plugin_dependencies().submitter.is_none());
+
+        let keypair = Arc::new(Keypair::new());
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&keypair.pubkey(), 0),
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ));
+        let (submission_context, _receivers) =
+            PluginSubmissionContext::new(PluginSubmissionConfig::default(), cluster_info);
+        let plugin_manager = RuntimePluginManager::new(
+            bank_forks,
+            Arc::<RwLock<BlockCommitmentCache>>::default(),
+            Arc::<AtomicBool>::default(),
+            Some(submission_context),
+        );
+        assert!(plugin_manager.plugin_dependencies().submitter.is_some());
+    }
+}
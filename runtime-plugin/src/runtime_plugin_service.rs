@@ -1,12 +1,12 @@
 use {
     crate::{
-        runtime_plugin::RuntimePluginError,
+        plugin_submitter::PluginSubmissionContext, runtime_plugin::RuntimePluginError,
         runtime_plugin_admin_rpc_service::RuntimePluginManagerRpcRequest,
         runtime_plugin_manager::RuntimePluginManager,
     },
-    crossbeam_channel::Receiver,
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
     log::{error, info},
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_runtime::{bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache},
     std::{
         path::PathBuf,
         sync::{
@@ -18,21 +18,41 @@ use {
     },
 };
 
+/// Bank lifecycle events delivered to every loaded runtime plugin.
+pub enum BankLifecycleEvent {
+    /// The bank was frozen.
+    Frozen(Arc<Bank>),
+    /// The bank became the new root.
+    Rooted(Arc<Bank>),
+    /// The validator created the bank to produce a block in one of its leader slots.
+    NewLeaderSlot(Arc<Bank>),
+}
+
+pub type BankLifecycleEventSender = Sender<BankLifecycleEvent>;
+pub type BankLifecycleEventReceiver = Receiver<BankLifecycleEvent>;
+
 pub struct RuntimePluginService {
     plugin_manager: Arc<RwLock<RuntimePluginManager>>,
     rpc_thread: JoinHandle<()>,
+    bank_lifecycle_event_thread: JoinHandle<()>,
 }
 
 impl RuntimePluginService {
     pub fn start(
         plugin_config_files: &[PathBuf],
         rpc_receiver: Receiver<RuntimePluginManagerRpcRequest>,
+        bank_lifecycle_event_receiver: BankLifecycleEventReceiver,
         bank_forks: Arc<RwLock<BankForks>>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         exit: Arc<AtomicBool>,
+        submission_context: Option<PluginSubmissionContext>,
     ) -> Result<Self, RuntimePluginError> {
-        let mut plugin_manager =
-            RuntimePluginManager::new(bank_forks, block_commitment_cache, exit.clone());
+        let mut plugin_manager = RuntimePluginManager::new(
+            bank_forks,
+            block_commitment_cache,
+            exit.clone(),
+            submission_context,
+        );
 
         for config in plugin_config_files {
             let name = plugin_manager
@@ -43,11 +63,17 @@ impl RuntimePluginService {
 
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
         let rpc_thread =
-            Self::start_rpc_request_handler(rpc_receiver, plugin_manager.clone(), exit);
+            Self::start_rpc_request_handler(rpc_receiver, plugin_manager.clone(), exit.clone());
+        let bank_lifecycle_event_thread = Self::start_bank_lifecycle_event_handler(
+            bank_lifecycle_event_receiver,
+            plugin_manager.clone(),
+            exit,
+        );
 
         Ok(Self {
             plugin_manager,
             rpc_thread,
+            bank_lifecycle_event_thread,
         })
     }
 
@@ -55,9 +81,35 @@ impl RuntimePluginService {
         if let Err(e) = self.rpc_thread.join() {
             error!("error joining rpc thread: {e:?}");
         }
+        if let Err(e) = self.bank_lifecycle_event_thread.join() {
+            error!("error joining bank lifecycle event thread: {e:?}");
+        }
         self.plugin_manager.write().unwrap().unload_all_plugins();
     }
 
+    fn start_bank_lifecycle_event_handler(
+        bank_lifecycle_event_receiver: BankLifecycleEventReceiver,
+        plugin_manager: Arc<RwLock<RuntimePluginManager>>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("solRuntimePluginEvt".to_string())
+            .spawn(move || {
+                const TIMEOUT: Duration = Duration::from_secs(1);
+                while !exit.load(Ordering::Relaxed) {
+                    match bank_lifecycle_event_receiver.recv_timeout(TIMEOUT) {
+                        Ok(event) => plugin_manager
+                            .read()
+                            .unwrap()
+                            .notify_bank_lifecycle_event(&event),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            })
+            .unwrap()
+    }
+
     fn start_rpc_request_handler(
         rpc_receiver: Receiver<RuntimePluginManagerRpcRequest>,
         plugin_manager: Arc<RwLock<RuntimePluginManager>>,
@@ -45,8 +45,12 @@ extern crate serde_derive;
 mod access_token;
 mod bigtable;
 mod compression;
+mod ledger_archive;
+mod local_storage;
 mod root_ca_certificate;
 
+pub use {ledger_archive::LedgerArchive, local_storage::LocalLedgerStorage};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -66,6 +70,12 @@ pub enum Error {
 
     #[error("tokio error")]
     TokioJoinError(JoinError),
+
+    #[error("Local storage: {0}")]
+    LocalStorageError(rocksdb::Error),
+
+    #[error("Object is corrupt: {0}")]
+    ObjectCorrupt(String),
 }
 
 impl std::convert::From<bigtable::Error> for Error {
@@ -80,6 +90,12 @@ impl std::convert::From<std::io::Error> for Error {
     }
 }
 
+impl std::convert::From<rocksdb::Error> for Error {
+    fn from(err: rocksdb::Error) -> Self {
+        Self::LocalStorageError(err)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 // Convert a slot to its bucket representation whereby lower slots are always lexically ordered
@@ -379,6 +395,167 @@ impl From<LegacyTransactionByAddrInfo> for TransactionByAddrInfo {
     }
 }
 
+// The cells written for a confirmed block, keyed by table. Every `LedgerArchive` backend stores
+// the same rows.
+struct ConfirmedBlockCells {
+    tx_cells: Vec<(RowKey, TransactionInfo)>,
+    tx_by_addr_cells: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    entry_cell: Option<(RowKey, entries::Entries)>, // None if the block has no entries
+    block_cell: (RowKey, generated::ConfirmedBlock),
+    num_transactions: usize,
+    num_entries: usize,
+}
+
+impl ConfirmedBlockCells {
+    fn new(slot: Slot, confirmed_block: VersionedConfirmedBlockWithEntries) -> Self {
+        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let VersionedConfirmedBlockWithEntries {
+            block: confirmed_block,
+            entries,
+        } = confirmed_block;
+
+        let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
+        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
+            let err = meta.status.clone().err();
+            let index = index as u32;
+            let signature = transaction.signatures[0];
+            let memo = extract_and_fmt_memos(transaction_with_meta);
+
+            for address in transaction_with_meta.account_keys().iter() {
+                if !is_sysvar_id(address) {
+                    by_addr
+                        .entry(address)
+                        .or_default()
+                        .push(TransactionByAddrInfo {
+                            signature,
+                            err: err.clone(),
+                            index,
+                            memo: memo.clone(),
+                            block_time: confirmed_block.block_time,
+                        });
+                }
+            }
+
+            tx_cells.push((
+                signature.to_string(),
+                TransactionInfo {
+                    slot,
+                    index,
+                    err,
+                    memo,
+                },
+            ));
+        }
+
+        let tx_by_addr_cells: Vec<_> = by_addr
+            .into_iter()
+            .map(|(address, transaction_info_by_addr)| {
+                (
+                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
+                    tx_by_addr::TransactionByAddr {
+                        tx_by_addrs: transaction_info_by_addr
+                            .into_iter()
+                            .map(|by_addr| by_addr.into())
+                            .collect(),
+                    },
+                )
+            })
+            .collect();
+
+        let num_entries = entries.len();
+        let entry_cell = (num_entries > 0).then(|| {
+            (
+                slot_to_entries_key(slot),
+                entries::Entries {
+                    entries: entries.into_iter().enumerate().map(Into::into).collect(),
+                },
+            )
+        });
+
+        let num_transactions = confirmed_block.transactions.len();
+        Self {
+            tx_cells,
+            tx_by_addr_cells,
+            entry_cell,
+            block_cell: (slot_to_blocks_key(slot), confirmed_block.into()),
+            num_transactions,
+            num_entries,
+        }
+    }
+}
+
+// Extract the transaction at `index` from the block at `slot`, checking that it has the expected
+// signature
+fn confirmed_transaction_from_block(
+    signature: &Signature,
+    slot: Slot,
+    index: u32,
+    block: ConfirmedBlock,
+) -> Option<ConfirmedTransactionWithStatusMeta> {
+    match block.transactions.into_iter().nth(index as usize) {
+        None => {
+            // report this somewhere actionable?
+            warn!("Transaction info for {} is corrupt", signature);
+            None
+        }
+        Some(tx_with_meta) => {
+            if tx_with_meta.transaction_signature() != signature {
+                warn!(
+                    "Transaction info or confirmed block for {} is corrupt",
+                    signature
+                );
+                None
+            } else {
+                Some(ConfirmedTransactionWithStatusMeta {
+                    slot,
+                    tx_with_meta,
+                    block_time: block.block_time,
+                })
+            }
+        }
+    }
+}
+
+// Append the `tx-by-addr` records of `slot` to `infos` in descending ledger order, skipping the
+// records at or before `before` and at or after `until`, given as (slot, transaction index).
+// Returns true once `limit` records have been collected.
+fn append_signatures_for_address(
+    infos: &mut Vec<(ConfirmedTransactionStatusWithSignature, u32)>,
+    slot: Slot,
+    mut tx_by_addr_infos: Vec<TransactionByAddrInfo>,
+    (first_slot, before_transaction_index): (Slot, u32),
+    (last_slot, until_transaction_index): (Slot, u32),
+    limit: usize,
+) -> bool {
+    tx_by_addr_infos.reverse();
+    for tx_by_addr_info in tx_by_addr_infos.into_iter() {
+        // Filter out records before `before_transaction_index`
+        if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
+            continue;
+        }
+        // Filter out records after `until_transaction_index`
+        if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
+            continue;
+        }
+        infos.push((
+            ConfirmedTransactionStatusWithSignature {
+                signature: tx_by_addr_info.signature,
+                slot,
+                err: tx_by_addr_info.err,
+                memo: tx_by_addr_info.memo,
+                block_time: tx_by_addr_info.block_time,
+            },
+            tx_by_addr_info.index,
+        ));
+        // Respect limit
+        if infos.len() >= limit {
+            return true;
+        }
+    }
+    false
+}
+
 pub const DEFAULT_INSTANCE_NAME: &str = "solana-ledger";
 pub const DEFAULT_APP_PROFILE_ID: &str = "default";
 pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; // 64MB
@@ -738,28 +915,9 @@ impl LedgerStorage {
 
         // Load the block and return the transaction
         let block = self.get_confirmed_block(slot).await?;
-        match block.transactions.into_iter().nth(index as usize) {
-            None => {
-                // report this somewhere actionable?
-                warn!("Transaction info for {} is corrupt", signature);
-                Ok(None)
-            }
-            Some(tx_with_meta) => {
-                if tx_with_meta.transaction_signature() != signature {
-                    warn!(
-                        "Transaction info or confirmed block for {} is corrupt",
-                        signature
-                    );
-                    Ok(None)
-                } else {
-                    Ok(Some(ConfirmedTransactionWithStatusMeta {
-                        slot,
-                        tx_with_meta,
-                        block_time: block.block_time,
-                    }))
-                }
-            }
-        }
+        Ok(confirmed_transaction_from_block(
+            signature, slot, index, block,
+        ))
     }
 
     /// Get confirmed signatures for the provided address, in descending ledger order
@@ -847,7 +1005,7 @@ impl LedgerStorage {
             )
             .await?;
 
-        'outer: for (row_key, data) in tx_by_addr_data {
+        for (row_key, data) in tx_by_addr_data {
             let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
                 bigtable::Error::ObjectCorrupt(format!(
                     "Failed to convert key to slot: tx-by-addr/{row_key}"
@@ -859,7 +1017,7 @@ impl LedgerStorage {
                 tx_by_addr::TransactionByAddr,
             >(&data, "tx-by-addr", row_key.clone())?;
 
-            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
+            let cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
                 bigtable::CellData::Bincode(tx_by_addr) => {
                     tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
                 }
@@ -874,30 +1032,15 @@ impl LedgerStorage {
                 }
             };
 
-            cell_data.reverse();
-            for tx_by_addr_info in cell_data.into_iter() {
-                // Filter out records before `before_transaction_index`
-                if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
-                    continue;
-                }
-                // Filter out records after `until_transaction_index`
-                if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
-                    continue;
-                }
-                infos.push((
-                    ConfirmedTransactionStatusWithSignature {
-                        signature: tx_by_addr_info.signature,
-                        slot,
-                        err: tx_by_addr_info.err,
-                        memo: tx_by_addr_info.memo,
-                        block_time: tx_by_addr_info.block_time,
-                    },
-                    tx_by_addr_info.index,
-                ));
-                // Respect limit
-                if infos.len() >= limit {
-                    break 'outer;
-                }
+            if append_signatures_for_address(
+                &mut infos,
+                slot,
+                cell_data,
+                (first_slot, before_transaction_index),
+                (last_slot, until_transaction_index),
+                limit,
+            ) {
+                break;
             }
         }
         Ok(infos)
@@ -932,68 +1075,14 @@ impl LedgerStorage {
             "LedgerStorage::upload_confirmed_block_with_entries request received: {:?}",
             slot
         );
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
-        let VersionedConfirmedBlockWithEntries {
-            block: confirmed_block,
-            entries,
-        } = confirmed_block;
-
-        let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
-        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
-            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
-            let err = meta.status.clone().err();
-            let index = index as u32;
-            let signature = transaction.signatures[0];
-            let memo = extract_and_fmt_memos(transaction_with_meta);
-
-            for address in transaction_with_meta.account_keys().iter() {
-                if !is_sysvar_id(address) {
-                    by_addr
-                        .entry(address)
-                        .or_default()
-                        .push(TransactionByAddrInfo {
-                            signature,
-                            err: err.clone(),
-                            index,
-                            memo: memo.clone(),
-                            block_time: confirmed_block.block_time,
-                        });
-                }
-            }
-
-            tx_cells.push((
-                signature.to_string(),
-                TransactionInfo {
-                    slot,
-                    index,
-                    err,
-                    memo,
-                },
-            ));
-        }
-
-        let tx_by_addr_cells: Vec<_> = by_addr
-            .into_iter()
-            .map(|(address, transaction_info_by_addr)| {
-                (
-                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
-                    tx_by_addr::TransactionByAddr {
-                        tx_by_addrs: transaction_info_by_addr
-                            .into_iter()
-                            .map(|by_addr| by_addr.into())
-                            .collect(),
-                    },
-                )
-            })
-            .collect();
-
-        let num_entries = entries.len();
-        let entry_cell = (
-            slot_to_entries_key(slot),
-            entries::Entries {
-                entries: entries.into_iter().enumerate().map(Into::into).collect(),
-            },
-        );
+        let ConfirmedBlockCells {
+            tx_cells,
+            tx_by_addr_cells,
+            entry_cell,
+            block_cell,
+            num_transactions,
+            num_entries,
+        } = ConfirmedBlockCells::new(slot, confirmed_block);
 
         let mut tasks = vec![];
 
@@ -1016,7 +1105,7 @@ impl LedgerStorage {
             }));
         }
 
-        if num_entries > 0 {
+        if let Some(entry_cell) = entry_cell {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
                 conn.put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
@@ -1050,12 +1139,10 @@ impl LedgerStorage {
             return Err(err);
         }
 
-        let num_transactions = confirmed_block.transactions.len();
-
         // Store the block itself last, after all other metadata about the block has been
         // successfully stored.  This avoids partial uploaded blocks from becoming visible to
         // `get_confirmed_block()` and `get_confirmed_blocks()`
-        let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
+        let blocks_cells = [block_cell];
         bytes_written += self
             .connection
             .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
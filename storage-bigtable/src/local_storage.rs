@@ -0,0 +1,620 @@
+//! A [`LedgerArchive`] kept in a local RocksDB database, for operators who can't use Bigtable.
+//!
+//! The database has one column family per Bigtable table, with the same row keys and the same
+//! compressed protobuf and bincode cells, so that data can be moved between the two backends.
+//!
+//! RocksDB calls block, so they are run on tokio's blocking thread pool rather than on the
+//! runtime serving RPC requests and uploads.
+use {
+    crate::{
+        append_signatures_for_address,
+        compression::{compress_best, decompress},
+        confirmed_transaction_from_block, key_to_slot, slot_to_blocks_key, slot_to_entries_key,
+        slot_to_tx_by_addr_key, ConfirmedBlockCells, Error, LedgerArchive, Result, TransactionInfo,
+    },
+    async_trait::async_trait,
+    log::*,
+    prost::Message,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    solana_storage_proto::convert::{entries, generated, tx_by_addr},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionByAddrInfo, TransactionStatus,
+        VersionedConfirmedBlockWithEntries,
+    },
+    std::{convert::TryInto, path::Path, sync::Arc},
+};
+
+const BLOCKS_CF: &str = "blocks";
+const ENTRIES_CF: &str = "entries";
+const TX_CF: &str = "tx";
+const TX_BY_ADDR_CF: &str = "tx-by-addr";
+
+#[derive(Clone)]
+pub struct LocalLedgerStorage {
+    db: Arc<DB>,
+}
+
+impl LocalLedgerStorage {
+    /// Open the archive at `path`, creating it if it doesn't exist
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let db = DB::open_cf(
+            &options,
+            path,
+            [BLOCKS_CF, ENTRIES_CF, TX_CF, TX_BY_ADDR_CF],
+        )?;
+        info!("Opened local ledger storage at {}", path.display());
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Fetches the entries of a block
+    pub async fn get_entries(&self, slot: Slot) -> Result<impl Iterator<Item = EntrySummary>> {
+        trace!(
+            "LocalLedgerStorage::get_entries request received: {:?}",
+            slot
+        );
+        let entries = self
+            .run_blocking(move |storage| {
+                storage
+                    .get_protobuf_cell::<entries::Entries>(ENTRIES_CF, &slot_to_entries_key(slot))?
+                    .ok_or(Error::BlockNotFound(slot))
+            })
+            .await?;
+        Ok(entries.entries.into_iter().map(Into::into))
+    }
+
+    fn cf(&self, table: &str) -> &ColumnFamily {
+        self.db
+            .cf_handle(table)
+            .expect("column families are created on open")
+    }
+
+    fn get_protobuf_cell<T>(&self, table: &str, key: &str) -> Result<Option<T>>
+    where
+        T: Message + Default,
+    {
+        self.db
+            .get_cf(self.cf(table), key)?
+            .map(|value| {
+                let data = decompress(&value)?;
+                T::decode(&data[..]).map_err(|err| {
+                    warn!("Failed to deserialize {}/{}: {}", table, key, err);
+                    Error::ObjectCorrupt(format!("{table}/{key}"))
+                })
+            })
+            .transpose()
+    }
+
+    /// Run `f` on the blocking thread pool
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || f(&storage))
+            .await
+            .map_err(Error::TokioJoinError)?
+    }
+
+    fn read_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        let key = slot_to_blocks_key(slot);
+        let block = self
+            .get_protobuf_cell::<generated::ConfirmedBlock>(BLOCKS_CF, &key)?
+            .ok_or(Error::BlockNotFound(slot))?;
+        block
+            .try_into()
+            .map_err(|_err| Error::ObjectCorrupt(format!("{BLOCKS_CF}/{key}")))
+    }
+
+    fn get_transaction_info(&self, signature: &Signature) -> Result<TransactionInfo> {
+        let key = signature.to_string();
+        let value = self
+            .db
+            .get_cf(self.cf(TX_CF), &key)?
+            .ok_or(Error::SignatureNotFound)?;
+        let data = decompress(&value)?;
+        bincode::deserialize(&data).map_err(|err| {
+            warn!("Failed to deserialize {}/{}: {}", TX_CF, key, err);
+            Error::ObjectCorrupt(format!("{TX_CF}/{key}"))
+        })
+    }
+
+    fn read_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<Signature>,
+        until_signature: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let address_prefix = format!("{address}/");
+
+        let (first_slot, before_transaction_index) = match before_signature {
+            None => (Slot::MAX, 0),
+            Some(before_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(&before_signature)?;
+                (slot, index)
+            }
+        };
+        let (last_slot, until_transaction_index) = match until_signature {
+            None => (0, u32::MAX),
+            Some(until_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(&until_signature)?;
+                (slot, index)
+            }
+        };
+
+        let mut infos = vec![];
+        if limit == 0 {
+            return Ok(infos);
+        }
+
+        let start_key = format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot));
+        let end_key = format!("{}{}", address_prefix, slot_to_tx_by_addr_key(last_slot));
+        for item in self.db.iterator_cf(
+            self.cf(TX_BY_ADDR_CF),
+            IteratorMode::From(start_key.as_bytes(), Direction::Forward),
+        ) {
+            let (key, value) = item?;
+            if &*key > end_key.as_bytes() {
+                break;
+            }
+            let row_key = String::from_utf8_lossy(&key);
+            let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
+                Error::ObjectCorrupt(format!(
+                    "Failed to convert key to slot: {TX_BY_ADDR_CF}/{row_key}"
+                ))
+            })?;
+
+            let data = decompress(&value)?;
+            let tx_by_addr = tx_by_addr::TransactionByAddr::decode(&data[..]).map_err(|err| {
+                warn!(
+                    "Failed to deserialize {}/{}: {}",
+                    TX_BY_ADDR_CF, row_key, err
+                );
+                Error::ObjectCorrupt(format!("{TX_BY_ADDR_CF}/{row_key}"))
+            })?;
+            let cell_data: Vec<TransactionByAddrInfo> = tx_by_addr.try_into().map_err(|error| {
+                Error::ObjectCorrupt(format!(
+                    "Failed to deserialize: {error}: {TX_BY_ADDR_CF}/{row_key}"
+                ))
+            })?;
+
+            if append_signatures_for_address(
+                &mut infos,
+                slot,
+                cell_data,
+                (first_slot, before_transaction_index),
+                (last_slot, until_transaction_index),
+                limit,
+            ) {
+                break;
+            }
+        }
+        Ok(infos)
+    }
+
+    fn write_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        let ConfirmedBlockCells {
+            tx_cells,
+            tx_by_addr_cells,
+            entry_cell,
+            block_cell,
+            num_transactions,
+            num_entries,
+        } = ConfirmedBlockCells::new(slot, confirmed_block);
+
+        // All rows of the block are written in a single batch, so partially uploaded blocks are
+        // never visible to readers
+        let mut batch = WriteBatch::default();
+        for (key, transaction_info) in &tx_cells {
+            batch.put_cf(self.cf(TX_CF), key, encode_bincode_cell(transaction_info)?);
+        }
+        for (key, tx_by_addr) in &tx_by_addr_cells {
+            batch.put_cf(
+                self.cf(TX_BY_ADDR_CF),
+                key,
+                encode_protobuf_cell(tx_by_addr)?,
+            );
+        }
+        if let Some((key, entries)) = &entry_cell {
+            batch.put_cf(self.cf(ENTRIES_CF), key, encode_protobuf_cell(entries)?);
+        }
+        let (key, block) = &block_cell;
+        batch.put_cf(self.cf(BLOCKS_CF), key, encode_protobuf_cell(block)?);
+
+        let bytes_written = batch.size_in_bytes();
+        self.db.write(batch)?;
+        datapoint_info!(
+            "storage-local-upload-block",
+            ("slot", slot, i64),
+            ("transactions", num_transactions, i64),
+            ("entries", num_entries, i64),
+            ("bytes", bytes_written, i64),
+        );
+        Ok(())
+    }
+}
+
+fn encode_protobuf_cell<T: Message>(data: &T) -> Result<Vec<u8>> {
+    Ok(compress_best(&data.encode_to_vec())?)
+}
+
+fn encode_bincode_cell<T: serde::ser::Serialize>(data: &T) -> Result<Vec<u8>> {
+    Ok(compress_best(&bincode::serialize(data).unwrap())?)
+}
+
+#[async_trait]
+impl LedgerArchive for LocalLedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        trace!("LocalLedgerStorage::get_first_available_block request received");
+        self.run_blocking(|storage| {
+            match storage
+                .db
+                .iterator_cf(storage.cf(BLOCKS_CF), IteratorMode::Start)
+                .next()
+            {
+                Some(item) => {
+                    let (key, _) = item?;
+                    Ok(key_to_slot(&String::from_utf8_lossy(&key)))
+                }
+                None => Ok(None),
+            }
+        })
+        .await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        trace!(
+            "LocalLedgerStorage::get_confirmed_blocks request received: {:?} {:?}",
+            start_slot,
+            limit
+        );
+        self.run_blocking(move |storage| {
+            let start_key = slot_to_blocks_key(start_slot);
+            let mut slots = Vec::with_capacity(limit.min(1_000));
+            for item in storage.db.iterator_cf(
+                storage.cf(BLOCKS_CF),
+                IteratorMode::From(start_key.as_bytes(), Direction::Forward),
+            ) {
+                if slots.len() >= limit {
+                    break;
+                }
+                let (key, _) = item?;
+                slots.extend(key_to_slot(&String::from_utf8_lossy(&key)));
+            }
+            Ok(slots)
+        })
+        .await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        trace!(
+            "LocalLedgerStorage::get_confirmed_block request received: {:?}",
+            slot
+        );
+        self.run_blocking(move |storage| storage.read_confirmed_block(slot))
+            .await
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        trace!(
+            "LocalLedgerStorage::confirmed_block_exists request received: {:?}",
+            slot
+        );
+        self.run_blocking(move |storage| {
+            Ok(storage
+                .db
+                .get_pinned_cf(storage.cf(BLOCKS_CF), slot_to_blocks_key(slot))?
+                .is_some())
+        })
+        .await
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        trace!(
+            "LocalLedgerStorage::get_signature_status request received: {:?}",
+            signature
+        );
+        let signature = *signature;
+        self.run_blocking(move |storage| Ok(storage.get_transaction_info(&signature)?.into()))
+            .await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        trace!(
+            "LocalLedgerStorage::get_confirmed_transaction request received: {:?}",
+            signature
+        );
+        let signature = *signature;
+        self.run_blocking(move |storage| {
+            let TransactionInfo { slot, index, .. } = storage.get_transaction_info(&signature)?;
+            let block = storage.read_confirmed_block(slot)?;
+            Ok(confirmed_transaction_from_block(
+                &signature, slot, index, block,
+            ))
+        })
+        .await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        trace!(
+            "LocalLedgerStorage::get_confirmed_signatures_for_address request received: {:?}",
+            address
+        );
+        let address = *address;
+        let before_signature = before_signature.copied();
+        let until_signature = until_signature.copied();
+        self.run_blocking(move |storage| {
+            storage.read_signatures_for_address(&address, before_signature, until_signature, limit)
+        })
+        .await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        trace!(
+            "LocalLedgerStorage::upload_confirmed_block_with_entries request received: {:?}",
+            slot
+        );
+        self.run_blocking(move |storage| storage.write_block(slot, confirmed_block))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, message::v0::LoadedAddresses, signature::Keypair, system_transaction,
+            transaction::VersionedTransaction, transaction_context::TransactionReturnData,
+        },
+        solana_transaction_status::{
+            TransactionStatusMeta, VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
+        },
+        std::future::Future,
+    };
+
+    // The archive runs its RocksDB calls on the blocking thread pool, which needs a runtime
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn transfer_with_meta(
+        from: &Keypair,
+        recipient: &Pubkey,
+    ) -> VersionedTransactionWithStatusMeta {
+        let transaction = system_transaction::transfer(from, recipient, 42, Hash::new_unique());
+        VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction::from(transaction),
+            meta: TransactionStatusMeta {
+                status: Ok(()),
+                fee: 1,
+                pre_balances: vec![43, 0, 1],
+                post_balances: vec![0, 42, 1],
+                inner_instructions: Some(vec![]),
+                log_messages: Some(vec![]),
+                pre_token_balances: Some(vec![]),
+                post_token_balances: Some(vec![]),
+                rewards: Some(vec![]),
+                loaded_addresses: LoadedAddresses::default(),
+                return_data: Some(TransactionReturnData::default()),
+                compute_units_consumed: Some(1234),
+            },
+        }
+    }
+
+    fn block_with_entries(
+        slot: Slot,
+        transactions: Vec<VersionedTransactionWithStatusMeta>,
+    ) -> VersionedConfirmedBlockWithEntries {
+        VersionedConfirmedBlockWithEntries {
+            block: VersionedConfirmedBlock {
+                previous_blockhash: Hash::default().to_string(),
+                blockhash: Hash::new_unique().to_string(),
+                parent_slot: slot.saturating_sub(1),
+                transactions,
+                rewards: vec![],
+                block_time: Some(1_234_567_890 + slot as i64),
+                block_height: Some(slot),
+            },
+            entries: vec![],
+        }
+    }
+
+    #[test]
+    fn test_upload_and_get_block() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalLedgerStorage::open(ledger_path.path()).unwrap();
+        assert_eq!(block_on(storage.get_first_available_block()).unwrap(), None);
+
+        let from = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let transaction = transfer_with_meta(&from, &recipient);
+        let signature = transaction.transaction.signatures[0];
+        let block = block_with_entries(5, vec![transaction]);
+        let expected_block = ConfirmedBlock::from(block.block.clone());
+        block_on(storage.upload_confirmed_block_with_entries(5, block)).unwrap();
+        block_on(storage.upload_confirmed_block_with_entries(9, block_with_entries(9, vec![])))
+            .unwrap();
+
+        assert_eq!(
+            block_on(storage.get_first_available_block()).unwrap(),
+            Some(5)
+        );
+        assert_eq!(
+            block_on(storage.get_confirmed_blocks(0, 10)).unwrap(),
+            vec![5, 9]
+        );
+        assert_eq!(
+            block_on(storage.get_confirmed_blocks(6, 1)).unwrap(),
+            vec![9]
+        );
+        assert!(block_on(storage.confirmed_block_exists(5)).unwrap());
+        assert!(!block_on(storage.confirmed_block_exists(6)).unwrap());
+        assert_eq!(
+            block_on(storage.get_confirmed_block(5)).unwrap(),
+            expected_block
+        );
+        assert!(matches!(
+            block_on(storage.get_confirmed_block(6)),
+            Err(Error::BlockNotFound(6))
+        ));
+
+        let status = block_on(storage.get_signature_status(&signature)).unwrap();
+        assert_eq!(status.slot, 5);
+        assert_eq!(status.err, None);
+        let confirmed_transaction = block_on(storage.get_confirmed_transaction(&signature))
+            .unwrap()
+            .unwrap();
+        assert_eq!(confirmed_transaction.slot, 5);
+        assert_eq!(
+            confirmed_transaction.tx_with_meta,
+            expected_block.transactions[0]
+        );
+        assert!(matches!(
+            block_on(storage.get_signature_status(&Signature::default())),
+            Err(Error::SignatureNotFound)
+        ));
+
+        // The archive can be reopened
+        drop(storage);
+        let storage = LocalLedgerStorage::open(ledger_path.path()).unwrap();
+        assert_eq!(
+            block_on(storage.get_confirmed_block(5)).unwrap(),
+            expected_block
+        );
+    }
+
+    #[test]
+    fn test_upload_and_get_entries() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalLedgerStorage::open(ledger_path.path()).unwrap();
+
+        let from = Keypair::new();
+        let transactions = (0..3)
+            .map(|_| transfer_with_meta(&from, &Pubkey::new_unique()))
+            .collect();
+        let mut block = block_with_entries(5, transactions);
+        let hashes = [Hash::new_unique(), Hash::new_unique()];
+        block.entries = vec![
+            EntrySummary {
+                num_hashes: 10,
+                hash: hashes[0],
+                num_transactions: 2,
+                starting_transaction_index: 0,
+            },
+            EntrySummary {
+                num_hashes: 12,
+                hash: hashes[1],
+                num_transactions: 1,
+                starting_transaction_index: 2,
+            },
+        ];
+        block_on(storage.upload_confirmed_block_with_entries(5, block)).unwrap();
+        block_on(storage.upload_confirmed_block_with_entries(6, block_with_entries(6, vec![])))
+            .unwrap();
+
+        let entries: Vec<_> = block_on(storage.get_entries(5))
+            .unwrap()
+            .map(|entry| {
+                (
+                    entry.num_hashes,
+                    entry.hash,
+                    entry.num_transactions,
+                    entry.starting_transaction_index,
+                )
+            })
+            .collect();
+        assert_eq!(entries, vec![(10, hashes[0], 2, 0), (12, hashes[1], 1, 2)]);
+
+        // Blocks without entries have no entries row
+        assert!(block_on(storage.confirmed_block_exists(6)).unwrap());
+        assert!(matches!(
+            block_on(storage.get_entries(6)).map(|entries| entries.count()),
+            Err(Error::BlockNotFound(6))
+        ));
+    }
+
+    #[test]
+    fn test_get_confirmed_signatures_for_address() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalLedgerStorage::open(ledger_path.path()).unwrap();
+
+        let from = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let mut signatures = vec![];
+        for slot in [3, 4, 7] {
+            let transactions: Vec<_> = (0..2)
+                .map(|_| transfer_with_meta(&from, &recipient))
+                .collect();
+            signatures.extend(
+                transactions
+                    .iter()
+                    .map(|transaction| transaction.transaction.signatures[0]),
+            );
+            block_on(
+                storage.upload_confirmed_block_with_entries(
+                    slot,
+                    block_with_entries(slot, transactions),
+                ),
+            )
+            .unwrap();
+        }
+        // Descending ledger order
+        signatures.reverse();
+
+        let get_signatures = |before: Option<&Signature>, until: Option<&Signature>, limit| {
+            block_on(storage.get_confirmed_signatures_for_address(&recipient, before, until, limit))
+                .unwrap()
+                .into_iter()
+                .map(|(status, _)| status.signature)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(get_signatures(None, None, 10), signatures);
+        assert_eq!(get_signatures(None, None, 3), signatures[..3]);
+        assert_eq!(
+            get_signatures(Some(&signatures[1]), None, 10),
+            signatures[2..]
+        );
+        assert_eq!(
+            get_signatures(Some(&signatures[0]), Some(&signatures[4]), 10),
+            signatures[1..4]
+        );
+        assert!(get_signatures(None, None, 0).is_empty());
+        assert!(block_on(storage.get_confirmed_signatures_for_address(
+            &Pubkey::new_unique(),
+            None,
+            None,
+            10
+        ))
+        .unwrap()
+        .is_empty());
+    }
+}
@@ -13,6 +13,7 @@ use {
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         consensus::tower_storage::TowerStorage,
+        tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
         validator::{Validator, ValidatorConfig, ValidatorStartProgress},
     },
     solana_geyser_plugin_manager::{
@@ -30,6 +31,7 @@ use {
     },
     solana_net_utils::PortRange,
     solana_program_runtime::compute_budget::ComputeBudget,
+    solana_program_test::programs::{jito_tip_distribution, jito_tip_payment},
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
     solana_rpc_client::{nonblocking, rpc_client::RpcClient},
     solana_runtime::{
@@ -156,7 +158,10 @@ impl Default for TestValidatorGenesis {
             ledger_path: Option::<PathBuf>::default(),
             tower_storage: Option::<Arc<dyn TowerStorage>>::default(),
             rent: Rent::default(),
-            rpc_config: JsonRpcConfig::default_for_test(),
+            rpc_config: JsonRpcConfig {
+                enable_send_bundle: true,
+                ..JsonRpcConfig::default_for_test()
+            },
             pubsub_config: PubSubConfig::default(),
             rpc_ports: Option::<(u16, u16)>::default(),
             warp_slot: Option::<Slot>::default(),
@@ -715,6 +720,9 @@ impl TestValidator {
         for (address, account) in solana_program_test::programs::spl_programs(&config.rent) {
             accounts.entry(address).or_insert(account);
         }
+        for (address, account) in solana_program_test::programs::jito_tip_accounts(&config.rent) {
+            accounts.entry(address).or_insert(account);
+        }
         #[allow(deprecated)]
         for program in &config.programs {
             let data = solana_program_test::read_file(&program.program_path);
@@ -966,6 +974,15 @@ impl TestValidator {
             accounts_db_config,
             runtime_config,
             account_indexes: config.rpc_config.account_indexes.clone(),
+            tip_manager_config: TipManagerConfig {
+                tip_payment_program_id: jito_tip_payment::id(),
+                tip_distribution_program_id: jito_tip_distribution::id(),
+                tip_distribution_account_config: TipDistributionAccountConfig {
+                    merkle_root_upload_authority: validator_identity.pubkey(),
+                    vote_account: vote_account_address,
+                    commission_bps: 0,
+                },
+            },
             ..ValidatorConfig::default_for_test()
         };
         if let Some(ref tower_storage) = config.tower_storage {
@@ -16,6 +16,7 @@ use {
         GeneratedMerkleTreeCollection,
     },
     std::{
+        fs,
         path::PathBuf,
         sync::Arc,
         time::{Duration, Instant},
@@ -53,31 +54,59 @@ struct Args {
     #[arg(long, env)]
     should_reclaim_tdas: bool,
 
-    /// The price to pay for priority fee
+    /// The minimum price to pay for priority fee, in micro-lamports per compute unit. Claims pay
+    /// the 75th percentile of recent prioritization fees when that is higher.
     #[arg(long, env, default_value_t = 1)]
     micro_lamports: u64,
+
+    /// The maximum price to pay for priority fee on claim transactions, in micro-lamports per
+    /// compute unit.
+    #[arg(long, env, default_value_t = 100_000)]
+    max_micro_lamports: u64,
+
+    /// Path to write a JSON report of claimed, already claimed and failed nodes to.
+    #[arg(long, env)]
+    report_path: Option<PathBuf>,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_mev_claim_process(
     merkle_trees: GeneratedMerkleTreeCollection,
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
     signer: Arc<Keypair>,
     max_loop_duration: Duration,
-    micro_lamports: u64,
+    min_micro_lamports: u64,
+    max_micro_lamports: u64,
+    report_path: Option<PathBuf>,
 ) -> Result<(), ClaimMevError> {
     let start = Instant::now();
 
-    match claim_mev_tips(
+    let result = claim_mev_tips(
         &merkle_trees,
         rpc_url,
         tip_distribution_program_id,
         signer,
         max_loop_duration,
-        micro_lamports,
+        min_micro_lamports,
+        max_micro_lamports,
     )
     .await
-    {
+    .and_then(|report| {
+        info!("{report}");
+        if let Some(report_path) = &report_path {
+            fs::write(report_path, serde_json::to_string_pretty(&report)?)?;
+        }
+        if report.is_finished() {
+            Ok(())
+        } else {
+            Err(ClaimMevError::ClaimsNotFinished {
+                claims_left: report.failed.len(),
+            })
+        }
+    });
+
+    match result {
         Err(e) => {
             datapoint_error!(
                 "claim_mev_workflow-claim_error",
@@ -169,6 +198,8 @@ async fn main() -> Result<(), ClaimMevError> {
         keypair.clone(),
         max_loop_duration,
         args.micro_lamports,
+        args.max_micro_lamports,
+        args.report_path,
     )));
     if args.should_reclaim_rent {
         futs.push(tokio::spawn(start_rent_claim(
@@ -0,0 +1,203 @@
+//! This binary runs the tip distribution workflows for an epoch end to end, replacing the need to
+//! glue the stake-meta-generator, merkle-root-generator, merkle-root-uploader and claim-mev-tips
+//! binaries together.
+use {
+    clap::{Parser, Subcommand},
+    gethostname::gethostname,
+    log::*,
+    solana_metrics::set_host_id,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, stake_history::Epoch},
+    solana_tip_distributor::{
+        pipeline_workflow::{run_pipeline, PipelineConfig},
+        verify_workflow::{verify_merkle_roots, VerifyFilter},
+    },
+    std::{fs, path::PathBuf, process::exit, time::Duration},
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Runs every workflow for an epoch in order, resuming from the last checkpoint if a previous
+    /// run failed part way through.
+    Run(RunArgs),
+
+    /// Recomputes stake meta and merkle trees from a snapshot and checks them against the merkle
+    /// roots uploaded on chain, printing each claimant's expected amount and proof.
+    Verify(VerifyArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// Epoch to distribute tips for.
+    #[arg(long, env)]
+    epoch: Epoch,
+
+    /// Directory the checkpoint and intermediate JSON files are written to.
+    #[arg(long, env)]
+    working_dir: PathBuf,
+
+    /// Ledger path, where you created the snapshot.
+    #[arg(long, env, value_parser = ledger_path_parser)]
+    ledger_path: PathBuf,
+
+    /// The expected snapshot slot. Defaults to the last slot in the epoch.
+    #[arg(long, env)]
+    snapshot_slot: Option<Slot>,
+
+    /// The RPC to read state from and send transactions to.
+    #[arg(long, env, default_value = "http://localhost:8899")]
+    rpc_url: String,
+
+    /// The path to the keypair used to sign and pay for upload and claim transactions.
+    #[arg(long, env)]
+    keypair_path: PathBuf,
+
+    /// The tip-distribution program id.
+    #[arg(long, env)]
+    tip_distribution_program_id: Pubkey,
+
+    /// The tip-payment program id.
+    #[arg(long, env)]
+    tip_payment_program_id: Pubkey,
+
+    /// Rate-limits the maximum number of requests per RPC connection
+    #[arg(long, env, default_value_t = 100)]
+    max_concurrent_rpc_get_reqs: usize,
+
+    /// Number of transactions to send to RPC at a time.
+    #[arg(long, env, default_value_t = 64)]
+    txn_send_batch_size: usize,
+
+    /// Limits how long the claim send loop runs before stopping
+    #[arg(long, env, default_value_t = 60 * 60)]
+    max_retry_duration_secs: u64,
+
+    /// The minimum price to pay for priority fee, in micro-lamports per compute unit.
+    #[arg(long, env, default_value_t = 1)]
+    micro_lamports: u64,
+
+    /// The maximum price to pay for priority fee on claim transactions, in micro-lamports per
+    /// compute unit.
+    #[arg(long, env, default_value_t = 100_000)]
+    max_micro_lamports: u64,
+}
+
+#[derive(clap::Args, Debug)]
+struct VerifyArgs {
+    /// Ledger path, where you created the snapshot.
+    #[arg(long, env, value_parser = ledger_path_parser)]
+    ledger_path: PathBuf,
+
+    /// The snapshot slot, typically the last slot in the epoch being audited.
+    #[arg(long, env)]
+    snapshot_slot: Slot,
+
+    /// The RPC to read the uploaded merkle roots from.
+    #[arg(long, env, default_value = "http://localhost:8899")]
+    rpc_url: String,
+
+    /// The tip-distribution program id.
+    #[arg(long, env)]
+    tip_distribution_program_id: Pubkey,
+
+    /// The tip-payment program id.
+    #[arg(long, env)]
+    tip_payment_program_id: Pubkey,
+
+    /// Only verify the tree of this validator.
+    #[arg(long, env)]
+    validator_vote_account: Option<Pubkey>,
+
+    /// Only print claims where this pubkey is the stake account, staker or withdrawer.
+    #[arg(long, env)]
+    claimant: Option<Pubkey>,
+
+    /// Don't fail verification for tip distribution accounts that don't exist or don't have a
+    /// merkle root uploaded yet, e.g. accounts that were closed after expiring.
+    #[arg(long)]
+    allow_missing: bool,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+fn ledger_path_parser(ledger_path: &str) -> Result<PathBuf, &'static str> {
+    Ok(fs::canonicalize(ledger_path).unwrap_or_else(|err| {
+        error!("Unable to access ledger path '{}': {}", ledger_path, err);
+        exit(1);
+    }))
+}
+
+fn main() {
+    env_logger::init();
+
+    gethostname()
+        .into_string()
+        .map(set_host_id)
+        .expect("set hostname");
+
+    let args: Args = Args::parse();
+    match args.command {
+        Commands::Run(args) => {
+            info!("Starting tip distribution for epoch {}...", args.epoch);
+            let config = PipelineConfig {
+                epoch: args.epoch,
+                working_dir: args.working_dir,
+                ledger_path: args.ledger_path,
+                snapshot_slot: args.snapshot_slot,
+                rpc_url: args.rpc_url,
+                keypair_path: args.keypair_path,
+                tip_distribution_program_id: args.tip_distribution_program_id,
+                tip_payment_program_id: args.tip_payment_program_id,
+                max_concurrent_rpc_get_reqs: args.max_concurrent_rpc_get_reqs,
+                txn_send_batch_size: args.txn_send_batch_size,
+                max_claim_duration: Duration::from_secs(args.max_retry_duration_secs),
+                micro_lamports: args.micro_lamports,
+                max_micro_lamports: args.max_micro_lamports,
+            };
+            let result = run_pipeline(&config);
+            solana_metrics::flush();
+            if let Err(e) = result {
+                error!(
+                    "tip distribution for epoch {} failed, rerun to resume from {:?}: {e}",
+                    config.epoch,
+                    config.checkpoint_path()
+                );
+                exit(1);
+            }
+            info!("finished tip distribution for epoch {}", config.epoch);
+        }
+        Commands::Verify(args) => {
+            let report = verify_merkle_roots(
+                &args.ledger_path,
+                &args.snapshot_slot,
+                &args.tip_distribution_program_id,
+                &args.tip_payment_program_id,
+                &args.rpc_url,
+                &VerifyFilter {
+                    validator_vote_account: args.validator_vote_account,
+                    claimant_or_authority: args.claimant,
+                },
+            )
+            .unwrap_or_else(|e| {
+                error!("failed to verify merkle roots: {e}");
+                exit(1);
+            });
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            } else {
+                print!("{report}");
+            }
+            if !report.is_verified(args.allow_missing) {
+                exit(1);
+            }
+        }
+    }
+}
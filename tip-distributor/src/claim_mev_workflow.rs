@@ -1,34 +1,57 @@
 use {
-    crate::{send_until_blockhash_expires, GeneratedMerkleTreeCollection},
+    crate::{
+        get_batched_signatures_statuses, GeneratedMerkleTree, GeneratedMerkleTreeCollection,
+        TreeNode,
+    },
     anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas},
+    futures::stream::{self, StreamExt},
     itertools::Itertools,
     jito_tip_distribution::state::{ClaimStatus, Config, TipDistributionAccount},
-    log::{error, info, warn},
+    log::{info, warn},
     rand::{prelude::SliceRandom, thread_rng},
-    solana_client::nonblocking::rpc_client::RpcClient,
+    serde::Serialize,
+    solana_client::{nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction},
     solana_metrics::datapoint_info,
     solana_program::{
         fee_calculator::DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE, native_token::LAMPORTS_PER_SOL,
         system_program,
     },
-    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
+    solana_program_runtime::compute_budget_processor::MAX_COMPUTE_UNIT_LIMIT,
+    solana_rpc_client_api::config::RpcSendTransactionConfig,
     solana_sdk::{
         account::Account,
-        commitment_config::CommitmentConfig,
+        commitment_config::{CommitmentConfig, CommitmentLevel},
         compute_budget::ComputeBudgetInstruction,
         instruction::Instruction,
+        packet::PACKET_DATA_SIZE,
         pubkey::Pubkey,
-        signature::{Keypair, Signer},
-        transaction::Transaction,
+        signature::{Keypair, Signature, Signer},
+        stake_history::Epoch,
+        transaction::{Transaction, TransactionError, MAX_TX_ACCOUNT_LOCKS},
     },
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
+        fmt,
         sync::Arc,
         time::{Duration, Instant},
     },
     thiserror::Error,
+    tokio::time::sleep,
 };
 
+/// Compute units budgeted for each claim instruction in a transaction
+const CLAIM_COMPUTE_UNITS: u32 = 40_000;
+
+/// Percentile of recent prioritization fees paid by claim transactions
+const PRIORITY_FEE_PERCENTILE: usize = 75;
+
+/// Maximum number of claims sent per blockhash before re-reading claim state
+const MAX_CLAIMS_PER_ROUND: usize = 10_000;
+
+const MAX_CONCURRENT_SENDS: usize = 64;
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Error, Debug)]
 pub enum ClaimMevError {
     #[error(transparent)]
@@ -54,17 +77,169 @@ pub enum ClaimMevError {
     #[error("Not finished with job, transactions left {transactions_left}")]
     NotFinished { transactions_left: usize },
 
+    #[error("Not finished claiming, claims left {claims_left}")]
+    ClaimsNotFinished { claims_left: usize },
+
     #[error("UncaughtError {e:?}")]
     UncaughtError { e: String },
 }
 
-pub async fn get_claim_transactions_for_valid_unclaimed(
+/// A node of a merkle tree as it appears in the [ClaimMevReport].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClaimReportNode {
+    #[serde(with = "crate::pubkey_string_conversion")]
+    pub tip_distribution_account: Pubkey,
+    #[serde(with = "crate::pubkey_string_conversion")]
+    pub claimant: Pubkey,
+    #[serde(with = "crate::pubkey_string_conversion")]
+    pub claim_status: Pubkey,
+    pub amount: u64,
+    /// The last error seen while claiming this node, only set for failed claims.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ClaimReportNode {
+    fn new(tree: &GeneratedMerkleTree, node: &TreeNode) -> Self {
+        Self {
+            tip_distribution_account: tree.tip_distribution_account,
+            claimant: node.claimant,
+            claim_status: node.claim_status_pubkey,
+            amount: node.amount,
+            error: None,
+        }
+    }
+}
+
+/// Summary of a [claim_mev_tips] run.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ClaimMevReport {
+    pub epoch: Epoch,
+    /// Nodes claimed during this run.
+    pub claimed: Vec<ClaimReportNode>,
+    /// Nodes that had already been claimed before this run started.
+    pub already_claimed: Vec<ClaimReportNode>,
+    /// Nodes that are still unclaimed.
+    pub failed: Vec<ClaimReportNode>,
+    pub num_transactions_landed: u64,
+    pub num_transactions_failed: u64,
+    /// The priority fee paid by the most recent round of claim transactions.
+    pub last_micro_lamports: u64,
+}
+
+impl ClaimMevReport {
+    pub fn is_finished(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn claimed_lamports(&self) -> u64 {
+        self.claimed.iter().map(|node| node.amount).sum()
+    }
+}
+
+impl fmt::Display for ClaimMevReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "epoch {}: {} claimed ({} lamports), {} already claimed, {} failed",
+            self.epoch,
+            self.claimed.len(),
+            self.claimed_lamports(),
+            self.already_claimed.len(),
+            self.failed.len(),
+        )?;
+        writeln!(
+            f,
+            "  transactions landed: {}, failed: {}, last priority fee: {} micro-lamports/CU",
+            self.num_transactions_landed, self.num_transactions_failed, self.last_micro_lamports
+        )?;
+        for node in &self.failed {
+            writeln!(
+                f,
+                "  failed: tda: {} claimant: {} amount: {} error: {}",
+                node.tip_distribution_account,
+                node.claimant,
+                node.amount,
+                node.error
+                    .as_deref()
+                    .unwrap_or("not landed before the time limit"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A claim instruction for a node that hasn't been claimed yet.
+#[derive(Clone, Debug)]
+struct PendingClaim {
+    node: ClaimReportNode,
+    instruction: Instruction,
+}
+
+/// On chain state of every claimable node in the merkle trees.
+#[derive(Debug, Default)]
+struct ClaimState {
+    unclaimed: Vec<PendingClaim>,
+    claimed: Vec<ClaimReportNode>,
+}
+
+/// A transaction carrying one or more claims.
+#[derive(Debug)]
+struct ClaimTransaction {
+    transaction: Transaction,
+    claims: Vec<PendingClaim>,
+}
+
+impl ClaimTransaction {
+    fn new(claims: Vec<PendingClaim>, payer_pubkey: &Pubkey, micro_lamports: u64) -> Self {
+        Self {
+            transaction: build_claim_transaction(&claims, payer_pubkey, micro_lamports),
+            claims,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SendClaimsResult {
+    num_transactions_landed: u64,
+    num_transactions_failed: u64,
+    /// Errors keyed by the claim status account of each claim that failed in a transaction of its
+    /// own
+    errors: HashMap<Pubkey, String>,
+}
+
+impl SendClaimsResult {
+    /// Records a failed transaction. A transaction carrying several claims is split into one
+    /// transaction per claim, returned to be resent, so that a claim that always fails doesn't
+    /// keep failing the claims packed with it.
+    fn record_failure(
+        &mut self,
+        claim_transaction: ClaimTransaction,
+        error: String,
+        payer_pubkey: &Pubkey,
+        micro_lamports: u64,
+    ) -> Vec<ClaimTransaction> {
+        self.num_transactions_failed += 1;
+        if claim_transaction.claims.len() > 1 {
+            return claim_transaction
+                .claims
+                .into_iter()
+                .map(|claim| ClaimTransaction::new(vec![claim], payer_pubkey, micro_lamports))
+                .collect();
+        }
+        for claim in claim_transaction.claims {
+            self.errors.insert(claim.node.claim_status, error.clone());
+        }
+        vec![]
+    }
+}
+
+async fn get_claim_state(
     rpc_client: &RpcClient,
     merkle_trees: &GeneratedMerkleTreeCollection,
     tip_distribution_program_id: Pubkey,
-    micro_lamports: u64,
     payer_pubkey: Pubkey,
-) -> Result<Vec<Transaction>, ClaimMevError> {
+) -> Result<ClaimState, ClaimMevError> {
     let tree_nodes = merkle_trees
         .generated_merkle_trees
         .iter()
@@ -125,59 +300,69 @@ pub async fn get_claim_transactions_for_valid_unclaimed(
         ("claim_statuses_onchain", claim_statuses.len(), i64),
     );
 
-    let transactions = build_mev_claim_transactions(
+    Ok(build_claim_state(
         tip_distribution_program_id,
         merkle_trees,
         tdas,
         claimants,
         claim_statuses,
-        micro_lamports,
         payer_pubkey,
-    );
-
-    Ok(transactions)
+    ))
 }
 
+/// Claims MEV tips for every node in the merkle trees, packing as many claims into each
+/// transaction as fit. Runs until every node is claimed or `max_loop_duration` elapses, paying a
+/// priority fee based on recent prioritization fees for the tip distribution accounts, bounded by
+/// `min_micro_lamports` and `max_micro_lamports`.
+///
+/// Nodes that are still unclaimed when this returns are listed in [ClaimMevReport::failed].
 pub async fn claim_mev_tips(
     merkle_trees: &GeneratedMerkleTreeCollection,
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
     keypair: Arc<Keypair>,
     max_loop_duration: Duration,
-    micro_lamports: u64,
-) -> Result<(), ClaimMevError> {
+    min_micro_lamports: u64,
+    max_micro_lamports: u64,
+) -> Result<ClaimMevReport, ClaimMevError> {
     let rpc_client = RpcClient::new_with_timeout_and_commitment(
         rpc_url,
         Duration::from_secs(300),
         CommitmentConfig::confirmed(),
     );
 
-    let start = Instant::now();
-    while start.elapsed() <= max_loop_duration {
-        let mut all_claim_transactions = get_claim_transactions_for_valid_unclaimed(
-            &rpc_client,
-            merkle_trees,
-            tip_distribution_program_id,
-            micro_lamports,
-            keypair.pubkey(),
-        )
-        .await?;
+    let mut state = get_claim_state(
+        &rpc_client,
+        merkle_trees,
+        tip_distribution_program_id,
+        keypair.pubkey(),
+    )
+    .await?;
+    let claimed_at_start: HashSet<Pubkey> =
+        state.claimed.iter().map(|node| node.claim_status).collect();
+
+    let mut report = ClaimMevReport {
+        epoch: merkle_trees.epoch,
+        ..ClaimMevReport::default()
+    };
+    let mut errors = HashMap::new();
 
+    let start = Instant::now();
+    while !state.unclaimed.is_empty() && start.elapsed() <= max_loop_duration {
         datapoint_info!(
             "claim_mev_tips-send_summary",
-            ("claim_transactions_left", all_claim_transactions.len(), i64),
+            ("claims_left", state.unclaimed.len(), i64),
         );
 
-        if all_claim_transactions.is_empty() {
-            return Ok(());
-        }
-
-        all_claim_transactions.shuffle(&mut thread_rng());
-        let transactions: Vec<_> = all_claim_transactions.into_iter().take(10_000).collect();
+        let mut claims = std::mem::take(&mut state.unclaimed);
+        claims.shuffle(&mut thread_rng());
+        claims.truncate(MAX_CLAIMS_PER_ROUND);
+        // claims against the same tip distribution account share accounts, so more fit per transaction
+        claims.sort_by_key(|claim| claim.node.tip_distribution_account);
 
         // only check balance for the ones we need to currently send since reclaim rent running in parallel
         if let Some((start_balance, desired_balance, sol_to_deposit)) =
-            is_sufficient_balance(&keypair.pubkey(), &rpc_client, transactions.len() as u64).await
+            is_sufficient_balance(&keypair.pubkey(), &rpc_client, claims.len() as u64).await
         {
             return Err(ClaimMevError::InsufficientBalance {
                 desired_balance,
@@ -187,83 +372,307 @@ pub async fn claim_mev_tips(
             });
         }
 
-        let blockhash = rpc_client.get_latest_blockhash().await?;
-        let _ = send_until_blockhash_expires(&rpc_client, transactions, blockhash, &keypair).await;
-    }
+        let micro_lamports =
+            get_priority_fee(&rpc_client, &claims, min_micro_lamports, max_micro_lamports).await?;
+        report.last_micro_lamports = micro_lamports;
 
-    let transactions = get_claim_transactions_for_valid_unclaimed(
-        &rpc_client,
-        merkle_trees,
-        tip_distribution_program_id,
-        micro_lamports,
-        keypair.pubkey(),
-    )
-    .await?;
-    if transactions.is_empty() {
-        return Ok(());
+        let transactions = pack_claim_transactions(claims, &keypair.pubkey(), micro_lamports);
+        info!(
+            "sending {} claim transactions with a priority fee of {micro_lamports} micro-lamports/CU",
+            transactions.len()
+        );
+        let result = send_and_confirm_claim_transactions(
+            &rpc_client,
+            transactions,
+            &keypair,
+            micro_lamports,
+        )
+        .await?;
+        report.num_transactions_landed += result.num_transactions_landed;
+        report.num_transactions_failed += result.num_transactions_failed;
+        errors.extend(result.errors);
+
+        state = get_claim_state(
+            &rpc_client,
+            merkle_trees,
+            tip_distribution_program_id,
+            keypair.pubkey(),
+        )
+        .await?;
     }
 
-    // if more transactions left, we'll simulate them all to make sure its not an uncaught error
-    let mut is_error = false;
-    let mut error_str = String::new();
-    for tx in &transactions {
-        match rpc_client
-            .simulate_transaction_with_config(
-                tx,
-                RpcSimulateTransactionConfig {
-                    sig_verify: false,
-                    replace_recent_blockhash: true,
-                    commitment: Some(CommitmentConfig::processed()),
-                    ..RpcSimulateTransactionConfig::default()
-                },
-            )
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => {
-                error_str = e.to_string();
-                is_error = true;
-
-                match e.get_transaction_error() {
-                    None => {
-                        break;
-                    }
-                    Some(e) => {
-                        warn!("transaction error. tx: {:?} error: {:?}", tx, e);
-                        break;
-                    }
+    let (already_claimed, claimed): (Vec<_>, Vec<_>) = state
+        .claimed
+        .into_iter()
+        .partition(|node| claimed_at_start.contains(&node.claim_status));
+    report.already_claimed = already_claimed;
+    report.claimed = claimed;
+    report.failed = state
+        .unclaimed
+        .into_iter()
+        .map(|claim| ClaimReportNode {
+            error: errors.get(&claim.node.claim_status).cloned(),
+            ..claim.node
+        })
+        .collect();
+
+    datapoint_info!(
+        "claim_mev_tips-report",
+        ("epoch", report.epoch, i64),
+        ("claimed", report.claimed.len(), i64),
+        ("claimed_lamports", report.claimed_lamports(), i64),
+        ("already_claimed", report.already_claimed.len(), i64),
+        ("failed", report.failed.len(), i64),
+        ("transactions_landed", report.num_transactions_landed, i64),
+        ("transactions_failed", report.num_transactions_failed, i64),
+        ("micro_lamports", report.last_micro_lamports, i64),
+    );
+
+    Ok(report)
+}
+
+/// Returns the priority fee to pay in micro-lamports per compute unit, based on the fees recently
+/// paid by transactions writing to the tip distribution accounts being claimed from.
+async fn get_priority_fee(
+    rpc_client: &RpcClient,
+    claims: &[PendingClaim],
+    min_micro_lamports: u64,
+    max_micro_lamports: u64,
+) -> Result<u64, ClaimMevError> {
+    let tda_pubkeys = claims
+        .iter()
+        .map(|claim| claim.node.tip_distribution_account)
+        .unique()
+        .take(MAX_TX_ACCOUNT_LOCKS)
+        .collect_vec();
+    let recent_fees = rpc_client
+        .get_recent_prioritization_fees(&tda_pubkeys)
+        .await?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect_vec();
+    Ok(priority_fee_from_recent_fees(
+        recent_fees,
+        min_micro_lamports,
+        max_micro_lamports,
+    ))
+}
+
+fn priority_fee_from_recent_fees(
+    mut recent_fees: Vec<u64>,
+    min_micro_lamports: u64,
+    max_micro_lamports: u64,
+) -> u64 {
+    recent_fees.sort_unstable();
+    let fee = recent_fees
+        .len()
+        .checked_sub(1)
+        .map(|last| recent_fees[last * PRIORITY_FEE_PERCENTILE / 100])
+        .unwrap_or_default();
+    fee.max(min_micro_lamports).min(max_micro_lamports)
+}
+
+/// Sends the claim transactions until they're confirmed, fail or the blockhash they were signed
+/// with expires. Claims of failed transactions carrying several claims are resent one per
+/// transaction. Transactions that don't land are left for the caller to rebuild and retry.
+async fn send_and_confirm_claim_transactions(
+    rpc_client: &RpcClient,
+    transactions: Vec<ClaimTransaction>,
+    keypair: &Keypair,
+    micro_lamports: u64,
+) -> Result<SendClaimsResult, ClaimMevError> {
+    let blockhash = rpc_client.get_latest_blockhash().await?;
+    let mut pending: HashMap<Signature, ClaimTransaction> = HashMap::new();
+    let add_pending = |pending: &mut HashMap<_, _>, transactions: Vec<ClaimTransaction>| {
+        for mut claim_transaction in transactions {
+            claim_transaction.transaction.sign(&[keypair], blockhash);
+            pending.insert(
+                *claim_transaction.transaction.get_signature(),
+                claim_transaction,
+            );
+        }
+    };
+    add_pending(&mut pending, transactions);
+
+    let mut result = SendClaimsResult::default();
+    while !pending.is_empty()
+        && rpc_client
+            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+            .await?
+    {
+        let send_results = stream::iter(&pending)
+            .map(|(signature, claim_transaction)| async move {
+                let send_result = rpc_client
+                    .send_transaction_with_config(
+                        &claim_transaction.transaction,
+                        RpcSendTransactionConfig {
+                            skip_preflight: false,
+                            preflight_commitment: Some(CommitmentLevel::Confirmed),
+                            max_retries: Some(0),
+                            ..RpcSendTransactionConfig::default()
+                        },
+                    )
+                    .await;
+                (*signature, send_result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_SENDS)
+            .collect::<Vec<_>>()
+            .await;
+
+        for (signature, send_result) in send_results {
+            let Err(e) = send_result else {
+                continue;
+            };
+            match e.get_transaction_error() {
+                // resent after already landing, the status check below picks it up
+                Some(TransactionError::AlreadyProcessed) => {}
+                // not worth resending, the caller rebuilds it from the latest state
+                Some(err) if err != TransactionError::BlockhashNotFound => {
+                    let claim_transaction = pending.remove(&signature).unwrap();
+                    let retries = result.record_failure(
+                        claim_transaction,
+                        err.to_string(),
+                        &keypair.pubkey(),
+                        micro_lamports,
+                    );
+                    add_pending(&mut pending, retries);
+                }
+                _ => {
+                    warn!("error sending claim transaction signature: {signature} error: {e:?}");
                 }
             }
         }
+
+        sleep(CONFIRMATION_POLL_INTERVAL).await;
+
+        let signatures = pending.keys().cloned().collect_vec();
+        for (signature, status) in get_batched_signatures_statuses(rpc_client, &signatures).await? {
+            let Some(status) = status else {
+                continue;
+            };
+            if let Some(err) = status.err {
+                let claim_transaction = pending.remove(&signature).unwrap();
+                let retries = result.record_failure(
+                    claim_transaction,
+                    err.to_string(),
+                    &keypair.pubkey(),
+                    micro_lamports,
+                );
+                add_pending(&mut pending, retries);
+            } else if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                pending.remove(&signature);
+                result.num_transactions_landed += 1;
+            }
+        }
     }
 
-    if is_error {
-        Err(ClaimMevError::UncaughtError { e: error_str })
-    } else {
-        Err(ClaimMevError::NotFinished {
-            transactions_left: transactions.len(),
-        })
+    info!(
+        "claim transactions landed: {}, failed: {}, expired: {}",
+        result.num_transactions_landed,
+        result.num_transactions_failed,
+        pending.len()
+    );
+
+    Ok(result)
+}
+
+/// Packs the claims, in order, into as few transactions as possible. A claim is added to the
+/// current transaction as long as it stays within the packet size, account lock and compute limits.
+fn pack_claim_transactions(
+    claims: Vec<PendingClaim>,
+    payer_pubkey: &Pubkey,
+    micro_lamports: u64,
+) -> Vec<ClaimTransaction> {
+    let mut transactions = Vec::new();
+    let mut batch: Vec<PendingClaim> = Vec::new();
+    for claim in claims {
+        batch.push(claim);
+        if batch.len() > 1 {
+            let transaction = build_claim_transaction(&batch, payer_pubkey, micro_lamports);
+            if !fits_in_transaction(&transaction, batch.len()) {
+                let overflow = batch.pop().unwrap();
+                let full_batch = std::mem::replace(&mut batch, vec![overflow]);
+                transactions.push(ClaimTransaction::new(
+                    full_batch,
+                    payer_pubkey,
+                    micro_lamports,
+                ));
+            }
+        }
+    }
+    if !batch.is_empty() {
+        transactions.push(ClaimTransaction::new(batch, payer_pubkey, micro_lamports));
     }
+    transactions
 }
 
-/// Returns a list of claim transactions for valid, unclaimed MEV tips
-/// A valid, unclaimed transaction consists of the following:
+fn build_claim_transaction(
+    claims: &[PendingClaim],
+    payer_pubkey: &Pubkey,
+    micro_lamports: u64,
+) -> Transaction {
+    let compute_unit_limit = (claims.len() as u32)
+        .saturating_mul(CLAIM_COMPUTE_UNITS)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
+    ]
+    .into_iter()
+    .chain(claims.iter().map(|claim| claim.instruction.clone()))
+    .collect_vec();
+    Transaction::new_with_payer(&instructions, Some(payer_pubkey))
+}
+
+fn fits_in_transaction(transaction: &Transaction, num_claims: usize) -> bool {
+    bincode::serialized_size(transaction).unwrap() as usize <= PACKET_DATA_SIZE
+        && transaction.message.account_keys.len() <= MAX_TX_ACCOUNT_LOCKS
+        && num_claims as u64 * CLAIM_COMPUTE_UNITS as u64 <= MAX_COMPUTE_UNIT_LIMIT as u64
+}
+
+fn build_claim_instruction(
+    tip_distribution_program_id: Pubkey,
+    tip_distribution_config: Pubkey,
+    tree: &GeneratedMerkleTree,
+    node: &TreeNode,
+    payer_pubkey: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: tip_distribution_program_id,
+        data: jito_tip_distribution::instruction::Claim {
+            proof: node.proof.clone().unwrap(),
+            amount: node.amount,
+            bump: node.claim_status_bump,
+        }
+        .data(),
+        accounts: jito_tip_distribution::accounts::Claim {
+            config: tip_distribution_config,
+            tip_distribution_account: tree.tip_distribution_account,
+            claimant: node.claimant,
+            claim_status: node.claim_status_pubkey,
+            payer: payer_pubkey,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+    }
+}
+
+/// Splits the claimable nodes into those already claimed and those still waiting on a claim
+/// instruction. A node is claimable when:
 /// - there must be lamports to claim for the tip distribution account.
 /// - there must be a merkle root.
 /// - the claimant (typically a stake account) must exist.
 /// - the claimant (typically a stake account) must have a non-zero amount of tips to claim
 /// - the claimant must have enough lamports post-claim to be rent-exempt.
 ///   - note: there aren't any rent exempt accounts on solana mainnet anymore.
-/// - it must not have already been claimed.
-fn build_mev_claim_transactions(
+fn build_claim_state(
     tip_distribution_program_id: Pubkey,
     merkle_trees: &GeneratedMerkleTreeCollection,
     tdas: HashMap<Pubkey, Account>,
     claimants: HashMap<Pubkey, Account>,
     claim_status: HashMap<Pubkey, Account>,
-    micro_lamports: u64,
     payer_pubkey: Pubkey,
-) -> Vec<Transaction> {
+) -> ClaimState {
     let tip_distribution_accounts: HashMap<Pubkey, TipDistributionAccount> = tdas
         .iter()
         .filter_map(|(pubkey, account)| {
@@ -297,7 +706,7 @@ fn build_mev_claim_transactions(
     let tip_distribution_config =
         Pubkey::find_program_address(&[Config::SEED], &tip_distribution_program_id).0;
 
-    let mut instructions = Vec::with_capacity(claimants.len());
+    let mut state = ClaimState::default();
     for tree in &merkle_trees.generated_merkle_trees {
         if tree.max_total_claim == 0 {
             continue;
@@ -316,46 +725,29 @@ fn build_mev_claim_transactions(
 
         for node in &tree.tree_nodes {
             // doesn't make sense to claim for claimants that don't exist anymore
-            // can't claim for something already claimed
             // don't need to claim for claimants that get 0 MEV
-            if claimants.get(&node.claimant).is_none()
-                || claim_statuses.get(&node.claim_status_pubkey).is_some()
-                || node.amount == 0
-            {
+            if claimants.get(&node.claimant).is_none() || node.amount == 0 {
                 continue;
             }
 
-            instructions.push(Instruction {
-                program_id: tip_distribution_program_id,
-                data: jito_tip_distribution::instruction::Claim {
-                    proof: node.proof.clone().unwrap(),
-                    amount: node.amount,
-                    bump: node.claim_status_bump,
-                }
-                .data(),
-                accounts: jito_tip_distribution::accounts::Claim {
-                    config: tip_distribution_config,
-                    tip_distribution_account: tree.tip_distribution_account,
-                    claimant: node.claimant,
-                    claim_status: node.claim_status_pubkey,
-                    payer: payer_pubkey,
-                    system_program: system_program::id(),
-                }
-                .to_account_metas(None),
-            });
+            if claim_statuses.contains_key(&node.claim_status_pubkey) {
+                state.claimed.push(ClaimReportNode::new(tree, node));
+            } else {
+                state.unclaimed.push(PendingClaim {
+                    node: ClaimReportNode::new(tree, node),
+                    instruction: build_claim_instruction(
+                        tip_distribution_program_id,
+                        tip_distribution_config,
+                        tree,
+                        node,
+                        payer_pubkey,
+                    ),
+                });
+            }
         }
     }
 
-    // TODO (LB): see if we can do >1 claim here
-    let transactions: Vec<Transaction> = instructions
-        .into_iter()
-        .map(|claim_ix| {
-            let priority_fee_ix = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
-            Transaction::new_with_payer(&[priority_fee_ix, claim_ix], Some(&payer_pubkey))
-        })
-        .collect();
-
-    transactions
+    state
 }
 
 /// heuristic to make sure we have enough funds to cover the rent costs if epoch has many validators
@@ -396,3 +788,151 @@ async fn is_sufficient_balance(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::hash::Hash};
+
+    fn pending_claims(
+        tip_distribution_account: Pubkey,
+        payer_pubkey: Pubkey,
+        num_claims: usize,
+        proof_len: usize,
+    ) -> Vec<PendingClaim> {
+        let tip_distribution_program_id = Pubkey::new_unique();
+        let tip_distribution_config = Pubkey::new_unique();
+        let tree = GeneratedMerkleTree {
+            tip_distribution_account,
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: Hash::default(),
+            tree_nodes: vec![],
+            max_total_claim: 0,
+            max_num_nodes: 0,
+        };
+        (0..num_claims)
+            .map(|_| {
+                let node = TreeNode {
+                    claimant: Pubkey::new_unique(),
+                    claim_status_pubkey: Pubkey::new_unique(),
+                    claim_status_bump: 255,
+                    staker_pubkey: Pubkey::new_unique(),
+                    withdrawer_pubkey: Pubkey::new_unique(),
+                    amount: 1_000,
+                    proof: Some(vec![[1; 32]; proof_len]),
+                };
+                PendingClaim {
+                    node: ClaimReportNode::new(&tree, &node),
+                    instruction: build_claim_instruction(
+                        tip_distribution_program_id,
+                        tip_distribution_config,
+                        &tree,
+                        &node,
+                        payer_pubkey,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_claim_transactions() {
+        let payer_pubkey = Pubkey::new_unique();
+        let claims = pending_claims(Pubkey::new_unique(), payer_pubkey, 20, 2);
+        let expected_nodes = claims.iter().map(|claim| claim.node.clone()).collect_vec();
+
+        let transactions = pack_claim_transactions(claims, &payer_pubkey, 100);
+        assert!(transactions.len() < 20);
+        for claim_transaction in &transactions {
+            assert!(fits_in_transaction(
+                &claim_transaction.transaction,
+                claim_transaction.claims.len()
+            ));
+            // compute budget instructions plus one instruction per claim
+            assert_eq!(
+                claim_transaction.transaction.message.instructions.len(),
+                claim_transaction.claims.len() + 2
+            );
+        }
+        // every claim is sent exactly once, in order
+        assert_eq!(
+            transactions
+                .into_iter()
+                .flat_map(|claim_transaction| claim_transaction.claims)
+                .map(|claim| claim.node)
+                .collect_vec(),
+            expected_nodes
+        );
+
+        // claims with large proofs only fit one per transaction
+        let claims = pending_claims(Pubkey::new_unique(), payer_pubkey, 3, 20);
+        let transactions = pack_claim_transactions(claims, &payer_pubkey, 100);
+        assert_eq!(transactions.len(), 3);
+        assert!(transactions
+            .iter()
+            .all(|claim_transaction| claim_transaction.claims.len() == 1));
+
+        assert!(pack_claim_transactions(vec![], &payer_pubkey, 100).is_empty());
+    }
+
+    #[test]
+    fn test_record_failure_resends_claims_individually() {
+        let payer_pubkey = Pubkey::new_unique();
+        let claims = pending_claims(Pubkey::new_unique(), payer_pubkey, 3, 2);
+        let bad_claim = claims[1].node.claim_status;
+        let transactions = pack_claim_transactions(claims, &payer_pubkey, 100);
+        assert_eq!(transactions.len(), 1);
+
+        // every transaction carrying the bad claim fails, the others land
+        let mut result = SendClaimsResult::default();
+        let mut pending = transactions;
+        let mut landed = vec![];
+        while let Some(claim_transaction) = pending.pop() {
+            if claim_transaction
+                .claims
+                .iter()
+                .any(|claim| claim.node.claim_status == bad_claim)
+            {
+                let retries = result.record_failure(
+                    claim_transaction,
+                    "custom program error".to_string(),
+                    &payer_pubkey,
+                    100,
+                );
+                // compute budget instructions plus the single claim
+                assert!(retries.iter().all(|retry| retry.claims.len() == 1
+                    && retry.transaction.message.instructions.len() == 3));
+                pending.extend(retries);
+            } else {
+                result.num_transactions_landed += 1;
+                landed.extend(claim_transaction.claims);
+            }
+        }
+
+        // the pack failed once, then only the bad claim failed on its own
+        assert_eq!(result.num_transactions_failed, 2);
+        assert_eq!(result.num_transactions_landed, 2);
+        assert_eq!(landed.len(), 2);
+        assert!(landed
+            .iter()
+            .all(|claim| claim.node.claim_status != bad_claim));
+        assert_eq!(
+            result.errors,
+            HashMap::from([(bad_claim, "custom program error".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_priority_fee_from_recent_fees() {
+        let recent_fees = (1..=100).rev().collect_vec();
+        assert_eq!(
+            priority_fee_from_recent_fees(recent_fees.clone(), 1, 1_000),
+            75
+        );
+        assert_eq!(
+            priority_fee_from_recent_fees(recent_fees.clone(), 80, 1_000),
+            80
+        );
+        assert_eq!(priority_fee_from_recent_fees(recent_fees, 1, 50), 50);
+        assert_eq!(priority_fee_from_recent_fees(vec![], 10, 1_000), 10);
+    }
+}
@@ -0,0 +1,611 @@
+//! Runs the stake-meta-generator, merkle-root-generator, merkle-root-uploader and claim-mev-tips
+//! workflows for a single epoch in order. Progress is checkpointed to disk after every step so a
+//! run that fails part way through resumes at the step that failed.
+use {
+    crate::{
+        claim_mev_workflow::{claim_mev_tips, ClaimMevError},
+        merkle_root_generator_workflow::{generate_merkle_root, MerkleRootGeneratorError},
+        merkle_root_upload_workflow::{upload_merkle_root, MerkleRootUploadError},
+        read_json_from_file,
+        stake_meta_generator_workflow::{generate_stake_meta, StakeMetaGeneratorError},
+        GeneratedMerkleTree, GeneratedMerkleTreeCollection, StakeMetaCollection,
+    },
+    anchor_lang::AccountDeserialize,
+    jito_tip_distribution::state::TipDistributionAccount,
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_accounts_db::hardened_unpack::{
+        open_genesis_config, OpenGenesisConfigError, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+    },
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_metrics::datapoint_info,
+    solana_sdk::{
+        clock::Slot,
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Signer},
+        stake_history::Epoch,
+    },
+    std::{
+        fmt::{Display, Formatter},
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+    tokio::runtime::{Builder, Runtime},
+};
+
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    AnchorError(#[from] anchor_lang::error::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] solana_rpc_client_api::client_error::Error),
+
+    #[error(transparent)]
+    GenesisConfigError(#[from] OpenGenesisConfigError),
+
+    #[error(transparent)]
+    StakeMetaGeneratorError(#[from] StakeMetaGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootGeneratorError(#[from] MerkleRootGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootUploadError(#[from] MerkleRootUploadError),
+
+    #[error(transparent)]
+    ClaimMevError(#[from] ClaimMevError),
+
+    #[error("failed to read keypair: {0}")]
+    KeypairError(String),
+
+    #[error("checkpoint {path:?} is for epoch {checkpoint_epoch}, expected epoch {epoch}")]
+    CheckpointEpochMismatch {
+        path: PathBuf,
+        checkpoint_epoch: Epoch,
+        epoch: Epoch,
+    },
+
+    #[error("{path:?} was generated for epoch {found}, expected epoch {expected}")]
+    EpochMismatch {
+        path: PathBuf,
+        found: Epoch,
+        expected: Epoch,
+    },
+
+    #[error("tip distribution account {0} doesn't exist")]
+    MissingTipDistributionAccount(Pubkey),
+
+    #[error("tip distribution account {tip_distribution_account} has a different merkle root uploaded and {total_funds_claimed} lamports have already been claimed against it")]
+    ConflictingMerkleRoot {
+        tip_distribution_account: Pubkey,
+        total_funds_claimed: u64,
+    },
+
+    #[error("{remaining} merkle roots are still waiting to be uploaded")]
+    MerkleRootsNotUploaded { remaining: usize },
+}
+
+/// The workflows run for an epoch, in the order they're run in.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStep {
+    GenerateStakeMeta,
+    GenerateMerkleRoots,
+    UploadMerkleRoots,
+    ClaimMevTips,
+}
+
+impl PipelineStep {
+    pub const ALL: [PipelineStep; 4] = [
+        PipelineStep::GenerateStakeMeta,
+        PipelineStep::GenerateMerkleRoots,
+        PipelineStep::UploadMerkleRoots,
+        PipelineStep::ClaimMevTips,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineStep::GenerateStakeMeta => "generate_stake_meta",
+            PipelineStep::GenerateMerkleRoots => "generate_merkle_roots",
+            PipelineStep::UploadMerkleRoots => "upload_merkle_roots",
+            PipelineStep::ClaimMevTips => "claim_mev_tips",
+        }
+    }
+}
+
+impl Display for PipelineStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Persisted after every step. Output files of a step are only trusted once the step shows up in
+/// `completed_steps`, a file left behind by a crash part way through writing it gets regenerated.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PipelineCheckpoint {
+    pub epoch: Epoch,
+    pub completed_steps: Vec<PipelineStep>,
+    /// Error the last attempt failed with, if any.
+    pub last_error: Option<String>,
+}
+
+impl PipelineCheckpoint {
+    pub fn new(epoch: Epoch) -> Self {
+        Self {
+            epoch,
+            completed_steps: Vec::default(),
+            last_error: None,
+        }
+    }
+
+    pub fn load_or_new(path: &Path, epoch: Epoch) -> Result<Self, PipelineError> {
+        if !path.exists() {
+            return Ok(Self::new(epoch));
+        }
+        let checkpoint: Self = serde_json::from_slice(&fs::read(path)?)?;
+        if checkpoint.epoch != epoch {
+            return Err(PipelineError::CheckpointEpochMismatch {
+                path: path.to_path_buf(),
+                checkpoint_epoch: checkpoint.epoch,
+                epoch,
+            });
+        }
+        Ok(checkpoint)
+    }
+
+    /// Writes to a temporary file first so a crash mid-write can't corrupt the checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), PipelineError> {
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Returns the first step that hasn't completed yet, None once the epoch is done.
+    pub fn next_step(&self) -> Option<PipelineStep> {
+        PipelineStep::ALL
+            .into_iter()
+            .find(|step| !self.completed_steps.contains(step))
+    }
+
+    pub fn complete(&mut self, step: PipelineStep) {
+        if !self.completed_steps.contains(&step) {
+            self.completed_steps.push(step);
+        }
+        self.last_error = None;
+    }
+}
+
+pub struct PipelineConfig {
+    pub epoch: Epoch,
+    /// Checkpoint and intermediate JSON files are kept in `<working_dir>/epoch-<epoch>`.
+    pub working_dir: PathBuf,
+    pub ledger_path: PathBuf,
+    /// Defaults to the last slot in `epoch`.
+    pub snapshot_slot: Option<Slot>,
+    pub rpc_url: String,
+    pub keypair_path: PathBuf,
+    pub tip_distribution_program_id: Pubkey,
+    pub tip_payment_program_id: Pubkey,
+    pub max_concurrent_rpc_get_reqs: usize,
+    pub txn_send_batch_size: usize,
+    pub max_claim_duration: Duration,
+    /// Minimum priority fee for claim transactions, in micro-lamports per compute unit.
+    pub micro_lamports: u64,
+    /// Maximum priority fee for claim transactions, in micro-lamports per compute unit.
+    pub max_micro_lamports: u64,
+}
+
+impl PipelineConfig {
+    pub fn epoch_dir(&self) -> PathBuf {
+        self.working_dir.join(format!("epoch-{}", self.epoch))
+    }
+
+    pub fn checkpoint_path(&self) -> PathBuf {
+        self.epoch_dir().join("checkpoint.json")
+    }
+
+    pub fn stake_meta_path(&self) -> PathBuf {
+        self.epoch_dir().join("stake-meta.json")
+    }
+
+    pub fn merkle_trees_path(&self) -> PathBuf {
+        self.epoch_dir().join("merkle-trees.json")
+    }
+}
+
+/// Runs every step that hasn't completed yet for `config.epoch`.
+pub fn run_pipeline(config: &PipelineConfig) -> Result<(), PipelineError> {
+    fs::create_dir_all(config.epoch_dir())?;
+    let checkpoint_path = config.checkpoint_path();
+    let mut checkpoint = PipelineCheckpoint::load_or_new(&checkpoint_path, config.epoch)?;
+    if let Some(last_error) = &checkpoint.last_error {
+        warn!(
+            "epoch {} previously failed at step {:?}: {last_error}",
+            config.epoch,
+            checkpoint.next_step()
+        );
+    }
+
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(16)
+        .enable_all()
+        .build()?;
+
+    while let Some(step) = checkpoint.next_step() {
+        info!("epoch {}: running step {step}", config.epoch);
+        let start = Instant::now();
+        let result = match step {
+            PipelineStep::GenerateStakeMeta => run_generate_stake_meta(config),
+            PipelineStep::GenerateMerkleRoots => run_generate_merkle_roots(config),
+            PipelineStep::UploadMerkleRoots => run_upload_merkle_roots(config, &runtime),
+            PipelineStep::ClaimMevTips => run_claim_mev_tips(config, &runtime),
+        };
+        datapoint_info!(
+            "tip_distributor-pipeline_step",
+            ("epoch", config.epoch, i64),
+            ("step", step.name(), String),
+            ("success", result.is_ok(), bool),
+            ("elapsed_us", start.elapsed().as_micros(), i64),
+        );
+
+        match result {
+            Ok(()) => {
+                checkpoint.complete(step);
+                checkpoint.save(&checkpoint_path)?;
+            }
+            Err(e) => {
+                error!("epoch {}: step {step} failed: {e}", config.epoch);
+                checkpoint.last_error = Some(e.to_string());
+                checkpoint.save(&checkpoint_path)?;
+                return Err(e);
+            }
+        }
+    }
+
+    info!("epoch {}: all steps complete", config.epoch);
+    Ok(())
+}
+
+fn run_generate_stake_meta(config: &PipelineConfig) -> Result<(), PipelineError> {
+    let snapshot_slot = match config.snapshot_slot {
+        Some(snapshot_slot) => snapshot_slot,
+        None => open_genesis_config(&config.ledger_path, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE)?
+            .epoch_schedule
+            .get_last_slot_in_epoch(config.epoch),
+    };
+    let stake_meta_path = config.stake_meta_path();
+    generate_stake_meta(
+        &config.ledger_path,
+        &snapshot_slot,
+        &config.tip_distribution_program_id,
+        &stake_meta_path.to_string_lossy(),
+        &config.tip_payment_program_id,
+    )?;
+
+    let stake_meta_coll: StakeMetaCollection = read_json_from_file(&stake_meta_path)?;
+    check_epoch(&stake_meta_path, stake_meta_coll.epoch, config.epoch)
+}
+
+fn run_generate_merkle_roots(config: &PipelineConfig) -> Result<(), PipelineError> {
+    let merkle_trees_path = config.merkle_trees_path();
+    generate_merkle_root(
+        &config.stake_meta_path(),
+        &merkle_trees_path,
+        &config.rpc_url,
+    )?;
+
+    let merkle_trees: GeneratedMerkleTreeCollection = read_json_from_file(&merkle_trees_path)?;
+    check_epoch(&merkle_trees_path, merkle_trees.epoch, config.epoch)
+}
+
+fn run_upload_merkle_roots(
+    config: &PipelineConfig,
+    runtime: &Runtime,
+) -> Result<(), PipelineError> {
+    let merkle_trees: GeneratedMerkleTreeCollection =
+        read_json_from_file(&config.merkle_trees_path())?;
+    let keypair = read_keypair_file(&config.keypair_path)
+        .map_err(|e| PipelineError::KeypairError(e.to_string()))?;
+    let rpc_client =
+        RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+
+    let status = runtime.block_on(get_merkle_root_upload_status(
+        &rpc_client,
+        &merkle_trees,
+        &keypair.pubkey(),
+    ))?;
+    if status.num_pending == 0 {
+        info!(
+            "epoch {}: all {} merkle roots already uploaded",
+            config.epoch, status.num_trees
+        );
+        return Ok(());
+    }
+    if status.num_uploaded > 0 {
+        info!(
+            "epoch {}: merkle roots partially uploaded, {} of {} remaining",
+            config.epoch, status.num_pending, status.num_trees
+        );
+    }
+
+    upload_merkle_root(
+        &config.merkle_trees_path(),
+        &config.keypair_path,
+        &config.rpc_url,
+        &config.tip_distribution_program_id,
+        config.max_concurrent_rpc_get_reqs,
+        config.txn_send_batch_size,
+    )?;
+
+    let status = runtime.block_on(get_merkle_root_upload_status(
+        &rpc_client,
+        &merkle_trees,
+        &keypair.pubkey(),
+    ))?;
+    if status.num_pending > 0 {
+        return Err(PipelineError::MerkleRootsNotUploaded {
+            remaining: status.num_pending,
+        });
+    }
+    Ok(())
+}
+
+fn run_claim_mev_tips(config: &PipelineConfig, runtime: &Runtime) -> Result<(), PipelineError> {
+    let merkle_trees: GeneratedMerkleTreeCollection =
+        read_json_from_file(&config.merkle_trees_path())?;
+    let keypair = Arc::new(
+        read_keypair_file(&config.keypair_path)
+            .map_err(|e| PipelineError::KeypairError(e.to_string()))?,
+    );
+
+    runtime.block_on(async {
+        let rpc_client =
+            RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+        let progress = get_claim_progress(&rpc_client, &merkle_trees).await?;
+        if progress.num_claimed > 0 {
+            info!(
+                "epoch {}: partially claimed, {} of {} claims already landed",
+                config.epoch, progress.num_claimed, progress.num_claims
+            );
+        }
+
+        let report = claim_mev_tips(
+            &merkle_trees,
+            config.rpc_url.clone(),
+            config.tip_distribution_program_id,
+            keypair,
+            config.max_claim_duration,
+            config.micro_lamports,
+            config.max_micro_lamports,
+        )
+        .await?;
+        info!("{report}");
+        if !report.is_finished() {
+            return Err(ClaimMevError::ClaimsNotFinished {
+                claims_left: report.failed.len(),
+            }
+            .into());
+        }
+        Ok(())
+    })
+}
+
+fn check_epoch(path: &Path, found: Epoch, expected: Epoch) -> Result<(), PipelineError> {
+    if found != expected {
+        return Err(PipelineError::EpochMismatch {
+            path: path.to_path_buf(),
+            found,
+            expected,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MerkleRootState {
+    /// The generated merkle root is on chain.
+    Uploaded,
+    /// No merkle root or a different one with nothing claimed yet; the uploader will overwrite it.
+    Pending,
+    /// A different merkle root is on chain and claims have already been paid out against it.
+    Conflicting { total_funds_claimed: u64 },
+}
+
+pub fn merkle_root_state(
+    tree: &GeneratedMerkleTree,
+    tip_distribution_account: &TipDistributionAccount,
+) -> MerkleRootState {
+    match &tip_distribution_account.merkle_root {
+        None => MerkleRootState::Pending,
+        Some(merkle_root) if merkle_root.root == tree.merkle_root.to_bytes() => {
+            MerkleRootState::Uploaded
+        }
+        Some(merkle_root) if merkle_root.total_funds_claimed == 0 => MerkleRootState::Pending,
+        Some(merkle_root) => MerkleRootState::Conflicting {
+            total_funds_claimed: merkle_root.total_funds_claimed,
+        },
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MerkleRootUploadStatus {
+    pub num_trees: usize,
+    pub num_uploaded: usize,
+    pub num_pending: usize,
+}
+
+/// Checks the on chain merkle roots of the trees `merkle_root_upload_authority` is responsible for.
+pub async fn get_merkle_root_upload_status(
+    rpc_client: &RpcClient,
+    merkle_trees: &GeneratedMerkleTreeCollection,
+    merkle_root_upload_authority: &Pubkey,
+) -> Result<MerkleRootUploadStatus, PipelineError> {
+    let trees: Vec<&GeneratedMerkleTree> = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .filter(|tree| &tree.merkle_root_upload_authority == merkle_root_upload_authority)
+        .collect();
+    let tda_pubkeys: Vec<Pubkey> = trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect();
+    let tdas = crate::get_batched_accounts(rpc_client, &tda_pubkeys).await?;
+
+    let mut status = MerkleRootUploadStatus {
+        num_trees: trees.len(),
+        ..MerkleRootUploadStatus::default()
+    };
+    for tree in trees {
+        let account = tdas
+            .get(&tree.tip_distribution_account)
+            .cloned()
+            .flatten()
+            .ok_or(PipelineError::MissingTipDistributionAccount(
+                tree.tip_distribution_account,
+            ))?;
+        let tip_distribution_account =
+            TipDistributionAccount::try_deserialize(&mut account.data.as_slice())?;
+        match merkle_root_state(tree, &tip_distribution_account) {
+            MerkleRootState::Uploaded => status.num_uploaded += 1,
+            MerkleRootState::Pending => status.num_pending += 1,
+            MerkleRootState::Conflicting {
+                total_funds_claimed,
+            } => {
+                return Err(PipelineError::ConflictingMerkleRoot {
+                    tip_distribution_account: tree.tip_distribution_account,
+                    total_funds_claimed,
+                })
+            }
+        }
+    }
+    Ok(status)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClaimProgress {
+    /// Number of tree nodes with a non-zero amount to claim.
+    pub num_claims: usize,
+    /// Number of those nodes whose claim status account exists.
+    pub num_claimed: usize,
+}
+
+pub async fn get_claim_progress(
+    rpc_client: &RpcClient,
+    merkle_trees: &GeneratedMerkleTreeCollection,
+) -> Result<ClaimProgress, PipelineError> {
+    let claim_status_pubkeys: Vec<Pubkey> = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .flat_map(|tree| &tree.tree_nodes)
+        .filter(|node| node.amount > 0)
+        .map(|node| node.claim_status_pubkey)
+        .collect();
+    let claim_statuses = crate::get_batched_accounts(rpc_client, &claim_status_pubkeys).await?;
+    Ok(ClaimProgress {
+        num_claims: claim_status_pubkeys.len(),
+        num_claimed: claim_statuses
+            .values()
+            .filter(|account| account.is_some())
+            .count(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, jito_tip_distribution::state::MerkleRoot, solana_sdk::hash::Hash,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = PipelineCheckpoint::load_or_new(&path, 500).unwrap();
+        assert_eq!(checkpoint, PipelineCheckpoint::new(500));
+        assert_eq!(
+            checkpoint.next_step(),
+            Some(PipelineStep::GenerateStakeMeta)
+        );
+
+        checkpoint.complete(PipelineStep::GenerateStakeMeta);
+        checkpoint.complete(PipelineStep::GenerateMerkleRoots);
+        checkpoint.last_error = Some("rpc timed out".to_string());
+        checkpoint.save(&path).unwrap();
+
+        let mut checkpoint = PipelineCheckpoint::load_or_new(&path, 500).unwrap();
+        assert_eq!(
+            checkpoint.next_step(),
+            Some(PipelineStep::UploadMerkleRoots)
+        );
+        assert_eq!(checkpoint.last_error.as_deref(), Some("rpc timed out"));
+
+        checkpoint.complete(PipelineStep::UploadMerkleRoots);
+        assert_eq!(checkpoint.last_error, None);
+        checkpoint.complete(PipelineStep::ClaimMevTips);
+        assert_eq!(checkpoint.next_step(), None);
+
+        assert!(matches!(
+            PipelineCheckpoint::load_or_new(&path, 501),
+            Err(PipelineError::CheckpointEpochMismatch {
+                checkpoint_epoch: 500,
+                epoch: 501,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_merkle_root_state() {
+        let tree = GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: Hash::new_unique(),
+            tree_nodes: vec![],
+            max_total_claim: 100,
+            max_num_nodes: 2,
+        };
+        let tda_with_root = |root: [u8; 32], total_funds_claimed: u64| TipDistributionAccount {
+            merkle_root: Some(MerkleRoot {
+                root,
+                max_total_claim: 100,
+                max_num_nodes: 2,
+                total_funds_claimed,
+                num_nodes_claimed: u64::from(total_funds_claimed > 0),
+            }),
+            ..TipDistributionAccount::default()
+        };
+
+        assert_eq!(
+            merkle_root_state(&tree, &TipDistributionAccount::default()),
+            MerkleRootState::Pending
+        );
+        assert_eq!(
+            merkle_root_state(&tree, &tda_with_root(tree.merkle_root.to_bytes(), 50)),
+            MerkleRootState::Uploaded
+        );
+        assert_eq!(
+            merkle_root_state(&tree, &tda_with_root([1; 32], 0)),
+            MerkleRootState::Pending
+        );
+        assert_eq!(
+            merkle_root_state(&tree, &tda_with_root([1; 32], 50)),
+            MerkleRootState::Conflicting {
+                total_funds_claimed: 50
+            }
+        );
+    }
+}
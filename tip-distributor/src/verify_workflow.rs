@@ -0,0 +1,514 @@
+//! Lets anyone audit a validator's MEV payouts: stake meta is recomputed from a snapshot, the
+//! merkle trees are rebuilt from it and the resulting roots are compared against the ones uploaded
+//! to each [TipDistributionAccount].
+use {
+    crate::{
+        merkle_root_generator_workflow::MerkleRootGeneratorError,
+        stake_meta_generator_workflow::{
+            create_bank_from_snapshot, generate_stake_meta_collection, StakeMetaGeneratorError,
+        },
+        GeneratedMerkleTree, GeneratedMerkleTreeCollection, TreeNode,
+    },
+    anchor_lang::AccountDeserialize,
+    jito_tip_distribution::{merkle_proof, state::TipDistributionAccount},
+    log::*,
+    serde::Serialize,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_program::hash::hashv,
+    solana_sdk::{
+        account::Account, clock::Slot, commitment_config::CommitmentConfig, hash::Hash,
+        pubkey::Pubkey,
+    },
+    std::{fmt::Display, path::Path},
+    thiserror::Error,
+    tokio::runtime::Builder,
+};
+
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] solana_rpc_client_api::client_error::Error),
+
+    #[error(transparent)]
+    StakeMetaGeneratorError(#[from] StakeMetaGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootGeneratorError(#[from] MerkleRootGeneratorError),
+}
+
+/// Narrows verification down to the trees and tree nodes someone cares about.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyFilter {
+    /// Only verify the tree of this validator.
+    pub validator_vote_account: Option<Pubkey>,
+    /// Only report tree nodes where this is the claimant, staker or withdrawer.
+    pub claimant_or_authority: Option<Pubkey>,
+}
+
+impl VerifyFilter {
+    fn matches_node(&self, node: &TreeNode) -> bool {
+        self.claimant_or_authority.map_or(true, |pubkey| {
+            node.claimant == pubkey
+                || node.staker_pubkey == pubkey
+                || node.withdrawer_pubkey == pubkey
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum MerkleRootVerification {
+    /// The recomputed merkle root matches the uploaded one.
+    Match,
+    /// A different merkle root was uploaded.
+    Mismatch {
+        uploaded_root: String,
+        uploaded_max_total_claim: u64,
+        uploaded_max_num_nodes: u64,
+    },
+    /// No merkle root has been uploaded yet.
+    NotUploaded,
+    /// The tip distribution account doesn't exist, it may have been closed after expiring.
+    MissingAccount,
+    /// The account exists but isn't a tip distribution account.
+    InvalidAccount { error: String },
+}
+
+impl MerkleRootVerification {
+    pub fn new(tree: &GeneratedMerkleTree, tip_distribution_account: Option<&Account>) -> Self {
+        let Some(account) = tip_distribution_account else {
+            return Self::MissingAccount;
+        };
+        let tip_distribution_account =
+            match TipDistributionAccount::try_deserialize(&mut account.data.as_slice()) {
+                Ok(tip_distribution_account) => tip_distribution_account,
+                Err(e) => {
+                    return Self::InvalidAccount {
+                        error: e.to_string(),
+                    }
+                }
+            };
+        match &tip_distribution_account.merkle_root {
+            None => Self::NotUploaded,
+            Some(merkle_root)
+                if merkle_root.root == tree.merkle_root.to_bytes()
+                    && merkle_root.max_total_claim == tree.max_total_claim
+                    && merkle_root.max_num_nodes == tree.max_num_nodes =>
+            {
+                Self::Match
+            }
+            Some(merkle_root) => Self::Mismatch {
+                uploaded_root: Hash::new_from_array(merkle_root.root).to_string(),
+                uploaded_max_total_claim: merkle_root.max_total_claim,
+                uploaded_max_num_nodes: merkle_root.max_num_nodes,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ClaimVerification {
+    pub claimant: String,
+    pub staker: String,
+    pub withdrawer: String,
+    pub amount: u64,
+    pub proof: Vec<String>,
+    /// Whether the proof checks out against the recomputed merkle root.
+    pub proof_valid: bool,
+}
+
+impl ClaimVerification {
+    fn new(node: &TreeNode, merkle_root: &Hash) -> Self {
+        let proof = node.proof.clone().unwrap_or_default();
+        let leaf = hashv(&[&[0u8], node.hash().as_ref()]);
+        Self {
+            claimant: node.claimant.to_string(),
+            staker: node.staker_pubkey.to_string(),
+            withdrawer: node.withdrawer_pubkey.to_string(),
+            amount: node.amount,
+            proof: proof
+                .iter()
+                .map(|hash| Hash::new_from_array(*hash).to_string())
+                .collect(),
+            proof_valid: merkle_proof::verify(proof, merkle_root.to_bytes(), leaf.to_bytes()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TreeVerification {
+    pub tip_distribution_account: String,
+    pub merkle_root_upload_authority: String,
+    pub merkle_root: String,
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub verification: MerkleRootVerification,
+    pub claims: Vec<ClaimVerification>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationReport {
+    pub epoch: u64,
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub trees: Vec<TreeVerification>,
+}
+
+impl VerificationReport {
+    /// True when every merkle root matches and every proof is valid. With `allow_missing`, roots
+    /// that haven't been uploaded yet and accounts that don't exist, e.g. because they were closed
+    /// after expiring, don't fail verification.
+    pub fn is_verified(&self, allow_missing: bool) -> bool {
+        self.trees.iter().all(|tree| {
+            let root_verified = match tree.verification {
+                MerkleRootVerification::Match => true,
+                MerkleRootVerification::NotUploaded | MerkleRootVerification::MissingAccount => {
+                    allow_missing
+                }
+                MerkleRootVerification::Mismatch { .. }
+                | MerkleRootVerification::InvalidAccount { .. } => false,
+            };
+            root_verified && tree.claims.iter().all(|claim| claim.proof_valid)
+        })
+    }
+
+    pub fn new(
+        merkle_trees: &GeneratedMerkleTreeCollection,
+        tip_distribution_accounts: &[Option<&Account>],
+        filter: &VerifyFilter,
+    ) -> Self {
+        let trees = merkle_trees
+            .generated_merkle_trees
+            .iter()
+            .zip(tip_distribution_accounts)
+            .map(|(tree, tip_distribution_account)| TreeVerification {
+                tip_distribution_account: tree.tip_distribution_account.to_string(),
+                merkle_root_upload_authority: tree.merkle_root_upload_authority.to_string(),
+                merkle_root: tree.merkle_root.to_string(),
+                max_total_claim: tree.max_total_claim,
+                max_num_nodes: tree.max_num_nodes,
+                verification: MerkleRootVerification::new(tree, *tip_distribution_account),
+                claims: tree
+                    .tree_nodes
+                    .iter()
+                    .filter(|node| filter.matches_node(node))
+                    .map(|node| ClaimVerification::new(node, &tree.merkle_root))
+                    .collect(),
+            })
+            .filter(|tree| filter.claimant_or_authority.is_none() || !tree.claims.is_empty())
+            .collect();
+
+        Self {
+            epoch: merkle_trees.epoch,
+            slot: merkle_trees.slot,
+            bank_hash: merkle_trees.bank_hash.clone(),
+            trees,
+        }
+    }
+}
+
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Epoch {} (slot {}, bank hash {})",
+            self.epoch, self.slot, self.bank_hash
+        )?;
+        for tree in &self.trees {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Tip distribution account: {}",
+                tree.tip_distribution_account
+            )?;
+            writeln!(f, "  Recomputed merkle root: {}", tree.merkle_root)?;
+            writeln!(
+                f,
+                "  Max total claim: {} lamports across {} nodes",
+                tree.max_total_claim, tree.max_num_nodes
+            )?;
+            match &tree.verification {
+                MerkleRootVerification::Match => writeln!(f, "  On-chain merkle root: matches")?,
+                MerkleRootVerification::Mismatch {
+                    uploaded_root,
+                    uploaded_max_total_claim,
+                    uploaded_max_num_nodes,
+                } => writeln!(
+                    f,
+                    "  On-chain merkle root: MISMATCH, uploaded {uploaded_root} with max total claim {uploaded_max_total_claim} lamports across {uploaded_max_num_nodes} nodes"
+                )?,
+                MerkleRootVerification::NotUploaded => {
+                    writeln!(f, "  On-chain merkle root: not uploaded")?
+                }
+                MerkleRootVerification::MissingAccount => {
+                    writeln!(f, "  On-chain merkle root: account not found")?
+                }
+                MerkleRootVerification::InvalidAccount { error } => writeln!(
+                    f,
+                    "  On-chain merkle root: INVALID ACCOUNT, failed to deserialize: {error}"
+                )?,
+            }
+            for claim in &tree.claims {
+                writeln!(
+                    f,
+                    "  Claimant {} (staker {}, withdrawer {}): {} lamports, proof {}",
+                    claim.claimant,
+                    claim.staker,
+                    claim.withdrawer,
+                    claim.amount,
+                    if claim.proof_valid {
+                        "valid"
+                    } else {
+                        "INVALID"
+                    }
+                )?;
+                for hash in &claim.proof {
+                    writeln!(f, "    {hash}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recomputes the merkle trees for the epoch of the snapshot at `snapshot_slot` and checks them
+/// against the merkle roots uploaded on chain.
+pub fn verify_merkle_roots(
+    ledger_path: &Path,
+    snapshot_slot: &Slot,
+    tip_distribution_program_id: &Pubkey,
+    tip_payment_program_id: &Pubkey,
+    rpc_url: &str,
+    filter: &VerifyFilter,
+) -> Result<VerificationReport, VerifyError> {
+    info!("Creating bank from ledger path...");
+    let bank = create_bank_from_snapshot(ledger_path, snapshot_slot)?;
+
+    info!("Recomputing stake meta...");
+    let mut stake_meta_coll =
+        generate_stake_meta_collection(&bank, tip_distribution_program_id, tip_payment_program_id)?;
+    if let Some(validator_vote_account) = filter.validator_vote_account {
+        stake_meta_coll
+            .stake_metas
+            .retain(|stake_meta| stake_meta.validator_vote_account == validator_vote_account);
+    }
+
+    info!("Rebuilding merkle trees...");
+    let merkle_trees =
+        GeneratedMerkleTreeCollection::new_from_stake_meta_collection(stake_meta_coll, None)?;
+
+    info!("Fetching uploaded merkle roots...");
+    let tda_pubkeys: Vec<Pubkey> = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect();
+    let runtime = Builder::new_multi_thread().enable_all().build()?;
+    let tdas = runtime.block_on(async {
+        let rpc_client =
+            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+        crate::get_batched_accounts(&rpc_client, &tda_pubkeys).await
+    })?;
+    let tip_distribution_accounts: Vec<Option<&Account>> = tda_pubkeys
+        .iter()
+        .map(|pubkey| tdas.get(pubkey).and_then(Option::as_ref))
+        .collect();
+
+    Ok(VerificationReport::new(
+        &merkle_trees,
+        &tip_distribution_accounts,
+        filter,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{Delegation, StakeMeta, StakeMetaCollection, TipDistributionMeta},
+        anchor_lang::AccountSerialize,
+        jito_tip_distribution::state::MerkleRoot,
+    };
+
+    fn merkle_trees(staker: &Pubkey) -> GeneratedMerkleTreeCollection {
+        let stake_meta = |delegations: Vec<Delegation>| StakeMeta {
+            validator_vote_account: Pubkey::new_unique(),
+            validator_node_pubkey: Pubkey::new_unique(),
+            maybe_tip_distribution_meta: Some(TipDistributionMeta {
+                merkle_root_upload_authority: Pubkey::new_unique(),
+                tip_distribution_pubkey: Pubkey::new_unique(),
+                total_tips: 1_000_000,
+                validator_fee_bps: 500,
+            }),
+            total_delegated: delegations.iter().map(|d| d.lamports_delegated).sum(),
+            delegations,
+            commission: 0,
+        };
+        let delegation = |staker_pubkey: Pubkey, lamports_delegated: u64| Delegation {
+            stake_account_pubkey: Pubkey::new_unique(),
+            staker_pubkey,
+            withdrawer_pubkey: staker_pubkey,
+            lamports_delegated,
+        };
+
+        GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
+            StakeMetaCollection {
+                stake_metas: vec![
+                    stake_meta(vec![
+                        delegation(*staker, 10_000),
+                        delegation(Pubkey::new_unique(), 30_000),
+                    ]),
+                    stake_meta(vec![delegation(Pubkey::new_unique(), 5_000)]),
+                ],
+                tip_distribution_program_id: Pubkey::new_unique(),
+                bank_hash: Hash::new_unique().to_string(),
+                epoch: 100,
+                slot: 43_200_000,
+            },
+            None,
+        )
+        .unwrap()
+    }
+
+    fn uploaded(tree: &GeneratedMerkleTree, root: [u8; 32]) -> TipDistributionAccount {
+        TipDistributionAccount {
+            merkle_root: Some(MerkleRoot {
+                root,
+                max_total_claim: tree.max_total_claim,
+                max_num_nodes: tree.max_num_nodes,
+                total_funds_claimed: 0,
+                num_nodes_claimed: 0,
+            }),
+            ..TipDistributionAccount::default()
+        }
+    }
+
+    fn account(tip_distribution_account: &TipDistributionAccount) -> Account {
+        let mut data = vec![];
+        tip_distribution_account.try_serialize(&mut data).unwrap();
+        Account {
+            data,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_verification_report() {
+        let staker = Pubkey::new_unique();
+        let merkle_trees = merkle_trees(&staker);
+        let trees = &merkle_trees.generated_merkle_trees;
+
+        let report = VerificationReport::new(
+            &merkle_trees,
+            &[
+                Some(&account(&uploaded(
+                    &trees[0],
+                    trees[0].merkle_root.to_bytes(),
+                ))),
+                None,
+            ],
+            &VerifyFilter::default(),
+        );
+        // the missing account only passes when explicitly allowed
+        assert!(!report.is_verified(false));
+        assert!(report.is_verified(true));
+        assert_eq!(report.trees.len(), 2);
+        assert_eq!(report.trees[0].verification, MerkleRootVerification::Match);
+        assert_eq!(
+            report.trees[1].verification,
+            MerkleRootVerification::MissingAccount
+        );
+        // validator + delegators, all with valid proofs
+        assert_eq!(report.trees[0].claims.len(), 3);
+        assert!(report.trees[0].claims.iter().all(|claim| claim.proof_valid));
+        assert_eq!(
+            report.trees[0]
+                .claims
+                .iter()
+                .map(|claim| claim.amount)
+                .sum::<u64>(),
+            trees[0]
+                .tree_nodes
+                .iter()
+                .map(|node| node.amount)
+                .sum::<u64>()
+        );
+
+        // only the staker's claim is reported when filtering by staker
+        let report = VerificationReport::new(
+            &merkle_trees,
+            &[
+                Some(&account(&uploaded(&trees[0], [7; 32]))),
+                Some(&account(&TipDistributionAccount::default())),
+            ],
+            &VerifyFilter {
+                claimant_or_authority: Some(staker),
+                ..VerifyFilter::default()
+            },
+        );
+        assert!(!report.is_verified(true));
+        assert_eq!(report.trees.len(), 1);
+        assert_eq!(report.trees[0].claims.len(), 1);
+        assert_eq!(report.trees[0].claims[0].staker, staker.to_string());
+        assert!(matches!(
+            report.trees[0].verification,
+            MerkleRootVerification::Mismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_missing_and_invalid_accounts() {
+        let merkle_trees = merkle_trees(&Pubkey::new_unique());
+        let trees = &merkle_trees.generated_merkle_trees;
+        let matching = account(&uploaded(&trees[0], trees[0].merkle_root.to_bytes()));
+
+        // nothing uploaded, or the accounts are gone
+        for accounts in [
+            [None, None],
+            [Some(&account(&TipDistributionAccount::default())), None],
+        ] {
+            let report =
+                VerificationReport::new(&merkle_trees, &accounts, &VerifyFilter::default());
+            assert!(!report.is_verified(false));
+            assert!(report.is_verified(true));
+        }
+
+        // accounts that don't deserialize fail verification, even when missing ones are allowed
+        let garbage = Account {
+            data: vec![7; 256],
+            ..Account::default()
+        };
+        let truncated = Account {
+            data: matching.data[..8].to_vec(),
+            ..Account::default()
+        };
+        for invalid in [&garbage, &truncated] {
+            let report = VerificationReport::new(
+                &merkle_trees,
+                &[Some(&matching), Some(invalid)],
+                &VerifyFilter::default(),
+            );
+            assert_eq!(report.trees[0].verification, MerkleRootVerification::Match);
+            assert!(matches!(
+                report.trees[1].verification,
+                MerkleRootVerification::InvalidAccount { .. }
+            ));
+            assert!(!report.is_verified(false));
+            assert!(!report.is_verified(true));
+        }
+    }
+
+    #[test]
+    fn test_invalid_proof() {
+        let merkle_trees = merkle_trees(&Pubkey::new_unique());
+        let mut node = merkle_trees.generated_merkle_trees[0].tree_nodes[1].clone();
+        let root = merkle_trees.generated_merkle_trees[0].merkle_root;
+        assert!(ClaimVerification::new(&node, &root).proof_valid);
+
+        node.amount += 1;
+        assert!(!ClaimVerification::new(&node, &root).proof_valid);
+    }
+}
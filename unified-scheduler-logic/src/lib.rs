@@ -1,15 +1,55 @@
-use solana_sdk::transaction::SanitizedTransaction;
+//! The task (transaction) scheduling logic of the unified scheduler.
+//!
+//! [`SchedulingStateMachine`] keeps a queue of usages for every address locked by the tasks it's
+//! given. A task is runnable once it holds all of its addresses; otherwise it waits in the queues
+//! of the addresses it couldn't lock and is unblocked as the tasks ahead of it are descheduled.
+//!
+//! Usages are granted strictly in the order the tasks were scheduled: a task never overtakes an
+//! earlier task it conflicts with, i.e. one writing an address the task uses or reading an address
+//! the task writes. Readers only share an address with the readers right next to them in its
+//! queue. This makes the outcome deterministic and equivalent to `blockstore_processor`'s
+//! batching, where conflicting transactions execute in the order they appear in the ledger and
+//! non-conflicting ones may execute in parallel.
+//!
+//! The state machine isn't thread safe by itself; it's meant to be driven by a single scheduler
+//! thread, which dispatches the runnable tasks to handler threads.
 
-pub struct Task {
+use {
+    solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+    std::{
+        collections::{hash_map::Entry, HashMap, VecDeque},
+        sync::{
+            atomic::{AtomicU32, Ordering::Relaxed},
+            Arc,
+        },
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestedUsage {
+    Readonly,
+    Writable,
+}
+
+#[derive(Debug)]
+struct LockContext {
+    address: Pubkey,
+    requested_usage: RequestedUsage,
+}
+
+pub type Task = Arc<TaskInner>;
+
+#[derive(Debug)]
+pub struct TaskInner {
     transaction: SanitizedTransaction,
     index: usize,
+    lock_contexts: Vec<LockContext>,
+    /// Number of addresses this task is still waiting for. Only ever accessed by the thread
+    /// driving the [`SchedulingStateMachine`], the atomic merely lets tasks be sent to handlers.
+    blocked_usage_count: AtomicU32,
 }
 
-impl Task {
-    pub fn create_task(transaction: SanitizedTransaction, index: usize) -> Self {
-        Task { transaction, index }
-    }
-
+impl TaskInner {
     pub fn task_index(&self) -> usize {
         self.index
     }
@@ -17,4 +57,520 @@ impl Task {
     pub fn transaction(&self) -> &SanitizedTransaction {
         &self.transaction
     }
+
+    fn blocked_usage_count(&self) -> u32 {
+        self.blocked_usage_count.load(Relaxed)
+    }
+
+    fn set_blocked_usage_count(&self, count: u32) {
+        self.blocked_usage_count.store(count, Relaxed)
+    }
+}
+
+#[derive(Debug)]
+enum Usage {
+    Readonly(u32),
+    Writable,
+}
+
+impl From<RequestedUsage> for Usage {
+    fn from(requested_usage: RequestedUsage) -> Self {
+        match requested_usage {
+            RequestedUsage::Readonly => Usage::Readonly(1),
+            RequestedUsage::Writable => Usage::Writable,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct UsageQueue {
+    current_usage: Option<Usage>,
+    blocked_usages_from_tasks: VecDeque<(RequestedUsage, Task)>,
+}
+
+impl UsageQueue {
+    fn try_lock(&mut self, requested_usage: RequestedUsage) -> bool {
+        match (&mut self.current_usage, requested_usage) {
+            (None, _) => {}
+            (Some(Usage::Readonly(count)), RequestedUsage::Readonly) => {
+                *count = count.checked_add(1).unwrap();
+                return true;
+            }
+            (Some(Usage::Readonly(_)), RequestedUsage::Writable) | (Some(Usage::Writable), _) => {
+                return false;
+            }
+        }
+        self.current_usage = Some(Usage::from(requested_usage));
+        true
+    }
+
+    /// Releases a usage. Returns the first blocked usage if the address isn't used anymore.
+    #[must_use]
+    fn unlock(&mut self, requested_usage: RequestedUsage) -> Option<(RequestedUsage, Task)> {
+        let is_unused = match (&mut self.current_usage, requested_usage) {
+            (Some(Usage::Readonly(count)), RequestedUsage::Readonly) => {
+                *count = count.checked_sub(1).unwrap();
+                *count == 0
+            }
+            (Some(Usage::Writable), RequestedUsage::Writable) => true,
+            (current_usage, requested_usage) => {
+                unreachable!("unlocking {requested_usage:?} while the usage is {current_usage:?}")
+            }
+        };
+        if is_unused {
+            self.current_usage = None;
+            self.blocked_usages_from_tasks.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Pops the next blocked usage if it can share the address with the current readers.
+    fn pop_blocked_readonly_usage(&mut self) -> Option<(RequestedUsage, Task)> {
+        let can_share = matches!(
+            (&self.current_usage, self.blocked_usages_from_tasks.front()),
+            (
+                Some(Usage::Readonly(_)),
+                Some((RequestedUsage::Readonly, _))
+            )
+        );
+        if can_share {
+            self.blocked_usages_from_tasks.pop_front()
+        } else {
+            None
+        }
+    }
+
+    fn has_no_blocked_usage(&self) -> bool {
+        self.blocked_usages_from_tasks.is_empty()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.current_usage.is_none() && self.has_no_blocked_usage()
+    }
+}
+
+/// Tracks the address usages of the scheduled tasks and decides which of them can run.
+#[derive(Debug, Default)]
+pub struct SchedulingStateMachine {
+    unblocked_task_queue: VecDeque<Task>,
+    /// Only addresses used or waited for by an active task have a queue
+    usage_queues: HashMap<Pubkey, UsageQueue>,
+    active_task_count: u32,
+    handled_task_count: u32,
+    unblocked_task_count: u32,
+    total_task_count: u32,
+}
+
+impl SchedulingStateMachine {
+    pub fn create_task(transaction: SanitizedTransaction, index: usize) -> Task {
+        let message = transaction.message();
+        let lock_contexts = message
+            .account_keys()
+            .iter()
+            .enumerate()
+            .map(|(account_index, address)| LockContext {
+                address: *address,
+                requested_usage: if message.is_writable(account_index) {
+                    RequestedUsage::Writable
+                } else {
+                    RequestedUsage::Readonly
+                },
+            })
+            .collect();
+
+        Arc::new(TaskInner {
+            transaction,
+            index,
+            lock_contexts,
+            blocked_usage_count: AtomicU32::default(),
+        })
+    }
+
+    pub fn has_no_active_task(&self) -> bool {
+        self.active_task_count == 0
+    }
+
+    pub fn has_unblocked_task(&self) -> bool {
+        !self.unblocked_task_queue.is_empty()
+    }
+
+    pub fn unblocked_task_queue_count(&self) -> usize {
+        self.unblocked_task_queue.len()
+    }
+
+    /// Number of tasks scheduled but not descheduled yet, whether blocked or running
+    pub fn active_task_count(&self) -> u32 {
+        self.active_task_count
+    }
+
+    pub fn handled_task_count(&self) -> u32 {
+        self.handled_task_count
+    }
+
+    /// Number of tasks which were blocked and later returned by
+    /// [`Self::schedule_next_unblocked_task`]
+    pub fn unblocked_task_count(&self) -> u32 {
+        self.unblocked_task_count
+    }
+
+    pub fn total_task_count(&self) -> u32 {
+        self.total_task_count
+    }
+
+    /// Schedules `task`, returning it back if it can run right away. Otherwise, it's returned by
+    /// [`Self::schedule_next_unblocked_task`] once the tasks it conflicts with are descheduled.
+    #[must_use]
+    pub fn schedule_task(&mut self, task: Task) -> Option<Task> {
+        self.total_task_count = self.total_task_count.checked_add(1).unwrap();
+        self.active_task_count = self.active_task_count.checked_add(1).unwrap();
+
+        let mut blocked_usage_count: u32 = 0;
+        for context in &task.lock_contexts {
+            let usage_queue = self.usage_queues.entry(context.address).or_default();
+            // a usage must not overtake the blocked ones, or conflicting tasks could run out of
+            // order
+            if usage_queue.has_no_blocked_usage() && usage_queue.try_lock(context.requested_usage) {
+                continue;
+            }
+            usage_queue
+                .blocked_usages_from_tasks
+                .push_back((context.requested_usage, task.clone()));
+            blocked_usage_count = blocked_usage_count.checked_add(1).unwrap();
+        }
+
+        if blocked_usage_count == 0 {
+            Some(task)
+        } else {
+            task.set_blocked_usage_count(blocked_usage_count);
+            None
+        }
+    }
+
+    /// Returns the next task which became runnable, in the order they were unblocked.
+    #[must_use]
+    pub fn schedule_next_unblocked_task(&mut self) -> Option<Task> {
+        let task = self.unblocked_task_queue.pop_front()?;
+        self.unblocked_task_count = self.unblocked_task_count.checked_add(1).unwrap();
+        Some(task)
+    }
+
+    /// Releases the addresses of a task which finished running, unblocking the tasks waiting for
+    /// them.
+    pub fn deschedule_task(&mut self, task: &Task) {
+        self.active_task_count = self.active_task_count.checked_sub(1).unwrap();
+        self.handled_task_count = self.handled_task_count.checked_add(1).unwrap();
+
+        for context in &task.lock_contexts {
+            let Entry::Occupied(mut entry) = self.usage_queues.entry(context.address) else {
+                panic!("no usage queue for {} of a scheduled task", context.address);
+            };
+            let usage_queue = entry.get_mut();
+
+            let mut next_usage = usage_queue.unlock(context.requested_usage);
+            while let Some((requested_usage, blocked_task)) = next_usage {
+                assert!(usage_queue.try_lock(requested_usage));
+                let blocked_usage_count =
+                    blocked_task.blocked_usage_count().checked_sub(1).unwrap();
+                blocked_task.set_blocked_usage_count(blocked_usage_count);
+                if blocked_usage_count == 0 {
+                    self.unblocked_task_queue.push_back(blocked_task);
+                }
+                next_usage = usage_queue.pop_blocked_readonly_usage();
+            }
+
+            if usage_queue.is_idle() {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Resets the counters so this state machine can be reused for the next session.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's still an active task.
+    pub fn reinitialize(&mut self) {
+        assert!(self.has_no_active_task());
+        assert!(self.unblocked_task_queue.is_empty());
+        assert!(self.usage_queues.is_empty());
+        self.handled_task_count = 0;
+        self.unblocked_task_count = 0;
+        self.total_task_count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            message::Message,
+            signature::{Keypair, Signer},
+            transaction::Transaction,
+        },
+    };
+
+    // each test transaction has its own fee payer, so tasks only conflict on the given addresses
+    fn create_task(index: usize, accounts: &[(Pubkey, RequestedUsage)]) -> Task {
+        let payer = Keypair::new();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            accounts
+                .iter()
+                .map(|(address, requested_usage)| match requested_usage {
+                    RequestedUsage::Readonly => AccountMeta::new_readonly(*address, false),
+                    RequestedUsage::Writable => AccountMeta::new(*address, false),
+                })
+                .collect(),
+        );
+        let message = Message::new(&[instruction], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[&payer], message, Hash::default());
+        SchedulingStateMachine::create_task(
+            SanitizedTransaction::from_transaction_for_tests(transaction),
+            index,
+        )
+    }
+
+    fn task_index(task: Option<Task>) -> Option<usize> {
+        task.map(|task| task.task_index())
+    }
+
+    #[test]
+    fn test_create_task() {
+        let readonly = Pubkey::new_unique();
+        let writable = Pubkey::new_unique();
+        let task = create_task(
+            3,
+            &[
+                (readonly, RequestedUsage::Readonly),
+                (writable, RequestedUsage::Writable),
+            ],
+        );
+
+        assert_eq!(task.task_index(), 3);
+        let requested_usage = |address| {
+            task.lock_contexts
+                .iter()
+                .find(|context| context.address == address)
+                .map(|context| context.requested_usage)
+        };
+        assert_eq!(
+            requested_usage(*task.transaction().message().fee_payer()),
+            Some(RequestedUsage::Writable)
+        );
+        assert_eq!(requested_usage(readonly), Some(RequestedUsage::Readonly));
+        assert_eq!(requested_usage(writable), Some(RequestedUsage::Writable));
+    }
+
+    #[test]
+    fn test_non_conflicting_tasks() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let shared = Pubkey::new_unique();
+        let task0 = create_task(0, &[(shared, RequestedUsage::Readonly)]);
+        let task1 = create_task(1, &[(shared, RequestedUsage::Readonly)]);
+        let task2 = create_task(2, &[(Pubkey::new_unique(), RequestedUsage::Writable)]);
+
+        assert_eq!(
+            task_index(state_machine.schedule_task(task0.clone())),
+            Some(0)
+        );
+        assert_eq!(
+            task_index(state_machine.schedule_task(task1.clone())),
+            Some(1)
+        );
+        assert_eq!(
+            task_index(state_machine.schedule_task(task2.clone())),
+            Some(2)
+        );
+        assert_eq!(state_machine.active_task_count(), 3);
+        assert!(!state_machine.has_unblocked_task());
+
+        for task in [&task1, &task0, &task2] {
+            state_machine.deschedule_task(task);
+        }
+        assert!(state_machine.has_no_active_task());
+        assert_eq!(state_machine.handled_task_count(), 3);
+        assert_eq!(state_machine.unblocked_task_count(), 0);
+        state_machine.reinitialize();
+        assert_eq!(state_machine.total_task_count(), 0);
+    }
+
+    #[test]
+    fn test_conflicting_writes_run_in_order() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let address = Pubkey::new_unique();
+        let task0 = create_task(0, &[(address, RequestedUsage::Writable)]);
+        let task1 = create_task(1, &[(address, RequestedUsage::Writable)]);
+        let task2 = create_task(2, &[(address, RequestedUsage::Writable)]);
+
+        assert_eq!(
+            task_index(state_machine.schedule_task(task0.clone())),
+            Some(0)
+        );
+        assert_eq!(task_index(state_machine.schedule_task(task1.clone())), None);
+        assert_eq!(task_index(state_machine.schedule_task(task2.clone())), None);
+        assert!(state_machine.schedule_next_unblocked_task().is_none());
+
+        state_machine.deschedule_task(&task0);
+        assert_eq!(state_machine.unblocked_task_queue_count(), 1);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(1)
+        );
+        assert!(!state_machine.has_unblocked_task());
+
+        state_machine.deschedule_task(&task1);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(2)
+        );
+        state_machine.deschedule_task(&task2);
+
+        assert!(state_machine.has_no_active_task());
+        assert_eq!(state_machine.total_task_count(), 3);
+        assert_eq!(state_machine.unblocked_task_count(), 2);
+        state_machine.reinitialize();
+    }
+
+    #[test]
+    fn test_readers_are_unblocked_together() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let address = Pubkey::new_unique();
+        let writer0 = create_task(0, &[(address, RequestedUsage::Writable)]);
+        let reader1 = create_task(1, &[(address, RequestedUsage::Readonly)]);
+        let reader2 = create_task(2, &[(address, RequestedUsage::Readonly)]);
+        let writer3 = create_task(3, &[(address, RequestedUsage::Writable)]);
+        let reader4 = create_task(4, &[(address, RequestedUsage::Readonly)]);
+
+        assert_eq!(
+            task_index(state_machine.schedule_task(writer0.clone())),
+            Some(0)
+        );
+        for task in [&reader1, &reader2, &writer3, &reader4] {
+            assert_eq!(task_index(state_machine.schedule_task(task.clone())), None);
+        }
+
+        // both readers take over from the writer, the reader after the next writer keeps waiting
+        state_machine.deschedule_task(&writer0);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(1)
+        );
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(2)
+        );
+        assert!(!state_machine.has_unblocked_task());
+
+        state_machine.deschedule_task(&reader2);
+        assert!(!state_machine.has_unblocked_task());
+        state_machine.deschedule_task(&reader1);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(3)
+        );
+
+        state_machine.deschedule_task(&writer3);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(4)
+        );
+        state_machine.deschedule_task(&reader4);
+        state_machine.reinitialize();
+    }
+
+    #[test]
+    fn test_reader_does_not_overtake_blocked_writer() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let address = Pubkey::new_unique();
+        let reader0 = create_task(0, &[(address, RequestedUsage::Readonly)]);
+        let writer1 = create_task(1, &[(address, RequestedUsage::Writable)]);
+        let reader2 = create_task(2, &[(address, RequestedUsage::Readonly)]);
+
+        assert_eq!(
+            task_index(state_machine.schedule_task(reader0.clone())),
+            Some(0)
+        );
+        assert_eq!(
+            task_index(state_machine.schedule_task(writer1.clone())),
+            None
+        );
+        // the address is only read right now, but the writer is waiting for it
+        assert_eq!(
+            task_index(state_machine.schedule_task(reader2.clone())),
+            None
+        );
+
+        state_machine.deschedule_task(&reader0);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(1)
+        );
+        assert!(!state_machine.has_unblocked_task());
+        state_machine.deschedule_task(&writer1);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(2)
+        );
+        state_machine.deschedule_task(&reader2);
+        state_machine.reinitialize();
+    }
+
+    #[test]
+    fn test_task_blocked_on_multiple_addresses() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let address_a = Pubkey::new_unique();
+        let address_b = Pubkey::new_unique();
+        let task0 = create_task(0, &[(address_a, RequestedUsage::Writable)]);
+        let task1 = create_task(1, &[(address_b, RequestedUsage::Writable)]);
+        let task2 = create_task(
+            2,
+            &[
+                (address_a, RequestedUsage::Readonly),
+                (address_b, RequestedUsage::Writable),
+            ],
+        );
+        // only conflicts with task2 through address_b, which task2 is waiting for
+        let task3 = create_task(3, &[(address_b, RequestedUsage::Readonly)]);
+
+        assert_eq!(
+            task_index(state_machine.schedule_task(task0.clone())),
+            Some(0)
+        );
+        assert_eq!(
+            task_index(state_machine.schedule_task(task1.clone())),
+            Some(1)
+        );
+        assert_eq!(task_index(state_machine.schedule_task(task2.clone())), None);
+        assert_eq!(task_index(state_machine.schedule_task(task3.clone())), None);
+
+        state_machine.deschedule_task(&task0);
+        assert!(!state_machine.has_unblocked_task());
+        state_machine.deschedule_task(&task1);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(2)
+        );
+        assert!(!state_machine.has_unblocked_task());
+        state_machine.deschedule_task(&task2);
+        assert_eq!(
+            task_index(state_machine.schedule_next_unblocked_task()),
+            Some(3)
+        );
+        state_machine.deschedule_task(&task3);
+        state_machine.reinitialize();
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: self.has_no_active_task()")]
+    fn test_reinitialize_with_active_task() {
+        let mut state_machine = SchedulingStateMachine::default();
+        let task = create_task(0, &[(Pubkey::new_unique(), RequestedUsage::Writable)]);
+        let _task = state_machine.schedule_task(task);
+        state_machine.reinitialize();
+    }
 }
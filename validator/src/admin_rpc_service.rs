@@ -14,17 +14,22 @@ use {
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         consensus::{tower_storage::TowerStorage, Tower},
         proxy::{
-            block_engine_stage::{BlockEngineConfig, BlockEngineStage},
+            block_engine_stage::{BlockEngineConfig, BlockEngineStage, BlockEngineStatus},
+            fetch_stage_manager::FetchStageModeOverride,
             relayer_stage::{RelayerConfig, RelayerStage},
+            ProxyConnectionStatus,
         },
         repair::repair_service,
         validator::ValidatorStartProgress,
     },
-    solana_geyser_plugin_manager::GeyserPluginManagerRequest,
+    solana_geyser_plugin_manager::{GeyserPluginInfo, GeyserPluginManagerRequest},
     solana_gossip::contact_info::{ContactInfo, Protocol, SOCKET_ADDR_UNSPECIFIED},
-    solana_rpc::rpc::verify_pubkey,
-    solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
+    solana_rpc::rpc::{utils::get_tip_revenue, verify_pubkey},
+    solana_rpc_client_api::{
+        bundles::RpcTipRevenue, config::RpcAccountIndex, custom_error::RpcCustomError,
+    },
     solana_sdk::{
+        clock::Slot,
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
@@ -94,6 +99,25 @@ pub struct AdminRpcRepairWhitelist {
     pub whitelist: Vec<Pubkey>,
 }
 
+/// Block engine urls accepted by `setBlockEngineConfig`, either a single url as sent by older
+/// clients or a list of urls to fail over between
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AdminRpcBlockEngineUrls {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl From<AdminRpcBlockEngineUrls> for Vec<String> {
+    fn from(urls: AdminRpcBlockEngineUrls) -> Self {
+        match urls {
+            AdminRpcBlockEngineUrls::Single(url) if url.is_empty() => vec![],
+            AdminRpcBlockEngineUrls::Single(url) => vec![url],
+            AdminRpcBlockEngineUrls::List(urls) => urls,
+        }
+    }
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -167,7 +191,7 @@ pub trait AdminRpc {
     fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> BoxFuture<Result<String>>;
 
     #[rpc(meta, name = "listPlugins")]
-    fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>>;
+    fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<GeyserPluginInfo>>>;
 
     #[rpc(meta, name = "rpcAddress")]
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>>;
@@ -253,10 +277,13 @@ pub trait AdminRpc {
     fn set_block_engine_config(
         &self,
         meta: Self::Metadata,
-        block_engine_url: String,
+        block_engine_urls: AdminRpcBlockEngineUrls,
         trust_packets: bool,
     ) -> Result<()>;
 
+    #[rpc(meta, name = "getBlockEngineStatus")]
+    fn get_block_engine_status(&self, meta: Self::Metadata) -> Result<BlockEngineStatus>;
+
     #[rpc(meta, name = "setRelayerConfig")]
     fn set_relayer_config(
         &self,
@@ -267,8 +294,29 @@ pub trait AdminRpc {
         max_failed_heartbeats: u64,
     ) -> Result<()>;
 
+    #[rpc(meta, name = "getRelayerStatus")]
+    fn get_relayer_status(&self, meta: Self::Metadata) -> Result<ProxyConnectionStatus>;
+
+    #[rpc(meta, name = "setFetchStageMode")]
+    fn set_fetch_stage_mode(&self, meta: Self::Metadata, mode: String) -> Result<()>;
+
     #[rpc(meta, name = "setShredReceiverAddress")]
     fn set_shred_receiver_address(&self, meta: Self::Metadata, addr: String) -> Result<()>;
+
+    #[rpc(meta, name = "reloadBundleDenylist")]
+    fn reload_bundle_denylist(
+        &self,
+        meta: Self::Metadata,
+        policy_file: Option<String>,
+    ) -> Result<usize>;
+
+    #[rpc(meta, name = "getTipRevenue")]
+    fn get_tip_revenue(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+    ) -> Result<Vec<RpcTipRevenue>>;
 }
 
 pub struct AdminRpcImpl;
@@ -390,7 +438,7 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
-    fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>> {
+    fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<GeyserPluginInfo>>> {
         Box::pin(async move {
             // Construct channel for plugin to respond to this particular rpc request instance
             let (response_sender, response_receiver) = oneshot_channel();
@@ -470,12 +518,12 @@ impl AdminRpc for AdminRpcImpl {
     fn set_block_engine_config(
         &self,
         meta: Self::Metadata,
-        block_engine_url: String,
+        block_engine_urls: AdminRpcBlockEngineUrls,
         trust_packets: bool,
     ) -> Result<()> {
         debug!("set_block_engine_config request received");
         let config = BlockEngineConfig {
-            block_engine_url,
+            block_engine_urls: block_engine_urls.into(),
             trust_packets,
         };
         // Detailed log messages are printed inside validate function
@@ -491,6 +539,16 @@ impl AdminRpc for AdminRpcImpl {
         }
     }
 
+    fn get_block_engine_status(&self, meta: Self::Metadata) -> Result<BlockEngineStatus> {
+        debug!("get_block_engine_status request received");
+        meta.with_post_init(|post_init| Ok(post_init.block_engine_status.read().unwrap().clone()))
+    }
+
+    fn get_relayer_status(&self, meta: Self::Metadata) -> Result<ProxyConnectionStatus> {
+        debug!("get_relayer_status request received");
+        meta.with_post_init(|post_init| Ok(post_init.relayer_status.read().unwrap().clone()))
+    }
+
     fn set_identity(
         &self,
         meta: Self::Metadata,
@@ -556,6 +614,17 @@ impl AdminRpc for AdminRpcImpl {
         }
     }
 
+    fn set_fetch_stage_mode(&self, meta: Self::Metadata, mode: String) -> Result<()> {
+        debug!("set_fetch_stage_mode request received");
+        let mode_override = FetchStageModeOverride::from_str(&mode)
+            .map_err(jsonrpc_core::error::Error::invalid_params)?;
+        meta.with_post_init(|post_init| {
+            *post_init.fetch_stage_mode_override.write().unwrap() = mode_override;
+            info!("fetch stage mode override set to {mode_override}");
+            Ok(())
+        })
+    }
+
     fn set_shred_receiver_address(&self, meta: Self::Metadata, addr: String) -> Result<()> {
         let shred_receiver_address = if addr.is_empty() {
             None
@@ -574,6 +643,45 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn reload_bundle_denylist(
+        &self,
+        meta: Self::Metadata,
+        policy_file: Option<String>,
+    ) -> Result<usize> {
+        meta.with_post_init(|post_init| {
+            // load outside of the lock so BundleStage isn't blocked on reading the file
+            let current_bundle_denylist = post_init.bundle_denylist.read().unwrap().clone();
+            let bundle_denylist = current_bundle_denylist
+                .reload(policy_file.map(PathBuf::from))
+                .map_err(|err| {
+                    error!("Failed to reload bundle denylist: {err}");
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "failed to reload bundle denylist: {err}"
+                    ))
+                })?;
+            let num_rules = bundle_denylist.rules().len();
+            info!(
+                "Reloaded {num_rules} bundle denylist rules from {:?}",
+                bundle_denylist.policy_file()
+            );
+            *post_init.bundle_denylist.write().unwrap() = bundle_denylist;
+            Ok(num_rules)
+        })
+    }
+
+    fn get_tip_revenue(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+    ) -> Result<Vec<RpcTipRevenue>> {
+        debug!("get_tip_revenue admin rpc request received: {start_slot}-{end_slot:?}");
+        meta.with_post_init(|post_init| {
+            let end_slot = end_slot.unwrap_or_else(|| post_init.blockstore.max_root());
+            get_tip_revenue(&post_init.blockstore, start_slot, end_slot)
+        })
+    }
+
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()> {
         let loaded_config = load_staked_nodes_overrides(&path)
             .map_err(|err| {
@@ -958,9 +1066,17 @@ mod tests {
         super::*,
         serde_json::Value,
         solana_accounts_db::{accounts_index::AccountSecondaryIndexes, inline_spl_token},
-        solana_core::consensus::tower_storage::NullTowerStorage,
+        solana_core::{
+            bundle_stage::bundle_denylist::BundleDenylist,
+            consensus::tower_storage::NullTowerStorage,
+        },
         solana_gossip::cluster_info::ClusterInfo,
-        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_ledger::{
+            blockstore::Blockstore,
+            blockstore_meta::TipRevenueMeta,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
+        },
         solana_rpc::rpc::create_validator_exit,
         solana_runtime::{
             bank::{Bank, BankTestConfig},
@@ -978,8 +1094,10 @@ mod tests {
         },
         std::{
             collections::HashSet,
+            io::Write,
             sync::{atomic::AtomicBool, Mutex},
         },
+        tempfile::{NamedTempFile, TempDir},
     };
 
     #[derive(Default)]
@@ -991,6 +1109,7 @@ mod tests {
         io: MetaIoHandler<AdminRpcRequestMetadata>,
         meta: AdminRpcRequestMetadata,
         bank_forks: Arc<RwLock<BankForks>>,
+        _ledger_path: TempDir,
     }
 
     impl RpcHandler {
@@ -1020,6 +1139,8 @@ mod tests {
             let block_engine_config = Arc::new(Mutex::new(BlockEngineConfig::default()));
             let relayer_config = Arc::new(Mutex::new(RelayerConfig::default()));
             let shred_receiver_address = Arc::new(RwLock::new(None));
+            let ledger_path = get_tmp_ledger_path_auto_delete!();
+            let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
             let meta = AdminRpcRequestMetadata {
                 rpc_addr: None,
                 start_time: SystemTime::now(),
@@ -1041,8 +1162,15 @@ mod tests {
                         solana_core::cluster_slots_service::cluster_slots::ClusterSlots::default(),
                     ),
                     block_engine_config,
+                    block_engine_status: Arc::new(RwLock::new(BlockEngineStatus::default())),
                     relayer_config,
+                    relayer_status: Arc::new(RwLock::new(ProxyConnectionStatus::default())),
                     shred_receiver_address,
+                    fetch_stage_mode_override: Arc::new(RwLock::new(
+                        FetchStageModeOverride::default(),
+                    )),
+                    bundle_denylist: Arc::new(RwLock::new(BundleDenylist::default())),
+                    blockstore,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -1054,6 +1182,7 @@ mod tests {
                 io,
                 meta,
                 bank_forks,
+                _ledger_path: ledger_path,
             }
         }
 
@@ -1380,4 +1509,237 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_block_engine_config_and_endpoints() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setBlockEngineConfig","params":[["http://primary.block-engine:1003","http://backup.block-engine:1003"],false]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], Value::Null);
+        let block_engine_config = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .block_engine_config
+            .lock()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            block_engine_config,
+            BlockEngineConfig {
+                block_engine_urls: vec![
+                    "http://primary.block-engine:1003".to_string(),
+                    "http://backup.block-engine:1003".to_string(),
+                ],
+                trust_packets: false,
+            }
+        );
+
+        // A single url, as sent by older clients, is still accepted
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setBlockEngineConfig","params":["http://primary.block-engine:1003",true]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], Value::Null);
+        let block_engine_config = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .block_engine_config
+            .lock()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            block_engine_config,
+            BlockEngineConfig {
+                block_engine_urls: vec!["http://primary.block-engine:1003".to_string()],
+                trust_packets: true,
+            }
+        );
+
+        // An empty list of block engines is rejected
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setBlockEngineConfig","params":[[],false]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+
+        // Nothing is connected until the block engine stage connects
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getBlockEngineStatus"}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let status: BlockEngineStatus = serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(status, BlockEngineStatus::default());
+    }
+
+    #[test]
+    fn test_get_relayer_status() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let expected_status = ProxyConnectionStatus {
+            connected_endpoint: Some("http://relayer:11226".to_string()),
+            access_token_expiry_ms: Some(1_700_000_000_000),
+            refresh_token_expiry_ms: Some(1_700_000_600_000),
+            last_heartbeat_ms: Some(1_699_999_999_500),
+            num_bundles_received: 0,
+            num_packets_received: 42,
+            last_error: Some("heartbeat expired".to_string()),
+            last_error_ms: Some(1_699_999_000_000),
+        };
+        *meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .relayer_status
+            .write()
+            .unwrap() = expected_status.clone();
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getRelayerStatus"}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let status: ProxyConnectionStatus =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(status, expected_status);
+    }
+
+    #[test]
+    fn test_set_fetch_stage_mode() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+        let fetch_stage_mode_override = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .fetch_stage_mode_override
+            .clone();
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setFetchStageMode","params":["direct"]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_null());
+        assert_eq!(
+            *fetch_stage_mode_override.read().unwrap(),
+            FetchStageModeOverride::Direct
+        );
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setFetchStageMode","params":["tpu"]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+        assert_eq!(
+            *fetch_stage_mode_override.read().unwrap(),
+            FetchStageModeOverride::Direct
+        );
+    }
+
+    #[test]
+    fn test_reload_bundle_denylist() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+
+        // Nothing to reload when the validator was started without a policy file
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"reloadBundleDenylist","params":[null]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+
+        let denied_account = Pubkey::new_unique();
+        let mut policy_file = NamedTempFile::new().unwrap();
+        write!(
+            policy_file,
+            r#"{{"rules": [{{"name": "pools", "accounts": ["{denied_account}"]}}]}}"#
+        )
+        .unwrap();
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"reloadBundleDenylist","params":["{}"]}}"#,
+            policy_file.path().display()
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], 1);
+
+        // A bad policy file is rejected and the loaded rules are kept
+        policy_file.as_file_mut().set_len(0).unwrap();
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"reloadBundleDenylist","params":[null]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+
+        let post_init = meta.post_init.read().unwrap();
+        let bundle_denylist = post_init.as_ref().unwrap().bundle_denylist.read().unwrap();
+        assert_eq!(bundle_denylist.policy_file(), Some(policy_file.path()));
+        assert_eq!(bundle_denylist.rules().len(), 1);
+        assert!(bundle_denylist.rules()[0]
+            .accounts
+            .contains(&denied_account));
+    }
+
+    #[test]
+    fn test_get_tip_revenue() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let tip_distribution_account = Pubkey::new_unique();
+        {
+            let post_init = meta.post_init.read().unwrap();
+            let blockstore = &post_init.as_ref().unwrap().blockstore;
+            for slot in [2, 4] {
+                blockstore
+                    .write_tip_revenue(
+                        slot,
+                        &TipRevenueMeta {
+                            total_tips: slot * 1_000,
+                            block_builder_commission_pct: 5,
+                            block_builder_commission: slot * 50,
+                            tip_distribution_account,
+                            net_tip_distribution: slot * 950,
+                            ..TipRevenueMeta::default()
+                        },
+                    )
+                    .unwrap();
+            }
+        }
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getTipRevenue","params":[3, 10]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let tip_revenue: Vec<RpcTipRevenue> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(tip_revenue.len(), 1);
+        assert_eq!(tip_revenue[0].slot, 4);
+        assert_eq!(tip_revenue[0].total_tips, 4_000);
+        assert_eq!(tip_revenue[0].block_builder_commission, 200);
+        assert_eq!(tip_revenue[0].net_tip_distribution, 3_800);
+        assert_eq!(
+            tip_revenue[0].tip_distribution_account,
+            tip_distribution_account.to_string()
+        );
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getTipRevenue","params":[0, 10]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"].as_array().unwrap().len(), 2);
+    }
 }
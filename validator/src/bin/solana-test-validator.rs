@@ -465,6 +465,7 @@ fn main() {
         rpc_bigtable_config,
         faucet_addr: Some(faucet_addr),
         account_indexes,
+        enable_send_bundle: true,
         ..JsonRpcConfig::default_for_test()
     });
 
@@ -21,6 +21,10 @@ use {
     },
     solana_core::{
         banking_trace::{DirByteLimit, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
+        bundle_stage::{
+            bundle_ordering_policy::BundleOrderingMethod, DEFAULT_BUNDLE_EXECUTION_THREADS,
+        },
+        proxy::fetch_stage_manager::DEFAULT_RELAYER_GRACE_WINDOW,
         validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_faucet::faucet::{self, FAUCET_PORT},
@@ -39,6 +43,7 @@ use {
             DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN, SUPPORTED_ARCHIVE_COMPRESSION,
         },
     },
+    solana_runtime_plugin::plugin_submitter::DEFAULT_MAX_PLUGIN_SUBMISSIONS_PER_SECOND,
     solana_sdk::{
         clock::Slot, epoch_schedule::MINIMUM_SLOTS_PER_EPOCH, hash::Hash, quic::QUIC_PORT_OFFSET,
         rpc_port,
@@ -77,8 +82,12 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
         .arg(
             Arg::with_name("block_engine_url")
                 .long("block-engine-url")
-                .help("Block engine url.  Set to empty string to disable block engine connection.")
+                .help("Block engine url.  Set to empty string to disable block engine connection. \
+                       May be specified multiple times; the block engine with the lowest \
+                       connection latency is used and the others are failed over to in order of \
+                       latency.")
                 .takes_value(true)
+                .multiple(true)
         )
         .arg(
             Arg::with_name("relayer_url")
@@ -106,6 +115,15 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Maximum number of heartbeats the Relayer can miss before falling back to the normal TPU pipeline.")
                 .default_value(DEFAULT_RELAYER_MAX_FAILED_HEARTBEATS)
         )
+        .arg(
+            Arg::with_name("relayer_grace_window_secs")
+                .long("relayer-grace-window-secs")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value(&default_args.relayer_grace_window_secs)
+                .help("How long the Relayer has to send heartbeats without missing one before the validator advertises the Relayer's TPU ports instead of its own.")
+        )
         .arg(
             Arg::with_name("trust_block_engine_packets")
                 .long("trust-block-engine-packets")
@@ -148,6 +166,34 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(DEFAULT_PREALLOCATED_BUNDLE_COST)
                 .help("Number of CUs to allocate for bundles at beginning of slot.")
         )
+        .arg(
+            Arg::with_name("bundle_ordering_method")
+                .long("bundle-ordering-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .possible_values(BundleOrderingMethod::cli_names())
+                .help(BundleOrderingMethod::cli_message())
+        )
+        .arg(
+            Arg::with_name("bundle_execution_threads")
+                .long("bundle-execution-threads")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.bundle_execution_threads)
+                .help("Number of threads BundleStage uses to execute bundles that don't lock \
+                       any of the same accounts in parallel. Bundles are always committed in \
+                       the order they were scheduled in.")
+        )
+        .arg(
+            Arg::with_name("bundle_denylist_policy_file")
+                .long("bundle-denylist-policy-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("JSON file of named rules listing programs and accounts that bundles aren't \
+                       allowed to reference, in addition to consensus accounts and the tip \
+                       payment program. Reload it with the reload-bundle-denylist subcommand.")
+        )
         .arg(
             Arg::with_name("shred_receiver_address")
                 .long("shred-receiver-address")
@@ -320,6 +366,28 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Upload new confirmed blocks into a BigTable instance"),
         )
+        .arg(
+            Arg::with_name("rpc_local_archive")
+                .long("rpc-local-archive")
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("enable_rpc_transaction_history")
+                .conflicts_with_all(&[
+                    "enable_rpc_bigtable_ledger_storage",
+                    "enable_bigtable_ledger_upload",
+                ])
+                .help("Fetch historical transaction info from a ledger archive in this directory \
+                       as a fallback to local ledger data. The archive is kept in the BigTable \
+                       format and is created if it doesn't exist"),
+        )
+        .arg(
+            Arg::with_name("enable_local_archive_upload")
+                .long("enable-local-archive-upload")
+                .requires("rpc_local_archive")
+                .takes_value(false)
+                .help("Upload new confirmed blocks into the ledger archive \
+                       given by --rpc-local-archive"),
+        )
         .arg(
             Arg::with_name("enable_extended_tx_metadata_storage")
                 .long("enable-extended-tx-metadata-storage")
@@ -646,6 +714,15 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .hidden(hidden_unless_forced())
                 .help("Disable reporting of OS disk statistics.")
         )
+        .arg(
+            Arg::with_name("prometheus_bind_address")
+                .long("prometheus-bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help("Serve the latest value of each metric in the Prometheus text format \
+                       at http://HOST:PORT/metrics"),
+        )
         .arg(
             Arg::with_name("snapshot_version")
                 .long("snapshot-version")
@@ -1198,6 +1275,27 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .multiple(true)
                 .help("Specify the configuration file for a Runtime plugin."),
         )
+        .arg(
+            Arg::with_name("runtime_plugin_enable_submission")
+                .long("runtime-plugin-enable-submission")
+                .takes_value(false)
+                .help(
+                    "Allow runtime plugins to submit transactions and bundles to this validator's \
+                     TPU and BundleStage while it is leader.",
+                ),
+        )
+        .arg(
+            Arg::with_name("runtime_plugin_max_submissions_per_second")
+                .long("runtime-plugin-max-submissions-per-second")
+                .value_name("COUNT")
+                .takes_value(true)
+                .default_value(&default_args.runtime_plugin_max_submissions_per_second)
+                .validator(is_parsable::<u64>)
+                .help(
+                    "The maximum number of transactions and bundles each runtime plugin may \
+                     submit per second.",
+                ),
+        )
         .arg(
             Arg::with_name("snapshot_archive_format")
                 .long("snapshot-archive-format")
@@ -1315,6 +1413,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Create ancient storages in one shot instead of appending.")
                 .hidden(hidden_unless_forced()),
             )
+        .arg(
+            Arg::with_name("accounts_db_ancient_cold_storage_path")
+                .long("accounts-db-ancient-cold-storage-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires("accounts_db_create_ancient_storage_packed")
+                .help("Also migrate packed ancient storages into compressed cold storage files \
+                       in this directory.")
+                .hidden(hidden_unless_forced()),
+            )
         .arg(
             Arg::with_name("accounts_db_ancient_append_vecs")
                 .long("accounts-db-ancient-append-vecs")
@@ -1465,6 +1573,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .possible_values(BlockVerificationMethod::cli_names())
                 .help(BlockVerificationMethod::cli_message())
         )
+        .arg(
+            Arg::with_name("unified_scheduler_handler_threads")
+                .long("unified-scheduler-handler-threads")
+                .hidden(hidden_unless_forced())
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1usize..))
+                .help("Change the number of the unified scheduler's transaction execution threads \
+                       dedicated to each block, otherwise calculated as cpu_cores/4 [default: cpu_cores/4]")
+        )
         .arg(
             Arg::with_name("block_production_method")
                 .long("block-production-method")
@@ -1489,9 +1607,11 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                     optimistically confirmed slot to ensure we do not roll back any
                     optimistically confirmed slots.
 
-                    The progress in this mode will be saved in the file location provided.
-                    If consensus is reached, the validator will automatically exit and then
-                    execute wait_for_supermajority logic so the cluster will resume execution.
+                    The progress in this mode will be saved in the file location provided,
+                    and restarting with the same file resumes from the last recorded step.
+                    If consensus is reached, the validator will generate a snapshot of the
+                    selected fork and exit, logging the wait_for_supermajority, hard_fork and
+                    expected_shred_version arguments to restart the cluster with.
                     The progress file will be kept around for future debugging.
 
                     After the cluster resumes normal operation, the validator arguments can
@@ -1510,8 +1630,10 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .arg(
                     Arg::with_name("block_engine_url")
                         .long("block-engine-url")
-                        .help("Block engine url.  Set to empty string to disable block engine connection.")
+                        .help("Block engine url.  Set to empty string to disable block engine connection. \
+                               May be specified multiple times to fail over between block engines.")
                         .takes_value(true)
+                        .multiple(true)
                         .required(true)
                 )
                 .arg(
@@ -1554,6 +1676,43 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .default_value(DEFAULT_RELAYER_MAX_FAILED_HEARTBEATS)
                 )
         )
+        .subcommand(
+            SubCommand::with_name("block-engine-status")
+                .about("Display the state of the connection to the block engine")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("relayer-status")
+                .about("Display the state of the connection to the relayer")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("set-fetch-stage-mode")
+                .about("Force the validator to advertise its own or the relayer's TPU ports")
+                .arg(
+                    Arg::with_name("mode")
+                        .long("mode")
+                        .value_name("MODE")
+                        .takes_value(true)
+                        .possible_values(&["auto", "direct", "relayer"])
+                        .required(true)
+                        .help("auto switches based on relayer heartbeats, direct always advertises this validator's TPU ports, relayer advertises the relayer's TPU ports even if its heartbeats are late.")
+                )
+        )
         .subcommand(
             SubCommand::with_name("set-shred-receiver-address")
                 .about("Changes shred receiver address")
@@ -1566,6 +1725,46 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .required(true)
                 )
         )
+        .subcommand(
+            SubCommand::with_name("reload-bundle-denylist")
+                .about("Reload the bundle denylist policy file")
+                .arg(
+                    Arg::with_name("policy_file")
+                        .long("policy-file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Switch to this policy file instead of re-reading the current one")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("tip-revenue")
+                .about("Display the MEV tips collected in this validator's rooted leader slots")
+                .arg(
+                    Arg::with_name("start_slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .validator(is_parsable::<Slot>)
+                        .required(true)
+                        .help("First slot to display")
+                )
+                .arg(
+                    Arg::with_name("end_slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .validator(is_parsable::<Slot>)
+                        .help("Last slot to display [default: highest root]")
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode")
+                )
+        )
         .subcommand(
             SubCommand::with_name("exit")
                 .about("Send an exit request to the validator")
@@ -2144,6 +2343,9 @@ pub struct DefaultArgs {
     pub tower_storage: String,
     pub etcd_domain_name: String,
     pub send_transaction_service_config: send_transaction_service::Config,
+    pub bundle_execution_threads: String,
+    pub relayer_grace_window_secs: String,
+    pub runtime_plugin_max_submissions_per_second: String,
 
     pub rpc_max_multiple_accounts: String,
     pub rpc_pubsub_max_active_subscriptions: String,
@@ -2214,6 +2416,10 @@ impl DefaultArgs {
             health_check_slot_distance: "150".to_string(),
             tower_storage: "file".to_string(),
             etcd_domain_name: "localhost".to_string(),
+            bundle_execution_threads: DEFAULT_BUNDLE_EXECUTION_THREADS.to_string(),
+            relayer_grace_window_secs: DEFAULT_RELAYER_GRACE_WINDOW.as_secs().to_string(),
+            runtime_plugin_max_submissions_per_second: DEFAULT_MAX_PLUGIN_SUBMISSIONS_PER_SECOND
+                .to_string(),
             rpc_pubsub_max_active_subscriptions: PubSubConfig::default()
                 .max_active_subscriptions
                 .to_string(),
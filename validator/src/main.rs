@@ -21,6 +21,9 @@ use {
     solana_clap_utils::input_parsers::{keypair_of, keypairs_of, pubkey_of, value_of},
     solana_core::{
         banking_trace::DISABLED_BAKING_TRACE_DIR,
+        bundle_stage::{
+            bundle_denylist::BundleDenylist, bundle_ordering_policy::BundleOrderingMethod,
+        },
         consensus::tower_storage,
         proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
         system_monitor_service::SystemMonitorService,
@@ -43,7 +46,7 @@ use {
     solana_perf::recycler::enable_recycler_warming,
     solana_poh::poh_service,
     solana_rpc::{
-        rpc::{JsonRpcConfig, RpcBigtableConfig},
+        rpc::{JsonRpcConfig, RpcBigtableConfig, RpcLocalArchiveConfig},
         rpc_pubsub_service::PubSubConfig,
     },
     solana_rpc_client::rpc_client::RpcClient,
@@ -55,7 +58,7 @@ use {
         snapshot_utils::{self, ArchiveFormat, SnapshotVersion},
     },
     solana_runtime_plugin::{
-        runtime_plugin_admin_rpc_service,
+        plugin_submitter::PluginSubmissionConfig, runtime_plugin_admin_rpc_service,
         runtime_plugin_admin_rpc_service::RuntimePluginAdminRpcRequestMetadata,
     },
     solana_sdk::{
@@ -477,14 +480,21 @@ pub fn main() {
     let operation = match matches.subcommand() {
         ("", _) | ("run", _) => Operation::Run,
         ("set-block-engine-config", Some(subcommand_matches)) => {
-            let block_engine_url = value_t_or_exit!(subcommand_matches, "block_engine_url", String);
+            let block_engine_urls: Vec<String> =
+                values_t_or_exit!(subcommand_matches, "block_engine_url", String)
+                    .into_iter()
+                    .filter(|url| !url.is_empty())
+                    .collect();
             let trust_packets = subcommand_matches.is_present("trust_block_engine_packets");
             let admin_client = admin_rpc_service::connect(&ledger_path);
             admin_rpc_service::runtime()
                 .block_on(async move {
                     admin_client
                         .await?
-                        .set_block_engine_config(block_engine_url, trust_packets)
+                        .set_block_engine_config(
+                            admin_rpc_service::AdminRpcBlockEngineUrls::List(block_engine_urls),
+                            trust_packets,
+                        )
                         .await
                 })
                 .unwrap_or_else(|err| {
@@ -519,6 +529,64 @@ pub fn main() {
                 });
             return;
         }
+        ("block-engine-status", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let block_engine_status = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.get_block_engine_status().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Block engine status query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&block_engine_status).unwrap()
+                    ),
+                    "json-compact" => {
+                        print!("{}", serde_json::to_string(&block_engine_status).unwrap())
+                    }
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{block_engine_status}");
+            }
+            return;
+        }
+        ("relayer-status", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let relayer_status = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.get_relayer_status().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Relayer status query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => {
+                        println!("{}", serde_json::to_string_pretty(&relayer_status).unwrap())
+                    }
+                    "json-compact" => print!("{}", serde_json::to_string(&relayer_status).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{relayer_status}");
+            }
+            return;
+        }
+        ("set-fetch-stage-mode", Some(subcommand_matches)) => {
+            let mode = value_t_or_exit!(subcommand_matches, "mode", String);
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.set_fetch_stage_mode(mode).await })
+                .unwrap_or_else(|err| {
+                    println!("set fetch stage mode failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
         ("set-shred-receiver-address", Some(subcommand_matches)) => {
             let addr = value_t_or_exit!(subcommand_matches, "shred_receiver_address", String);
             let admin_client = admin_rpc_service::connect(&ledger_path);
@@ -530,6 +598,80 @@ pub fn main() {
                 });
             return;
         }
+        ("reload-bundle-denylist", Some(subcommand_matches)) => {
+            let policy_file = subcommand_matches
+                .value_of("policy_file")
+                .map(|policy_file| {
+                    fs::canonicalize(policy_file)
+                        .unwrap_or_else(|err| {
+                            println!("Unable to access path: {policy_file}: {err:?}");
+                            exit(1);
+                        })
+                        .to_string_lossy()
+                        .to_string()
+                });
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let num_rules = admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .reload_bundle_denylist(policy_file)
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("reload bundle denylist failed: {err}");
+                    exit(1);
+                });
+            println!("Loaded {num_rules} bundle denylist rules");
+            return;
+        }
+        ("tip-revenue", Some(subcommand_matches)) => {
+            let start_slot = value_t_or_exit!(subcommand_matches, "start_slot", Slot);
+            let end_slot = value_t!(subcommand_matches, "end_slot", Slot).ok();
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let tip_revenue = admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .get_tip_revenue(start_slot, end_slot)
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    eprintln!("Tip revenue query failed: {err}");
+                    exit(1);
+                });
+            match output_mode {
+                Some("json") => println!("{}", serde_json::to_string_pretty(&tip_revenue).unwrap()),
+                Some("json-compact") => print!("{}", serde_json::to_string(&tip_revenue).unwrap()),
+                Some(_) => unreachable!(),
+                None => {
+                    println!(
+                        "{:>12} {:>8} {:>16} {:>16} {:>16}",
+                        "Slot", "Epoch", "Total Tips", "Commission", "Net Tips"
+                    );
+                    for slot_revenue in &tip_revenue {
+                        println!(
+                            "{:>12} {:>8} {:>16} {:>16} {:>16}",
+                            slot_revenue.slot,
+                            slot_revenue.epoch,
+                            slot_revenue.total_tips,
+                            slot_revenue.block_builder_commission,
+                            slot_revenue.net_tip_distribution,
+                        );
+                    }
+                    let total_tips: u64 = tip_revenue.iter().map(|r| r.total_tips).sum();
+                    let net_tip_distribution: u64 =
+                        tip_revenue.iter().map(|r| r.net_tip_distribution).sum();
+                    println!(
+                        "{} leader slots, {total_tips} lamports in tips, \
+                         {net_tip_distribution} lamports net to the tip distribution account",
+                        tip_revenue.len(),
+                    );
+                }
+            }
+            return;
+        }
         ("authorized-voter", Some(authorized_voter_subcommand_matches)) => {
             match authorized_voter_subcommand_matches.subcommand() {
                 ("add", Some(subcommand_matches)) => {
@@ -621,7 +763,32 @@ pub fn main() {
                     if !plugins.is_empty() {
                         println!("Currently the following plugins are loaded:");
                         for (plugin, i) in plugins.into_iter().zip(1..) {
-                            println!("  {i}) {plugin}");
+                            let stats = plugin.filter_stats;
+                            println!("  {i}) {}", plugin.name);
+                            println!(
+                                "     accounts: {} notified, {} filtered",
+                                stats.accounts_notified, stats.accounts_filtered
+                            );
+                            println!(
+                                "     transactions: {} notified, {} filtered",
+                                stats.transactions_notified, stats.transactions_filtered
+                            );
+                            if let Some(delivery) = plugin.async_delivery {
+                                println!(
+                                    "     async delivery: {}/{} queued, {} delivered, {} dropped, \
+                                     {} blocked{}",
+                                    delivery.queue_depth,
+                                    delivery.queue_capacity,
+                                    delivery.delivered,
+                                    delivery.dropped,
+                                    delivery.blocked,
+                                    if delivery.disconnected {
+                                        ", DISCONNECTED (reload the plugin to reconnect)"
+                                    } else {
+                                        ""
+                                    },
+                                );
+                            }
                         }
                     } else {
                         println!("There are currently no plugins loaded");
@@ -1366,6 +1533,12 @@ pub fn main() {
             .is_present("accounts_db_create_ancient_storage_packed")
             .then_some(CreateAncientStorage::Pack)
             .unwrap_or_default(),
+        ancient_cold_storage_path: value_t!(
+            matches,
+            "accounts_db_ancient_cold_storage_path",
+            PathBuf
+        )
+        .ok(),
         test_partitioned_epoch_rewards,
         test_skip_rewrites_but_include_in_bank_hash: matches
             .is_present("accounts_db_test_skip_rewrites"),
@@ -1406,6 +1579,14 @@ pub fn main() {
         None
     };
 
+    let rpc_local_archive_config =
+        value_t!(matches, "rpc_local_archive", PathBuf)
+            .ok()
+            .map(|archive_path| RpcLocalArchiveConfig {
+                enable_local_archive_upload: matches.is_present("enable_local_archive_upload"),
+                archive_path,
+            });
+
     let rpc_send_retry_rate_ms = value_t_or_exit!(matches, "rpc_send_transaction_retry_ms", u64);
     let rpc_send_batch_size = value_t_or_exit!(matches, "rpc_send_transaction_batch_size", usize);
     let rpc_send_batch_send_rate_ms =
@@ -1435,11 +1616,11 @@ pub fn main() {
     let tip_manager_config = tip_manager_config_from_matches(&matches, voting_disabled);
 
     let block_engine_config = BlockEngineConfig {
-        block_engine_url: if matches.is_present("block_engine_url") {
-            value_of(&matches, "block_engine_url").expect("couldn't parse block_engine_url")
-        } else {
-            "".to_string()
-        },
+        block_engine_urls: values_t!(matches, "block_engine_url", String)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|url| !url.is_empty())
+            .collect(),
         trust_packets: matches.is_present("trust_block_engine_packets"),
     };
 
@@ -1469,6 +1650,14 @@ pub fn main() {
         trust_packets: matches.is_present("trust_relayer_packets"),
     };
 
+    let bundle_denylist = match matches.value_of("bundle_denylist_policy_file") {
+        Some(policy_file) => BundleDenylist::load(policy_file).unwrap_or_else(|err| {
+            eprintln!("Unable to load bundle denylist: {err}");
+            exit(1);
+        }),
+        None => BundleDenylist::default(),
+    };
+
     let mut validator_config = ValidatorConfig {
         require_tower: matches.is_present("require_tower"),
         tower_storage,
@@ -1486,6 +1675,7 @@ pub fn main() {
             enable_extended_tx_metadata_storage: matches.is_present("enable_cpi_and_log_storage")
                 || matches.is_present("enable_extended_tx_metadata_storage"),
             rpc_bigtable_config,
+            rpc_local_archive_config,
             faucet_addr: matches.value_of("rpc_faucet_addr").map(|address| {
                 solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
             }),
@@ -1502,6 +1692,7 @@ pub fn main() {
                 u64
             ),
             disable_health_check: false,
+            enable_send_bundle: false,
             rpc_threads: value_t_or_exit!(matches, "rpc_threads", usize),
             rpc_niceness_adj: value_t_or_exit!(matches, "rpc_niceness_adj", i8),
             account_indexes: account_indexes.clone(),
@@ -1584,6 +1775,14 @@ pub fn main() {
         no_os_network_stats_reporting: matches.is_present("no_os_network_stats_reporting"),
         no_os_cpu_stats_reporting: matches.is_present("no_os_cpu_stats_reporting"),
         no_os_disk_stats_reporting: matches.is_present("no_os_disk_stats_reporting"),
+        prometheus_bind_address: matches
+            .value_of("prometheus_bind_address")
+            .map(|host_port| {
+                solana_net_utils::parse_host_port(host_port).unwrap_or_else(|err| {
+                    eprintln!("Failed to parse --prometheus-bind-address: {err}");
+                    exit(1);
+                })
+            }),
         poh_pinned_cpu_core: value_of(&matches, "poh_pinned_cpu_core")
             .unwrap_or(poh_service::DEFAULT_PINNED_CPU_CORE),
         poh_hashes_per_batch: value_of(&matches, "poh_hashes_per_batch")
@@ -1609,6 +1808,11 @@ pub fn main() {
                 .value_of("shred_receiver_address")
                 .map(|addr| SocketAddr::from_str(addr).expect("shred_receiver_address invalid")),
         )),
+        relayer_grace_window: Duration::from_secs(value_t_or_exit!(
+            matches,
+            "relayer_grace_window_secs",
+            u64
+        )),
         staked_nodes_overrides: staked_nodes_overrides.clone(),
         replay_slots_concurrently: matches.is_present("replay_slots_concurrently"),
         use_snapshot_archives_at_startup: value_t_or_exit!(
@@ -1618,6 +1822,19 @@ pub fn main() {
         ),
         preallocated_bundle_cost: value_of(&matches, "preallocated_bundle_cost")
             .expect("preallocated_bundle_cost set as default"),
+        bundle_ordering_method: value_t!(matches, "bundle_ordering_method", BundleOrderingMethod)
+            .unwrap_or_default(),
+        bundle_execution_threads: value_t_or_exit!(matches, "bundle_execution_threads", usize),
+        bundle_denylist: Arc::new(RwLock::new(bundle_denylist)),
+        runtime_plugin_submission_config: matches
+            .is_present("runtime_plugin_enable_submission")
+            .then(|| PluginSubmissionConfig {
+                max_submissions_per_second: value_t_or_exit!(
+                    matches,
+                    "runtime_plugin_max_submissions_per_second",
+                    u64
+                ),
+            }),
         ..ValidatorConfig::default()
     };
 
@@ -1838,6 +2055,8 @@ pub fn main() {
         BlockVerificationMethod
     )
     .unwrap_or_default();
+    validator_config.unified_scheduler_handler_threads =
+        value_t!(matches, "unified_scheduler_handler_threads", usize).ok();
     validator_config.block_production_method = value_t!(
         matches, // comment to align formatting...
         "block_production_method",
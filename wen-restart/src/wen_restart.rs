@@ -1,59 +1,583 @@
 //! The `wen-restart` module handles automatic repair during a cluster restart
 
 use {
-    crate::solana::wen_restart_proto::{
-        MyLastVotedForkSlots, State as RestartState, WenRestartProgress,
+    crate::{
+        last_voted_fork_slots_aggregate::{
+            LastVotedForkSlotsAggregate, LastVotedForkSlotsFinalResult,
+        },
+        solana::wen_restart_proto::{
+            GenerateSnapshotRecord, HeaviestForkRecord, LastVotedForkSlotsAggregateRecord,
+            MyLastVotedForkSlots, State as RestartState, WenRestartProgress,
+        },
     },
     log::*,
     prost::Message,
-    solana_gossip::{cluster_info::ClusterInfo, epoch_slots::MAX_SLOTS_PER_ENTRY},
-    solana_ledger::{ancestor_iterator::AncestorIterator, blockstore::Blockstore},
+    solana_entry::entry::VerifyRecyclers,
+    solana_gossip::{cluster_info::ClusterInfo, crds::Cursor, epoch_slots::MAX_SLOTS_PER_ENTRY},
+    solana_ledger::{
+        ancestor_iterator::AncestorIterator,
+        blockstore::Blockstore,
+        blockstore_processor::{process_single_slot, ConfirmationProgress, ProcessOptions},
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_program_runtime::timings::ExecuteTimings,
+    solana_runtime::{
+        accounts_background_service::AbsRequestSender,
+        bank::Bank,
+        bank_forks::BankForks,
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_bank_utils::{
+            bank_to_full_snapshot_archive, bank_to_incremental_snapshot_archive,
+        },
+        snapshot_config::SnapshotConfig,
+        snapshot_utils::get_highest_full_snapshot_archive_slot,
+    },
+    solana_sdk::{
+        clock::Slot, hash::Hash, shred_version::compute_shred_version, timing::timestamp,
+    },
     solana_vote_program::vote_state::VoteTransaction,
     std::{
-        fs::File,
-        io::{Error, Write},
+        collections::HashSet,
+        fs::{read, File},
+        io::{self, Write},
         path::PathBuf,
-        sync::Arc,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::sleep,
+        time::Duration,
     },
+    thiserror::Error,
 };
 
-pub fn wait_for_wen_restart(
-    wen_restart_path: &PathBuf,
-    last_vote: VoteTransaction,
-    blockstore: Arc<Blockstore>,
-    cluster_info: Arc<ClusterInfo>,
-) -> Result<(), Box<dyn std::error::Error>> {
+// If >42% of the validators have this block, repair this block locally.
+const REPAIR_THRESHOLD: f64 = 0.42;
+// When counting Heaviest Fork, only count those with no less than
+// 67% - 5% - (100% - active_stake) = active_stake - 38% stake.
+const HEAVIEST_FORK_THRESHOLD_DELTA: f64 = 0.38;
+// How often gossip is checked for new RestartLastVotedForkSlots.
+const GOSSIP_SLEEP_MILLIS: u64 = 1_000;
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum WenRestartError {
+    #[error("Block {0} not found in blockstore")]
+    BlockNotFound(Slot),
+    #[error("Block {0} is not full")]
+    BlockNotFull(Slot),
+    #[error("Block {0} failed to replay: {1:?}")]
+    BlockNotFrozenAfterReplay(Slot, Option<String>),
+    #[error("Block {0} has parent {1:?}, expected {2}")]
+    BlockNotLinkedToExpectedParent(Slot, Option<Slot>, Slot),
+    #[error("Exiting")]
+    Exiting,
+    #[error("Heaviest fork {0} has bank hash {1}, recorded {2}")]
+    HeaviestForkBankHashMismatch(Slot, Hash, Hash),
+    #[error("Heaviest fork {0} is older than local root {1}")]
+    HeaviestForkOlderThanRoot(Slot, Slot),
+    #[error("Leader of slot {0} is unknown")]
+    LeaderUnknown(Slot),
+    #[error("Heaviest fork missing from progress file")]
+    MissingHeaviestFork,
+    #[error("wen_restart doesn't work if local tower is wiped")]
+    MissingLastVote,
+    #[error("Last voted fork slots missing from progress file")]
+    MissingLastVotedForkSlots,
+    #[error("Snapshot missing from progress file")]
+    MissingSnapshot,
+    #[error("Unexpected state {0:?}")]
+    UnexpectedState(RestartState),
+}
+
+pub struct WenRestartConfig {
+    pub wen_restart_path: PathBuf,
+    pub last_vote: VoteTransaction,
+    pub blockstore: Arc<Blockstore>,
+    pub cluster_info: Arc<ClusterInfo>,
+    pub bank_forks: Arc<RwLock<BankForks>>,
+    /// Shared with RepairService, which only repairs these slots while set
+    pub wen_restart_repair_slots: Option<Arc<RwLock<Vec<Slot>>>>,
+    pub wait_for_supermajority_threshold_percent: u64,
+    pub snapshot_config: SnapshotConfig,
+    pub accounts_background_request_sender: AbsRequestSender,
+    pub genesis_config_hash: Hash,
+    pub exit: Arc<AtomicBool>,
+}
+
+/// Runs the wen_restart protocol until a snapshot of the heaviest fork has been
+/// generated. Every state transition is persisted, so a restarted validator picks
+/// up where it left off.
+pub fn wait_for_wen_restart(config: WenRestartConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut progress = initialize(
+        &config.wen_restart_path,
+        &config.last_vote,
+        &config.blockstore,
+        config.cluster_info.my_shred_version(),
+    )?;
+    loop {
+        let state = progress.state();
+        info!("wen_restart state {:?}", state);
+        match state {
+            RestartState::Init => progress.set_state(RestartState::LastVotedForkSlots),
+            RestartState::LastVotedForkSlots => {
+                let my_last_voted_fork_slots = progress
+                    .my_last_voted_fork_slots
+                    .as_ref()
+                    .ok_or(WenRestartError::MissingLastVotedForkSlots)?;
+                config.cluster_info.push_restart_last_voted_fork_slots(
+                    &my_last_voted_fork_slots.last_voted_fork_slots,
+                    Hash::from_str(&my_last_voted_fork_slots.last_vote_bankhash)?,
+                )?;
+                aggregate_restart_last_voted_fork_slots(
+                    &config.wen_restart_path,
+                    config.wait_for_supermajority_threshold_percent,
+                    &config.cluster_info,
+                    &config.bank_forks,
+                    &config.blockstore,
+                    config.wen_restart_repair_slots.as_ref(),
+                    &config.exit,
+                    &mut progress,
+                )?;
+                progress.set_state(RestartState::HeaviestFork);
+            }
+            RestartState::HeaviestFork => {
+                let aggregate_final_result = new_last_voted_fork_slots_aggregate(
+                    &progress,
+                    &config.bank_forks,
+                    &config.cluster_info,
+                )?
+                .get_final_result();
+                let total_active_stake = aggregate_final_result.total_active_stake;
+                let (slot, bankhash) = find_heaviest_fork(
+                    aggregate_final_result,
+                    &config.bank_forks,
+                    &config.blockstore,
+                    &config.exit,
+                )?;
+                config
+                    .cluster_info
+                    .push_restart_heaviest_fork(slot, bankhash, total_active_stake);
+                progress.my_heaviest_fork = Some(HeaviestForkRecord {
+                    slot,
+                    bankhash: bankhash.to_string(),
+                    total_active_stake,
+                });
+                progress.set_state(RestartState::GeneratingSnapshot);
+            }
+            RestartState::GeneratingSnapshot => {
+                let my_heaviest_fork = progress
+                    .my_heaviest_fork
+                    .as_ref()
+                    .ok_or(WenRestartError::MissingHeaviestFork)?;
+                progress.my_snapshot = Some(generate_snapshot(
+                    &config.bank_forks,
+                    &config.blockstore,
+                    &config.snapshot_config,
+                    &config.accounts_background_request_sender,
+                    config.genesis_config_hash,
+                    my_heaviest_fork,
+                    &config.exit,
+                )?);
+                progress.set_state(RestartState::FinishedSnapshot);
+            }
+            RestartState::FinishedSnapshot => {
+                let my_snapshot = progress
+                    .my_snapshot
+                    .as_ref()
+                    .ok_or(WenRestartError::MissingSnapshot)?;
+                // Restarting the cluster is still coordinated by operators.
+                info!(
+                    "wen_restart generated snapshot {}, once the cluster agrees restart with \
+                     --wait-for-supermajority {} --expected-bank-hash {} --hard-fork {} \
+                     --expected-shred-version {}",
+                    my_snapshot.path,
+                    my_snapshot.slot,
+                    my_snapshot.bankhash,
+                    my_snapshot.slot,
+                    my_snapshot.shred_version,
+                );
+                progress.set_state(RestartState::Done);
+            }
+            RestartState::Done => return Ok(()),
+            RestartState::WaitingForSupermajority => {
+                return Err(WenRestartError::UnexpectedState(state).into())
+            }
+        }
+        write_wen_restart_records(&config.wen_restart_path, &progress)?;
+    }
+}
+
+/// Loads the progress file if there is one, otherwise records our last voted fork.
+pub(crate) fn initialize(
+    records_path: &PathBuf,
+    last_vote: &VoteTransaction,
+    blockstore: &Blockstore,
+    shred_version: u16,
+) -> Result<WenRestartProgress, Box<dyn std::error::Error>> {
+    if let Some(progress) = read_wen_restart_records(records_path)? {
+        info!("wen_restart resuming from {:?}", progress.state());
+        return Ok(progress);
+    }
     // repair and restart option does not work without last voted slot.
     let last_vote_slot = last_vote
         .last_voted_slot()
-        .expect("wen_restart doesn't work if local tower is wiped");
-    let mut last_vote_fork: Vec<u64> = AncestorIterator::new_inclusive(last_vote_slot, &blockstore)
-        .take(MAX_SLOTS_PER_ENTRY)
-        .collect();
+        .ok_or(WenRestartError::MissingLastVote)?;
+    let mut last_voted_fork_slots: Vec<Slot> =
+        AncestorIterator::new_inclusive(last_vote_slot, blockstore)
+            .take(MAX_SLOTS_PER_ENTRY)
+            .collect();
     info!(
         "wen_restart last voted fork {} {:?}",
-        last_vote_slot, last_vote_fork
+        last_vote_slot, last_voted_fork_slots
     );
-    last_vote_fork.reverse();
-    // Todo(wen): add the following back in after Gossip code is checked in.
-    //    cluster_info.push_last_voted_fork_slots(&last_voted_fork, last_vote.hash());
-    // The rest of the protocol will be in another PR.
-    let current_progress = WenRestartProgress {
+    last_voted_fork_slots.reverse();
+    let progress = WenRestartProgress {
         state: RestartState::Init.into(),
         my_last_voted_fork_slots: Some(MyLastVotedForkSlots {
             last_vote_slot,
             last_vote_bankhash: last_vote.hash().to_string(),
-            shred_version: cluster_info.my_shred_version() as u32,
+            shred_version: shred_version as u32,
+            last_voted_fork_slots,
         }),
+        ..WenRestartProgress::default()
     };
-    write_wen_restart_records(wen_restart_path, current_progress)?;
+    write_wen_restart_records(records_path, &progress)?;
+    Ok(progress)
+}
+
+fn new_last_voted_fork_slots_aggregate(
+    progress: &WenRestartProgress,
+    bank_forks: &RwLock<BankForks>,
+    cluster_info: &ClusterInfo,
+) -> Result<LastVotedForkSlotsAggregate, WenRestartError> {
+    let my_last_voted_fork_slots = progress
+        .my_last_voted_fork_slots
+        .as_ref()
+        .ok_or(WenRestartError::MissingLastVotedForkSlots)?;
+    let root_bank = bank_forks.read().unwrap().root_bank();
+    let mut last_voted_fork_slots_aggregate = LastVotedForkSlotsAggregate::new(
+        root_bank.slot(),
+        REPAIR_THRESHOLD,
+        root_bank
+            .epoch_stakes(root_bank.epoch())
+            .expect("root bank must have stakes for its own epoch"),
+        &my_last_voted_fork_slots.last_voted_fork_slots,
+        &cluster_info.id(),
+    );
+    if let Some(aggregate_record) = &progress.last_voted_fork_slots_aggregate {
+        for (key_string, record) in &aggregate_record.received {
+            if let Err(e) =
+                last_voted_fork_slots_aggregate.aggregate_from_record(key_string, record)
+            {
+                error!("Failed to aggregate from record {}: {:?}", key_string, e);
+            }
+        }
+    }
+    Ok(last_voted_fork_slots_aggregate)
+}
+
+/// Aggregates RestartLastVotedForkSlots from gossip until enough stake is active
+/// and every slot held by more than REPAIR_THRESHOLD of the stake is full locally.
+#[allow(clippy::too_many_arguments)]
+fn aggregate_restart_last_voted_fork_slots(
+    wen_restart_path: &PathBuf,
+    wait_for_supermajority_threshold_percent: u64,
+    cluster_info: &ClusterInfo,
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    wen_restart_repair_slots: Option<&Arc<RwLock<Vec<Slot>>>>,
+    exit: &AtomicBool,
+    progress: &mut WenRestartProgress,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root_slot = bank_forks.read().unwrap().root();
+    let mut last_voted_fork_slots_aggregate =
+        new_last_voted_fork_slots_aggregate(progress, bank_forks, cluster_info)?;
+    let mut cursor = Cursor::default();
+    let mut is_full_slots = HashSet::new();
+    loop {
+        if exit.load(Ordering::Relaxed) {
+            return Err(WenRestartError::Exiting.into());
+        }
+        let start = timestamp();
+        let mut progress_changed = false;
+        for new_last_voted_fork_slots in cluster_info.get_restart_last_voted_fork_slots(&mut cursor)
+        {
+            let from = new_last_voted_fork_slots.from.to_string();
+            if let Some(record) =
+                last_voted_fork_slots_aggregate.aggregate(new_last_voted_fork_slots)
+            {
+                progress
+                    .last_voted_fork_slots_aggregate
+                    .get_or_insert_with(LastVotedForkSlotsAggregateRecord::default)
+                    .received
+                    .insert(from, record);
+                progress_changed = true;
+            }
+        }
+        let active_percent = last_voted_fork_slots_aggregate.active_percent();
+        let mut filtered_slots: Vec<Slot> = last_voted_fork_slots_aggregate
+            .slots_to_repair_iter()
+            .filter(|slot| {
+                if **slot <= root_slot || is_full_slots.contains(*slot) {
+                    return false;
+                }
+                if blockstore.is_full(**slot) {
+                    is_full_slots.insert(**slot);
+                    false
+                } else {
+                    true
+                }
+            })
+            .copied()
+            .collect();
+        filtered_slots.sort();
+        info!(
+            "wen_restart active stake {:.2}%, slots to repair: {:?}",
+            active_percent, filtered_slots
+        );
+        let done = filtered_slots.is_empty()
+            && active_percent >= wait_for_supermajority_threshold_percent as f64;
+        if let Some(wen_restart_repair_slots) = wen_restart_repair_slots {
+            *wen_restart_repair_slots.write().unwrap() = filtered_slots;
+        }
+        if progress_changed {
+            write_wen_restart_records(wen_restart_path, progress)?;
+        }
+        if done {
+            return Ok(());
+        }
+        let elapsed = timestamp().saturating_sub(start);
+        let time_left = GOSSIP_SLEEP_MILLIS.saturating_sub(elapsed);
+        if time_left > 0 {
+            sleep(Duration::from_millis(time_left));
+        }
+    }
+}
+
+/// Picks the highest slot in the chain of slots held by at least
+/// `total_active_stake - HEAVIEST_FORK_THRESHOLD_DELTA` of the stake, replaying
+/// it locally if needed to learn its bank hash.
+pub(crate) fn find_heaviest_fork(
+    aggregate_final_result: LastVotedForkSlotsFinalResult,
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    exit: &AtomicBool,
+) -> Result<(Slot, Hash), WenRestartError> {
+    let root_bank = bank_forks.read().unwrap().root_bank();
+    let root_slot = root_bank.slot();
+    // TODO(wen): should use the epoch stakes of each slot if crossing an epoch boundary.
+    let total_stake = root_bank
+        .epoch_stakes(root_bank.epoch())
+        .expect("root bank must have stakes for its own epoch")
+        .total_stake();
+    let stake_threshold = aggregate_final_result
+        .total_active_stake
+        .saturating_sub((HEAVIEST_FORK_THRESHOLD_DELTA * total_stake as f64) as u64);
+    let mut slots: Vec<Slot> = aggregate_final_result
+        .slots_stake_map
+        .iter()
+        .filter(|(slot, stake)| **slot > root_slot && **stake > stake_threshold)
+        .map(|(slot, _)| *slot)
+        .collect();
+    slots.sort();
+    verify_fork_is_linked_to_root(root_slot, &slots, blockstore, exit)?;
+    let heaviest_fork_slot = slots.last().copied().unwrap_or(root_slot);
+    let heaviest_fork_bankhash = replay_fork(&slots, root_bank, bank_forks, blockstore, exit)?;
+    info!(
+        "wen_restart heaviest fork {} {}",
+        heaviest_fork_slot, heaviest_fork_bankhash
+    );
+    Ok((heaviest_fork_slot, heaviest_fork_bankhash))
+}
+
+fn verify_fork_is_linked_to_root(
+    root_slot: Slot,
+    slots: &[Slot],
+    blockstore: &Blockstore,
+    exit: &AtomicBool,
+) -> Result<(), WenRestartError> {
+    let mut expected_parent = root_slot;
+    for slot in slots {
+        if exit.load(Ordering::Relaxed) {
+            return Err(WenRestartError::Exiting);
+        }
+        let Ok(Some(slot_meta)) = blockstore.meta(*slot) else {
+            return Err(WenRestartError::BlockNotFound(*slot));
+        };
+        if slot_meta.parent_slot != Some(expected_parent) {
+            return Err(WenRestartError::BlockNotLinkedToExpectedParent(
+                *slot,
+                slot_meta.parent_slot,
+                expected_parent,
+            ));
+        }
+        if !slot_meta.is_full() {
+            return Err(WenRestartError::BlockNotFull(*slot));
+        }
+        expected_parent = *slot;
+    }
     Ok(())
 }
 
+/// Replays `slots`, a chain starting right after `root_bank`, skipping banks which
+/// already exist, and returns the bank hash of the last one.
+fn replay_fork(
+    slots: &[Slot],
+    root_bank: Arc<Bank>,
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    exit: &AtomicBool,
+) -> Result<Hash, WenRestartError> {
+    let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&root_bank);
+    let recyclers = VerifyRecyclers::default();
+    let opts = ProcessOptions::default();
+    let mut parent_bank = root_bank;
+    for slot in slots {
+        if exit.load(Ordering::Relaxed) {
+            return Err(WenRestartError::Exiting);
+        }
+        let existing_bank = bank_forks.read().unwrap().get(*slot);
+        let bank = match existing_bank {
+            Some(bank) => bank,
+            None => {
+                let collector_id = leader_schedule_cache
+                    .slot_leader_at(*slot, Some(parent_bank.as_ref()))
+                    .ok_or(WenRestartError::LeaderUnknown(*slot))?;
+                let new_bank = Bank::new_from_parent(parent_bank.clone(), &collector_id, *slot);
+                let bank_with_scheduler = bank_forks.write().unwrap().insert_from_ledger(new_bank);
+                let mut progress = ConfirmationProgress::new(parent_bank.last_blockhash());
+                let mut timing = ExecuteTimings::default();
+                process_single_slot(
+                    blockstore,
+                    &bank_with_scheduler,
+                    &opts,
+                    &recyclers,
+                    &mut progress,
+                    None,
+                    None,
+                    None,
+                    None,
+                    &mut timing,
+                )
+                .map_err(|e| {
+                    WenRestartError::BlockNotFrozenAfterReplay(*slot, Some(e.to_string()))
+                })?;
+                bank_with_scheduler.clone_without_scheduler()
+            }
+        };
+        if !bank.is_frozen() {
+            return Err(WenRestartError::BlockNotFrozenAfterReplay(*slot, None));
+        }
+        parent_bank = bank;
+    }
+    Ok(parent_bank.hash())
+}
+
+/// Roots the heaviest fork and writes a snapshot of it, incremental on top of the
+/// latest full snapshot archive when there is one.
+pub(crate) fn generate_snapshot(
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    snapshot_config: &SnapshotConfig,
+    accounts_background_request_sender: &AbsRequestSender,
+    genesis_config_hash: Hash,
+    my_heaviest_fork: &HeaviestForkRecord,
+    exit: &AtomicBool,
+) -> Result<GenerateSnapshotRecord, Box<dyn std::error::Error>> {
+    let my_heaviest_fork_slot = my_heaviest_fork.slot;
+    let expected_bankhash = Hash::from_str(&my_heaviest_fork.bankhash)?;
+    let root_bank = bank_forks.read().unwrap().root_bank();
+    let root_slot = root_bank.slot();
+    if my_heaviest_fork_slot < root_slot {
+        return Err(
+            WenRestartError::HeaviestForkOlderThanRoot(my_heaviest_fork_slot, root_slot).into(),
+        );
+    }
+    // The banks are gone if the validator restarted after picking the heaviest fork.
+    let mut slots: Vec<Slot> = AncestorIterator::new_inclusive(my_heaviest_fork_slot, blockstore)
+        .take_while(|slot| *slot > root_slot)
+        .collect();
+    slots.reverse();
+    verify_fork_is_linked_to_root(root_slot, &slots, blockstore, exit)?;
+    let bankhash = replay_fork(&slots, root_bank, bank_forks, blockstore, exit)?;
+    if bankhash != expected_bankhash {
+        return Err(WenRestartError::HeaviestForkBankHashMismatch(
+            my_heaviest_fork_slot,
+            bankhash,
+            expected_bankhash,
+        )
+        .into());
+    }
+
+    bank_forks.write().unwrap().set_root(
+        my_heaviest_fork_slot,
+        accounts_background_request_sender,
+        None,
+    );
+    let new_root_bank = bank_forks.read().unwrap().root_bank();
+    let archive_path = match get_highest_full_snapshot_archive_slot(
+        &snapshot_config.full_snapshot_archives_dir,
+        None,
+    ) {
+        Some(full_snapshot_slot) if full_snapshot_slot < my_heaviest_fork_slot => {
+            bank_to_incremental_snapshot_archive(
+                &snapshot_config.bank_snapshots_dir,
+                &new_root_bank,
+                full_snapshot_slot,
+                Some(snapshot_config.snapshot_version),
+                &snapshot_config.full_snapshot_archives_dir,
+                &snapshot_config.incremental_snapshot_archives_dir,
+                snapshot_config.archive_format,
+                snapshot_config.maximum_full_snapshot_archives_to_retain,
+                snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+            )?
+            .path()
+            .clone()
+        }
+        _ => bank_to_full_snapshot_archive(
+            &snapshot_config.bank_snapshots_dir,
+            &new_root_bank,
+            Some(snapshot_config.snapshot_version),
+            &snapshot_config.full_snapshot_archives_dir,
+            &snapshot_config.incremental_snapshot_archives_dir,
+            snapshot_config.archive_format,
+            snapshot_config.maximum_full_snapshot_archives_to_retain,
+            snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+        )?
+        .path()
+        .clone(),
+    };
+
+    // The cluster restarts with a hard fork at the heaviest fork slot.
+    let mut hard_forks = new_root_bank.hard_forks();
+    hard_forks.register(my_heaviest_fork_slot);
+    let shred_version = compute_shred_version(&genesis_config_hash, Some(&hard_forks));
+    Ok(GenerateSnapshotRecord {
+        slot: my_heaviest_fork_slot,
+        bankhash: bankhash.to_string(),
+        shred_version: shred_version as u32,
+        path: archive_path.display().to_string(),
+    })
+}
+
+fn read_wen_restart_records(
+    records_path: &PathBuf,
+) -> Result<Option<WenRestartProgress>, io::Error> {
+    let buffer = match read(records_path) {
+        Ok(buffer) => buffer,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let progress = WenRestartProgress::decode(&mut io::Cursor::new(buffer))?;
+    info!("read record {:?}", progress);
+    Ok(Some(progress))
+}
+
 fn write_wen_restart_records(
     records_path: &PathBuf,
-    new_progress: WenRestartProgress,
-) -> Result<(), Error> {
+    new_progress: &WenRestartProgress,
+) -> Result<(), io::Error> {
     // overwrite anything if exists
     let mut file = File::create(records_path)?;
     info!("writing new record {:?}", new_progress);
@@ -62,36 +586,217 @@ fn write_wen_restart_records(
     file.write_all(&buf)?;
     Ok(())
 }
+
 #[cfg(test)]
 mod tests {
     use {
         crate::wen_restart::*,
         solana_entry::entry,
-        solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+        solana_gossip::{
+            contact_info::ContactInfo,
+            crds::GossipRoute,
+            crds_value::{CrdsData, CrdsValue},
+            restart_crds_values::RestartLastVotedForkSlots,
+        },
         solana_ledger::{blockstore, get_tmp_ledger_path_auto_delete},
         solana_program::{hash::Hash, vote::state::Vote},
+        solana_runtime::genesis_utils::{
+            create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+        },
         solana_sdk::{
+            pubkey::Pubkey,
             signature::{Keypair, Signer},
             timing::timestamp,
         },
         solana_streamer::socket::SocketAddrSpace,
-        std::{fs::read, sync::Arc},
+        std::{fs::read, path::Path, sync::Arc},
+        tempfile::TempDir,
     };
 
-    #[test]
-    fn test_wen_restart_normal_flow() {
-        solana_logger::setup();
-        let node_keypair = Arc::new(Keypair::new());
-        let cluster_info = Arc::new(ClusterInfo::new(
+    const SHRED_VERSION: u16 = 2;
+    const EXPECTED_SLOTS: Slot = 10;
+    const TOTAL_VALIDATOR_COUNT: u16 = 10;
+    const MY_INDEX: usize = 0;
+
+    fn new_cluster_info(node_keypair: Keypair) -> Arc<ClusterInfo> {
+        Arc::new(ClusterInfo::new(
             {
                 let mut contact_info =
                     ContactInfo::new_localhost(&node_keypair.pubkey(), timestamp());
-                contact_info.set_shred_version(2);
+                contact_info.set_shred_version(SHRED_VERSION);
                 contact_info
             },
-            node_keypair,
+            Arc::new(node_keypair),
             SocketAddrSpace::Unspecified,
-        ));
+        ))
+    }
+
+    fn read_progress(wen_restart_proto_path: &Path) -> WenRestartProgress {
+        let buffer = read(wen_restart_proto_path).unwrap();
+        WenRestartProgress::decode(&mut std::io::Cursor::new(buffer)).unwrap()
+    }
+
+    fn insert_full_slots_into_blockstore(blockstore: &Blockstore, slots: &[Slot]) {
+        let mut parent_slot = 0;
+        for slot in slots {
+            let entries = entry::create_ticks(1, 0, Hash::default());
+            let shreds = blockstore::entries_to_test_shreds(
+                &entries,
+                *slot,
+                parent_slot,
+                true, // is_full_slot
+                0,
+                true, // merkle_variant
+            );
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            parent_slot = *slot;
+        }
+    }
+
+    fn insert_and_freeze_banks(bank_forks: &RwLock<BankForks>, slots: &[Slot]) {
+        let mut parent_bank = bank_forks.read().unwrap().root_bank();
+        for slot in slots {
+            let bank = Bank::new_from_parent(parent_bank, &Pubkey::default(), *slot);
+            bank.fill_bank_with_ticks_for_tests();
+            bank.freeze();
+            parent_bank = bank_forks
+                .write()
+                .unwrap()
+                .insert(bank)
+                .clone_without_scheduler();
+        }
+    }
+
+    #[test]
+    fn test_wen_restart_normal_flow() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let mut wen_restart_proto_path = ledger_path.path().to_path_buf();
+        wen_restart_proto_path.push("wen_restart_status.proto");
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let validator_voting_keypairs: Vec<_> = (0..TOTAL_VALIDATOR_COUNT)
+            .map(|_| ValidatorVoteKeypairs::new_rand())
+            .collect();
+        let cluster_info = new_cluster_info(
+            validator_voting_keypairs[MY_INDEX]
+                .node_keypair
+                .insecure_clone(),
+        );
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config_with_vote_accounts(
+            10_000,
+            &validator_voting_keypairs,
+            vec![100; validator_voting_keypairs.len()],
+        );
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let last_voted_fork_slots: Vec<Slot> = (1..=EXPECTED_SLOTS).collect();
+        insert_full_slots_into_blockstore(&blockstore, &last_voted_fork_slots);
+        insert_and_freeze_banks(&bank_forks, &last_voted_fork_slots);
+        let last_vote_bankhash = bank_forks
+            .read()
+            .unwrap()
+            .get(EXPECTED_SLOTS)
+            .unwrap()
+            .hash();
+
+        // 8 other validators plus ourselves is 90% of the stake.
+        for keypairs in validator_voting_keypairs
+            .iter()
+            .filter(|keypairs| keypairs.node_keypair.pubkey() != cluster_info.id())
+            .take(8)
+        {
+            let slots = RestartLastVotedForkSlots::new(
+                keypairs.node_keypair.pubkey(),
+                timestamp(),
+                &last_voted_fork_slots,
+                last_vote_bankhash,
+                SHRED_VERSION,
+            )
+            .unwrap();
+            cluster_info
+                .gossip
+                .crds
+                .write()
+                .unwrap()
+                .insert(
+                    CrdsValue::new_signed(
+                        CrdsData::RestartLastVotedForkSlots(slots),
+                        &keypairs.node_keypair,
+                    ),
+                    timestamp(),
+                    GossipRoute::LocalMessage,
+                )
+                .unwrap();
+        }
+
+        let full_snapshot_archives_dir = TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = TempDir::new().unwrap();
+        let bank_snapshots_dir = TempDir::new().unwrap();
+        let wen_restart_repair_slots = Arc::new(RwLock::new(vec![]));
+        assert!(wait_for_wen_restart(WenRestartConfig {
+            wen_restart_path: wen_restart_proto_path.clone(),
+            last_vote: VoteTransaction::from(Vote::new(vec![EXPECTED_SLOTS], last_vote_bankhash)),
+            blockstore,
+            cluster_info: cluster_info.clone(),
+            bank_forks: bank_forks.clone(),
+            wen_restart_repair_slots: Some(wen_restart_repair_slots.clone()),
+            wait_for_supermajority_threshold_percent: 80,
+            snapshot_config: SnapshotConfig {
+                full_snapshot_archives_dir: full_snapshot_archives_dir.path().to_path_buf(),
+                incremental_snapshot_archives_dir: incremental_snapshot_archives_dir
+                    .path()
+                    .to_path_buf(),
+                bank_snapshots_dir: bank_snapshots_dir.path().to_path_buf(),
+                ..SnapshotConfig::default()
+            },
+            accounts_background_request_sender: AbsRequestSender::default(),
+            genesis_config_hash: genesis_config.hash(),
+            exit: Arc::new(AtomicBool::new(false)),
+        })
+        .is_ok());
+
+        // Nothing was missing locally.
+        assert!(wen_restart_repair_slots.read().unwrap().is_empty());
+        assert_eq!(bank_forks.read().unwrap().root(), EXPECTED_SLOTS);
+        cluster_info.flush_push_queue();
+        let heaviest_forks = cluster_info.get_restart_heaviest_fork(&mut Cursor::default());
+        assert_eq!(heaviest_forks.len(), 1);
+        assert_eq!(heaviest_forks[0].last_slot, EXPECTED_SLOTS);
+        assert_eq!(heaviest_forks[0].last_slot_hash, last_vote_bankhash);
+        assert_eq!(heaviest_forks[0].observed_stake, 900);
+
+        let progress = read_progress(&wen_restart_proto_path);
+        assert_eq!(progress.state(), RestartState::Done);
+        assert_eq!(
+            progress
+                .last_voted_fork_slots_aggregate
+                .unwrap()
+                .received
+                .len(),
+            8
+        );
+        assert_eq!(
+            progress.my_heaviest_fork,
+            Some(HeaviestForkRecord {
+                slot: EXPECTED_SLOTS,
+                bankhash: last_vote_bankhash.to_string(),
+                total_active_stake: 900,
+            })
+        );
+        let my_snapshot = progress.my_snapshot.unwrap();
+        let mut hard_forks = bank_forks.read().unwrap().root_bank().hard_forks();
+        hard_forks.register(EXPECTED_SLOTS);
+        assert_eq!(my_snapshot.slot, EXPECTED_SLOTS);
+        assert_eq!(my_snapshot.bankhash, last_vote_bankhash.to_string());
+        assert_eq!(
+            my_snapshot.shred_version,
+            compute_shred_version(&genesis_config.hash(), Some(&hard_forks)) as u32
+        );
+        assert!(Path::new(&my_snapshot.path).exists());
+    }
+
+    #[test]
+    fn test_wen_restart_initialize() {
+        solana_logger::setup();
         let ledger_path = get_tmp_ledger_path_auto_delete!();
         let mut wen_restart_proto_path = ledger_path.path().to_path_buf();
         wen_restart_proto_path.push("wen_restart_status.proto");
@@ -116,7 +821,6 @@ mod tests {
             );
             blockstore.insert_shreds(shreds, None, false).unwrap();
         }
-        // link directly to slot 1 whose distance to last_vote > MAX_SLOTS_PER_ENTRY so it will not be included.
         let entries = entry::create_ticks(1, 0, Hash::default());
         let shreds = blockstore::entries_to_test_shreds(
             &entries,
@@ -128,25 +832,115 @@ mod tests {
         );
         blockstore.insert_shreds(shreds, None, false).unwrap();
         let last_vote_bankhash = Hash::new_unique();
-        assert!(wait_for_wen_restart(
+
+        // Without a progress file the local tower is required.
+        assert!(initialize(
             &wen_restart_proto_path,
-            VoteTransaction::from(Vote::new(vec![last_vote_slot], last_vote_bankhash)),
-            blockstore,
-            cluster_info
+            &VoteTransaction::from(Vote::new(vec![], last_vote_bankhash)),
+            &blockstore,
+            SHRED_VERSION,
         )
-        .is_ok());
-        let buffer = read(wen_restart_proto_path).unwrap();
-        let progress = WenRestartProgress::decode(&mut std::io::Cursor::new(buffer)).unwrap();
-        assert_eq!(
-            progress,
-            WenRestartProgress {
-                state: RestartState::Init.into(),
-                my_last_voted_fork_slots: Some(MyLastVotedForkSlots {
-                    last_vote_slot,
-                    last_vote_bankhash: last_vote_bankhash.to_string(),
-                    shred_version: 2,
-                }),
-            }
+        .is_err());
+        assert!(!wen_restart_proto_path.exists());
+
+        let progress = initialize(
+            &wen_restart_proto_path,
+            &VoteTransaction::from(Vote::new(vec![last_vote_slot], last_vote_bankhash)),
+            &blockstore,
+            SHRED_VERSION,
         )
+        .unwrap();
+        assert_eq!(progress, read_progress(&wen_restart_proto_path));
+        assert_eq!(progress.state(), RestartState::Init);
+        let my_last_voted_fork_slots = progress.my_last_voted_fork_slots.clone().unwrap();
+        assert_eq!(my_last_voted_fork_slots.last_vote_slot, last_vote_slot);
+        assert_eq!(
+            my_last_voted_fork_slots.last_vote_bankhash,
+            last_vote_bankhash.to_string()
+        );
+        assert_eq!(my_last_voted_fork_slots.shred_version, SHRED_VERSION as u32);
+        assert_eq!(
+            my_last_voted_fork_slots.last_voted_fork_slots.last(),
+            Some(&last_vote_slot)
+        );
+        assert!(my_last_voted_fork_slots
+            .last_voted_fork_slots
+            .contains(&last_parent));
+        assert!(my_last_voted_fork_slots
+            .last_voted_fork_slots
+            .windows(2)
+            .all(|pair| pair[0] < pair[1]));
+
+        // An existing progress file wins over the local tower.
+        let resumed_progress = WenRestartProgress {
+            state: RestartState::HeaviestFork.into(),
+            my_heaviest_fork: Some(HeaviestForkRecord {
+                slot: last_vote_slot,
+                bankhash: last_vote_bankhash.to_string(),
+                total_active_stake: 900,
+            }),
+            ..progress
+        };
+        write_wen_restart_records(&wen_restart_proto_path, &resumed_progress).unwrap();
+        assert_eq!(
+            initialize(
+                &wen_restart_proto_path,
+                &VoteTransaction::from(Vote::new(vec![], Hash::default())),
+                &blockstore,
+                SHRED_VERSION,
+            )
+            .unwrap(),
+            resumed_progress
+        );
+    }
+
+    #[test]
+    fn test_find_heaviest_fork_failures() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let validator_voting_keypairs: Vec<_> = (0..TOTAL_VALIDATOR_COUNT)
+            .map(|_| ValidatorVoteKeypairs::new_rand())
+            .collect();
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config_with_vote_accounts(
+            10_000,
+            &validator_voting_keypairs,
+            vec![100; validator_voting_keypairs.len()],
+        );
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let exit = AtomicBool::new(false);
+        let final_result = |slots: &[Slot]| LastVotedForkSlotsFinalResult {
+            slots_stake_map: slots.iter().map(|slot| (*slot, 900)).collect(),
+            total_active_stake: 900,
+        };
+
+        // Nothing to replay, the root is the heaviest fork.
+        let root_hash = bank_forks.read().unwrap().root_bank().hash();
+        assert_eq!(
+            find_heaviest_fork(final_result(&[]), &bank_forks, &blockstore, &exit),
+            Ok((0, root_hash))
+        );
+        assert_eq!(
+            find_heaviest_fork(final_result(&[1]), &bank_forks, &blockstore, &exit),
+            Err(WenRestartError::BlockNotFound(1))
+        );
+        insert_full_slots_into_blockstore(&blockstore, &[1, 3]);
+        assert_eq!(
+            find_heaviest_fork(final_result(&[1, 2]), &bank_forks, &blockstore, &exit),
+            Err(WenRestartError::BlockNotFound(2))
+        );
+        assert_eq!(
+            find_heaviest_fork(final_result(&[3]), &bank_forks, &blockstore, &exit),
+            Err(WenRestartError::BlockNotLinkedToExpectedParent(
+                3,
+                Some(1),
+                0
+            ))
+        );
+        exit.store(true, Ordering::Relaxed);
+        assert_eq!(
+            find_heaviest_fork(final_result(&[1]), &bank_forks, &blockstore, &exit),
+            Err(WenRestartError::Exiting)
+        );
     }
 }